@@ -0,0 +1,3 @@
+-- Z-score of the synthetic spread close over the rolling window (0 for real instruments)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS spread_zscore Float64 DEFAULT 0;
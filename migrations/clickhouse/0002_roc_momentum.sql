@@ -0,0 +1,4 @@
+-- Rate of change (%) and momentum for each lag of indicators_updater.roc_lags, in config order
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS roc Array(Float64) DEFAULT [],
+    ADD COLUMN IF NOT EXISTS momentum Array(Float64) DEFAULT [];
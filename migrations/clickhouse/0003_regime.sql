@@ -0,0 +1,3 @@
+-- Market regime: 0 range, 1 trend-up, -1 trend-down, 2 high volatility
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS regime Int8 DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Online CUSUM changepoints on returns (price) and absolute returns (volatility)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS price_changepoint Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vol_changepoint Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS minutes_since_price_cp Int32 DEFAULT -1,
+    ADD COLUMN IF NOT EXISTS minutes_since_vol_cp Int32 DEFAULT -1;
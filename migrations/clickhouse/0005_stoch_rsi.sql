@@ -0,0 +1,5 @@
+-- Stochastic RSI(14) with smoothed %K(3) / %D(3), 0-100 scale
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS stoch_rsi Float64 DEFAULT 50,
+    ADD COLUMN IF NOT EXISTS stoch_rsi_k Float64 DEFAULT 50,
+    ADD COLUMN IF NOT EXISTS stoch_rsi_d Float64 DEFAULT 50;
@@ -0,0 +1,6 @@
+-- Heikin-Ashi candle columns
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS ha_open Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ha_high Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ha_low Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ha_close Float64 DEFAULT 0;
@@ -0,0 +1,13 @@
+-- Seasonal (weekday x minute-of-day) volume baseline per instrument, estimated over a trailing month
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_volume_baseline
+(
+    instrument_uid String,
+    weekday UInt8,
+    minute_of_day UInt16,
+    mean Float64,
+    stddev Float64,
+    samples UInt32,
+    update_time DateTime
+)
+ENGINE = ReplacingMergeTree(update_time)
+ORDER BY (instrument_uid, weekday, minute_of_day);
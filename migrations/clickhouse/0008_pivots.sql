@@ -0,0 +1,12 @@
+-- Classic floor-trader pivots of the previous trading day and distance of close from them (%)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS pivot_p Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_r1 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_r2 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_r3 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_s1 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_s2 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_s3 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_dist_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_r1_dist_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS pivot_s1_dist_pct Float64 DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Adaptive (per-instrument EWMA) counterparts of rsi_zone, volume_anomaly and ma_cross
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS rsi_zone_adaptive Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS volume_anomaly_adaptive Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS ma_cross_adaptive Int8 DEFAULT 0;
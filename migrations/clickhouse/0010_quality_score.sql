@@ -0,0 +1,3 @@
+-- Data-quality score [0, 1] of the inputs behind each row
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS quality_score Float64 DEFAULT 0;
@@ -0,0 +1,10 @@
+-- Rolling 60/240-candle highs/lows and distance of close from them (%)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS high_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS low_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS high_240 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS low_240 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dist_high_60_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dist_low_60_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dist_high_240_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dist_low_240_pct Float64 DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Stale price flag (repeated close with zero volume) and length of the current stale run
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS stale_price Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS stale_count Int32 DEFAULT 0;
@@ -0,0 +1,5 @@
+-- TRIX(15) with EMA(9) signal line and cross flag
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS trix Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS trix_signal Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS trix_cross Int8 DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Ultimate Oscillator (7/14/28), 0-100 scale
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS ultimate_osc Float64 DEFAULT 50;
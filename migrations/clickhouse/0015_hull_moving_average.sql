@@ -0,0 +1,5 @@
+-- WMA / Hull MA (period from indicators_updater.hma_period) and HMA slope, % per candle
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS wma Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS hma Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS hma_slope Float64 DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Close z-score against the rolling mean/stddev of the last 50 closes
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS close_zscore_50 Float64 DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Stddev of 1-minute log returns over 30/60/240 candles
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS volatility_30 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS volatility_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS volatility_240 Float64 DEFAULT 0;
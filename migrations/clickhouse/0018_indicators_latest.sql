@@ -0,0 +1,6 @@
+-- Latest indicator row per instrument, replaced at the end of every calculation batch.
+-- Shares the column set of tinkoff_indicators_1min: later column migrations must alter both tables.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_latest
+    AS market_data.tinkoff_indicators_1min
+    ENGINE = ReplacingMergeTree(time)
+    ORDER BY instrument_uid;
@@ -0,0 +1,8 @@
+-- Gap to the previous candle: open vs previous close (%) and missing 1-minute candles
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS gap_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS gap_minutes Int32 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS gap_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS gap_minutes Int32 DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Backward log returns over 1/5/15/60 candles
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS log_return_1m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_5m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_15m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_60m Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS log_return_1m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_5m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_15m Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS log_return_60m Float64 DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Future price change (%) and signal for each horizon of indicators_updater.targets, in config order
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS target_change Array(Float64) DEFAULT [],
+    ADD COLUMN IF NOT EXISTS target_signal Array(Int8) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS target_change Array(Float64) DEFAULT [],
+    ADD COLUMN IF NOT EXISTS target_signal Array(Int8) DEFAULT [];
@@ -0,0 +1,8 @@
+-- Triple-barrier label (1 profit-take, -1 stop-loss, 0 time barrier/unknown) and barrier touch time
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS tb_label Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS tb_hit_time Int64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS tb_label Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS tb_hit_time Int64 DEFAULT 0;
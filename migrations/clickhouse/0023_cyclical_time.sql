@@ -0,0 +1,14 @@
+-- Cyclical time-of-day / day-of-week encodings and minutes since the session open
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS hour_sin Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS hour_cos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dow_sin Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dow_cos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS minutes_since_session_open Int32 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS hour_sin Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS hour_cos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dow_sin Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS dow_cos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS minutes_since_session_open Int32 DEFAULT 0;
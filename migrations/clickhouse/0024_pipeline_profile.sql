@@ -0,0 +1,18 @@
+-- Per-stage timings of every instrument in every updater run
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_pipeline_profile
+(
+    run_id String,
+    run_time DateTime,
+    instrument_uid String,
+    batches UInt32,
+    candles UInt64,
+    fetch_ms Float64,
+    compute_ms Float64,
+    insert_ms Float64,
+    status_ms Float64,
+    total_ms Float64
+)
+ENGINE = MergeTree
+PARTITION BY toYYYYMM(run_time)
+ORDER BY (instrument_uid, run_time)
+TTL run_time + INTERVAL 90 DAY;
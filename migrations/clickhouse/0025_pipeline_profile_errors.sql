@@ -0,0 +1,3 @@
+-- Failed inserts / label updates per instrument and run, input of the tuning advisor
+ALTER TABLE market_data.tinkoff_pipeline_profile
+    ADD COLUMN IF NOT EXISTS insert_errors UInt32 DEFAULT 0;
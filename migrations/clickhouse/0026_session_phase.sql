@@ -0,0 +1,6 @@
+-- Trading-session phase from the exchange calendar: 0 closed, 1 pre-market, 2 main, 3 evening
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS session_phase Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS session_phase Int8 DEFAULT 0;
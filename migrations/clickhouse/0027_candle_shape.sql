@@ -0,0 +1,12 @@
+-- High-low range in % of the open; body and upper/lower wick in % of the range
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS range_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS body_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS upper_wick_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS lower_wick_pct Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS range_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS body_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS upper_wick_pct Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS lower_wick_pct Float64 DEFAULT 0;
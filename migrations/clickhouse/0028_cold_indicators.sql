@@ -0,0 +1,13 @@
+-- Cold tier for indicators older than clickhouse.hot_days, meant for a slower storage policy
+-- (ALTER TABLE ... MODIFY SETTING storage_policy = '<cold policy>').
+-- Shares the column set of tinkoff_indicators_1min: later column migrations must alter
+-- tinkoff_indicators_1min, tinkoff_indicators_1min_cold and tinkoff_indicators_latest, and
+-- recreate tinkoff_indicators_all (CREATE OR REPLACE VIEW), whose columns are fixed at creation.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_1min_cold
+    AS market_data.tinkoff_indicators_1min;
+
+-- Read path of the API when the split is on
+CREATE VIEW IF NOT EXISTS market_data.tinkoff_indicators_all AS
+    SELECT * FROM market_data.tinkoff_indicators_1min
+    UNION ALL
+    SELECT * FROM market_data.tinkoff_indicators_1min_cold;
@@ -0,0 +1,12 @@
+-- VWMA(20) and volume relative to the time-of-day average from tinkoff_volume_baseline
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS vwma_20 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS rel_volume Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS vwma_20 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS rel_volume Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS vwma_20 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS rel_volume Float64 DEFAULT 0;
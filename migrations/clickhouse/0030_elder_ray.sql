@@ -0,0 +1,12 @@
+-- Elder-Ray Bull Power (high - EMA13) and Bear Power (low - EMA13)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS bull_power Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS bear_power Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS bull_power Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS bear_power Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS bull_power Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS bear_power Float64 DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Vortex indicator (14): VI+, VI- and their crossover flag
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS vortex_pos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_neg Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_cross Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS vortex_pos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_neg Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_cross Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS vortex_pos Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_neg Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS vortex_cross Int8 DEFAULT 0;
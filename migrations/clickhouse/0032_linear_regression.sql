@@ -0,0 +1,12 @@
+-- Rolling 30-candle linear regression of closes: slope (% of mean price per candle) and R²
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS linreg_slope_30 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS linreg_r2_30 Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS linreg_slope_30 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS linreg_r2_30 Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS linreg_slope_30 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS linreg_r2_30 Float64 DEFAULT 0;
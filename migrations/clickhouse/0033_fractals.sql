@@ -0,0 +1,12 @@
+-- Bill Williams fractal flags, set by the label backfill once the 2 following candles exist
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS fractal_high Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS fractal_low Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS fractal_high Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS fractal_low Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS fractal_high Int8 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS fractal_low Int8 DEFAULT 0;
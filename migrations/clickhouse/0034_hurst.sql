@@ -0,0 +1,9 @@
+-- Rolling Hurst exponent of log returns (R/S or DFA, indicators_updater.hurst)
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS hurst Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS hurst Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS hurst Float64 DEFAULT 0;
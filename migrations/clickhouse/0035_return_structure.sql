@@ -0,0 +1,12 @@
+-- Lag-1 autocorrelation of log returns and normalized sign entropy over 60 candles
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS return_autocorr_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS sign_entropy_60 Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS return_autocorr_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS sign_entropy_60 Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS return_autocorr_60 Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS sign_entropy_60 Float64 DEFAULT 0;
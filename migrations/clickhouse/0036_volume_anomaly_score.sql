@@ -0,0 +1,9 @@
+-- Graded volume anomaly: capped volume z-score above the configured threshold, 0 below
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS volume_anomaly_score Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS volume_anomaly_score Float64 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS volume_anomaly_score Float64 DEFAULT 0;
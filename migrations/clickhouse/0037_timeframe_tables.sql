@@ -0,0 +1,9 @@
+-- Indicator tables of the higher timeframes, same columns as the 1-minute table.
+-- Later column migrations must alter these tables as well.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_5min AS market_data.tinkoff_indicators_1min;
+
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_15min AS market_data.tinkoff_indicators_1min;
+
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_1hour AS market_data.tinkoff_indicators_1min;
+
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_1day AS market_data.tinkoff_indicators_1min;
@@ -0,0 +1,28 @@
+-- Higher-timeframe context on every row: EMA(20) slope and RSI(14) of the completed hourly closes
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    ADD COLUMN IF NOT EXISTS htf_1h_ema_slope Float64 DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS htf_1h_rsi Float64 DEFAULT 50;
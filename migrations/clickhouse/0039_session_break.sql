@@ -0,0 +1,21 @@
+-- First candle after a break longer than indicators_updater.gaps.max_gap_minutes
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    ADD COLUMN IF NOT EXISTS session_break Int8 DEFAULT 0;
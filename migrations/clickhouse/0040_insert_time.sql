@@ -0,0 +1,65 @@
+-- Time a row was written, the high-watermark of the incremental export (export.targets).
+-- Rows written before this migration are pinned to the epoch: the default is evaluated on read
+-- for parts without the column, so it is materialized before switching to now64().
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_1min
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_1min
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    ADD COLUMN IF NOT EXISTS insert_time DateTime64(3) DEFAULT toDateTime64(0, 3);
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    MATERIALIZE COLUMN insert_time SETTINGS mutations_sync = 2;
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    MODIFY COLUMN insert_time DateTime64(3) DEFAULT now64(3);
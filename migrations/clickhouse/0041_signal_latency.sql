@@ -0,0 +1,21 @@
+-- Stage timestamps (unix ms) of every published signal, from candle close to notification delivery
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_signal_latency
+(
+    trace_id String,
+    run_id String,
+    instrument_uid String,
+    kind LowCardinality(String),
+    candle_time DateTime,
+    candle_close_ms Int64,
+    fetched_ms Int64,
+    computed_ms Int64,
+    inserted_ms Int64,
+    emitted_ms Int64,
+    delivered_ms Int64, -- 0 when no channel accepted the notification
+    deliveries UInt32,
+    delivery_failures UInt32
+)
+ENGINE = MergeTree
+PARTITION BY toYYYYMM(candle_time)
+ORDER BY (instrument_uid, candle_time)
+TTL candle_time + INTERVAL 90 DAY;
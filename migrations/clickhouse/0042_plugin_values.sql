@@ -0,0 +1,21 @@
+-- Values of the custom indicator plugins of indicators_updater.plugins, in config order
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    ADD COLUMN IF NOT EXISTS plugin_values Array(Float64) DEFAULT [];
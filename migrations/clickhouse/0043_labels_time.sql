@@ -0,0 +1,23 @@
+-- Time the label columns of a row (price_change_15m, signal_15m, target_*, tb_*, fractal_*)
+-- were last filled, for reads with `as_of`. Rows written before this migration read it as their
+-- insert_time; the service writes both columns explicitly from now on.
+ALTER TABLE market_data.tinkoff_indicators_1min
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_1min_cold
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_latest
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_5min
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_15min
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_1hour
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
+
+ALTER TABLE market_data.tinkoff_indicators_1day
+    ADD COLUMN IF NOT EXISTS labels_time DateTime64(3) DEFAULT insert_time;
@@ -0,0 +1,6 @@
+-- A view keeps the column list it was created with: recreate the hot/cold union so it exposes
+-- the columns added since 0028. Later column migrations must end with this statement as well.
+CREATE OR REPLACE VIEW market_data.tinkoff_indicators_all AS
+    SELECT * FROM market_data.tinkoff_indicators_1min
+    UNION ALL
+    SELECT * FROM market_data.tinkoff_indicators_1min_cold;
@@ -0,0 +1,5 @@
+-- Day (UTC start, unix) a stored baseline is estimated as of, over the volume_baseline_days
+-- before it; rows of history only use the baseline of their own day. Rows stored before this
+-- migration have 0 and are re-estimated on the next run.
+ALTER TABLE market_data.tinkoff_volume_baseline
+    ADD COLUMN IF NOT EXISTS as_of Int64 DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Runtime overrides of feature flags declared in config ([feature_flags])
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_feature_flags (
+    name TEXT PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
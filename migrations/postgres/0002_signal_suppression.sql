@@ -0,0 +1,7 @@
+-- Instruments whose signals are not published (corporate actions, known bad data);
+-- indicators are still calculated for them
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_signal_suppression (
+    instrument_uid TEXT PRIMARY KEY,
+    reason TEXT NOT NULL DEFAULT '',
+    suppressed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Time of the last published signal per instrument and kind, for the notification cooldown
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_signal_cooldown (
+    instrument_uid TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    last_emitted_time BIGINT NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (instrument_uid, kind)
+);
@@ -0,0 +1,8 @@
+-- Last processed bar per instrument and higher timeframe
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_status_tf (
+    instrument_uid TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    last_processed_time BIGINT NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (instrument_uid, timeframe)
+);
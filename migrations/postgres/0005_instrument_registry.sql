@@ -0,0 +1,5 @@
+-- Instruments seen by the indicator pipeline; a UID missing here is announced as new
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_instrument_registry (
+    instrument_uid TEXT PRIMARY KEY,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- Serialized state of the rolling and recursive indicators after the last processed candle,
+-- so the next run continues them exactly instead of re-warming on a historical window
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicator_state (
+    instrument_uid TEXT PRIMARY KEY,
+    last_time BIGINT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    state JSONB NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
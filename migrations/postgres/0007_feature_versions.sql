@@ -0,0 +1,7 @@
+-- Parameter versions of the configurable indicator features the stored rows were computed
+-- with; a changed version schedules recomputation of that feature's columns
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_feature_versions (
+    feature TEXT PRIMARY KEY,
+    version TEXT NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,7 @@
+-- High-watermarks of the incremental export: insert_time (unix ms) of the newest ClickHouse
+-- indicator rows already shipped to each export target
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_export_watermarks (
+    target TEXT PRIMARY KEY,
+    watermark BIGINT NOT NULL,
+    update_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
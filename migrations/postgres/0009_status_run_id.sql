@@ -0,0 +1,6 @@
+-- Indicators run (UUID, also the run_id field of its log lines) that last advanced each status row
+ALTER TABLE market_data.tinkoff_indicators_status
+    ADD COLUMN IF NOT EXISTS run_id TEXT;
+
+ALTER TABLE market_data.tinkoff_indicators_status_tf
+    ADD COLUMN IF NOT EXISTS run_id TEXT;
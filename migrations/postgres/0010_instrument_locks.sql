@@ -0,0 +1,8 @@
+-- Job (indicators run ID) holding the advisory lock of each instrument, reported to contenders.
+-- The lock itself is pg_try_advisory_lock(classid, hashtext(instrument_uid)); a row outlives
+-- its lock and is overwritten by the next holder.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_locks (
+    instrument_uid TEXT PRIMARY KEY,
+    job_id TEXT NOT NULL,
+    lock_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
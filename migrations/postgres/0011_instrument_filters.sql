@@ -0,0 +1,9 @@
+-- Instruments allowed or denied for indicator processing on top of [instrument_filter] in config.
+-- With any allow entry only allowed instruments are processed; a deny entry always wins.
+-- Candle data is left untouched.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_instrument_filters (
+    instrument_uid TEXT PRIMARY KEY,
+    mode TEXT NOT NULL CHECK (mode IN ('allow', 'deny')),
+    reason TEXT NOT NULL DEFAULT '',
+    create_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
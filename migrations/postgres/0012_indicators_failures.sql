@@ -0,0 +1,17 @@
+-- Failed indicator runs per instrument (dead-letter tracking). Failures since the last
+-- successful run have no resolve_time; after [indicators_updater.dead_letter] max_failures
+-- of them the instrument is skipped for the cooldown.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_failures (
+    id BIGSERIAL PRIMARY KEY,
+    instrument_uid TEXT NOT NULL,
+    job_id TEXT NOT NULL,
+    error TEXT NOT NULL,
+    batch_from BIGINT NOT NULL,      -- last processed candle time when the batch started
+    batch_to BIGINT,                 -- latest candle time of the batch, NULL if it failed before fetching
+    failure_time TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolve_time TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS tinkoff_indicators_failures_open_idx
+    ON market_data.tinkoff_indicators_failures (instrument_uid, failure_time DESC)
+    WHERE resolve_time IS NULL;
@@ -0,0 +1,20 @@
+-- History of indicators runs (run_id as in the log lines and status rows). A run is inserted
+-- as running when it starts and completed or failed with its totals when it ends; a process
+-- killed mid-run leaves it running.
+CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_runs (
+    run_id TEXT PRIMARY KEY,
+    trigger TEXT NOT NULL,               -- scheduled or manual
+    cycle BIGINT NOT NULL,               -- scheduler cycle, 0 outside the schedule
+    status TEXT NOT NULL,                -- running, completed or failed
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ,
+    instruments BIGINT NOT NULL DEFAULT 0,          -- sources processed, failed ones included
+    instruments_failed BIGINT NOT NULL DEFAULT 0,
+    candles_processed BIGINT NOT NULL DEFAULT 0,    -- 1-minute candles read
+    rows_inserted BIGINT NOT NULL DEFAULT 0,        -- 1-minute indicator rows written
+    insert_errors BIGINT NOT NULL DEFAULT 0,
+    error TEXT                                      -- why a failed run stopped
+);
+
+CREATE INDEX IF NOT EXISTS tinkoff_indicators_runs_start_idx
+    ON market_data.tinkoff_indicators_runs (start_time DESC);
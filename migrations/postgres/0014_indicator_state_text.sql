@@ -0,0 +1,4 @@
+-- The indicator state is base64 of its bincode encoding, bit-exact for every float including
+-- NaN and infinities, which JSON can't carry. Saved JSON states no longer match the state
+-- version of their fingerprint and are replaced after one warm-up.
+ALTER TABLE market_data.tinkoff_indicator_state ALTER COLUMN state TYPE TEXT USING state::text;
@@ -0,0 +1,430 @@
+use axum::{
+    extract::{rejection::JsonRejection, Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::api::error::ApiError;
+use crate::api::query::{ApiQuery, Pagination};
+use crate::app_state::models::AppState;
+use crate::db::clickhouse::models::indicator::DbIndicator;
+use crate::db::postgres::models::indicator_failure::PgIndicatorFailure;
+use crate::db::postgres::models::instrument_filter::PgInstrumentFilter;
+use crate::db::postgres::models::signal_suppression::PgSignalSuppression;
+use crate::services::instrument_filter::InstrumentFilter;
+use crate::services::query_cache::QueryCache;
+use crate::services::sample_export;
+use crate::services::tuning::{PipelineTuning, Recommendation, TuningSettings};
+
+/// Runs analyzed when `runs` is not given
+const DEFAULT_TUNING_RUNS: usize = 20;
+const MAX_TUNING_RUNS: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct TuningParams {
+    pub runs: Option<usize>,
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TuningReport {
+    pub runs_analyzed: usize,
+    pub current: TuningSettings,
+    pub recommendations: Vec<Recommendation>,
+}
+
+/// Suggests updater settings from the recent pipeline profiles; `apply=true` switches
+/// the runtime-tunable ones from the next run on
+pub async fn tuning_recommendations(
+    Extension(app_state): Extension<Arc<AppState>>,
+    ApiQuery(params): ApiQuery<TuningParams>,
+) -> Result<Json<TuningReport>, ApiError> {
+    let runs = params.runs.unwrap_or(DEFAULT_TUNING_RUNS);
+    if runs == 0 || runs > MAX_TUNING_RUNS {
+        return Err(ApiError::bad_request(json!({
+            "runs": format!("must be between 1 and {}", MAX_TUNING_RUNS),
+        })));
+    }
+
+    let tuning = app_state
+        .service::<PipelineTuning>()
+        .ok_or_else(ApiError::service_unavailable)?;
+
+    let summaries = app_state
+        .clickhouse_service()
+        .repository_pipeline_profile
+        .get_run_summaries(runs)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch pipeline profiles: {}", e);
+            ApiError::internal()
+        })?;
+
+    let current = tuning.settings();
+    let mut recommendations = tuning.recommend(&summaries);
+
+    if params.apply {
+        for recommendation in &mut recommendations {
+            recommendation.applied = tuning.apply(recommendation);
+            if recommendation.applied {
+                info!(
+                    "Applied tuning recommendation {:?}: {} -> {}",
+                    recommendation.setting, recommendation.current, recommendation.recommended
+                );
+            }
+        }
+    }
+
+    Ok(Json(TuningReport {
+        runs_analyzed: summaries.len(),
+        current,
+        recommendations,
+    }))
+}
+
+/// Upper bound on instruments per sample export
+const MAX_SAMPLE_INSTRUMENTS: usize = 20;
+/// Upper bound on rows per sample export across all instruments
+const MAX_SAMPLE_ROWS: usize = 200_000;
+
+#[derive(Debug, Deserialize)]
+pub struct SampleExportRequest {
+    /// Instruments to export; a random sample of `instruments` of them when empty
+    #[serde(default)]
+    pub instrument_uids: Vec<String>,
+    #[serde(default)]
+    pub instruments: Option<usize>,
+    pub from: i64,
+    pub to: i64,
+    /// Multiplier applied to price-denominated columns
+    #[serde(default)]
+    pub price_scale: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SampleExport {
+    pub instruments: usize,
+    pub price_scaled: bool,
+    pub rows: Vec<DbIndicator>,
+}
+
+/// Exports indicator rows of a few instruments under random UIDs, optionally with scaled
+/// prices, for sharing bug reproductions outside the team. The alias mapping is only logged.
+pub async fn sample_export(
+    Extension(app_state): Extension<Arc<AppState>>,
+    request: Result<Json<SampleExportRequest>, JsonRejection>,
+) -> Result<Json<SampleExport>, ApiError> {
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+
+    if request.from > request.to {
+        return Err(ApiError::bad_request(json!({ "from": "must not be after `to`" })));
+    }
+    if request.price_scale.is_some_and(|scale| !scale.is_finite() || scale <= 0.0) {
+        return Err(ApiError::bad_request(json!({ "price_scale": "must be a positive number" })));
+    }
+
+    let repository = &app_state.clickhouse_service().repository_indicator;
+
+    let instrument_uids = if request.instrument_uids.is_empty() {
+        let count = request.instruments.unwrap_or(1);
+        if count == 0 || count > MAX_SAMPLE_INSTRUMENTS {
+            return Err(ApiError::bad_request(json!({
+                "instruments": format!("must be between 1 and {}", MAX_SAMPLE_INSTRUMENTS),
+            })));
+        }
+        let all = repository.get_all_instrument_uids().await.map_err(|e| {
+            error!("Failed to fetch instruments for sample export: {}", e);
+            ApiError::internal()
+        })?;
+        let all = InstrumentFilter::load(&app_state).await.apply(all);
+        sample_export::sample_instruments(all, count)
+    } else if request.instrument_uids.len() > MAX_SAMPLE_INSTRUMENTS {
+        return Err(ApiError::bad_request(json!({
+            "instrument_uids": format!("must contain at most {} instruments", MAX_SAMPLE_INSTRUMENTS),
+        })));
+    } else {
+        request.instrument_uids
+    };
+
+    let rows = repository
+        .get_indicators_multi(&instrument_uids, request.from, request.to, MAX_SAMPLE_ROWS + 1, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch indicators for sample export: {}", e);
+            ApiError::internal()
+        })?;
+
+    if rows.len() > MAX_SAMPLE_ROWS {
+        return Err(ApiError::bad_request(json!({
+            "range": format!("covers more than {} rows", MAX_SAMPLE_ROWS),
+        })));
+    }
+
+    let aliases = sample_export::scramble_uids(&instrument_uids);
+    for (uid, alias) in &aliases {
+        info!("Sample export alias {} -> {}", alias, uid);
+    }
+
+    let price_scale = request.price_scale.unwrap_or(1.0);
+    Ok(Json(SampleExport {
+        instruments: instrument_uids.len(),
+        price_scaled: price_scale != 1.0,
+        rows: sample_export::anonymize(rows, &aliases, price_scale),
+    }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SuppressionRequest {
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Lists instruments whose signals are currently not published
+pub async fn signal_suppressions(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<PgSignalSuppression>>, ApiError> {
+    let suppressions = app_state
+        .postgres_service()
+        .repository_signal_suppression
+        .get_suppressions()
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch signal suppressions: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(suppressions))
+}
+
+/// Stops publishing signals of an instrument; its indicators are still calculated
+pub async fn suppress_signals(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    request: Option<Json<SuppressionRequest>>,
+) -> Result<StatusCode, ApiError> {
+    let Json(request) = request.unwrap_or_default();
+
+    app_state
+        .postgres_service()
+        .repository_signal_suppression
+        .suppress(&instrument_uid, &request.reason)
+        .await
+        .map_err(|e| {
+            error!("Failed to suppress signals of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    invalidate_signals(&app_state, &instrument_uid);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Resumes publishing signals of an instrument
+pub async fn release_signals(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let released = app_state
+        .postgres_service()
+        .repository_signal_suppression
+        .release(&instrument_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to release signal suppression of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    if !released {
+        return Err(ApiError::not_found());
+    }
+
+    invalidate_signals(&app_state, &instrument_uid);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstrumentFilterRequest {
+    pub mode: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Stored allow/deny entries; the config lists are in `/api/version`
+pub async fn instrument_filters(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<PgInstrumentFilter>>, ApiError> {
+    let filters = app_state
+        .postgres_service()
+        .repository_instrument_filter
+        .get_instrument_filters()
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch instrument filters: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(filters))
+}
+
+/// Allows or denies an instrument from the next indicator run on
+pub async fn save_instrument_filter(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    request: Result<Json<InstrumentFilterRequest>, JsonRejection>,
+) -> Result<StatusCode, ApiError> {
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    if request.mode != "allow" && request.mode != "deny" {
+        return Err(ApiError::bad_request(json!({ "mode": "must be `allow` or `deny`" })));
+    }
+
+    let filter = PgInstrumentFilter {
+        instrument_uid,
+        mode: request.mode,
+        reason: request.reason,
+    };
+    app_state
+        .postgres_service()
+        .repository_instrument_filter
+        .save_instrument_filter(&filter)
+        .await
+        .map_err(|e| {
+            error!("Failed to save instrument filter of {}: {}", filter.instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Removes the stored entry of an instrument; config entries stay in effect
+pub async fn delete_instrument_filter(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let deleted = app_state
+        .postgres_service()
+        .repository_instrument_filter
+        .delete_instrument_filter(&instrument_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete instrument filter of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    if !deleted {
+        return Err(ApiError::not_found());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterParams {
+    /// Also list instruments below `dead_letter.max_failures`
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetter {
+    pub instrument_uid: String,
+    pub failures: i64,
+    pub first_failure_time: DateTime<Utc>,
+    pub last_failure_time: DateTime<Utc>,
+    pub last_error: String,
+    /// End of the cooldown while the instrument is skipped
+    pub skipped_until: Option<DateTime<Utc>>,
+}
+
+/// Instruments that failed `dead_letter.max_failures` runs in a row (any number with
+/// `all=true`), most failures first
+pub async fn dead_letters(
+    Extension(app_state): Extension<Arc<AppState>>,
+    ApiQuery(params): ApiQuery<DeadLetterParams>,
+) -> Result<Json<Vec<DeadLetter>>, ApiError> {
+    let failing = app_state
+        .postgres_service()
+        .repository_indicator_failure
+        .get_failing_instruments()
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch failing instruments: {}", e);
+            ApiError::internal()
+        })?;
+
+    let config = &app_state.settings.app_config.indicators_updater.dead_letter;
+    let now = Utc::now();
+    let dead_letters = failing
+        .into_iter()
+        .filter(|failing| params.all || failing.failures >= config.max_failures.max(1) as i64)
+        .map(|failing| {
+            let cooling_down =
+                config.is_cooling_down(failing.failures, failing.last_failure_time.timestamp(), now.timestamp());
+            DeadLetter {
+                skipped_until: cooling_down.then(|| failing.last_failure_time + config.cooldown),
+                instrument_uid: failing.instrument_uid,
+                failures: failing.failures,
+                first_failure_time: failing.first_failure_time,
+                last_failure_time: failing.last_failure_time,
+                last_error: failing.last_error,
+            }
+        })
+        .collect();
+
+    Ok(Json(dead_letters))
+}
+
+/// Failures of an instrument since its last successful run, newest first. `after` pages on to
+/// the failures before it.
+pub async fn instrument_failures(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    pagination: Pagination,
+) -> Result<Json<Vec<PgIndicatorFailure>>, ApiError> {
+    let failures = app_state
+        .postgres_service()
+        .repository_indicator_failure
+        .get_failures(&instrument_uid, pagination.after, pagination.limit as i64)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch failures of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(failures))
+}
+
+/// Closes the failures of an instrument once its cause is fixed, so the next run retries it
+pub async fn resolve_dead_letter(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let resolved = app_state
+        .postgres_service()
+        .repository_indicator_failure
+        .resolve_failures(&instrument_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to resolve failures of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    if resolved == 0 {
+        return Err(ApiError::not_found());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Cached annotations were computed under the previous suppression state
+fn invalidate_signals(app_state: &AppState, instrument_uid: &str) {
+    if let Some(cache) = app_state.service::<QueryCache>() {
+        cache.invalidate(instrument_uid);
+    }
+}
@@ -0,0 +1,22 @@
+use axum::http::header;
+use axum::response::{Html, IntoResponse};
+
+// Static assets of the admin UI, compiled into the binary; the page itself only calls
+// the /api/admin endpoints and /api/version
+const INDEX_HTML: &str = include_str!("admin_ui/index.html");
+const ADMIN_JS: &str = include_str!("admin_ui/admin.js");
+const ADMIN_CSS: &str = include_str!("admin_ui/admin.css");
+
+/// Admin UI for operators: signal suppressions, instrument filters, dead letters, tuning and the
+/// config-managed scheduler tiers and portfolios
+pub async fn admin_ui() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+pub async fn admin_js() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/javascript; charset=utf-8")], ADMIN_JS)
+}
+
+pub async fn admin_css() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/css; charset=utf-8")], ADMIN_CSS)
+}
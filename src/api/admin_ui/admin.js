@@ -0,0 +1,194 @@
+// Admin UI of t-indicators: a thin layer over /api/admin and /api/version.
+// Basic auth credentials entered at /admin are resent by the browser on these requests.
+"use strict";
+
+const message = document.getElementById("message");
+
+function showMessage(text, ok) {
+  message.textContent = text;
+  message.className = ok ? "ok" : "";
+  message.hidden = false;
+}
+
+async function api(method, path, body) {
+  const options = { method, headers: {} };
+  if (body !== undefined) {
+    options.headers["Content-Type"] = "application/json";
+    options.body = JSON.stringify(body);
+  }
+  const response = await fetch(path, options);
+  if (!response.ok) {
+    const error = await response.json().catch(() => ({}));
+    const details = error.details ? " " + JSON.stringify(error.details) : "";
+    throw new Error(`${method} ${path}: ${error.message || response.status}${details}`);
+  }
+  return response.status === 204 ? null : response.json();
+}
+
+function cell(text) {
+  const td = document.createElement("td");
+  td.textContent = text;
+  return td;
+}
+
+function row(values, action) {
+  const tr = document.createElement("tr");
+  values.forEach((value) => tr.appendChild(cell(value)));
+  if (action) {
+    const td = document.createElement("td");
+    const button = document.createElement("button");
+    button.textContent = action.label;
+    button.onclick = () => run(action.handler);
+    td.appendChild(button);
+    tr.appendChild(td);
+  }
+  return tr;
+}
+
+function fill(id, rows, empty) {
+  const target = document.getElementById(id);
+  target.replaceChildren(...rows);
+  if (rows.length === 0) {
+    const placeholder = document.createElement(target.tagName === "UL" ? "li" : "tr");
+    placeholder.className = "hint";
+    placeholder.textContent = empty;
+    target.appendChild(placeholder);
+  }
+}
+
+function item(text) {
+  const li = document.createElement("li");
+  li.textContent = text;
+  return li;
+}
+
+const path = (base, uid) => `${base}/${encodeURIComponent(uid)}`;
+
+async function loadSuppressions() {
+  const suppressions = await api("GET", "/api/admin/signal-suppressions");
+  fill(
+    "suppressions",
+    suppressions.map((s) =>
+      row([s.instrument_uid, s.reason, s.suppressed_at], {
+        label: "Release",
+        handler: async () => {
+          await api("DELETE", path("/api/admin/signal-suppressions", s.instrument_uid));
+          showMessage(`Released signals of ${s.instrument_uid}`, true);
+          await loadSuppressions();
+        },
+      })
+    ),
+    "No suppressed instruments"
+  );
+}
+
+async function loadFilters() {
+  const filters = await api("GET", "/api/admin/instrument-filters");
+  fill(
+    "filters",
+    filters.map((f) =>
+      row([f.instrument_uid, f.mode, f.reason], {
+        label: "Delete",
+        handler: async () => {
+          await api("DELETE", path("/api/admin/instrument-filters", f.instrument_uid));
+          showMessage(`Deleted the filter of ${f.instrument_uid}`, true);
+          await loadFilters();
+        },
+      })
+    ),
+    "No stored filters"
+  );
+}
+
+async function loadDeadLetters() {
+  const deadLetters = await api("GET", "/api/admin/dead-letters?all=true");
+  fill(
+    "dead-letters",
+    deadLetters.map((d) =>
+      row([d.instrument_uid, d.failures, d.last_failure_time, d.last_error, d.skipped_until || "-"], {
+        label: "Retry",
+        handler: async () => {
+          await api("DELETE", path("/api/admin/dead-letters", d.instrument_uid));
+          showMessage(`${d.instrument_uid} is retried on the next run`, true);
+          await loadDeadLetters();
+        },
+      })
+    ),
+    "No failing instruments"
+  );
+}
+
+async function loadTuning(apply) {
+  const report = await api("GET", `/api/admin/tuning-recommendations?apply=${apply}`);
+  fill(
+    "recommendations",
+    report.recommendations.map((r) =>
+      row([r.setting, r.current, r.recommended, r.reason, r.applied ? "yes" : "no"])
+    ),
+    `No recommendations from the last ${report.runs_analyzed} runs`
+  );
+}
+
+async function loadConfig() {
+  const info = await api("GET", "/api/version");
+  const config = info.config;
+  document.getElementById("version").textContent = `${info.version} (${info.environment})`;
+  document.getElementById("config-filters").textContent =
+    `Config lists: ${config.instrument_filter.allow} allowed, ${config.instrument_filter.deny} denied.`;
+  fill(
+    "tiers",
+    config.scheduler.tiers.map((t) => row([t.name, t.priority, t.every, t.instruments])),
+    "No tiers, all instruments every cycle"
+  );
+  fill("rules", config.notifications.rules.map(item), "No notification rules");
+  fill("portfolios", config.indicators.portfolios.map(item), "No portfolios");
+  fill(
+    "flags",
+    Object.entries(config.feature_flags.flags).map(([name, enabled]) => item(`${name}: ${enabled ? "on" : "off"}`)),
+    "No feature flags"
+  );
+}
+
+async function run(action) {
+  try {
+    await action();
+  } catch (error) {
+    showMessage(error.message, false);
+  }
+}
+
+document.getElementById("suppress-form").onsubmit = (event) => {
+  event.preventDefault();
+  const form = event.target;
+  run(async () => {
+    const uid = form.instrument_uid.value.trim();
+    await api("PUT", path("/api/admin/signal-suppressions", uid), { reason: form.reason.value });
+    showMessage(`Suppressed signals of ${uid}`, true);
+    form.reset();
+    await loadSuppressions();
+  });
+};
+
+document.getElementById("filter-form").onsubmit = (event) => {
+  event.preventDefault();
+  const form = event.target;
+  run(async () => {
+    const uid = form.instrument_uid.value.trim();
+    await api("PUT", path("/api/admin/instrument-filters", uid), { mode: form.mode.value, reason: form.reason.value });
+    showMessage(`Saved ${form.mode.value} filter of ${uid}`, true);
+    form.reset();
+    await loadFilters();
+  });
+};
+
+document.getElementById("apply-tuning").onclick = () =>
+  run(async () => {
+    await loadTuning(true);
+    showMessage("Applied the recommendations that could be applied", true);
+  });
+
+run(loadConfig);
+run(loadSuppressions);
+run(loadFilters);
+run(loadDeadLetters);
+run(() => loadTuning(false));
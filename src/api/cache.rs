@@ -0,0 +1,39 @@
+use axum::Json;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::app_state::models::AppState;
+use crate::services::query_cache::QueryCache;
+
+/// Serves a query result from `QueryCache`, computing and storing it on a miss.
+/// `instruments` are the instruments whose inserts invalidate the result; a result computed
+/// across such an insert is returned but not stored.
+pub async fn cached<T, F>(
+    app_state: &AppState,
+    key: String,
+    instruments: Vec<String>,
+    compute: F,
+) -> Result<Json<Value>, ApiError>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, ApiError>>,
+{
+    let cache = app_state.service::<QueryCache>();
+    if let Some(value) = cache.and_then(|cache| cache.get(&key)) {
+        return Ok(Json(Value::clone(&value)));
+    }
+    let generation = cache.map(|cache| cache.generation(&instruments));
+
+    let value = serde_json::to_value(compute.await?).map_err(|e| {
+        error!("Failed to serialize query result: {}", e);
+        ApiError::internal()
+    })?;
+
+    match cache.zip(generation) {
+        Some((cache, generation)) => Ok(Json(Value::clone(&cache.insert(key, instruments, generation, value)))),
+        None => Ok(Json(value)),
+    }
+}
@@ -0,0 +1,90 @@
+use axum::{
+    extract::{rejection::JsonRejection, Extension},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::api::query::Columns;
+use crate::app_state::models::AppState;
+use crate::db::clickhouse::models::indicator::{DbCandleConverted, DbIndicator};
+use crate::services::indicators::calculator::IndicatorCalculator;
+
+/// Upper bound on candles per request
+const MAX_COMPUTE_CANDLES: usize = 100_000;
+
+/// OHLCV candle supplied by the caller, `time` in unix seconds
+#[derive(Debug, Deserialize)]
+pub struct ComputeCandle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComputeRequest {
+    /// Echoed in the `instrument_uid` column of the rows
+    #[serde(default)]
+    pub instrument_uid: Option<String>,
+    pub candles: Vec<ComputeCandle>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+/// Calculates indicators of the supplied 1-minute candles with the pipeline's math, without
+/// reading or writing storage. Rows start with the first candle, as for a new instrument.
+pub async fn compute_indicators(
+    Extension(app_state): Extension<Arc<AppState>>,
+    request: Result<Json<ComputeRequest>, JsonRejection>,
+) -> Result<Json<Vec<Map<String, Value>>>, ApiError> {
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let columns = Columns::from_list(request.columns);
+    columns.validate::<DbIndicator>()?;
+
+    let calculator = IndicatorCalculator::new(app_state);
+    let min_candles = calculator.min_candles();
+    if request.candles.len() < min_candles || request.candles.len() > MAX_COMPUTE_CANDLES {
+        return Err(ApiError::bad_request(json!({
+            "candles": format!("must contain between {} and {} candles", min_candles, MAX_COMPUTE_CANDLES),
+        })));
+    }
+    if request.candles.windows(2).any(|pair| pair[0].time >= pair[1].time) {
+        return Err(ApiError::bad_request(json!({ "candles": "times must be strictly increasing" })));
+    }
+    if request.candles.iter().any(|candle| {
+        ![candle.open, candle.high, candle.low, candle.close].iter().all(|price| price.is_finite())
+    }) {
+        return Err(ApiError::bad_request(json!({ "candles": "prices must be finite numbers" })));
+    }
+
+    let instrument_uid = request.instrument_uid.unwrap_or_default();
+    let candles: Vec<DbCandleConverted> = request
+        .candles
+        .into_iter()
+        .map(|candle| DbCandleConverted {
+            instrument_uid: instrument_uid.clone(),
+            time: candle.time,
+            open_price: candle.open,
+            high_price: candle.high,
+            low_price: candle.low,
+            close_price: candle.close,
+            volume: candle.volume,
+        })
+        .collect();
+
+    // CPU-bound; keep it off the request workers
+    let rows = tokio::task::spawn_blocking(move || calculator.compute(&candles))
+        .await
+        .map_err(|e| {
+            error!("Ad-hoc indicator calculation failed: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(rows.iter().map(|row| columns.project(row)).collect()))
+}
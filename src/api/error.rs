@@ -0,0 +1,154 @@
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Header carrying the request id, set by `create_request_id` if the client didn't send one
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Stable machine-readable error codes, part of the API contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    Conflict,
+    ServiceUnavailable,
+    Internal,
+}
+
+/// Language of the human-readable `message`, picked from `Accept-Language`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Ru,
+}
+
+impl Lang {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let preferred = headers
+            .get("accept-language")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+
+        if preferred.starts_with("ru") {
+            Lang::Ru
+        } else {
+            Lang::En
+        }
+    }
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn message(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (ErrorCode::BadRequest, Lang::En) => "Invalid request parameters",
+            (ErrorCode::BadRequest, Lang::Ru) => "Некорректные параметры запроса",
+            (ErrorCode::Unauthorized, Lang::En) => "Authentication required",
+            (ErrorCode::Unauthorized, Lang::Ru) => "Требуется авторизация",
+            (ErrorCode::NotFound, Lang::En) => "Resource not found",
+            (ErrorCode::NotFound, Lang::Ru) => "Ресурс не найден",
+            (ErrorCode::Conflict, Lang::En) => "Resource is busy, retry later",
+            (ErrorCode::Conflict, Lang::Ru) => "Ресурс занят, повторите позже",
+            (ErrorCode::ServiceUnavailable, Lang::En) => "Service is temporarily unavailable",
+            (ErrorCode::ServiceUnavailable, Lang::Ru) => "Сервис временно недоступен",
+            (ErrorCode::Internal, Lang::En) => "Internal server error",
+            (ErrorCode::Internal, Lang::Ru) => "Внутренняя ошибка сервера",
+        }
+    }
+}
+
+/// Error returned by API handlers.
+///
+/// Rendered as `{code, message, details, request_id}`; `render_api_errors` fills in the
+/// request id and localizes the message once the handler has returned.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub details: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: &'static str,
+    pub details: Option<Value>,
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode) -> Self {
+        Self { code, details: None }
+    }
+
+    pub fn bad_request(details: impl Into<Value>) -> Self {
+        Self::new(ErrorCode::BadRequest).with_details(details)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(ErrorCode::Unauthorized)
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(ErrorCode::NotFound)
+    }
+
+    pub fn conflict(details: impl Into<Value>) -> Self {
+        Self::new(ErrorCode::Conflict).with_details(details)
+    }
+
+    pub fn service_unavailable() -> Self {
+        Self::new(ErrorCode::ServiceUnavailable)
+    }
+
+    pub fn internal() -> Self {
+        Self::new(ErrorCode::Internal)
+    }
+
+    pub fn with_details(mut self, details: impl Into<Value>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn to_response(&self, lang: Lang, request_id: Option<String>) -> Response {
+        let body = ErrorBody {
+            code: self.code,
+            message: self.code.message(lang),
+            details: self.details.clone(),
+            request_id,
+        };
+
+        let mut response = (self.code.status(), Json(body)).into_response();
+        response.extensions_mut().insert(self.clone());
+        response
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        self.to_response(Lang::En, None)
+    }
+}
+
+/// Fallback for unknown routes
+pub async fn not_found() -> ApiError {
+    ApiError::not_found()
+}
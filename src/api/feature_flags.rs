@@ -0,0 +1,17 @@
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+use crate::app_state::models::AppState;
+use crate::services::feature_flags::{FeatureFlagSnapshot, FeatureFlags};
+
+/// Returns the feature flags as evaluated for this request
+pub async fn feature_flags(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Json<FeatureFlagSnapshot> {
+    let snapshot = match app_state.service::<FeatureFlags>() {
+        Some(flags) => flags.snapshot().await,
+        None => FeatureFlagSnapshot::default(),
+    };
+
+    Json(snapshot)
+}
@@ -1,18 +1,24 @@
 use axum::{extract::Extension, http::StatusCode};
+use serde_json::json;
 use std::sync::Arc;
 
+use crate::api::error::ApiError;
 use crate::app_state::models::AppState;
 
 pub async fn health_db(
     Extension(app_state): Extension<Arc<AppState>>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     // Check ClickHouse connection
-    let client = app_state.clickhouse_service.connection.get_client();
-    let clickhouse_ok = client.query("SELECT 1").execute().await.is_ok();
+    let clickhouse_ok = app_state
+        .clickhouse_service()
+        .repository_indicator
+        .ping()
+        .await
+        .is_ok();
 
     // Check PostgreSQL connection
     let pg_health_check = app_state
-        .postgres_service
+        .postgres_service()
         .repository_health_check
         .check()
         .await
@@ -22,6 +28,9 @@ pub async fn health_db(
     if clickhouse_ok && pg_health_check {
         Ok(StatusCode::OK)
     } else {
-        Err(StatusCode::INTERNAL_SERVER_ERROR)
+        Err(ApiError::service_unavailable().with_details(json!({
+            "clickhouse": clickhouse_ok,
+            "postgres": pg_health_check,
+        })))
     }
 }
@@ -0,0 +1,415 @@
+use axum::{
+    extract::{rejection::JsonRejection, Extension, Path},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::api::cache::cached;
+use crate::api::error::ApiError;
+use crate::api::query::{Aggregation, ApiQuery, AsOf, Columns, Page, Pagination, Sort};
+use crate::app_state::models::AppState;
+use crate::db::clickhouse::models::column_stats::{DbColumnStats, STATS_QUANTILES};
+use crate::db::clickhouse::models::indicator::DbIndicator;
+use crate::services::query_cache::ALL_INSTRUMENTS;
+
+/// Returns calculated indicators of one instrument, paginated by time; with `as_of`, as a live
+/// reader saw them at that processing time
+pub async fn indicators(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    pagination: Pagination,
+    sort: Sort,
+    columns: Columns,
+    as_of: AsOf,
+) -> Result<Json<Page<Map<String, Value>>>, ApiError> {
+    columns.validate::<DbIndicator>()?;
+
+    let as_of = as_of.millis();
+    let rows = app_state
+        .clickhouse_service()
+        .repository_indicator
+        .get_indicators_page(&instrument_uid, pagination.after, sort.order, pagination.limit, as_of)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch indicators for {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    let page = Page::new(rows, &pagination, |row| row.time).map(|row| match as_of {
+        Some(as_of) => columns.project(&row.seen_at(as_of)),
+        None => columns.project(&row),
+    });
+
+    Ok(Json(page))
+}
+
+/// Returns the latest indicator row of every instrument
+pub async fn latest_indicators(
+    Extension(app_state): Extension<Arc<AppState>>,
+    columns: Columns,
+) -> Result<Json<Value>, ApiError> {
+    columns.validate::<DbIndicator>()?;
+
+    let key = format!("latest:{}", columns.cache_key());
+    cached(&app_state, key, vec![ALL_INSTRUMENTS.to_string()], async {
+        let rows = app_state
+            .clickhouse_service()
+            .repository_indicator
+            .get_latest_indicators()
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch latest indicators: {}", e);
+                ApiError::internal()
+            })?;
+
+        Ok(rows.iter().map(|row| columns.project(row)).collect::<Vec<_>>())
+    })
+    .await
+}
+
+/// Upper bound on instruments per bulk query
+const MAX_QUERY_INSTRUMENTS: usize = 200;
+/// Upper bound on rows per bulk query across all instruments
+const MAX_QUERY_ROWS: usize = 200_000;
+
+/// Native resolution of the indicators table
+const RAW_RESOLUTION_SECONDS: i64 = 60;
+/// Upper bound on points per series; longer ranges are downsampled to stay under it
+const MAX_POINTS_PER_SERIES: i64 = 2000;
+/// Bucket sizes used for downsampling, seconds
+const RESOLUTIONS_SECONDS: [i64; 8] = [60, 300, 900, 1800, 3600, 14_400, 86_400, 604_800];
+
+#[derive(Debug, Deserialize)]
+pub struct IndicatorsQueryRequest {
+    pub instrument_uids: Vec<String>,
+    pub from: i64,
+    pub to: i64,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Requested bucket size; raised automatically if the range would exceed the point limit
+    #[serde(default)]
+    pub resolution_seconds: Option<i64>,
+    #[serde(default)]
+    pub aggregation: Aggregation,
+    /// Processing time to read at, unix seconds; native resolution only
+    #[serde(default)]
+    pub as_of: Option<i64>,
+}
+
+/// Time series of one instrument, one array per column
+#[derive(Debug, Serialize)]
+pub struct ColumnarSeries {
+    pub instrument_uid: String,
+    pub columns: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnarResponse {
+    /// Effective bucket size of the returned series
+    pub resolution_seconds: i64,
+    /// Aggregation applied to float columns, `None` at native resolution
+    pub aggregation: Option<Aggregation>,
+    pub series: Vec<ColumnarSeries>,
+}
+
+/// Smallest standard bucket that is at least the requested one and keeps every series under
+/// `MAX_POINTS_PER_SERIES` points
+fn effective_resolution(request: &IndicatorsQueryRequest) -> i64 {
+    let requested = request.resolution_seconds.unwrap_or(RAW_RESOLUTION_SECONDS);
+    let span = request.to.saturating_sub(request.from);
+
+    RESOLUTIONS_SECONDS
+        .iter()
+        .copied()
+        .find(|&bucket| bucket >= requested && span / bucket < MAX_POINTS_PER_SERIES)
+        .unwrap_or(RESOLUTIONS_SECONDS[RESOLUTIONS_SECONDS.len() - 1])
+}
+
+/// Requested indicator columns with their aggregation. Only float columns can be averaged,
+/// the rest (flags, counters, arrays) always take the last value of the bucket.
+fn aggregated_columns(columns: &Columns, aggregation: Aggregation) -> Vec<(String, Aggregation)> {
+    let Ok(Value::Object(fields)) = serde_json::to_value(DbIndicator::default()) else {
+        return Vec::new();
+    };
+
+    fields
+        .into_iter()
+        .filter(|(name, _)| name != "instrument_uid" && name != "time" && columns.includes(name))
+        .map(|(name, value)| {
+            let is_float = matches!(&value, Value::Number(n) if n.is_f64());
+            let aggregation = if is_float { aggregation } else { Aggregation::Last };
+            (name, aggregation)
+        })
+        .collect()
+}
+
+/// Returns indicators of several instruments over a time range in columnar form
+pub async fn query_indicators(
+    Extension(app_state): Extension<Arc<AppState>>,
+    request: Result<Json<IndicatorsQueryRequest>, JsonRejection>,
+) -> Result<Json<Value>, ApiError> {
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+
+    if request.instrument_uids.is_empty() || request.instrument_uids.len() > MAX_QUERY_INSTRUMENTS {
+        return Err(ApiError::bad_request(json!({
+            "instrument_uids": format!("must contain between 1 and {} instruments", MAX_QUERY_INSTRUMENTS),
+        })));
+    }
+    if request.from >= request.to {
+        return Err(ApiError::bad_request(json!({ "from": "must be before `to`" })));
+    }
+
+    let columns = Columns::from_list(request.columns.clone());
+    columns.validate::<DbIndicator>()?;
+
+    let max_resolution = RESOLUTIONS_SECONDS[RESOLUTIONS_SECONDS.len() - 1];
+    if request.resolution_seconds.is_some_and(|resolution| resolution <= 0 || resolution > max_resolution) {
+        return Err(ApiError::bad_request(json!({
+            "resolution_seconds": format!("must be between 1 and {}", max_resolution),
+        })));
+    }
+    // Buckets aggregate in ClickHouse, past the per-row labels of `DbIndicator::seen_at`
+    if request.as_of.is_some() && effective_resolution(&request) != RAW_RESOLUTION_SECONDS {
+        return Err(ApiError::bad_request(json!({
+            "as_of": "needs the native 60 s resolution, narrow the range or drop resolution_seconds",
+        })));
+    }
+
+    let key = format!(
+        "query:{}:{}:{}:{}:{}:{:?}:{:?}",
+        request.instrument_uids.join(","),
+        request.from,
+        request.to,
+        columns.cache_key(),
+        effective_resolution(&request),
+        request.aggregation,
+        request.as_of
+    );
+    cached(&app_state, key, request.instrument_uids.clone(), async {
+        query_columnar(&app_state, &request, &columns).await
+    })
+    .await
+}
+
+async fn query_columnar(
+    app_state: &AppState,
+    request: &IndicatorsQueryRequest,
+    columns: &Columns,
+) -> Result<ColumnarResponse, ApiError> {
+    let repository = &app_state.clickhouse_service().repository_indicator;
+    let resolution_seconds = effective_resolution(request);
+
+    let (rows, aggregation) = if resolution_seconds == RAW_RESOLUTION_SECONDS {
+        let as_of = AsOf(request.as_of).millis();
+        let rows = repository
+            .get_indicators_multi(&request.instrument_uids, request.from, request.to, MAX_QUERY_ROWS + 1, as_of)
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| {
+                        let row = match as_of {
+                            Some(as_of) => row.seen_at(as_of),
+                            None => row,
+                        };
+                        (row.instrument_uid.clone(), columns.project(&row))
+                    })
+                    .collect::<Vec<_>>()
+            });
+        (rows, None)
+    } else {
+        let aggregated = aggregated_columns(columns, request.aggregation);
+        let rows = repository
+            .get_indicators_downsampled(
+                &request.instrument_uids,
+                request.from,
+                request.to,
+                resolution_seconds,
+                &aggregated,
+                MAX_QUERY_ROWS + 1,
+            )
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| {
+                        let uid = row.get("instrument_uid").and_then(Value::as_str).unwrap_or_default().to_string();
+                        (uid, columns.project(&row))
+                    })
+                    .collect::<Vec<_>>()
+            });
+        (rows, Some(request.aggregation))
+    };
+
+    let rows = rows.map_err(|e| {
+        error!("Failed to run bulk indicators query: {}", e);
+        ApiError::internal()
+    })?;
+
+    // Keep ClickHouse reads bounded: ask for a narrower query instead of truncating silently
+    if rows.len() > MAX_QUERY_ROWS {
+        return Err(ApiError::bad_request(json!({
+            "range": format!("covers more than {} rows", MAX_QUERY_ROWS),
+        })));
+    }
+
+    // Rows come ordered by instrument, so each series is a contiguous run
+    let mut series: Vec<ColumnarSeries> = Vec::new();
+    for (instrument_uid, row) in rows {
+        if series.last().is_none_or(|last| last.instrument_uid != instrument_uid) {
+            series.push(ColumnarSeries {
+                instrument_uid,
+                columns: Map::new(),
+            });
+        }
+        let Some(current) = series.last_mut() else {
+            continue;
+        };
+
+        for (name, value) in row {
+            if let Value::Array(values) = current.columns.entry(name).or_insert_with(|| Value::Array(Vec::new())) {
+                values.push(value);
+            }
+        }
+    }
+
+    Ok(ColumnarResponse {
+        resolution_seconds,
+        aggregation,
+        series,
+    })
+}
+
+/// Histogram buckets of the column statistics by default and at most
+const DEFAULT_HISTOGRAM_BUCKETS: usize = 20;
+const MAX_HISTOGRAM_BUCKETS: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ColumnStatsParams {
+    pub column: String,
+    pub from: i64,
+    pub to: i64,
+    #[serde(default)]
+    pub buckets: Option<usize>,
+}
+
+/// Value of an extreme and the first time it occurred
+#[derive(Debug, Serialize)]
+pub struct Extreme {
+    pub value: f64,
+    pub time: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: u64,
+}
+
+/// Distribution of one column; everything but `count` is empty without finite values
+#[derive(Debug, Serialize)]
+pub struct ColumnStats {
+    pub instrument_uid: String,
+    pub column: String,
+    pub from: i64,
+    pub to: i64,
+    pub count: u64,
+    pub min: Option<Extreme>,
+    pub max: Option<Extreme>,
+    pub mean: Option<f64>,
+    pub stddev: Option<f64>,
+    /// `p1`, `p5`, `p25`, `p50`, `p75`, `p95`, `p99`
+    pub quantiles: BTreeMap<String, f64>,
+    pub histogram: Vec<HistogramBucket>,
+}
+
+impl ColumnStats {
+    fn new(instrument_uid: &str, params: &ColumnStatsParams, stats: Option<DbColumnStats>) -> Self {
+        let mut result = Self {
+            instrument_uid: instrument_uid.to_string(),
+            column: params.column.clone(),
+            from: params.from,
+            to: params.to,
+            count: 0,
+            min: None,
+            max: None,
+            mean: None,
+            stddev: None,
+            quantiles: BTreeMap::new(),
+            histogram: Vec::new(),
+        };
+        let Some(stats) = stats else {
+            return result;
+        };
+
+        let width = (stats.max - stats.min) / stats.histogram.len() as f64;
+        result.count = stats.count;
+        result.min = Some(Extreme { value: stats.min, time: stats.min_time });
+        result.max = Some(Extreme { value: stats.max, time: stats.max_time });
+        result.mean = Some(stats.mean);
+        result.stddev = Some(stats.stddev);
+        result.quantiles = STATS_QUANTILES
+            .iter()
+            .zip(stats.quantiles)
+            .map(|(level, value)| (format!("p{}", (level * 100.0).round()), value))
+            .collect();
+        result.histogram = stats
+            .histogram
+            .iter()
+            .enumerate()
+            .map(|(index, &count)| HistogramBucket {
+                lower: stats.min + width * index as f64,
+                upper: stats.min + width * (index + 1) as f64,
+                count,
+            })
+            .collect();
+        result
+    }
+}
+
+/// Numeric scalar columns of the indicators table; strings and arrays have no distribution
+fn is_numeric_column(column: &str) -> bool {
+    let Ok(Value::Object(fields)) = serde_json::to_value(DbIndicator::default()) else {
+        return false;
+    };
+    column != "time" && matches!(fields.get(column), Some(Value::Number(_)))
+}
+
+/// Returns quantiles, a histogram and the extremes of one indicator column in `[from, to]`
+pub async fn column_stats(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    ApiQuery(params): ApiQuery<ColumnStatsParams>,
+) -> Result<Json<Value>, ApiError> {
+    if !is_numeric_column(&params.column) {
+        return Err(ApiError::bad_request(json!({ "column": "must be a numeric indicator column" })));
+    }
+    if params.from > params.to {
+        return Err(ApiError::bad_request(json!({ "from": "must not be after `to`" })));
+    }
+    let buckets = params.buckets.unwrap_or(DEFAULT_HISTOGRAM_BUCKETS);
+    if buckets == 0 || buckets > MAX_HISTOGRAM_BUCKETS {
+        return Err(ApiError::bad_request(json!({
+            "buckets": format!("must be between 1 and {}", MAX_HISTOGRAM_BUCKETS),
+        })));
+    }
+
+    let key = format!("stats:{}:{}:{}:{}:{}", instrument_uid, params.column, params.from, params.to, buckets);
+    cached(&app_state, key, vec![instrument_uid.clone()], async {
+        let stats = app_state
+            .clickhouse_service()
+            .repository_indicator
+            .get_column_stats(&instrument_uid, &params.column, params.from, params.to, buckets)
+            .await
+            .map_err(|e| {
+                error!("Failed to compute stats of {} for {}: {}", params.column, instrument_uid, e);
+                ApiError::internal()
+            })?;
+
+        Ok(ColumnStats::new(&instrument_uid, &params, stats))
+    })
+    .await
+}
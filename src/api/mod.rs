@@ -1,5 +1,35 @@
+pub mod admin;
+pub mod admin_ui;
+pub mod cache;
+pub mod compute;
+pub mod error;
+pub mod feature_flags;
 pub mod health_api;
 pub mod health_db;
+pub mod indicators;
+pub mod openapi;
+pub mod query;
+pub mod recalculate;
+pub mod runs;
+pub mod signals;
+pub mod status;
+pub mod version;
 
+pub use admin::{
+    dead_letters, delete_instrument_filter, instrument_failures, instrument_filters, release_signals,
+    resolve_dead_letter, sample_export, save_instrument_filter, signal_suppressions, suppress_signals,
+    tuning_recommendations,
+};
+pub use admin_ui::{admin_css, admin_js, admin_ui};
+pub use compute::compute_indicators;
+pub use error::not_found;
+pub use feature_flags::feature_flags;
 pub use health_api::health_api;
 pub use health_db::health_db;
+pub use indicators::{column_stats, indicators, latest_indicators, query_indicators};
+pub use openapi::openapi;
+pub use recalculate::recalculate;
+pub use runs::runs;
+pub use signals::{signal_annotations, signal_counts, signal_latency};
+pub use status::status;
+pub use version::version;
@@ -0,0 +1,9 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+
+/// OpenAPI description of this API, the source of truth for `t-indicators-client`
+const OPENAPI_SPEC: &str = include_str!("../../openapi/openapi.json");
+
+pub async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_SPEC)
+}
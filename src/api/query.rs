@@ -0,0 +1,251 @@
+//! Query-string conventions shared by the read APIs:
+//! `?after=<time>&limit=<n>` for cursor pagination on `time`, `?order=asc|desc` for sorting,
+//! `?columns=a,b,c` for projection and `?as_of=<time>` for the rows as they were processed by
+//! then.
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::api::error::ApiError;
+
+/// Page size used when `limit` is not given
+pub const DEFAULT_PAGE_LIMIT: usize = 500;
+/// Upper bound for `limit`, keeps ClickHouse queries bounded
+pub const MAX_PAGE_LIMIT: usize = 5000;
+
+fn parse_query<T: DeserializeOwned>(parts: &Parts) -> Result<T, ApiError> {
+    Query::<T>::try_from_uri(&parts.uri)
+        .map(|Query(params)| params)
+        .map_err(|e| ApiError::bad_request(e.body_text()))
+}
+
+/// `Query` that rejects with a structured `ApiError` instead of a plain-text 400
+#[derive(Debug, Clone)]
+pub struct ApiQuery<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequestParts<S> for ApiQuery<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parse_query(parts).map(ApiQuery)
+    }
+}
+
+/// Cursor pagination on `time`: rows strictly after (or, for `order=desc`, before) `after`
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub after: Option<i64>,
+    pub limit: usize,
+}
+
+#[derive(Deserialize)]
+struct PaginationParams {
+    after: Option<i64>,
+    limit: Option<usize>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let params: PaginationParams = parse_query(parts)?;
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+
+        if limit == 0 || limit > MAX_PAGE_LIMIT {
+            return Err(ApiError::bad_request(json!({
+                "limit": format!("must be between 1 and {}", MAX_PAGE_LIMIT),
+            })));
+        }
+
+        Ok(Self {
+            after: params.after,
+            limit,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// How a column is reduced to one value per time bucket when downsampling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    Avg,
+    #[default]
+    Last,
+}
+
+/// Sort direction on `time`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sort {
+    pub order: SortOrder,
+}
+
+#[derive(Deserialize)]
+struct SortParams {
+    #[serde(default)]
+    order: SortOrder,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Sort {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let params: SortParams = parse_query(parts)?;
+        Ok(Self {
+            order: params.order,
+        })
+    }
+}
+
+/// Processing time to read indicators at, unix seconds: rows written later are left out and
+/// labels filled in later read as not yet known
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsOf(pub Option<i64>);
+
+#[derive(Deserialize)]
+struct AsOfParams {
+    as_of: Option<i64>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AsOf {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let params: AsOfParams = parse_query(parts)?;
+        Ok(Self(params.as_of))
+    }
+}
+
+impl AsOf {
+    /// Bound on the stored write times (unix ms), the whole `as_of` second included
+    pub fn millis(self) -> Option<i64> {
+        self.0.map(|seconds| seconds * 1000 + 999)
+    }
+}
+
+/// Column projection; all columns when `columns` is absent
+#[derive(Debug, Clone, Default)]
+pub struct Columns(Option<Vec<String>>);
+
+#[derive(Deserialize)]
+struct ColumnsParams {
+    columns: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Columns {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let params: ColumnsParams = parse_query(parts)?;
+        let columns = params.columns.map(|columns| {
+            columns
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .filter(|column| !column.is_empty())
+                .collect()
+        });
+        Ok(Self(columns))
+    }
+}
+
+impl Columns {
+    /// Projection from an explicit list, all columns if the list is empty
+    pub fn from_list(columns: Vec<String>) -> Self {
+        if columns.is_empty() {
+            Self(None)
+        } else {
+            Self(Some(columns))
+        }
+    }
+
+    /// Stable representation for cache keys
+    pub fn cache_key(&self) -> String {
+        match &self.0 {
+            Some(columns) => columns.join(","),
+            None => "*".to_string(),
+        }
+    }
+
+    pub fn includes(&self, column: &str) -> bool {
+        match &self.0 {
+            Some(columns) => columns.iter().any(|c| c == column),
+            None => true,
+        }
+    }
+
+    /// Rejects columns that `T` doesn't have
+    pub fn validate<T: Serialize + Default>(&self) -> Result<(), ApiError> {
+        let Some(columns) = &self.0 else {
+            return Ok(());
+        };
+
+        let known = match serde_json::to_value(T::default()) {
+            Ok(Value::Object(fields)) => fields,
+            _ => return Err(ApiError::internal()),
+        };
+        let unknown: Vec<&String> = columns.iter().filter(|c| !known.contains_key(*c)).collect();
+
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::bad_request(json!({ "unknown_columns": unknown })))
+        }
+    }
+
+    /// Serializes a row keeping only the requested columns
+    pub fn project<T: Serialize>(&self, row: &T) -> Map<String, Value> {
+        let mut fields = match serde_json::to_value(row) {
+            Ok(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        if let Some(columns) = &self.0 {
+            fields.retain(|name, _| columns.contains(name));
+        }
+        fields
+    }
+}
+
+/// One page of results with the cursor for the next one (`None` on the last page)
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from at most `pagination.limit` rows, `time_of` gives each row's cursor
+    pub fn new(items: Vec<T>, pagination: &Pagination, time_of: impl Fn(&T) -> i64) -> Self {
+        let next_cursor = if items.len() >= pagination.limit {
+            items.last().map(time_of)
+        } else {
+            None
+        };
+        Self { items, next_cursor }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{error, info};
+
+use crate::api::error::ApiError;
+use crate::api::query::ApiQuery;
+use crate::app_state::models::AppState;
+use crate::services::indicators::calculator::{IndicatorCalculator, InstrumentRecalcError, RecalcRange};
+use crate::services::query_cache::QueryCache;
+
+#[derive(Debug, Deserialize)]
+pub struct RecalculateParams {
+    pub from: Option<i64>, // Строки после from удаляются и считаются заново, unix секунды
+    pub to: Option<i64>,   // Последняя свеча пересчёта, unix секунды
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecalculateResponse {
+    pub instrument_uid: String,
+    pub job_id: String,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub candles_processed: usize,
+    pub duration_ms: u64,
+}
+
+/// Deletes the rows of one instrument (spread, portfolio) after `from`, moves its status back
+/// and recalculates it up to `to`, within the request. 409 while another job holds the
+/// instrument; the next run continues after `to`.
+pub async fn recalculate(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    ApiQuery(params): ApiQuery<RecalculateParams>,
+) -> Result<Json<RecalculateResponse>, ApiError> {
+    if params.from.zip(params.to).is_some_and(|(from, to)| from >= to) {
+        return Err(ApiError::bad_request(json!({ "from": "must be before to" })));
+    }
+
+    let calculator = IndicatorCalculator::new(app_state.clone()).with_recalc(RecalcRange {
+        instrument_uids: vec![instrument_uid.clone()],
+        from: params.from,
+        to: params.to,
+    });
+    let job_id = calculator.run_id().to_string();
+    info!("Recalculating {} as job {}: from {:?} to {:?}", instrument_uid, job_id, params.from, params.to);
+
+    let started = Instant::now();
+    let candles_processed = calculator.recalculate_instrument(&instrument_uid).await.map_err(|e| match e {
+        InstrumentRecalcError::NotFound => ApiError::not_found(),
+        InstrumentRecalcError::Held { job_id } => ApiError::conflict(json!({
+            "instrument_uid": instrument_uid,
+            "job_id": job_id,
+        })),
+        InstrumentRecalcError::Failed(e) => {
+            error!("Failed to recalculate {}: {}", instrument_uid, e);
+            ApiError::internal()
+        }
+    })?;
+
+    // Cached responses hold the rows before the rewrite
+    if let Some(cache) = app_state.service::<QueryCache>() {
+        cache.invalidate(&instrument_uid);
+    }
+
+    Ok(Json(RecalculateResponse {
+        instrument_uid,
+        job_id,
+        from: params.from,
+        to: params.to,
+        candles_processed,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }))
+}
@@ -0,0 +1,27 @@
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::api::query::Pagination;
+use crate::app_state::models::AppState;
+use crate::db::postgres::models::indicator_run::PgIndicatorRun;
+
+/// History of indicators runs with their totals, newest first; a running one has no end time.
+/// `after` pages on to the runs started before it.
+pub async fn runs(
+    Extension(app_state): Extension<Arc<AppState>>,
+    pagination: Pagination,
+) -> Result<Json<Vec<PgIndicatorRun>>, ApiError> {
+    let runs = app_state
+        .postgres_service()
+        .repository_indicator_run
+        .get_runs(pagination.after, pagination.limit as i64)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch indicators runs: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(runs))
+}
@@ -0,0 +1,306 @@
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::api::cache::cached;
+use crate::api::error::ApiError;
+use crate::api::query::{ApiQuery, MAX_PAGE_LIMIT};
+use crate::app_state::models::AppState;
+use crate::db::clickhouse::models::signal_counts::DbSignalCounts;
+use crate::db::clickhouse::models::signal_latency::DbStageLatency;
+use crate::services::query_cache::ALL_INSTRUMENTS;
+use crate::services::signals::{detect_signals, Severity, SignalCooldown, SignalEvent, SignalKind};
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotationRange {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// Signal event formatted for a chart overlay
+#[derive(Debug, Serialize)]
+pub struct Annotation {
+    pub time: i64,
+    pub label: &'static str,
+    pub color: &'static str,
+    pub direction: i8,
+    pub kind: SignalKind,
+    pub score: f64,
+    pub severity: Severity,
+}
+
+impl Annotation {
+    fn new(event: SignalEvent) -> Self {
+        let SignalEvent { time, kind, score, severity } = event;
+        let label = match kind {
+            SignalKind::GoldenCross => "Golden cross",
+            SignalKind::DeathCross => "Death cross",
+            SignalKind::RsiOversold => "RSI oversold",
+            SignalKind::RsiOverbought => "RSI overbought",
+        };
+        let color = if kind.direction() > 0 { "#26a69a" } else { "#ef5350" };
+
+        Self {
+            time,
+            label,
+            color,
+            direction: kind.direction(),
+            kind,
+            score,
+            severity,
+        }
+    }
+}
+
+/// Returns signal events of one instrument in `[from, to]` as chart annotations
+pub async fn signal_annotations(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(instrument_uid): Path<String>,
+    ApiQuery(range): ApiQuery<AnnotationRange>,
+) -> Result<Json<Value>, ApiError> {
+    if range.from > range.to {
+        return Err(ApiError::bad_request(json!({ "from": "must not be after `to`" })));
+    }
+
+    let key = format!("annotations:{}:{}:{}", instrument_uid, range.from, range.to);
+    cached(&app_state, key, vec![instrument_uid.clone()], async {
+        compute_annotations(&app_state, &instrument_uid, &range).await
+    })
+    .await
+}
+
+async fn compute_annotations(
+    app_state: &AppState,
+    instrument_uid: &str,
+    range: &AnnotationRange,
+) -> Result<Vec<Annotation>, ApiError> {
+    // Suppressed instruments publish no signals; the admin endpoints invalidate this result
+    let suppressed = app_state
+        .postgres_service()
+        .repository_signal_suppression
+        .is_suppressed(instrument_uid)
+        .await
+        .map_err(|e| {
+            error!("Failed to check signal suppression of {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+    if suppressed {
+        return Ok(Vec::new());
+    }
+
+    let rows = app_state
+        .clickhouse_service()
+        .repository_indicator
+        .get_indicators_range(instrument_uid, range.from, range.to, MAX_PAGE_LIMIT + 1)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch indicators for {}: {}", instrument_uid, e);
+            ApiError::internal()
+        })?;
+
+    // Keep ClickHouse reads bounded: ask for a narrower range instead of truncating silently
+    if rows.len() > MAX_PAGE_LIMIT {
+        return Err(ApiError::bad_request(json!({
+            "range": format!("covers more than {} candles", MAX_PAGE_LIMIT),
+        })));
+    }
+
+    // Repeats within the cooldown are dropped, as in notifications
+    let cooldown = SignalCooldown::new(&app_state.settings.app_config.signals);
+    let events = cooldown.apply(detect_signals(&rows), &mut HashMap::new());
+
+    Ok(events.into_iter().map(Annotation::new).collect())
+}
+
+/// Upper bound on intervals per counts request
+const MAX_COUNT_BUCKETS: i64 = 2000;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl CountInterval {
+    fn seconds(self) -> i64 {
+        match self {
+            CountInterval::Hour => 3600,
+            CountInterval::Day => 86_400,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignalCountsParams {
+    pub from: i64,
+    pub to: i64,
+    #[serde(default)]
+    pub interval: CountInterval,
+    /// Portfolio name from `indicators_updater.portfolios`; every instrument when absent
+    #[serde(default)]
+    pub watchlist: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignalCounts {
+    pub interval: CountInterval,
+    pub from: i64,
+    pub to: i64,
+    pub watchlist: Option<String>,
+    pub totals: HashMap<&'static str, u64>,
+    pub buckets: Vec<DbSignalCounts>,
+}
+
+/// Returns the number of signal events of each kind per hour or day across all instruments
+/// or a watchlist. Suppressed instruments are left out; repeats within the cooldown are not.
+pub async fn signal_counts(
+    Extension(app_state): Extension<Arc<AppState>>,
+    ApiQuery(params): ApiQuery<SignalCountsParams>,
+) -> Result<Json<Value>, ApiError> {
+    if params.from > params.to {
+        return Err(ApiError::bad_request(json!({ "from": "must not be after `to`" })));
+    }
+    if (params.to - params.from) / params.interval.seconds() >= MAX_COUNT_BUCKETS {
+        return Err(ApiError::bad_request(json!({
+            "range": format!("covers more than {} intervals", MAX_COUNT_BUCKETS),
+        })));
+    }
+    let instrument_uids = match &params.watchlist {
+        Some(name) => {
+            let portfolios = &app_state.settings.app_config.indicators_updater.portfolios;
+            let Some(portfolio) = portfolios.iter().find(|portfolio| &portfolio.name == name) else {
+                return Err(ApiError::bad_request(json!({ "watchlist": format!("unknown watchlist {}", name) })));
+            };
+            Some(portfolio.members.iter().map(|member| member.instrument_uid.clone()).collect::<Vec<_>>())
+        }
+        None => None,
+    };
+
+    let key = format!(
+        "signal-counts:{}:{}:{:?}:{}",
+        params.from,
+        params.to,
+        params.interval,
+        params.watchlist.as_deref().unwrap_or("*")
+    );
+    cached(&app_state, key, vec![ALL_INSTRUMENTS.to_string()], async {
+        let suppressed: Vec<String> = app_state
+            .postgres_service()
+            .repository_signal_suppression
+            .get_suppressions()
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch signal suppressions: {}", e);
+                ApiError::internal()
+            })?
+            .into_iter()
+            .map(|suppression| suppression.instrument_uid)
+            .collect();
+
+        let buckets = app_state
+            .clickhouse_service()
+            .repository_indicator
+            .get_signal_counts(
+                instrument_uids.as_deref(),
+                &suppressed,
+                params.from,
+                params.to,
+                params.interval.seconds(),
+            )
+            .await
+            .map_err(|e| {
+                error!("Failed to count signals: {}", e);
+                ApiError::internal()
+            })?;
+
+        let mut totals: HashMap<&'static str, u64> = HashMap::new();
+        for bucket in &buckets {
+            for (kind, count) in [
+                (SignalKind::GoldenCross, bucket.golden_cross),
+                (SignalKind::DeathCross, bucket.death_cross),
+                (SignalKind::RsiOversold, bucket.rsi_oversold),
+                (SignalKind::RsiOverbought, bucket.rsi_overbought),
+            ] {
+                *totals.entry(kind.name()).or_default() += count;
+            }
+        }
+
+        Ok(SignalCounts {
+            interval: params.interval,
+            from: params.from,
+            to: params.to,
+            watchlist: params.watchlist.clone(),
+            totals,
+            buckets,
+        })
+    })
+    .await
+}
+
+/// Latency of one stage of the signal path, ms; quantiles are `None` without signals
+#[derive(Debug, Serialize)]
+pub struct StageLatency {
+    pub stage: String,
+    pub count: u64,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+impl StageLatency {
+    fn new(stage: DbStageLatency) -> Self {
+        // Quantiles come at LATENCY_QUANTILES
+        let quantile = |index: usize| stage.quantiles_ms.get(index).copied();
+        Self {
+            p50_ms: quantile(0),
+            p90_ms: quantile(1),
+            p99_ms: quantile(2),
+            max_ms: quantile(3),
+            count: stage.count,
+            stage: stage.stage,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignalLatency {
+    pub from: i64,
+    pub to: i64,
+    pub stages: Vec<StageLatency>,
+}
+
+/// Returns the latency distribution of published signals of candles in `[from, to]` per stage:
+/// candle close to fetch, compute, insert, emission, delivery, and close to delivery in total
+pub async fn signal_latency(
+    Extension(app_state): Extension<Arc<AppState>>,
+    ApiQuery(range): ApiQuery<AnnotationRange>,
+) -> Result<Json<SignalLatency>, ApiError> {
+    if range.from > range.to {
+        return Err(ApiError::bad_request(json!({ "from": "must not be after `to`" })));
+    }
+
+    let stages = app_state
+        .clickhouse_service()
+        .repository_signal_latency
+        .get_stage_latencies(range.from, range.to)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch signal latency: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(SignalLatency {
+        from: range.from,
+        to: range.to,
+        stages: stages.into_iter().map(StageLatency::new).collect(),
+    }))
+}
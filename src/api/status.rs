@@ -0,0 +1,224 @@
+use axum::{
+    extract::Extension,
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::app_state::models::AppState;
+use crate::services::indicators::session::{ExchangeCalendar, SessionPhase};
+
+/// Overall pipeline health as shown on the status page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Green,
+    Yellow,
+    Red,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub status: Health,
+    pub checked_at: i64,
+    pub components: Components,
+    pub freshness: Freshness,
+    pub suppressed_instruments: usize,
+    pub maintenance: Vec<Maintenance>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Components {
+    pub clickhouse: bool,
+    pub postgres: bool,
+}
+
+/// Age of the newest indicator rows; lag only counts while the exchange trades
+#[derive(Debug, Serialize)]
+pub struct Freshness {
+    pub market_open: bool,
+    pub latest_time: Option<i64>,
+    pub lag_seconds: Option<i64>,
+    pub instruments: usize,
+    pub stale_instruments: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Maintenance {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub description: String,
+    pub active: bool,
+}
+
+/// Unauthenticated pipeline status: JSON by default, an HTML page for browsers
+/// (`Accept: text/html`). Failing checks turn the status red instead of failing the request.
+pub async fn status(Extension(app_state): Extension<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let report = build_report(&app_state).await;
+
+    let wants_html = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    match wants_html {
+        true => Html(render_html(&report)).into_response(),
+        false => Json(report).into_response(),
+    }
+}
+
+async fn build_report(app_state: &AppState) -> StatusReport {
+    let config = &app_state.settings.app_config;
+    let now = Utc::now();
+    let clickhouse = app_state.clickhouse_service();
+    let postgres = app_state.postgres_service();
+
+    let postgres_ok = postgres.repository_health_check.check().await.is_ok();
+    let latest = match clickhouse.repository_indicator.get_latest_indicators().await {
+        Ok(rows) => Some(rows),
+        Err(e) => {
+            error!("Status: failed to fetch latest indicators: {}", e);
+            None
+        }
+    };
+    let suppressed_instruments = match postgres.repository_signal_suppression.get_suppressions().await {
+        Ok(suppressions) => suppressions.len(),
+        Err(e) => {
+            error!("Status: failed to fetch signal suppressions: {}", e);
+            0
+        }
+    };
+
+    let calendar = ExchangeCalendar::new(&config.indicators_updater.calendar);
+    let market_open = calendar.phase(now.timestamp()) != SessionPhase::Closed;
+    let rows = latest.as_deref().unwrap_or_default();
+    let latest_time = rows.iter().map(|row| row.time).max();
+    let lag_seconds = latest_time.map(|time| now.timestamp() - time);
+    let degraded_lag_seconds = config.status.degraded_lag.as_secs() as i64;
+    let down_lag_seconds = config.status.down_lag.as_secs() as i64;
+    let stale_instruments = rows
+        .iter()
+        .filter(|row| now.timestamp() - row.time > degraded_lag_seconds)
+        .count();
+
+    let maintenance: Vec<Maintenance> = config
+        .status
+        .maintenance
+        .iter()
+        .filter(|window| window.end > now)
+        .map(|window| Maintenance {
+            start: window.start,
+            end: window.end,
+            description: window.description.clone(),
+            active: window.start <= now,
+        })
+        .collect();
+
+    let mut status = Health::Green;
+    if market_open && stale_instruments > 0 {
+        status = Health::Yellow;
+    }
+    if maintenance.iter().any(|window| window.active) {
+        status = status.max(Health::Yellow);
+    }
+    if market_open {
+        match lag_seconds {
+            Some(lag) if lag > down_lag_seconds => status = Health::Red,
+            Some(lag) if lag > degraded_lag_seconds => status = status.max(Health::Yellow),
+            Some(_) => {}
+            None => status = Health::Red,
+        }
+    }
+    if latest.is_none() || !postgres_ok {
+        status = Health::Red;
+    }
+
+    StatusReport {
+        status,
+        checked_at: now.timestamp(),
+        components: Components {
+            clickhouse: latest.is_some(),
+            postgres: postgres_ok,
+        },
+        freshness: Freshness {
+            market_open,
+            latest_time,
+            lag_seconds,
+            instruments: rows.len(),
+            stale_instruments,
+        },
+        suppressed_instruments,
+        maintenance,
+    }
+}
+
+fn render_html(report: &StatusReport) -> String {
+    let (color, title) = match report.status {
+        Health::Green => ("#26a69a", "All systems operational"),
+        Health::Yellow => ("#f9a825", "Degraded"),
+        Health::Red => ("#ef5350", "Outage"),
+    };
+    let up = |ok: bool| if ok { "up" } else { "down" };
+    let latest = report
+        .freshness
+        .latest_time
+        .and_then(|time| DateTime::<Utc>::from_timestamp(time, 0))
+        .map_or("-".to_string(), |time| time.to_rfc3339());
+    let lag = report.freshness.lag_seconds.map_or("-".to_string(), |lag| format!("{} s", lag));
+
+    let maintenance: String = report
+        .maintenance
+        .iter()
+        .map(|window| {
+            format!(
+                "<li>{} &ndash; {}{}: {}</li>",
+                window.start.to_rfc3339(),
+                window.end.to_rfc3339(),
+                if window.active { " (in progress)" } else { "" },
+                escape_html(&window.description)
+            )
+        })
+        .collect();
+    let maintenance = match maintenance.is_empty() {
+        true => "<p>No planned maintenance</p>".to_string(),
+        false => format!("<ul>{}</ul>", maintenance),
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>t-indicators status</title></head>
+<body style="font-family: sans-serif">
+<h1 style="color: {color}">{title}</h1>
+<table>
+<tr><td>ClickHouse</td><td>{clickhouse}</td></tr>
+<tr><td>PostgreSQL</td><td>{postgres}</td></tr>
+<tr><td>Market</td><td>{market}</td></tr>
+<tr><td>Latest candle</td><td>{latest}</td></tr>
+<tr><td>Lag</td><td>{lag}</td></tr>
+<tr><td>Instruments</td><td>{instruments} ({stale} stale)</td></tr>
+<tr><td>Suppressed signals</td><td>{suppressed}</td></tr>
+</table>
+<h2>Maintenance</h2>
+{maintenance}
+</body>
+</html>
+"#,
+        clickhouse = up(report.components.clickhouse),
+        postgres = up(report.components.postgres),
+        market = if report.freshness.market_open { "open" } else { "closed" },
+        instruments = report.freshness.instruments,
+        stale = report.freshness.stale_instruments,
+        suppressed = report.suppressed_instruments,
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
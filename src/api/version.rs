@@ -0,0 +1,10 @@
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+use crate::app_state::models::AppState;
+use crate::services::config_summary::VersionInfo;
+
+/// Returns the build version and the effective configuration summary
+pub async fn version(Extension(app_state): Extension<Arc<AppState>>) -> Json<VersionInfo> {
+    Json(VersionInfo::collect(&app_state).await)
+}
@@ -3,27 +3,62 @@ use crate::db::postgres::postgres_service::PostgresService;
 // src/app_state/mod.rs
 use crate::env_config::models::app_setting::AppSettings;
 
+use super::registry::ServiceRegistry;
 use std::sync::Arc;
 
 pub struct AppState {
     pub settings: Arc<AppSettings>,
-    pub clickhouse_service: Arc<ClickhouseService>,
-    pub postgres_service: Arc<PostgresService>,
-
+    services: ServiceRegistry,
 }
 
 impl AppState {
-    pub fn new(
-        settings: Arc<AppSettings>,
-        clickhouse_service: Arc<ClickhouseService>,
-        postgres_service: Arc<PostgresService>,
-
-    ) -> Self {
-        Self {
+    pub fn builder(settings: Arc<AppSettings>) -> AppStateBuilder {
+        AppStateBuilder {
             settings,
-            clickhouse_service,
-            postgres_service,
+            services: ServiceRegistry::default(),
+        }
+    }
 
+    /// Looks up a registered service by type (concrete type or `dyn Trait`)
+    pub fn service<T: ?Sized + Send + Sync + 'static>(&self) -> Option<&Arc<T>> {
+        self.services.get::<T>()
+    }
+
+    pub fn clickhouse_service(&self) -> &ClickhouseService {
+        self.service::<ClickhouseService>()
+            .expect("ClickhouseService is required by AppStateBuilder")
+    }
+
+    pub fn postgres_service(&self) -> &PostgresService {
+        self.service::<PostgresService>()
+            .expect("PostgresService is required by AppStateBuilder")
+    }
+}
+
+/// Builder collecting services before `AppState` is shared
+pub struct AppStateBuilder {
+    settings: Arc<AppSettings>,
+    services: ServiceRegistry,
+}
+
+impl AppStateBuilder {
+    pub fn with_service<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// Builds the state, failing if a required core service is missing
+    pub fn build(self) -> Result<AppState, String> {
+        if !self.services.contains::<ClickhouseService>() {
+            return Err("ClickhouseService is not registered".to_string());
         }
+        if !self.services.contains::<PostgresService>() {
+            return Err("PostgresService is not registered".to_string());
+        }
+
+        Ok(AppState {
+            settings: self.settings,
+            services: self.services,
+        })
     }
 }
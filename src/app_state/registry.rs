@@ -0,0 +1,32 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Typed registry of shared services.
+///
+/// Services are keyed by their type, which may be a trait object (`dyn Trait`), so a
+/// subsystem can be registered and looked up without adding a field to `AppState`.
+/// The registry is filled once by `AppStateBuilder` and read-only afterwards, which
+/// makes it safe to share across tasks without locking.
+#[derive(Default)]
+pub struct ServiceRegistry {
+    services: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl ServiceRegistry {
+    /// Registers a service, replacing any previous service of the same type
+    pub fn insert<T: ?Sized + Send + Sync + 'static>(&mut self, service: Arc<T>) {
+        self.services.insert(TypeId::of::<T>(), Box::new(service));
+    }
+
+    /// Looks up a service by type
+    pub fn get<T: ?Sized + Send + Sync + 'static>(&self) -> Option<&Arc<T>> {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<Arc<T>>())
+    }
+
+    pub fn contains<T: ?Sized + Send + Sync + 'static>(&self) -> bool {
+        self.services.contains_key(&TypeId::of::<T>())
+    }
+}
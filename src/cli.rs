@@ -0,0 +1,170 @@
+// File: src/cli.rs
+//! Command line of the service. No subcommand (or `serve`) runs the HTTP server and the
+//! updater; the others run one operational task and exit, so they can be driven from
+//! Kubernetes Jobs and cron without an HTTP round-trip.
+use crate::services::indicators::calculator::RecalcRange;
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(name = "t-indicators", version, about = "Technical indicators of Tinkoff candles")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, PartialEq, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server and the scheduled updates (default)
+    Serve,
+    /// Run one indicators update of a range and exit
+    Recalc(RecalcArgs),
+    /// Catch up instruments far behind the latest candles, ignoring the backfill window and budget
+    Backfill(BackfillArgs),
+    /// Check the config of ENV (or --config) without connecting to the databases
+    ValidateConfig(ValidateConfigArgs),
+    /// Apply the ClickHouse and PostgreSQL migrations
+    Migrate(MigrateArgs),
+    /// Run the incremental export to every [export] target once
+    Export,
+    /// Run synthetic candles through the pipeline against an in-memory embedded store
+    /// (build with --features embedded) and write PATH.json and PATH.md
+    Bench(BenchArgs),
+}
+
+#[derive(Debug, Default, PartialEq, Args)]
+pub struct RecalcArgs {
+    /// Instrument or spread to recalculate, repeatable; all by default
+    #[arg(long = "instrument", value_name = "UID")]
+    pub instruments: Vec<String>,
+    /// Delete the rows after this time (unix seconds) and calculate them again
+    #[arg(long, value_name = "TS", value_parser = positive::<i64>)]
+    pub from: Option<i64>,
+    /// Last candle to process (unix seconds); later ones are left for the next update
+    #[arg(long, value_name = "TS", value_parser = positive::<i64>)]
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Default, PartialEq, Args)]
+pub struct BackfillArgs {
+    /// Instrument or spread to catch up, repeatable; all by default
+    #[arg(long = "instrument", value_name = "UID")]
+    pub instruments: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Args)]
+pub struct ValidateConfigArgs {
+    /// Config file to check instead of config/<ENV>.toml
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Args)]
+pub struct MigrateArgs {
+    /// Directory with the clickhouse/ and postgres/ migrations
+    #[arg(long, value_name = "PATH", default_value = "migrations")]
+    pub dir: String,
+    /// Record the pending migrations as applied without running them, for databases migrated
+    /// by hand
+    #[arg(long)]
+    pub baseline: bool,
+}
+
+#[derive(Debug, PartialEq, Args)]
+pub struct BenchArgs {
+    /// Synthetic instruments
+    #[arg(long, value_name = "N", default_value_t = 10, value_parser = positive::<usize>)]
+    pub instruments: usize,
+    /// Days of 1-minute candles per instrument
+    #[arg(long, value_name = "M", default_value_t = 5, value_parser = positive::<u32>)]
+    pub days: u32,
+    /// Report path without extension: PATH.json and PATH.md
+    #[arg(long, value_name = "PATH", default_value = "bench-report")]
+    pub output: String,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        Self {
+            instruments: 10,
+            days: 5,
+            output: "bench-report".to_string(),
+        }
+    }
+}
+
+impl RecalcArgs {
+    pub fn range(&self) -> Result<RecalcRange, String> {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) if from >= to => Err(format!("--from ({}) must be before --to ({})", from, to)),
+            _ => Ok(RecalcRange {
+                instrument_uids: self.instruments.clone(),
+                from: self.from,
+                to: self.to,
+            }),
+        }
+    }
+}
+
+fn positive<T: std::str::FromStr + PartialOrd + Default>(value: &str) -> Result<T, String> {
+    match value.parse::<T>() {
+        Ok(parsed) if parsed > T::default() => Ok(parsed),
+        _ => Err(format!("expected a positive number, got '{}'", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, clap::Error> {
+        Cli::try_parse_from(std::iter::once("t-indicators").chain(args.iter().copied())).map(|cli| cli.command)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(parse(&["serve"]).unwrap(), Some(Command::Serve));
+        assert_eq!(parse(&["export"]).unwrap(), Some(Command::Export));
+        assert_eq!(parse(&["bench"]).unwrap(), Some(Command::Bench(BenchArgs::default())));
+        assert_eq!(
+            parse(&["bench", "--instruments", "50", "--days", "30", "--output", "out/main"]).unwrap(),
+            Some(Command::Bench(BenchArgs { instruments: 50, days: 30, output: "out/main".to_string() }))
+        );
+        assert_eq!(
+            parse(&["migrate"]).unwrap(),
+            Some(Command::Migrate(MigrateArgs { dir: "migrations".to_string(), baseline: false }))
+        );
+        assert_eq!(
+            parse(&["validate-config", "--config", "config/prod.toml"]).unwrap(),
+            Some(Command::ValidateConfig(ValidateConfigArgs { config: Some("config/prod.toml".to_string()) }))
+        );
+        assert_eq!(
+            parse(&["backfill", "--instrument", "a"]).unwrap(),
+            Some(Command::Backfill(BackfillArgs { instruments: vec!["a".to_string()] }))
+        );
+
+        let Some(Command::Recalc(recalc)) =
+            parse(&["recalc", "--instrument", "a", "--instrument", "b", "--from", "1700000000", "--to", "1700086400"])
+                .unwrap()
+        else {
+            panic!("recalc expected");
+        };
+        assert_eq!(
+            recalc.range(),
+            Ok(RecalcRange {
+                instrument_uids: vec!["a".to_string(), "b".to_string()],
+                from: Some(1_700_000_000),
+                to: Some(1_700_086_400),
+            })
+        );
+        let reversed = RecalcArgs { from: Some(1_700_086_400), to: Some(1_700_000_000), ..Default::default() };
+        assert!(reversed.range().is_err());
+
+        assert!(parse(&["recalc", "--from", "yesterday"]).is_err());
+        assert!(parse(&["bench", "--days", "0"]).is_err());
+        assert!(parse(&["bench", "--instruments"]).is_err());
+        assert!(parse(&["bench", "--threads", "4"]).is_err());
+        assert!(parse(&["serve", "now"]).is_err());
+        assert!(parse(&["run"]).is_err());
+    }
+}
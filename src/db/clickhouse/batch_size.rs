@@ -0,0 +1,96 @@
+// File: src/db/clickhouse/batch_size.rs
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Rows a candle select returns at most, whatever limit it is asked for. A batch shorter than
+/// the limit it was fetched with ends the series only when that limit is within this cap.
+pub const MAX_SELECT_ROWS: usize = 10_000;
+
+/// Successful batches in a row after which a reduced size grows back by a quarter
+const GROW_AFTER: usize = 10;
+
+/// ClickHouse errors a smaller batch avoids: the query ran out of memory, or the table has
+/// too many unmerged parts
+pub fn is_resource_error(error: &impl Display) -> bool {
+    let message = error.to_string();
+    message.contains("MEMORY_LIMIT_EXCEEDED") || message.contains("TOO_MANY_PARTS")
+}
+
+/// Batch size that halves on resource errors, so the failed batch can be retried smaller
+/// instead of skipped, and grows back gradually while batches succeed
+pub struct AdaptiveBatchSize {
+    current: AtomicUsize,
+    successes: AtomicUsize,
+    min: usize,
+    max: usize,
+}
+
+impl AdaptiveBatchSize {
+    pub fn new(max: usize, min: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            current: AtomicUsize::new(max),
+            successes: AtomicUsize::new(0),
+            min: min.clamp(1, max),
+            max,
+        }
+    }
+
+    pub fn get(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Records a successful batch, growing the size by a quarter (up to the maximum) after
+    /// `GROW_AFTER` of them in a row
+    pub fn record_success(&self) {
+        if self.successes.fetch_add(1, Ordering::Relaxed) + 1 < GROW_AFTER {
+            return;
+        }
+        self.successes.store(0, Ordering::Relaxed);
+        let _ = self.current.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some((current + (current / 4).max(1)).min(self.max))
+        });
+    }
+
+    /// Halves the size after a resource error; false when it is already at the minimum and
+    /// the batch can't be retried smaller
+    pub fn shrink(&self) -> bool {
+        self.successes.store(0, Ordering::Relaxed);
+        self.current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                (current > self.min).then(|| (current / 2).max(self.min))
+            })
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_batch_size() {
+        let size = AdaptiveBatchSize::new(1000, 200);
+        assert!(size.shrink());
+        assert_eq!(size.get(), 500);
+        assert!(size.shrink());
+        assert!(size.shrink());
+        assert_eq!(size.get(), 200);
+        // At the minimum the error is final
+        assert!(!size.shrink());
+
+        for _ in 0..GROW_AFTER - 1 {
+            size.record_success();
+        }
+        assert_eq!(size.get(), 200);
+        size.record_success();
+        assert_eq!(size.get(), 250);
+        for _ in 0..GROW_AFTER * 20 {
+            size.record_success();
+        }
+        assert_eq!(size.get(), 1000);
+
+        assert!(is_resource_error(&"Code: 241. DB::Exception: MEMORY_LIMIT_EXCEEDED"));
+        assert!(!is_resource_error(&"Code: 60. DB::Exception: UNKNOWN_TABLE"));
+    }
+}
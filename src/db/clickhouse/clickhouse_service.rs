@@ -1,14 +1,22 @@
 use crate::db::clickhouse::connection::ClickhouseConnection;
-use crate::db::clickhouse::repository::indicator_repository::IndicatorRepository;
+use crate::db::clickhouse::repository::indicator_repository::{IndicatorRepository, TraitIndicatorRepository};
+use crate::db::clickhouse::repository::pipeline_profile_repository::{
+    PipelineProfileRepository, TraitPipelineProfileRepository,
+};
+use crate::db::clickhouse::repository::signal_latency_repository::{
+    SignalLatencyRepository, TraitSignalLatencyRepository,
+};
+#[cfg(feature = "embedded")]
+use crate::db::embedded::store::EmbeddedStore;
 use crate::env_config::models::app_setting::AppSettings;
 use std::sync::Arc;
 use tracing::{error, info};
 
 pub struct ClickhouseService {
-    // Соединения
-    pub connection: Arc<ClickhouseConnection>,
     // Аналитические репозитории (ClickHouse)
-    pub repository_indicator: Arc<IndicatorRepository>,
+    pub repository_indicator: Arc<dyn TraitIndicatorRepository + Send + Sync>,
+    pub repository_pipeline_profile: Arc<dyn TraitPipelineProfileRepository + Send + Sync>,
+    pub repository_signal_latency: Arc<dyn TraitSignalLatencyRepository + Send + Sync>,
 }
 
 impl ClickhouseService {
@@ -33,14 +41,35 @@ impl ClickhouseService {
         
         let indicator_repository = Arc::new(IndicatorRepository::new(
             clickhouse_connection.clone(),
-        ));
+            settings.app_config.clickhouse.hot_days,
+            settings.app_config.retry.clone(),
+        ))
+            as Arc<dyn TraitIndicatorRepository + Send + Sync>;
+        let pipeline_profile_repository = Arc::new(PipelineProfileRepository::new(
+            clickhouse_connection.clone(),
+        ))
+            as Arc<dyn TraitPipelineProfileRepository + Send + Sync>;
+        let signal_latency_repository = Arc::new(SignalLatencyRepository::new(
+            clickhouse_connection.clone(),
+        ))
+            as Arc<dyn TraitSignalLatencyRepository + Send + Sync>;
         
         info!("Database service initialized successfully");
         
         Ok(Self {
-            connection: clickhouse_connection,
-
             repository_indicator: indicator_repository,
+            repository_pipeline_profile: pipeline_profile_repository,
+            repository_signal_latency: signal_latency_repository,
         })
     }
+
+    /// Analytics repositories backed by the embedded dev-mode store
+    #[cfg(feature = "embedded")]
+    pub fn embedded(store: Arc<EmbeddedStore>) -> Self {
+        Self {
+            repository_indicator: store.clone(),
+            repository_pipeline_profile: store.clone(),
+            repository_signal_latency: store,
+        }
+    }
 }
\ No newline at end of file
@@ -18,15 +18,16 @@ impl ClickhouseConnection {
 
 
         
-        // Create client with the authenticated URL
+        // Create client with the authenticated URL; ClickHouse takes the timeouts in seconds
+        let timeout_seconds = settings.app_config.clickhouse.timeout.as_secs().to_string();
         let client = Client::default()
             .with_url(&settings.app_env.clickhouse_url)
             .with_user(&settings.app_env.clickhouse_user)
             .with_password(&settings.app_env.clickhouse_password)
             .with_database(&settings.app_env.clickhouse_database)
-            .with_option("connect_timeout", settings.app_config.clickhouse.timeout.to_string())
-            .with_option("receive_timeout", settings.app_config.clickhouse.timeout.to_string())
-            .with_option("send_timeout", settings.app_config.clickhouse.timeout.to_string());
+            .with_option("connect_timeout", timeout_seconds.clone())
+            .with_option("receive_timeout", timeout_seconds.clone())
+            .with_option("send_timeout", timeout_seconds);
             
       
             
@@ -1,3 +1,4 @@
+pub mod batch_size;
 pub mod connection;
 pub mod repository;
 pub mod models;
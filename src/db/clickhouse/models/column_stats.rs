@@ -0,0 +1,90 @@
+// File: src/db/clickhouse/models/column_stats.rs
+use serde::Serialize;
+
+/// Quantile levels of the column statistics
+pub const STATS_QUANTILES: [f64; 7] = [0.01, 0.05, 0.25, 0.5, 0.75, 0.95, 0.99];
+
+/// Distribution of one indicator column over a time range, finite values only
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DbColumnStats {
+    pub count: u64,
+    pub min: f64,
+    pub min_time: i64, // first row holding the minimum
+    pub max: f64,
+    pub max_time: i64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub quantiles: Vec<f64>, // at STATS_QUANTILES
+    pub histogram: Vec<u64>, // equal-width buckets over [min, max]
+}
+
+#[cfg(any(feature = "embedded", test))]
+impl DbColumnStats {
+    /// Statistics of `(time, value)` pairs in memory for the embedded store, `None` without
+    /// finite values. Quantiles interpolate linearly between the closest ranks.
+    pub fn from_values(values: &[(i64, f64)], buckets: usize) -> Option<Self> {
+        let finite: Vec<(i64, f64)> = values.iter().copied().filter(|(_, value)| value.is_finite()).collect();
+        let (min_time, min) = finite.iter().copied().reduce(|a, b| if b.1 < a.1 { b } else { a })?;
+        let (max_time, max) = finite.iter().copied().reduce(|a, b| if b.1 > a.1 { b } else { a })?;
+
+        let count = finite.len() as f64;
+        let mean = finite.iter().map(|(_, value)| value).sum::<f64>() / count;
+        let variance = finite.iter().map(|(_, value)| (value - mean).powi(2)).sum::<f64>() / count;
+
+        let mut sorted: Vec<f64> = finite.iter().map(|(_, value)| *value).collect();
+        sorted.sort_by(f64::total_cmp);
+        let quantiles = STATS_QUANTILES
+            .iter()
+            .map(|level| {
+                let rank = level * (sorted.len() - 1) as f64;
+                let (lower, upper) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+                lower + (upper - lower) * rank.fract()
+            })
+            .collect();
+
+        let width = (max - min) / buckets as f64;
+        let mut histogram = vec![0; buckets];
+        for (_, value) in &finite {
+            // The maximum falls into the last bucket
+            let bucket = if width > 0.0 { ((value - min) / width).floor() as usize } else { 0 };
+            histogram[bucket.min(buckets - 1)] += 1;
+        }
+
+        Some(Self {
+            count: finite.len() as u64,
+            min,
+            min_time,
+            max,
+            max_time,
+            mean,
+            stddev: variance.sqrt(),
+            quantiles,
+            histogram,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_from_values() {
+        let values: Vec<(i64, f64)> = (0..=100).map(|i| (i * 60, i as f64)).chain([(9_000, f64::NAN)]).collect();
+        let stats = DbColumnStats::from_values(&values, 4).unwrap();
+
+        assert_eq!(stats.count, 101);
+        assert_eq!((stats.min, stats.min_time), (0.0, 0));
+        assert_eq!((stats.max, stats.max_time), (100.0, 6000));
+        assert_eq!(stats.mean, 50.0);
+        assert_eq!(stats.quantiles, vec![1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0]);
+        // 100 lands in the last bucket
+        assert_eq!(stats.histogram, vec![25, 25, 25, 26]);
+
+        let constant = DbColumnStats::from_values(&[(0, 3.0), (60, 3.0)], 4).unwrap();
+        assert_eq!(constant.histogram, vec![2, 0, 0, 0]);
+        assert_eq!(constant.stddev, 0.0);
+
+        assert!(DbColumnStats::from_values(&[(0, f64::INFINITY)], 4).is_none());
+    }
+}
@@ -3,7 +3,7 @@ use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 
 /// Структура для хранения рассчитанных технических индикаторов
-#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Row)]
 pub struct DbIndicator {
     // Базовые поля для идентификации
     pub instrument_uid: String,
@@ -27,18 +27,327 @@ pub struct DbIndicator {
     pub ma_cross: i8,
     pub rsi_zone: i8,
     pub volume_anomaly: i8,
+    // z-score объёма выше порога indicators_updater.volume_anomaly_sigma, ограниченный сверху
+    // VOLUME_ANOMALY_SCORE_CAP; 0 ниже порога
+    pub volume_anomaly_score: f64,
     
     // Дополнительные признаки времени
     pub hour_of_day: i8,
     pub day_of_week: i8,
+    // Циклические кодировки времени суток (по минутам) и дня недели; минуты с открытия сессии
+    pub hour_sin: f64,
+    pub hour_cos: f64,
+    pub dow_sin: f64,
+    pub dow_cos: f64,
+    pub minutes_since_session_open: i32,
+    // Фаза торговой сессии по календарю биржи: 0 - закрыто, 1 - аукцион открытия, 2 - основная, 3 - вечерняя
+    pub session_phase: i8,
     
     // Целевая переменная
     pub price_change_15m: f64,
     pub signal_15m: i8,
+
+    // Z-score спреда (только для синтетических инструментов)
+    pub spread_zscore: f64,
+
+    // Rate of change (%) и momentum по лагам из indicators_updater.roc_lags (в том же порядке)
+    pub roc: Vec<f64>,
+    pub momentum: Vec<f64>,
+
+    // Режим рынка: 0 - флэт, 1 - восходящий тренд, -1 - нисходящий тренд, 2 - высокая волатильность
+    pub regime: i8,
+
+    // Точки разладки (CUSUM): 1 - сдвиг вверх, -1 - сдвиг вниз; минуты с последней (-1 если не было)
+    pub price_changepoint: i8,
+    pub vol_changepoint: i8,
+    pub minutes_since_price_cp: i32,
+    pub minutes_since_vol_cp: i32,
+
+    // Stochastic RSI(14) и сглаженные %K(3)/%D(3), шкала 0-100
+    pub stoch_rsi: f64,
+    pub stoch_rsi_k: f64,
+    pub stoch_rsi_d: f64,
+
+    // Свечи Heikin-Ashi
+    pub ha_open: f64,
+    pub ha_high: f64,
+    pub ha_low: f64,
+    pub ha_close: f64,
+
+    // Дневные пивоты (floor trader) по предыдущему торговому дню и расстояния до них, %
+    pub pivot_p: f64,
+    pub pivot_r1: f64,
+    pub pivot_r2: f64,
+    pub pivot_r3: f64,
+    pub pivot_s1: f64,
+    pub pivot_s2: f64,
+    pub pivot_s3: f64,
+    pub pivot_dist_pct: f64,
+    pub pivot_r1_dist_pct: f64,
+    pub pivot_s1_dist_pct: f64,
+
+    // Адаптивные (EWMA по инструменту) версии rsi_zone, volume_anomaly и ma_cross
+    pub rsi_zone_adaptive: i8,
+    pub volume_anomaly_adaptive: i8,
+    pub ma_cross_adaptive: i8,
+
+    // Оценка качества входных данных строки [0, 1]: прогрев окна, пропуски, дубликаты, залипшие цены
+    pub quality_score: f64,
+
+    // Скользящие максимумы/минимумы за 60/240 свечей и расстояние закрытия до них, %
+    pub high_60: f64,
+    pub low_60: f64,
+    pub high_240: f64,
+    pub low_240: f64,
+    pub dist_high_60_pct: f64,
+    pub dist_low_60_pct: f64,
+    pub dist_high_240_pct: f64,
+    pub dist_low_240_pct: f64,
+
+    // Залипшая цена: закрытие повторяет предыдущее при нулевом объёме; длина серии таких свечей
+    pub stale_price: i8,
+    pub stale_count: i32,
+
+    // TRIX(15), сигнальная линия EMA(9) и флаг пересечения (1 - вверх, -1 - вниз)
+    pub trix: f64,
+    pub trix_signal: f64,
+    pub trix_cross: i8,
+
+    // Ultimate Oscillator (7/14/28), шкала 0-100
+    pub ultimate_osc: f64,
+
+    // Elder-Ray: high - EMA(13) и low - EMA(13)
+    pub bull_power: f64,
+    pub bear_power: f64,
+
+    // Vortex (14): VI+, VI- и пересечение VI+ с VI- (1 - вверх, -1 - вниз, 0 - нет)
+    pub vortex_pos: f64,
+    pub vortex_neg: f64,
+    pub vortex_cross: i8,
+
+    // Линейная регрессия close за 30 свечей: наклон в % от средней цены за свечу и R²
+    pub linreg_slope_30: f64,
+    pub linreg_r2_30: f64,
+
+    // Показатель Херста логарифмических доходностей (окно и метод - indicators_updater.hurst),
+    // >0.5 - тренд, <0.5 - возврат к среднему, 0 - мало данных
+    pub hurst: f64,
+
+    // Контекст старшего таймфрейма по завершённым часам: наклон EMA(20) часовых закрытий, %
+    // за час, и RSI(14) часовых закрытий. Расстояние до дневного пивота - pivot_dist_pct
+    pub htf_1h_ema_slope: f64,
+    pub htf_1h_rsi: f64,
+
+    // Первая свеча после разрыва длиннее indicators_updater.gaps.max_gap_minutes (1 - да)
+    pub session_break: i8,
+
+    // Автокорреляция лог-доходностей с лагом 1 и энтропия Шеннона знаков доходностей
+    // (рост/падение/без изменений, нормирована в 0-1) за 60 свечей
+    pub return_autocorr_60: f64,
+    pub sign_entropy_60: f64,
+
+    // Взвешенная (WMA) и Hull (HMA) скользящие средние, наклон HMA в % к предыдущей свече
+    pub wma: f64,
+    pub hma: f64,
+    pub hma_slope: f64,
+
+    // VWMA(20) и объём относительно среднего объёма в ту же минуту дня за volume_baseline_days
+    // до дня свечи (0 - нет истории)
+    pub vwma_20: f64,
+    pub rel_volume: f64,
+
+    // Z-оценка цены закрытия относительно скользящих среднего и ст. отклонения за 50 свечей
+    pub close_zscore_50: f64,
+
+    // Реализованная волатильность: ст. отклонение минутных лог-доходностей за 30/60/240 свечей
+    pub volatility_30: f64,
+    pub volatility_60: f64,
+    pub volatility_240: f64,
+
+    // Разрыв с предыдущей свечой: открытие к предыдущему закрытию, % и число пропущенных минут
+    pub gap_pct: f64,
+    pub gap_minutes: i32,
+
+    // Размах свечи (high - low) в % к открытию; тело и верхняя/нижняя тени в % от размаха
+    pub range_pct: f64,
+    pub body_pct: f64,
+    pub upper_wick_pct: f64,
+    pub lower_wick_pct: f64,
+
+    // Логарифмические доходности назад на 1/5/15/60 минут
+    pub log_return_1m: f64,
+    pub log_return_5m: f64,
+    pub log_return_15m: f64,
+    pub log_return_60m: f64,
+
+    // Целевые переменные по горизонтам из indicators_updater.targets (в том же порядке):
+    // изменение цены, % и сигнал (1 - рост, -1 - падение, 0 - боковик)
+    pub target_change: Vec<f64>,
+    pub target_signal: Vec<i8>,
+
+    // Тройной барьер: 1 - профит, -1 - стоп, 0 - время (или исход ещё неизвестен); время касания (0 - неизвестно)
+    pub tb_label: i8,
+    pub tb_hit_time: i64,
+
+    // Фракталы Билла Вильямса (локальный экстремум 5 свечей), 1 - есть; подтверждаются через
+    // 2 свечи и дозаполняются вместе с целевыми переменными
+    pub fractal_high: i8,
+    pub fractal_low: i8,
+
+    // Значения пользовательских индикаторов из indicators_updater.plugins (в том же порядке)
+    pub plugin_values: Vec<f64>,
+
+    // Время записи строки и последнего дозаполнения меток, unix ms (DateTime64(3)); проставляются
+    // при записи, по ним читается состояние на момент as_of
+    pub insert_time: i64,
+    pub labels_time: i64,
+}
+
+/// Пересчитанные целевые переменные строки (instrument_uid, time) для дозаполнения меток
+#[derive(Debug, Clone, Default)]
+pub struct DbLabelUpdate {
+    pub instrument_uid: String,
+    pub time: i64,
+    pub price_change_15m: f64,
+    pub signal_15m: i8,
+    pub target_change: Vec<f64>,
+    pub target_signal: Vec<i8>,
+    pub tb_label: i8,
+    pub tb_hit_time: i64,
+    pub fractal_high: i8,
+    pub fractal_low: i8,
+}
+
+impl DbIndicator {
+    /// Replaces NaN and ±inf in every float column with 0 so they never reach ClickHouse.
+    /// New float columns must be added here.
+    pub fn sanitized(mut self) -> Self {
+        for value in [
+            &mut self.open_price,
+            &mut self.high_price,
+            &mut self.low_price,
+            &mut self.close_price,
+            &mut self.rsi_14,
+            &mut self.ma_10,
+            &mut self.ma_30,
+            &mut self.volume_norm,
+            &mut self.ma_diff,
+            &mut self.price_change_15m,
+            &mut self.hour_sin,
+            &mut self.hour_cos,
+            &mut self.dow_sin,
+            &mut self.dow_cos,
+            &mut self.spread_zscore,
+            &mut self.stoch_rsi,
+            &mut self.stoch_rsi_k,
+            &mut self.stoch_rsi_d,
+            &mut self.ha_open,
+            &mut self.ha_high,
+            &mut self.ha_low,
+            &mut self.ha_close,
+            &mut self.pivot_p,
+            &mut self.pivot_r1,
+            &mut self.pivot_r2,
+            &mut self.pivot_r3,
+            &mut self.pivot_s1,
+            &mut self.pivot_s2,
+            &mut self.pivot_s3,
+            &mut self.pivot_dist_pct,
+            &mut self.pivot_r1_dist_pct,
+            &mut self.pivot_s1_dist_pct,
+            &mut self.quality_score,
+            &mut self.high_60,
+            &mut self.low_60,
+            &mut self.high_240,
+            &mut self.low_240,
+            &mut self.dist_high_60_pct,
+            &mut self.dist_low_60_pct,
+            &mut self.dist_high_240_pct,
+            &mut self.dist_low_240_pct,
+            &mut self.trix,
+            &mut self.trix_signal,
+            &mut self.ultimate_osc,
+            &mut self.bull_power,
+            &mut self.bear_power,
+            &mut self.vortex_pos,
+            &mut self.vortex_neg,
+            &mut self.linreg_slope_30,
+            &mut self.linreg_r2_30,
+            &mut self.hurst,
+            &mut self.htf_1h_ema_slope,
+            &mut self.htf_1h_rsi,
+            &mut self.volume_anomaly_score,
+            &mut self.return_autocorr_60,
+            &mut self.sign_entropy_60,
+            &mut self.wma,
+            &mut self.hma,
+            &mut self.hma_slope,
+            &mut self.vwma_20,
+            &mut self.rel_volume,
+            &mut self.close_zscore_50,
+            &mut self.volatility_30,
+            &mut self.volatility_60,
+            &mut self.volatility_240,
+            &mut self.gap_pct,
+            &mut self.range_pct,
+            &mut self.body_pct,
+            &mut self.upper_wick_pct,
+            &mut self.lower_wick_pct,
+            &mut self.log_return_1m,
+            &mut self.log_return_5m,
+            &mut self.log_return_15m,
+            &mut self.log_return_60m,
+        ] {
+            *value = finite_or_zero(*value);
+        }
+        self.roc.iter_mut().for_each(|value| *value = finite_or_zero(*value));
+        self.momentum.iter_mut().for_each(|value| *value = finite_or_zero(*value));
+        self.target_change.iter_mut().for_each(|value| *value = finite_or_zero(*value));
+        self.plugin_values.iter_mut().for_each(|value| *value = finite_or_zero(*value));
+
+        self
+    }
+
+    /// Stamps the write time of a row about to be inserted, unix ms
+    pub fn stamped(mut self, now_ms: i64) -> Self {
+        self.insert_time = now_ms;
+        self.labels_time = now_ms;
+        self
+    }
+
+    /// The row as a reader saw it at `as_of` (unix ms): label columns filled in after that
+    /// read as not yet known, as they were on insert
+    pub fn seen_at(mut self, as_of: i64) -> Self {
+        if self.labels_time > as_of {
+            self.price_change_15m = 0.0;
+            self.signal_15m = 0;
+            self.target_change.iter_mut().for_each(|value| *value = 0.0);
+            self.target_signal.iter_mut().for_each(|value| *value = 0);
+            self.tb_label = 0;
+            self.tb_hit_time = 0;
+            self.fractal_high = 0;
+            self.fractal_low = 0;
+        }
+        self
+    }
+}
+
+/// Заменяет NaN и бесконечности нулём
+pub fn finite_or_zero(value: f64) -> f64 {
+    if value.is_finite() { value } else { 0.0 }
+}
+
+/// Дневные high/low/close, агрегированные из минутных свечей
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct DbDailyOhlc {
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
 }
 
 /// Структура для хранения исходных данных минутной свечи
 #[derive(Debug, Clone, Serialize, Deserialize, Row)]
+#[cfg_attr(feature = "embedded", derive(sqlx::FromRow))]
 pub struct DbCandleRaw {
     pub instrument_uid: String,
     pub time: i64,
@@ -54,16 +363,7 @@ pub struct DbCandleRaw {
 }
 
 /// Структура для хранения конвертированных данных минутной свечи
-#[derive(Debug, Clone)]
-pub struct DbCandleConverted {
-    pub instrument_uid: String,
-    pub time: i64,
-    pub open_price: f64,
-    pub high_price: f64,
-    pub low_price: f64,
-    pub close_price: f64,
-    pub volume: i64,
-}
+pub use t_indicators_core::Candle as DbCandleConverted;
 
 impl From<DbCandleRaw> for DbCandleConverted {
     fn from(raw: DbCandleRaw) -> Self {
@@ -90,4 +390,66 @@ pub struct DbIndicatorStatus {
     pub instrument_uid: String,
     pub last_processed_time: i64,
     pub update_time: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finite_or_zero() {
+        assert_eq!(finite_or_zero(1.5), 1.5);
+        assert_eq!(finite_or_zero(f64::NAN), 0.0);
+        assert_eq!(finite_or_zero(f64::INFINITY), 0.0);
+        assert_eq!(finite_or_zero(f64::NEG_INFINITY), 0.0);
+    }
+
+    #[test]
+    fn test_sanitized_indicator_has_no_non_finite_values() {
+        let indicator = DbIndicator {
+            instrument_uid: "test".to_string(),
+            close_price: 100.0,
+            rsi_14: f64::NAN,
+            volume_norm: f64::INFINITY,
+            spread_zscore: f64::NEG_INFINITY,
+            stoch_rsi: f64::NAN,
+            pivot_dist_pct: f64::INFINITY,
+            roc: vec![1.0, f64::NAN, f64::INFINITY],
+            momentum: vec![f64::NEG_INFINITY],
+            ..Default::default()
+        }
+        .sanitized();
+
+        // serde_json serializes non-finite floats as null
+        let value = serde_json::to_value(&indicator).unwrap();
+        let object = value.as_object().unwrap();
+        for (column, value) in object {
+            assert!(!value.is_null(), "column {} is not finite", column);
+            if let Some(items) = value.as_array() {
+                assert!(items.iter().all(|item| !item.is_null()), "column {} is not finite", column);
+            }
+        }
+
+        assert_eq!(indicator.close_price, 100.0);
+        assert_eq!(indicator.roc, vec![1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_as_of_hides_later_labels() {
+        let indicator = DbIndicator {
+            signal_15m: 1,
+            target_change: vec![0.5, -1.2],
+            target_signal: vec![1, -1],
+            fractal_high: 1,
+            ..Default::default()
+        }
+        .stamped(1_000);
+        let relabeled = DbIndicator { labels_time: 5_000, ..indicator.clone() };
+
+        assert_eq!(relabeled.clone().seen_at(5_000).target_signal, vec![1, -1]);
+        let seen = relabeled.seen_at(4_999);
+        assert_eq!((seen.signal_15m, seen.fractal_high), (0, 0));
+        assert_eq!((seen.target_change, seen.target_signal), (vec![0.0, 0.0], vec![0, 0]));
+        assert_eq!(indicator.seen_at(1_000).signal_15m, 1);
+    }
+}
@@ -1,2 +1,7 @@
 
+pub mod column_stats;
 pub mod indicator;
+pub mod pipeline_profile;
+pub mod signal_counts;
+pub mod signal_latency;
+pub mod volume_baseline;
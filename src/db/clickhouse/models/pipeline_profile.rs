@@ -0,0 +1,35 @@
+// File: src/db/clickhouse/models/pipeline_profile.rs
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// Профиль обработки одного инструмента за один запуск: время по этапам, мс
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Row)]
+pub struct DbPipelineProfile {
+    pub run_id: String,
+    pub run_time: u32, // DateTime начала запуска
+    pub instrument_uid: String,
+    pub batches: u32,
+    pub candles: u64,     // Вставлено строк индикаторов
+    pub fetch_ms: f64,    // Загрузка свечей, окна прогрева и дневных OHLC
+    pub compute_ms: f64,  // Расчёт индикаторов и меток
+    pub insert_ms: f64,   // Вставка индикаторов и дозаполнение меток
+    pub status_ms: f64,   // Обновление статуса обработки
+    pub total_ms: f64,
+    pub insert_errors: u32, // Неудачные вставки и дозаполнения меток
+}
+
+/// Суммарный профиль одного запуска по всем инструментам
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Row)]
+pub struct DbPipelineRunSummary {
+    pub run_id: String,
+    pub run_started: u32, // DateTime
+    pub instruments: u64,
+    pub batches_total: u64,
+    pub candles_total: u64,
+    pub fetch_ms_total: f64,
+    pub compute_ms_total: f64,
+    pub insert_ms_total: f64,
+    pub status_ms_total: f64,
+    pub wall_ms: f64,
+    pub insert_errors_total: u64,
+}
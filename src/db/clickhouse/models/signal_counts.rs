@@ -0,0 +1,15 @@
+// File: src/db/clickhouse/models/signal_counts.rs
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// Число сигналов каждого вида за один интервал (час/день) по всем выбранным инструментам,
+/// без учёта cooldown
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Row)]
+pub struct DbSignalCounts {
+    pub time: i64,        // Начало интервала, UTC
+    pub instruments: u64, // Инструменты со строками в интервале
+    pub golden_cross: u64,
+    pub death_cross: u64,
+    pub rsi_oversold: u64,   // Входы в зону перепроданности
+    pub rsi_overbought: u64, // Входы в зону перекупленности
+}
@@ -0,0 +1,145 @@
+// File: src/db/clickhouse/models/signal_latency.rs
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// Quantile levels of the latency report; 1.0 is the maximum
+pub const LATENCY_QUANTILES: [f64; 4] = [0.5, 0.9, 0.99, 1.0];
+
+/// Stages of the latency report in pipeline order; `total` runs from candle close to delivery
+pub const LATENCY_STAGES: [&str; 6] = ["fetch", "compute", "insert", "emit", "deliver", "total"];
+
+/// Путь одного сигнала через сервис: время прохождения этапов, unix ms
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Row)]
+pub struct DbSignalLatency {
+    pub trace_id: String, // run_id:instrument_uid:время свечи:вид сигнала
+    pub run_id: String,
+    pub instrument_uid: String,
+    pub kind: String,
+    pub candle_time: u32,       // DateTime начала минутной свечи
+    pub candle_close_ms: i64,   // Закрытие свечи, с него сигнал становится возможен
+    pub fetched_ms: i64,        // Свечи пакета загружены
+    pub computed_ms: i64,       // Индикаторы пакета рассчитаны
+    pub inserted_ms: i64,       // Индикаторы вставлены
+    pub emitted_ms: i64,        // Сигнал прошёл cooldown и передан в маршрутизацию
+    pub delivered_ms: i64,      // Последняя успешная доставка, 0 - не доставлен
+    pub deliveries: u32,
+    pub delivery_failures: u32,
+}
+
+/// Latency distribution of one stage over a time range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Row)]
+pub struct DbStageLatency {
+    pub stage: String,
+    pub count: u64,
+    pub quantiles_ms: Vec<f64>, // at LATENCY_QUANTILES, empty without signals
+}
+
+impl DbStageLatency {
+    /// Every stage of `LATENCY_STAGES` in order, the ones missing from `rows` as empty
+    pub fn complete(rows: Vec<DbStageLatency>) -> Vec<DbStageLatency> {
+        LATENCY_STAGES
+            .iter()
+            .map(|stage| {
+                rows.iter().find(|row| row.stage == *stage).cloned().unwrap_or_else(|| DbStageLatency {
+                    stage: stage.to_string(),
+                    count: 0,
+                    quantiles_ms: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(any(feature = "embedded", test))]
+impl DbSignalLatency {
+    /// Duration of every stage of `LATENCY_STAGES`, ms; `None` for the delivery stages of an
+    /// undelivered signal
+    pub fn stage_durations(&self) -> [Option<i64>; 6] {
+        let delivered = (self.delivered_ms > 0).then_some(self.delivered_ms);
+        [
+            Some(self.fetched_ms - self.candle_close_ms),
+            Some(self.computed_ms - self.fetched_ms),
+            Some(self.inserted_ms - self.computed_ms),
+            Some(self.emitted_ms - self.inserted_ms),
+            delivered.map(|delivered| delivered - self.emitted_ms),
+            delivered.map(|delivered| delivered - self.candle_close_ms),
+        ]
+    }
+}
+
+#[cfg(any(feature = "embedded", test))]
+impl DbStageLatency {
+    /// Stage distributions of the records in memory for the embedded store. Quantiles
+    /// interpolate linearly between the closest ranks.
+    pub fn from_records(records: &[DbSignalLatency]) -> Vec<DbStageLatency> {
+        let mut durations: Vec<Vec<f64>> = vec![Vec::new(); LATENCY_STAGES.len()];
+        for record in records {
+            for (stage, duration) in record.stage_durations().into_iter().enumerate() {
+                if let Some(duration) = duration {
+                    durations[stage].push(duration as f64);
+                }
+            }
+        }
+
+        let rows = LATENCY_STAGES
+            .iter()
+            .zip(durations)
+            .filter(|(_, values)| !values.is_empty())
+            .map(|(stage, mut sorted)| {
+                sorted.sort_by(f64::total_cmp);
+                let quantiles_ms = LATENCY_QUANTILES
+                    .iter()
+                    .map(|level| {
+                        let rank = level * (sorted.len() - 1) as f64;
+                        let (lower, upper) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+                        lower + (upper - lower) * rank.fract()
+                    })
+                    .collect();
+                DbStageLatency {
+                    stage: stage.to_string(),
+                    count: sorted.len() as u64,
+                    quantiles_ms,
+                }
+            })
+            .collect();
+        Self::complete(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(offset: i64, delivered: bool) -> DbSignalLatency {
+        let close = 60_000;
+        DbSignalLatency {
+            candle_close_ms: close,
+            fetched_ms: close + 1_000 + offset,
+            computed_ms: close + 1_100 + offset,
+            inserted_ms: close + 1_300 + offset,
+            emitted_ms: close + 1_400 + offset,
+            delivered_ms: if delivered { close + 1_500 + offset } else { 0 },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stage_latency_from_records() {
+        let records: Vec<DbSignalLatency> = (0..=100).map(|i| record(i * 10, i % 2 == 0)).collect();
+        let stages = DbStageLatency::from_records(&records);
+
+        assert_eq!(stages.iter().map(|s| s.stage.as_str()).collect::<Vec<_>>(), LATENCY_STAGES);
+        let fetch = &stages[0];
+        assert_eq!(fetch.count, 101);
+        assert_eq!(fetch.quantiles_ms, vec![1_500.0, 1_900.0, 1_990.0, 2_000.0]);
+        assert_eq!(stages[1].quantiles_ms, vec![100.0; 4]);
+
+        // Only delivered signals count towards delivery and the total
+        let total = &stages[5];
+        assert_eq!(total.count, 51);
+        assert_eq!(total.quantiles_ms[3], 2_500.0);
+
+        let empty = DbStageLatency::from_records(&[record(0, false)]);
+        assert_eq!((empty[4].count, empty[4].quantiles_ms.len()), (0, 0));
+    }
+}
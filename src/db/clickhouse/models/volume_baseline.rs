@@ -0,0 +1,17 @@
+// File: src/db/clickhouse/models/volume_baseline.rs
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// Сезонная базовая линия объёма: минута дня × день недели
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+#[cfg_attr(feature = "embedded", derive(sqlx::FromRow))]
+pub struct DbVolumeBaseline {
+    pub instrument_uid: String,
+    pub weekday: u8,        // 1 - понедельник ... 7 - воскресенье
+    pub minute_of_day: u16, // 0..1439, UTC
+    pub mean: f64,
+    pub stddev: f64,
+    pub samples: u32,
+    pub update_time: u32, // DateTime
+    pub as_of: i64,       // Начало дня (UTC), до которого оценена линия
+}
@@ -1,34 +1,349 @@
 // File: src/db/clickhouse/repository/indicator_repository.rs
+use crate::api::query::{Aggregation, SortOrder};
+use crate::db::clickhouse::batch_size::{AdaptiveBatchSize, MAX_SELECT_ROWS, is_resource_error};
 use crate::db::clickhouse::connection::ClickhouseConnection;
-use crate::db::clickhouse::models::indicator::{DbCandleRaw, DbIndicator, DbIndicatorStatus};
+use crate::db::clickhouse::models::column_stats::{DbColumnStats, STATS_QUANTILES};
+use crate::db::clickhouse::models::indicator::{DbCandleRaw, DbDailyOhlc, DbIndicator, DbLabelUpdate, finite_or_zero};
+use crate::db::clickhouse::models::signal_counts::DbSignalCounts;
+use crate::db::clickhouse::models::volume_baseline::DbVolumeBaseline;
+use crate::env_config::models::app_config::{RetryConfig, Timeframe};
+use crate::utils::retry::with_retry;
 use async_trait::async_trait;
-use clickhouse::error::Error as ClickhouseError;
 use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+/// Recent rows (all rows when the hot/cold split is off)
+const HOT_TABLE: &str = "market_data.tinkoff_indicators_1min";
+/// Rows older than `clickhouse.hot_days`
+const COLD_TABLE: &str = "market_data.tinkoff_indicators_1min_cold";
+/// `UNION ALL` of the hot and cold tables
+const ALL_VIEW: &str = "market_data.tinkoff_indicators_all";
+/// Rows per INSERT, and the floor it is halved to on resource errors
+const INSERT_BATCH_SIZE: usize = 100_000;
+const MIN_INSERT_BATCH_SIZE: usize = 1_000;
+/// Rows per label update mutation; its arrays travel in the URL, capped at 1 MiB by default
+pub const LABEL_UPDATE_ROWS: usize = 1_000;
+/// Rows written by a time (unix ms), bound after the other conditions
+const AS_OF_CONDITION: &str = "AND insert_time <= fromUnixTimestamp64Milli(?)";
+
+/// Writes one batch of rows in a single INSERT
+async fn insert_batch(
+    client: &clickhouse::Client,
+    table: &str,
+    batch: &[DbIndicator],
+) -> Result<(), clickhouse::error::Error> {
+    let mut insert = client.insert(table)?;
+    for indicator in batch {
+        insert.write(indicator).await?;
+    }
+    insert.end().await
+}
+
+/// Analytics store of candles and indicators: ClickHouse, or the embedded store in dev mode
+#[async_trait]
+pub trait TraitIndicatorRepository {
+    /// Cheap round trip to the store, used by the health check
+    async fn ping(&self) -> Result<(), clickhouse::error::Error>;
+
+    /// Deletes every indicator row before a full recalculation
+    async fn truncate_indicators(&self) -> Result<(), clickhouse::error::Error>;
+
+    /// Deletes rows of an instrument after `time` from the 1-minute tables, or from the
+    /// `timeframe` table when given, before the range is rewritten
+    async fn delete_indicators_after(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+        timeframe: Option<Timeframe>,
+    ) -> Result<(), clickhouse::error::Error>;
+
+    async fn get_candles_after_time(
+        &self,
+        instrument_uid: &str,
+        last_processed_time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error>;
+
+    /// Fetches the last `limit` candles at or before `time`, in descending time order
+    async fn get_candles_before_time(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error>;
+
+    /// Aggregates 1-minute candles into up to `limit` complete `timeframe` bars starting
+    /// strictly after the bar at `after_time`, in ascending time order. A bar is complete once
+    /// the candle of its last minute exists.
+    async fn get_timeframe_candles_after(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        after_time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error>;
+
+    /// Aggregates the last `limit` `timeframe` bars starting at or before `time`, in ascending
+    /// time order
+    async fn get_timeframe_candles_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error>;
+
+    /// Writes indicator rows of a higher timeframe, returns the number written
+    async fn insert_timeframe_indicators(
+        &self,
+        timeframe: Timeframe,
+        indicators: Vec<DbIndicator>,
+        async_insert: bool,
+    ) -> Result<u64, clickhouse::error::Error>;
+
+    /// Fetches the row of the latest `timeframe` bar closed by the 1-minute candle at `time`,
+    /// i.e. whose last minute is at or before it
+    async fn get_timeframe_indicator_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+    ) -> Result<Option<DbIndicator>, clickhouse::error::Error>;
+
+    /// Fetches up to `limit` indicator rows strictly after `after` in the given order
+    /// (for descending order, strictly before it). With `as_of` (unix ms) only rows inserted
+    /// by then; see `DbIndicator::seen_at` for their labels.
+    async fn get_indicators_page(
+        &self,
+        instrument_uid: &str,
+        after: Option<i64>,
+        order: SortOrder,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error>;
+
+    /// Fetches up to `limit` indicator rows with `from <= time <= to`, in ascending time order
+    async fn get_indicators_range(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        to: i64,
+        limit: usize,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error>;
+
+    /// Fetches up to `limit` indicator rows of several instruments with `from <= time <= to`,
+    /// ordered by instrument and time; `as_of` as in `get_indicators_page`
+    async fn get_indicators_multi(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error>;
+
+    /// Fetches indicators of several instruments aggregated into `bucket_seconds` time buckets,
+    /// one JSON object per (instrument, bucket) with `instrument_uid`, bucket start `time` and
+    /// the requested columns. Column names must be validated identifiers.
+    async fn get_indicators_downsampled(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+        columns: &[(String, Aggregation)],
+        limit: usize,
+    ) -> Result<Vec<Map<String, Value>>, clickhouse::error::Error>;
+
+    /// Distribution of a numeric column of one instrument in [`from`, `to`] with `buckets`
+    /// histogram buckets, `None` without finite values. The column must be a validated identifier.
+    async fn get_column_stats(
+        &self,
+        instrument_uid: &str,
+        column: &str,
+        from: i64,
+        to: i64,
+        buckets: usize,
+    ) -> Result<Option<DbColumnStats>, clickhouse::error::Error>;
+
+    /// Signal events per `bucket_seconds` interval in [`from`, `to`] across `instrument_uids`
+    /// (every instrument when `None`) except `excluded`. Counted as `detect_signals` finds
+    /// them: every MA cross, RSI zone entries only.
+    async fn get_signal_counts(
+        &self,
+        instrument_uids: Option<&[String]>,
+        excluded: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+    ) -> Result<Vec<DbSignalCounts>, clickhouse::error::Error>;
+
+    /// Overwrites target columns of already written rows of any number of instruments, at
+    /// most `LABEL_UPDATE_ROWS` per call
+    async fn update_labels(&self, updates: &[DbLabelUpdate]) -> Result<(), clickhouse::error::Error>;
+
+    /// Replaces the row of an instrument in the latest-row table
+    async fn upsert_latest_indicator(&self, indicator: DbIndicator) -> Result<(), clickhouse::error::Error>;
+
+    /// Fetches the latest indicator row of every instrument
+    async fn get_latest_indicators(&self) -> Result<Vec<DbIndicator>, clickhouse::error::Error>;
+
+    /// Writes indicator rows, returns the number written. Batches hitting a ClickHouse
+    /// resource limit are retried smaller, other failures are returned rather than skipped.
+    async fn insert_indicators(
+        &self,
+        indicators: Vec<DbIndicator>,
+        async_insert: bool,
+    ) -> Result<u64, clickhouse::error::Error>;
+
+    /// Moves rows older than the hot horizon to the cold tier, no-op without a hot/cold split
+    async fn move_to_cold(&self) -> Result<(), clickhouse::error::Error>;
+
+    async fn get_all_instrument_uids(&self) -> Result<Vec<String>, clickhouse::error::Error>;
+
+    /// Copies 1-minute rows with `insert_time` (unix ms) in (`after`, `until`] into a ClickHouse
+    /// table function (`s3(...)`, `remoteSecure(...)`, `url(...)`), all rows up to `until`
+    /// without `after`. Returns the number of rows copied, nothing is written when there are none.
+    async fn export_inserted(
+        &self,
+        destination: &str,
+        after: Option<i64>,
+        until: i64,
+    ) -> Result<u64, clickhouse::error::Error>;
+
+    /// Time of the latest 1-minute candle of every instrument, in one grouped query
+    async fn get_latest_candle_times(&self) -> Result<HashMap<String, i64>, clickhouse::error::Error>;
+
+    /// Aggregates the OHLC of the last trading day (UTC) strictly before the day of `time`
+    async fn get_previous_day_ohlc(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+    ) -> Result<Option<DbDailyOhlc>, clickhouse::error::Error>;
+
+    /// Loads the stored seasonal volume baseline of an instrument, the one of its `as_of` day
+    async fn get_volume_baseline(
+        &self,
+        instrument_uid: &str,
+    ) -> Result<Vec<DbVolumeBaseline>, clickhouse::error::Error>;
+
+    /// Stores the seasonal volume baseline as of the UTC day starting at `as_of`, estimated over
+    /// the `lookback_days` before that day
+    async fn refresh_volume_baseline(
+        &self,
+        instrument_uid: &str,
+        as_of: i64,
+        lookback_days: u32,
+    ) -> Result<(), clickhouse::error::Error>;
+
+    /// Seasonal volume baselines as of every UTC day starting in `from..=until`, each estimated
+    /// over the `lookback_days` before its day, without storing them
+    async fn compute_volume_baselines(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        until: i64,
+        lookback_days: u32,
+    ) -> Result<Vec<DbVolumeBaseline>, clickhouse::error::Error>;
+}
+
 pub struct IndicatorRepository {
     pub connection: Arc<ClickhouseConnection>,
+    hot_days: u32,
+    // Rows per INSERT, reduced while ClickHouse reports resource limits
+    insert_batch: AdaptiveBatchSize,
+    retry: RetryConfig,
 }
 
 impl IndicatorRepository {
-    pub fn new(connection: Arc<ClickhouseConnection>) -> Self {
-        Self { connection }
+    pub fn new(connection: Arc<ClickhouseConnection>, hot_days: u32, retry: RetryConfig) -> Self {
+        Self {
+            connection,
+            hot_days,
+            insert_batch: AdaptiveBatchSize::new(INSERT_BATCH_SIZE, MIN_INSERT_BATCH_SIZE),
+            retry,
+        }
+    }
+
+    /// Start of the hot horizon, `None` when the hot/cold split is off
+    fn hot_cutoff(&self) -> Option<i64> {
+        (self.hot_days > 0).then(|| chrono::Utc::now().timestamp() - self.hot_days as i64 * 86_400)
+    }
+
+    /// Table (or view) the read queries select from
+    fn read_table(&self) -> &'static str {
+        if self.hot_days > 0 { ALL_VIEW } else { HOT_TABLE }
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorRepository for IndicatorRepository {
+    async fn ping(&self) -> Result<(), clickhouse::error::Error> {
+        self.connection.get_client().query("SELECT 1").execute().await
+    }
+
+    async fn truncate_indicators(&self) -> Result<(), clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let tables = if self.hot_days > 0 {
+            vec![HOT_TABLE, COLD_TABLE]
+        } else {
+            vec![HOT_TABLE]
+        };
+        for table in tables {
+            client.query(&format!("TRUNCATE TABLE {}", table)).execute().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_indicators_after(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+        timeframe: Option<Timeframe>,
+    ) -> Result<(), clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let tables = match timeframe {
+            Some(timeframe) => vec![format!("market_data.tinkoff_indicators_{}", timeframe.name())],
+            None if self.hot_days > 0 => vec![HOT_TABLE.to_string(), COLD_TABLE.to_string()],
+            None => vec![HOT_TABLE.to_string()],
+        };
+        for table in tables {
+            client
+                .query(&format!(
+                    "ALTER TABLE {} DELETE WHERE instrument_uid = ? AND time > ?",
+                    table
+                ))
+                .bind(instrument_uid)
+                .bind(time)
+                .execute()
+                .await?;
+        }
+
+        debug!(
+            "Submitted deletion of rows after {} for instrument_uid={}",
+            time, instrument_uid
+        );
+
+        Ok(())
     }
 
-    pub async fn get_candles_after_time(
+    async fn get_candles_after_time(
         &self,
         instrument_uid: &str,
         last_processed_time: i64,
         limit: usize,
     ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error> {
         let client = self.connection.get_client();
-        
-        // Increased batch size for powerful server
-        let safe_limit = std::cmp::min(limit, 10000);
-        
-        let query = format!(
-            "SELECT 
+
+        let safe_limit = std::cmp::min(limit, MAX_SELECT_ROWS);
+
+        let query = "SELECT 
                 instrument_uid,
                 time,
                 open_units,
@@ -41,18 +356,22 @@ impl IndicatorRepository {
                 close_nano,
                 volume
             FROM market_data.tinkoff_candles_1min
-            WHERE instrument_uid = '{}' AND time > {}
+            WHERE instrument_uid = ? AND time > ?
             ORDER BY time ASC
-            LIMIT {}",
-            instrument_uid, last_processed_time, safe_limit
-        );
+            LIMIT ?";
 
         debug!(
             "Fetching candles for instrument_uid={} after time={} (limit={})",
             instrument_uid, last_processed_time, safe_limit
         );
 
-        let result = client.query(&query).fetch_all::<DbCandleRaw>().await?;
+        let result = client
+            .query(query)
+            .bind(instrument_uid)
+            .bind(last_processed_time)
+            .bind(safe_limit as u64)
+            .fetch_all::<DbCandleRaw>()
+            .await?;
 
         debug!(
             "Retrieved {} candles for instrument_uid={} after time={}",
@@ -63,122 +382,1110 @@ impl IndicatorRepository {
 
         Ok(result)
     }
-    
-    pub async fn insert_indicators(
+
+    async fn get_candles_before_time(
         &self,
+        instrument_uid: &str,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let query = "SELECT 
+                instrument_uid,
+                time,
+                open_units,
+                open_nano,
+                high_units,
+                high_nano,
+                low_units,
+                low_nano,
+                close_units,
+                close_nano,
+                volume
+            FROM market_data.tinkoff_candles_1min
+            WHERE instrument_uid = ? AND time <= ?
+            ORDER BY time DESC
+            LIMIT ?";
+
+        let result = client
+            .query(query)
+            .bind(instrument_uid)
+            .bind(time)
+            .bind(limit as u64)
+            .fetch_all::<DbCandleRaw>()
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn get_timeframe_candles_after(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        after_time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error> {
+        let seconds = timeframe.seconds();
+        let from = (after_time.div_euclid(seconds) + 1) * seconds;
+
+        // Minutes of incomplete bars stay out: the bound is the start of the bar that
+        // contains the minute after the latest candle
+        let query = timeframe_bars_query(
+            "minute_time >= ? AND minute_time < intDiv(
+                (SELECT max(time) FROM market_data.tinkoff_candles_1min WHERE instrument_uid = ?) + 60, ?
+            ) * ?",
+            "ASC",
+        );
+
+        let result = self
+            .connection
+            .get_client()
+            .query(&query)
+            .bind(seconds)
+            .bind(seconds)
+            .bind(instrument_uid)
+            .bind(from)
+            .bind(instrument_uid)
+            .bind(seconds)
+            .bind(seconds)
+            .bind(limit.min(MAX_SELECT_ROWS) as u64)
+            .fetch_all::<DbCandleRaw>()
+            .await?;
+
+        debug!(
+            "Retrieved {} {} bars for instrument_uid={} after time={}",
+            result.len(),
+            timeframe.name(),
+            instrument_uid,
+            after_time
+        );
+
+        Ok(result)
+    }
+
+    async fn get_timeframe_candles_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, clickhouse::error::Error> {
+        let seconds = timeframe.seconds();
+        let until = (time.div_euclid(seconds) + 1) * seconds;
+
+        let query = timeframe_bars_query("minute_time < ?", "DESC");
+
+        let mut result = self
+            .connection
+            .get_client()
+            .query(&query)
+            .bind(seconds)
+            .bind(seconds)
+            .bind(instrument_uid)
+            .bind(until)
+            .bind(limit as u64)
+            .fetch_all::<DbCandleRaw>()
+            .await?;
+        result.reverse();
+
+        Ok(result)
+    }
+
+    async fn insert_timeframe_indicators(
+        &self,
+        timeframe: Timeframe,
         indicators: Vec<DbIndicator>,
+        async_insert: bool,
     ) -> Result<u64, clickhouse::error::Error> {
         if indicators.is_empty() {
-            debug!("No indicators to insert");
             return Ok(0);
         }
-        
-    let client = self.connection.get_client()
-        .with_option("async_insert", "1")
-        .with_option("wait_for_async_insert", "0");
-        
-    const BATCH_SIZE: usize = 100000;
-        let total_count = indicators.len();
-        let mut successful_inserts = 0;
-        
-        info!("Starting batch insertion of {} indicators", total_count);
-        
-        // Process in smaller batches to avoid memory errors entirely
-        for batch_start in (0..indicators.len()).step_by(BATCH_SIZE) {
-            let batch_end = std::cmp::min(batch_start + BATCH_SIZE, indicators.len());
-            let batch = &indicators[batch_start..batch_end];
-            
-            debug!(
-                "Processing batch of {} indicators, {}/{}",
-                batch.len(),
-                batch_start + batch.len(),
-                total_count
+
+        let client = if async_insert {
+            self.connection
+                .get_client()
+                .with_option("async_insert", "1")
+                .with_option("wait_for_async_insert", "0")
+        } else {
+            self.connection.get_client()
+        };
+
+        let table = format!("market_data.tinkoff_indicators_{}", timeframe.name());
+        let mut insert = client.insert(&table)?;
+        let count = indicators.len();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        for indicator in indicators {
+            // NaN/inf must never reach ClickHouse
+            insert.write(&indicator.sanitized().stamped(now_ms)).await?;
+        }
+        insert.end().await?;
+
+        debug!("Inserted {} indicators into {}", count, table);
+
+        Ok(count as u64)
+    }
+
+    async fn get_timeframe_indicator_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+    ) -> Result<Option<DbIndicator>, clickhouse::error::Error> {
+        let query = format!(
+            "SELECT ?fields
+            FROM market_data.tinkoff_indicators_{}
+            WHERE instrument_uid = ? AND time <= ?
+            ORDER BY time DESC
+            LIMIT 1",
+            timeframe.name()
+        );
+
+        self.connection
+            .get_client()
+            .query(&query)
+            .bind(instrument_uid)
+            .bind(time + 60 - timeframe.seconds())
+            .fetch_optional::<DbIndicator>()
+            .await
+    }
+
+    async fn get_indicators_page(
+        &self,
+        instrument_uid: &str,
+        after: Option<i64>,
+        order: SortOrder,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let cursor_condition = match (after, order) {
+            (None, _) => "",
+            (Some(_), SortOrder::Asc) => "AND time > ?",
+            (Some(_), SortOrder::Desc) => "AND time < ?",
+        };
+        let query = format!(
+            "SELECT ?fields
+            FROM {}
+            WHERE instrument_uid = ? {} {}
+            ORDER BY time {}
+            LIMIT ?",
+            self.read_table(),
+            cursor_condition,
+            if as_of.is_some() { AS_OF_CONDITION } else { "" },
+            order.as_sql()
+        );
+
+        let mut query = client.query(&query).bind(instrument_uid);
+        if let Some(after) = after {
+            query = query.bind(after);
+        }
+        if let Some(as_of) = as_of {
+            query = query.bind(as_of);
+        }
+
+        let result = query.bind(limit as u64).fetch_all::<DbIndicator>().await?;
+
+        debug!(
+            "Retrieved {} indicators for instrument_uid={} (after={:?}, order={:?})",
+            result.len(),
+            instrument_uid,
+            after,
+            order
+        );
+
+        Ok(result)
+    }
+
+    async fn get_indicators_range(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        to: i64,
+        limit: usize,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let result = client
+            .query(&format!(
+                "SELECT ?fields
+                FROM {}
+                WHERE instrument_uid = ? AND time >= ? AND time <= ?
+                ORDER BY time ASC
+                LIMIT ?",
+                self.read_table()
+            ))
+            .bind(instrument_uid)
+            .bind(from)
+            .bind(to)
+            .bind(limit as u64)
+            .fetch_all::<DbIndicator>()
+            .await?;
+
+        debug!(
+            "Retrieved {} indicators for instrument_uid={} in [{}, {}]",
+            result.len(),
+            instrument_uid,
+            from,
+            to
+        );
+
+        Ok(result)
+    }
+
+    async fn get_indicators_multi(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let mut query = client
+            .query(&format!(
+                "SELECT ?fields
+                FROM {}
+                WHERE instrument_uid IN ? AND time >= ? AND time <= ? {}
+                ORDER BY instrument_uid ASC, time ASC
+                LIMIT ?",
+                self.read_table(),
+                if as_of.is_some() { AS_OF_CONDITION } else { "" }
+            ))
+            .bind(instrument_uids)
+            .bind(from)
+            .bind(to);
+        if let Some(as_of) = as_of {
+            query = query.bind(as_of);
+        }
+        let result = query.bind(limit as u64).fetch_all::<DbIndicator>().await?;
+
+        debug!(
+            "Retrieved {} indicators for {} instruments in [{}, {}]",
+            result.len(),
+            instrument_uids.len(),
+            from,
+            to
+        );
+
+        Ok(result)
+    }
+
+    async fn get_indicators_downsampled(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+        columns: &[(String, Aggregation)],
+        limit: usize,
+    ) -> Result<Vec<Map<String, Value>>, clickhouse::error::Error> {
+        const PREFIX: &str = "agg_";
+
+        // Aliases are prefixed so they never shadow source columns inside other aggregates
+        let aggregates: String = columns
+            .iter()
+            .map(|(column, aggregation)| match aggregation {
+                Aggregation::Avg => format!(", avg({0}) AS {1}{0}", column, PREFIX),
+                Aggregation::Last => format!(", argMax({0}, time) AS {1}{0}", column, PREFIX),
+            })
+            .collect();
+        let query = format!(
+            "SELECT instrument_uid, intDiv(time, ?) * ? AS bucket_time{}
+            FROM {}
+            WHERE instrument_uid IN ? AND time >= ? AND time <= ?
+            GROUP BY instrument_uid, bucket_time
+            ORDER BY instrument_uid ASC, bucket_time ASC
+            LIMIT ?",
+            aggregates,
+            self.read_table()
+        );
+
+        let client = self
+            .connection
+            .get_client()
+            .with_option("output_format_json_quote_64bit_integers", "0");
+
+        let bytes = client
+            .query(&query)
+            .bind(bucket_seconds)
+            .bind(bucket_seconds)
+            .bind(instrument_uids)
+            .bind(from)
+            .bind(to)
+            .bind(limit as u64)
+            .fetch_bytes("JSONEachRow")?
+            .collect()
+            .await?;
+
+        let mut result = Vec::new();
+        for line in bytes.split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+            let row: Map<String, Value> =
+                serde_json::from_slice(line).map_err(|e| clickhouse::error::Error::Custom(e.to_string()))?;
+            result.push(
+                row.into_iter()
+                    .map(|(name, value)| match name.as_str() {
+                        "bucket_time" => ("time".to_string(), value),
+                        _ => (name.strip_prefix(PREFIX).unwrap_or(&name).to_string(), value),
+                    })
+                    .collect(),
             );
-            
-            // Build VALUES for SQL batch insert
-        let mut insert = match client.insert("market_data.tinkoff_indicators_1min") {
-            Ok(i) => i,
-            Err(e) => {
-                error!("Failed to create insert context: {}", e);
-                continue;
+        }
+
+        debug!(
+            "Retrieved {} downsampled rows ({}s buckets) for {} instruments in [{}, {}]",
+            result.len(),
+            bucket_seconds,
+            instrument_uids.len(),
+            from,
+            to
+        );
+
+        Ok(result)
+    }
+
+    async fn get_column_stats(
+        &self,
+        instrument_uid: &str,
+        column: &str,
+        from: i64,
+        to: i64,
+        buckets: usize,
+    ) -> Result<Option<DbColumnStats>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let value = format!("toFloat64({})", column);
+        let filter = format!("instrument_uid = ? AND time >= ? AND time <= ? AND isFinite({})", value);
+        let levels: Vec<String> = STATS_QUANTILES.iter().map(|level| level.to_string()).collect();
+
+        #[derive(Debug, Deserialize, clickhouse::Row)]
+        struct SummaryRow {
+            count: u64,
+            min: f64,
+            min_time: i64,
+            max: f64,
+            max_time: i64,
+            mean: f64,
+            stddev: f64,
+            quantiles: Vec<f64>,
+        }
+
+        let summary = client
+            .query(&format!(
+                "SELECT count() AS count,
+                    min({0}) AS min, argMin(time, {0}) AS min_time,
+                    max({0}) AS max, argMax(time, {0}) AS max_time,
+                    avg({0}) AS mean, stddevPop({0}) AS stddev,
+                    quantiles({1})({0}) AS quantiles
+                FROM {2}
+                WHERE {3}",
+                value,
+                levels.join(", "),
+                self.read_table(),
+                filter
+            ))
+            .bind(instrument_uid)
+            .bind(from)
+            .bind(to)
+            .fetch_one::<SummaryRow>()
+            .await?;
+        if summary.count == 0 {
+            return Ok(None);
+        }
+
+        // Equal-width buckets over [min, max], the maximum is counted in the last one
+        let width = (summary.max - summary.min) / buckets as f64;
+        let mut histogram = vec![0; buckets];
+        if width > 0.0 {
+            #[derive(Debug, Deserialize, clickhouse::Row)]
+            struct BucketRow {
+                bucket: u64,
+                count: u64,
             }
-        };
-            
-        for indicator in batch {
-            if let Err(e) = insert.write(indicator).await {
-                error!("Failed to write indicator: {}", e);
-            // Build the complete SQL query
-                continue;
+
+            let rows = client
+                .query(&format!(
+                    "SELECT least(toUInt64(floor(({} - ?) / ?)), ?) AS bucket, count() AS count
+                    FROM {}
+                    WHERE {}
+                    GROUP BY bucket",
+                    value,
+                    self.read_table(),
+                    filter
+                ))
+                .bind(summary.min)
+                .bind(width)
+                .bind(buckets as u64 - 1)
+                .bind(instrument_uid)
+                .bind(from)
+                .bind(to)
+                .fetch_all::<BucketRow>()
+                .await?;
+            for row in rows {
+                histogram[row.bucket as usize] = row.count;
             }
+        } else {
+            histogram[0] = summary.count;
         }
-            
-            // Execute batch insert - no retries on memory errors
-        match insert.end().await {
-                Ok(_) => {
-                    successful_inserts += batch.len();
-                    debug!(
-                        "Successfully inserted batch of {} indicators ({}/{})",
-                        batch.len(),
-                        successful_inserts,
-                        total_count
-                    );
-                }
-                Err(e) => {
-                    error!("Batch insertion failed: {}", e);
-                    
-                    // Instead of retrying on MEMORY_LIMIT_EXCEEDED, just report it and continue
-                    if e.to_string().contains("MEMORY_LIMIT_EXCEEDED") || 
-                       e.to_string().contains("TOO_MANY_PARTS") {
-                        warn!("Memory limit exceeded, skipping this batch and continuing with next");
-                        // For other errors, return immediately
+
+        debug!(
+            "Computed stats of {} for instrument_uid={} over {} rows",
+            column, instrument_uid, summary.count
+        );
+
+        Ok(Some(DbColumnStats {
+            count: summary.count,
+            min: summary.min,
+            min_time: summary.min_time,
+            max: summary.max,
+            max_time: summary.max_time,
+            mean: summary.mean,
+            stddev: summary.stddev,
+            quantiles: summary.quantiles,
+            histogram,
+        }))
+    }
+
+    async fn get_signal_counts(
+        &self,
+        instrument_uids: Option<&[String]>,
+        excluded: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+    ) -> Result<Vec<DbSignalCounts>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let instrument_filter = if instrument_uids.is_some() {
+            "AND instrument_uid IN ?"
+        } else {
+            ""
+        };
+        // The zone before the first row of the range counts as neutral, as in detect_signals
+        let query = format!(
+            "SELECT bucket_time AS time,
+                uniqExact(instrument_uid) AS instruments,
+                countIf(ma_cross = 1) AS golden_cross,
+                countIf(ma_cross = -1) AS death_cross,
+                countIf(rsi_zone = 1 AND prev_rsi_zone != 1) AS rsi_oversold,
+                countIf(rsi_zone = -1 AND prev_rsi_zone != -1) AS rsi_overbought
+            FROM (
+                SELECT instrument_uid, intDiv(time, ?) * ? AS bucket_time, ma_cross, rsi_zone,
+                    lagInFrame(rsi_zone, 1, 0) OVER (
+                        PARTITION BY instrument_uid ORDER BY time ASC
+                        ROWS BETWEEN 1 PRECEDING AND CURRENT ROW
+                    ) AS prev_rsi_zone
+                FROM {}
+                WHERE time >= ? AND time <= ? AND NOT has(?, instrument_uid) {}
+            )
+            GROUP BY bucket_time
+            ORDER BY bucket_time ASC",
+            self.read_table(),
+            instrument_filter
+        );
+
+        let mut query = client
+            .query(&query)
+            .bind(bucket_seconds)
+            .bind(bucket_seconds)
+            .bind(from)
+            .bind(to)
+            .bind(excluded);
+        if let Some(instrument_uids) = instrument_uids {
+            query = query.bind(instrument_uids);
+        }
+        let counts = query.fetch_all::<DbSignalCounts>().await?;
+
+        debug!(
+            "Counted signals in {} buckets of {}s in [{}, {}]",
+            counts.len(),
+            bucket_seconds,
+            from,
+            to
+        );
+
+        Ok(counts)
+    }
+
+    /// Overwrites target columns of already written rows in a single mutation per table,
+    /// whatever the number of instruments. Each row picks its values by the position of its
+    /// (instrument, time) key; the arrays are sent once as query parameters rather than in the
+    /// SQL text, so `max_query_size` doesn't apply. Callers keep to `LABEL_UPDATE_ROWS`.
+    async fn update_labels(&self, updates: &[DbLabelUpdate]) -> Result<(), clickhouse::error::Error> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        let client = self.connection.get_client();
+
+        let keys: Vec<(&str, i64)> = updates.iter().map(|u| (u.instrument_uid.as_str(), u.time)).collect();
+        let mut instrument_uids: Vec<&str> = updates.iter().map(|u| u.instrument_uid.as_str()).collect();
+        instrument_uids.sort_unstable();
+        instrument_uids.dedup();
+        let price_changes: Vec<f64> = updates.iter().map(|u| finite_or_zero(u.price_change_15m)).collect();
+        let signals: Vec<i8> = updates.iter().map(|u| u.signal_15m).collect();
+        let target_changes: Vec<Vec<f64>> = updates
+            .iter()
+            .map(|u| u.target_change.iter().map(|v| finite_or_zero(*v)).collect())
+            .collect();
+        let target_signals: Vec<Vec<i8>> = updates.iter().map(|u| u.target_signal.clone()).collect();
+        let tb_labels: Vec<i8> = updates.iter().map(|u| u.tb_label).collect();
+        let tb_hit_times: Vec<i64> = updates.iter().map(|u| u.tb_hit_time).collect();
+        let fractal_highs: Vec<i8> = updates.iter().map(|u| u.fractal_high).collect();
+        let fractal_lows: Vec<i8> = updates.iter().map(|u| u.fractal_low).collect();
+
+        // Bounds the parts the mutation rewrites to the time range of the updates
+        let oldest = updates.iter().map(|u| u.time).min().unwrap_or(i64::MAX);
+        let newest = updates.iter().map(|u| u.time).max().unwrap_or(i64::MIN);
+
+        // Rows past the hot horizon may already have been moved to the cold table
+        let tables = match self.hot_cutoff() {
+            Some(cutoff) if oldest < cutoff => vec![HOT_TABLE, COLD_TABLE],
+            _ => vec![HOT_TABLE],
+        };
+
+        for table in tables {
+            client
+                .query(&update_labels_query(table))
+                .bind(&instrument_uids)
+                .bind(oldest)
+                .bind(newest)
+                .param("keys", &keys)
+                .param("price_changes", &price_changes)
+                .param("signals", &signals)
+                .param("target_changes", &target_changes)
+                .param("target_signals", &target_signals)
+                .param("tb_labels", &tb_labels)
+                .param("tb_hit_times", &tb_hit_times)
+                .param("fractal_highs", &fractal_highs)
+                .param("fractal_lows", &fractal_lows)
+                .execute()
+                .await?;
+        }
+
+        debug!(
+            "Submitted label update of {} rows for {} instruments",
+            updates.len(),
+            instrument_uids.len()
+        );
+
+        Ok(())
+    }
+
+    async fn upsert_latest_indicator(&self, indicator: DbIndicator) -> Result<(), clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let mut insert = client.insert("market_data.tinkoff_indicators_latest")?;
+        insert
+            .write(&indicator.sanitized().stamped(chrono::Utc::now().timestamp_millis()))
+            .await?;
+        insert.end().await?;
+
+        Ok(())
+    }
+
+    async fn get_latest_indicators(&self) -> Result<Vec<DbIndicator>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let result = client
+            .query(
+                "SELECT ?fields
+                FROM market_data.tinkoff_indicators_latest FINAL
+                ORDER BY instrument_uid",
+            )
+            .fetch_all::<DbIndicator>()
+            .await?;
+
+        debug!("Retrieved latest indicators for {} instruments", result.len());
+
+        Ok(result)
+    }
+
+    async fn insert_indicators(
+        &self,
+        indicators: Vec<DbIndicator>,
+        async_insert: bool,
+    ) -> Result<u64, clickhouse::error::Error> {
+        if indicators.is_empty() {
+            debug!("No indicators to insert");
+            return Ok(0);
+        }
+
+        let client = if async_insert {
+            self.connection
+                .get_client()
+                .with_option("async_insert", "1")
+                .with_option("wait_for_async_insert", "0")
+        } else {
+            self.connection.get_client()
+        };
+
+        // NaN/inf must never reach ClickHouse
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let indicators: Vec<DbIndicator> = indicators
+            .into_iter()
+            .map(|row| row.sanitized().stamped(now_ms))
+            .collect();
+
+        let total_count = indicators.len();
+        let mut successful_inserts = 0;
+
+        // Rows older than the hot horizon go straight to the cold table
+        let (cold, hot): (Vec<DbIndicator>, Vec<DbIndicator>) = match self.hot_cutoff() {
+            Some(cutoff) => indicators.into_iter().partition(|row| row.time < cutoff),
+            None => (Vec::new(), indicators),
+        };
+
+        info!("Starting batch insertion of {} indicators", total_count);
+
+        // A batch that hits a ClickHouse resource limit is retried smaller and a transient
+        // failure after a pause; any other failure is returned so the caller doesn't move past
+        // rows that were never written
+        for (table, rows) in [(HOT_TABLE, &hot), (COLD_TABLE, &cold)] {
+            let mut offset = 0;
+            while offset < rows.len() {
+                let batch = &rows[offset..(offset + self.insert_batch.get()).min(rows.len())];
+
+                match with_retry(&self.retry, "Indicator insert", || insert_batch(&client, table, batch)).await {
+                    Ok(()) => {
+                        offset += batch.len();
+                        successful_inserts += batch.len();
+                        self.insert_batch.record_success();
+                        debug!(
+                            "Successfully inserted batch of {} indicators into {} ({}/{})",
+                            batch.len(),
+                            table,
+                            successful_inserts,
+                            total_count
+                        );
+                    }
+                    Err(e) if is_resource_error(&e) && self.insert_batch.shrink() => {
+                        warn!(
+                            "Insert of {} indicators into {} hit a resource limit, retrying with batches of {}: {}",
+                            batch.len(),
+                            table,
+                            self.insert_batch.get(),
+                            e
+                        );
+                    }
+                    Err(e) => {
+                        error!("Batch insertion into {} failed: {}", table, e);
+                        return Err(e);
                     }
                 }
+
+                // Short pause between batches
+                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
             }
-            
-            // Short pause between batches
-            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
         }
 
         info!(
             "Insertion complete. Successfully inserted {} indicators out of {}",
-            successful_inserts,
-            total_count
+            successful_inserts, total_count
         );
 
         Ok(successful_inserts as u64)
     }
 
-    pub async fn get_all_instrument_uids(&self) -> Result<Vec<String>, clickhouse::error::Error> {
+    /// Moves rows older than the hot horizon to the cold table. Copies first and deletes after,
+    /// so a failed delete leaves the rows in both tables rather than losing them; the next move
+    /// copies only the rows the cold table lacks and deletes them again.
+    async fn move_to_cold(&self) -> Result<(), clickhouse::error::Error> {
+        let Some(cutoff) = self.hot_cutoff() else {
+            return Ok(());
+        };
+        let client = self.connection.get_client();
+
+        // Nothing crossed the horizon since the last move: don't queue a mutation
+        let expired: u64 = client
+            .query(&format!("SELECT count() FROM {} WHERE time < ?", HOT_TABLE))
+            .bind(cutoff)
+            .fetch_one()
+            .await?;
+        if expired == 0 {
+            return Ok(());
+        }
+
+        let (copy, delete) = move_to_cold_queries();
+        client.query(&copy).bind(cutoff).bind(cutoff).execute().await?;
+        client.query(&delete).bind(cutoff).execute().await?;
+
+        info!("Moved {} indicators older than {} to {}", expired, cutoff, COLD_TABLE);
+
+        Ok(())
+    }
+
+    async fn get_all_instrument_uids(&self) -> Result<Vec<String>, clickhouse::error::Error> {
         let client = self.connection.get_client();
-        
+
         // Use more efficient query with a LIMIT to prevent loading too many distinct values at once
         let query = "SELECT DISTINCT instrument_uid FROM market_data.tinkoff_candles_1min";
-        
+
         debug!("Fetching all instrument UIDs with candles");
-        
+
         // Define structure for results
         #[derive(Debug, Deserialize, clickhouse::Row)]
         struct UidRow {
             instrument_uid: String,
         }
-        
+
         let rows = client.query(query).fetch_all::<UidRow>().await?;
-        
+
         // Convert results to Vec<String>
         let result: Vec<String> = rows.into_iter().map(|row| row.instrument_uid).collect();
-        
+
         info!("Fetched {} instrument UIDs with candles", result.len());
-        
+
+        Ok(result)
+    }
+
+    async fn export_inserted(
+        &self,
+        destination: &str,
+        after: Option<i64>,
+        until: i64,
+    ) -> Result<u64, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let mut filter = "insert_time <= fromUnixTimestamp64Milli(?)".to_string();
+        if after.is_some() {
+            filter.push_str(" AND insert_time > fromUnixTimestamp64Milli(?)");
+        }
+        let bind = |query: clickhouse::query::Query| match after {
+            Some(after) => query.bind(until).bind(after),
+            None => query.bind(until),
+        };
+
+        let count = bind(client.query(&format!("SELECT count() FROM {} WHERE {}", self.read_table(), filter)))
+            .fetch_one::<u64>()
+            .await?;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        // `?` is a bind placeholder, URLs of the destination may contain it literally
+        let destination = destination.replace('?', "??");
+        bind(client.query(&format!(
+            "INSERT INTO FUNCTION {} SELECT * FROM {} WHERE {}",
+            destination,
+            self.read_table(),
+            filter
+        )))
+        .execute()
+        .await?;
+
+        info!("Exported {} indicator rows inserted up to {}", count, until);
+
+        Ok(count)
+    }
+
+    async fn get_latest_candle_times(&self) -> Result<HashMap<String, i64>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let query = "SELECT instrument_uid, max(time) AS time
+            FROM market_data.tinkoff_candles_1min
+            GROUP BY instrument_uid";
+
+        #[derive(Debug, Deserialize, clickhouse::Row)]
+        struct LatestTimeRow {
+            instrument_uid: String,
+            time: i64,
+        }
+
+        let rows = client.query(query).fetch_all::<LatestTimeRow>().await?;
+        debug!("Fetched latest candle times of {} instruments", rows.len());
+
+        Ok(rows.into_iter().map(|row| (row.instrument_uid, row.time)).collect())
+    }
+
+    async fn get_previous_day_ohlc(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+    ) -> Result<Option<DbDailyOhlc>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        // Look back a week to skip weekends and holidays
+        let query = "SELECT
+                max(high_units + high_nano / 1000000000) AS high,
+                min(low_units + low_nano / 1000000000) AS low,
+                argMax(close_units + close_nano / 1000000000, time) AS close
+            FROM market_data.tinkoff_candles_1min
+            WHERE instrument_uid = ?
+              AND time < intDiv(?, 86400) * 86400
+              AND time >= intDiv(?, 86400) * 86400 - 7 * 86400
+            GROUP BY intDiv(time, 86400)
+            ORDER BY intDiv(time, 86400) DESC
+            LIMIT 1";
+
+        let result = client
+            .query(query)
+            .bind(instrument_uid)
+            .bind(time)
+            .bind(time)
+            .fetch_optional::<DbDailyOhlc>()
+            .await?;
+
+        debug!(
+            "Retrieved previous day OHLC for instrument_uid={} before time={}: {:?}",
+            instrument_uid, time, result
+        );
+
+        Ok(result)
+    }
+
+    async fn get_volume_baseline(
+        &self,
+        instrument_uid: &str,
+    ) -> Result<Vec<DbVolumeBaseline>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let query = "SELECT
+                instrument_uid,
+                weekday,
+                minute_of_day,
+                mean,
+                stddev,
+                samples,
+                update_time,
+                as_of
+            FROM market_data.tinkoff_volume_baseline FINAL
+            WHERE instrument_uid = ?";
+
+        let result = client
+            .query(query)
+            .bind(instrument_uid)
+            .fetch_all::<DbVolumeBaseline>()
+            .await?;
+
+        debug!(
+            "Retrieved {} volume baseline buckets for instrument_uid={}",
+            result.len(),
+            instrument_uid
+        );
+
+        Ok(result)
+    }
+
+    async fn refresh_volume_baseline(
+        &self,
+        instrument_uid: &str,
+        as_of: i64,
+        lookback_days: u32,
+    ) -> Result<(), clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let query = format!(
+            "INSERT INTO market_data.tinkoff_volume_baseline {}",
+            volume_baselines_query()
+        );
+        bind_volume_baselines(client.query(&query), instrument_uid, as_of, as_of, lookback_days)
+            .execute()
+            .await?;
+
+        info!(
+            "Refreshed volume baseline for instrument_uid={} as of {} over {} days",
+            instrument_uid, as_of, lookback_days
+        );
+
+        Ok(())
+    }
+
+    async fn compute_volume_baselines(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        until: i64,
+        lookback_days: u32,
+    ) -> Result<Vec<DbVolumeBaseline>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let query = volume_baselines_query();
+        let result = bind_volume_baselines(client.query(&query), instrument_uid, from, until, lookback_days)
+            .fetch_all::<DbVolumeBaseline>()
+            .await?;
+
+        debug!(
+            "Computed {} volume baseline buckets for instrument_uid={} as of {}..={}",
+            result.len(),
+            instrument_uid,
+            from,
+            until
+        );
+
         Ok(result)
     }
 }
 
-// Helper to format floating point numbers safely for SQL insertion
-// Replaces NaN and Infinity with NULL
-fn format_float_safe(value: f64) -> String {
-    if value.is_nan() || value.is_infinite() {
-        "NULL".to_string()
-    } else {
-        value.to_string()
+/// Seasonal volume baselines in the column order of tinkoff_volume_baseline, one set per UTC day
+/// `as_of`. A candle of day `d` counts towards the days `d + 1..=d + lookback`, so the baseline
+/// of a day only sees the candles before it. Bound by `bind_volume_baselines`.
+fn volume_baselines_query() -> String {
+    "SELECT
+        instrument_uid,
+        toDayOfWeek(toDateTime(time, 'UTC')) AS weekday,
+        toHour(toDateTime(time, 'UTC')) * 60 + toMinute(toDateTime(time, 'UTC')) AS minute_of_day,
+        avg(volume) AS mean,
+        if(isFinite(stddevSamp(volume)), stddevSamp(volume), 0) AS stddev,
+        count() AS samples,
+        now() AS update_time,
+        day * 86400 AS as_of
+    FROM (
+        SELECT
+            instrument_uid,
+            time,
+            volume,
+            arrayJoin(range(
+                greatest(toInt64(intDiv(toUnixTimestamp(time), 86400)) + 1, ?),
+                least(toInt64(intDiv(toUnixTimestamp(time), 86400)) + ?, ?) + 1
+            )) AS day
+        FROM market_data.tinkoff_candles_1min
+        WHERE instrument_uid = ? AND time >= ? AND time < ?
+    )
+    GROUP BY instrument_uid, weekday, minute_of_day, as_of"
+        .to_string()
+}
+
+/// Binds `volume_baselines_query` for the days starting in `from..=until`
+fn bind_volume_baselines(
+    query: clickhouse::query::Query,
+    instrument_uid: &str,
+    from: i64,
+    until: i64,
+    lookback_days: u32,
+) -> clickhouse::query::Query {
+    let (from_day, until_day) = (from.div_euclid(86_400), until.div_euclid(86_400));
+    query
+        .bind(from_day)
+        .bind(lookback_days as i64)
+        .bind(until_day)
+        .bind(instrument_uid)
+        .bind((from_day - lookback_days as i64) * 86_400)
+        .bind(until_day * 86_400)
+}
+
+/// Copy and delete of `move_to_cold`, each bound to the cutoff (the copy twice). The copy skips
+/// rows the cold table already holds from a move whose delete didn't finish; the delete waits
+/// for its mutation, so the next move never sees the copied rows still in the hot table.
+fn move_to_cold_queries() -> (String, String) {
+    let copy = format!(
+        "INSERT INTO {cold} SELECT * FROM {hot}
+        WHERE time < ?
+            AND (instrument_uid, time) NOT IN (
+                SELECT instrument_uid, time FROM {cold}
+                WHERE time < ? AND time >= (SELECT min(time) FROM {hot})
+            )",
+        cold = COLD_TABLE,
+        hot = HOT_TABLE
+    );
+    let delete = format!(
+        "ALTER TABLE {} DELETE WHERE time < ? SETTINGS mutations_sync = 2",
+        HOT_TABLE
+    );
+    (copy, delete)
+}
+
+/// Label mutation of `update_labels`, bound to the instrument UIDs and the time range of the
+/// updates. The keys and the label columns are server-side parameters, each sent once.
+fn update_labels_query(table: &str) -> String {
+    format!(
+        "ALTER TABLE {}
+        UPDATE
+            price_change_15m = arrayElement({{price_changes:Array(Float64)}}, {index}),
+            signal_15m = arrayElement({{signals:Array(Int8)}}, {index}),
+            target_change = arrayElement({{target_changes:Array(Array(Float64))}}, {index}),
+            target_signal = arrayElement({{target_signals:Array(Array(Int8))}}, {index}),
+            tb_label = arrayElement({{tb_labels:Array(Int8)}}, {index}),
+            tb_hit_time = arrayElement({{tb_hit_times:Array(Int64)}}, {index}),
+            fractal_high = arrayElement({{fractal_highs:Array(Int8)}}, {index}),
+            fractal_low = arrayElement({{fractal_lows:Array(Int8)}}, {index}),
+            labels_time = now64(3)
+        WHERE instrument_uid IN ? AND time >= ? AND time <= ? AND has({keys}, {key})",
+        table,
+        index = "indexOf({keys:Array(Tuple(String, Int64))}, (instrument_uid, toInt64(time)))",
+        keys = "{keys:Array(Tuple(String, Int64))}",
+        key = "(instrument_uid, toInt64(time))",
+    )
+}
+
+/// Aggregation of 1-minute candles into bars of `?` seconds (bound twice), filtered by
+/// `instrument_uid = ?` and `minute_filter` over the minute times, ordered by bar time and
+/// limited by a trailing `?`. OHLC keep their units/nano split: open and close are the
+/// first and last minute, high and low the extreme minutes.
+fn timeframe_bars_query(minute_filter: &str, order: &str) -> String {
+    format!(
+        "SELECT
+            instrument_uid,
+            toInt64(intDiv(minute_time, ?) * ?) AS time,
+            argMin(open_units, minute_time) AS open_units,
+            argMin(open_nano, minute_time) AS open_nano,
+            argMax(high_units, high) AS high_units,
+            argMax(high_nano, high) AS high_nano,
+            argMin(low_units, low) AS low_units,
+            argMin(low_nano, low) AS low_nano,
+            argMax(close_units, minute_time) AS close_units,
+            argMax(close_nano, minute_time) AS close_nano,
+            toInt64(sum(volume)) AS volume
+        FROM (
+            SELECT
+                instrument_uid,
+                time AS minute_time,
+                open_units,
+                open_nano,
+                high_units,
+                high_nano,
+                low_units,
+                low_nano,
+                close_units,
+                close_nano,
+                high_units + high_nano / 1e9 AS high,
+                low_units + low_nano / 1e9 AS low,
+                volume
+            FROM market_data.tinkoff_candles_1min
+            WHERE instrument_uid = ?
+        )
+        WHERE {}
+        GROUP BY instrument_uid, time
+        ORDER BY time {}
+        LIMIT ?",
+        minute_filter, order
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_labels_query() {
+        let query = update_labels_query(HOT_TABLE);
+
+        // Only the instrument UIDs and the time range are written into the text
+        assert_eq!(query.matches('?').count(), 3);
+        assert!(query.contains("WHERE instrument_uid IN ? AND time >= ? AND time <= ?"));
+        assert_eq!(query.matches("{keys:Array(Tuple(String, Int64))}").count(), 9);
+        for param in ["price_changes", "signals", "target_changes", "target_signals", "tb_labels"] {
+            assert_eq!(query.matches(&format!("{{{}:", param)).count(), 1);
+        }
+        assert!(!query.contains("{{"));
+    }
+
+    #[test]
+    fn test_move_to_cold_queries() {
+        let (copy, delete) = move_to_cold_queries();
+
+        // Both statements bind the cutoff; the copy leaves out rows already in the cold table
+        assert_eq!(copy.matches('?').count(), 2);
+        assert!(copy.starts_with(&format!("INSERT INTO {} SELECT * FROM {}", COLD_TABLE, HOT_TABLE)));
+        assert!(copy.contains(&format!(
+            "NOT IN (\n                SELECT instrument_uid, time FROM {}",
+            COLD_TABLE
+        )));
+
+        // The delete finishes before the next run copies again
+        assert_eq!(delete.matches('?').count(), 1);
+        assert!(delete.starts_with(&format!("ALTER TABLE {} DELETE", HOT_TABLE)));
+        assert!(delete.ends_with("SETTINGS mutations_sync = 2"));
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,3 @@
-
 pub mod indicator_repository;
-
+pub mod pipeline_profile_repository;
+pub mod signal_latency_repository;
@@ -0,0 +1,84 @@
+// File: src/db/clickhouse/repository/pipeline_profile_repository.rs
+use crate::db::clickhouse::connection::ClickhouseConnection;
+use crate::db::clickhouse::models::pipeline_profile::{DbPipelineProfile, DbPipelineRunSummary};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Per-run stage timings written by the updater
+#[async_trait]
+pub trait TraitPipelineProfileRepository {
+    /// Writes the per-instrument stage timings of one updater run
+    async fn insert_profiles(&self, profiles: &[DbPipelineProfile]) -> Result<(), clickhouse::error::Error>;
+
+    /// Totals of the last `limit` runs, newest first
+    async fn get_run_summaries(&self, limit: usize) -> Result<Vec<DbPipelineRunSummary>, clickhouse::error::Error>;
+}
+
+pub struct PipelineProfileRepository {
+    pub connection: Arc<ClickhouseConnection>,
+}
+
+impl PipelineProfileRepository {
+    pub fn new(connection: Arc<ClickhouseConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitPipelineProfileRepository for PipelineProfileRepository {
+    async fn insert_profiles(
+        &self,
+        profiles: &[DbPipelineProfile],
+    ) -> Result<(), clickhouse::error::Error> {
+        if profiles.is_empty() {
+            return Ok(());
+        }
+
+        let client = self.connection.get_client();
+
+        let mut insert = client.insert("market_data.tinkoff_pipeline_profile")?;
+        for profile in profiles {
+            insert.write(profile).await?;
+        }
+        insert.end().await?;
+
+        debug!("Recorded pipeline profile of {} instruments", profiles.len());
+
+        Ok(())
+    }
+
+    async fn get_run_summaries(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DbPipelineRunSummary>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let result = client
+            .query(
+                "SELECT
+                    run_id,
+                    toUInt32(min(run_time)) AS run_started,
+                    count() AS instruments,
+                    sum(batches) AS batches_total,
+                    sum(candles) AS candles_total,
+                    sum(fetch_ms) AS fetch_ms_total,
+                    sum(compute_ms) AS compute_ms_total,
+                    sum(insert_ms) AS insert_ms_total,
+                    sum(status_ms) AS status_ms_total,
+                    sum(total_ms) AS wall_ms,
+                    sum(insert_errors) AS insert_errors_total
+                FROM market_data.tinkoff_pipeline_profile
+                GROUP BY run_id
+                ORDER BY run_started DESC
+                LIMIT ?",
+            )
+            .bind(limit as u64)
+            .fetch_all::<DbPipelineRunSummary>()
+            .await?;
+
+        debug!("Retrieved pipeline profile of {} runs", result.len());
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,94 @@
+// File: src/db/clickhouse/repository/signal_latency_repository.rs
+use crate::db::clickhouse::connection::ClickhouseConnection;
+use crate::db::clickhouse::models::signal_latency::{DbSignalLatency, DbStageLatency, LATENCY_QUANTILES};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Stage timestamps of published signals, for the end-to-end latency report
+#[async_trait]
+pub trait TraitSignalLatencyRepository {
+    /// Writes the latency records of published signals
+    async fn insert_latencies(&self, latencies: &[DbSignalLatency]) -> Result<(), clickhouse::error::Error>;
+
+    /// Latency distribution of every stage for signals of candles in `[from, to]`
+    async fn get_stage_latencies(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<DbStageLatency>, clickhouse::error::Error>;
+}
+
+pub struct SignalLatencyRepository {
+    pub connection: Arc<ClickhouseConnection>,
+}
+
+impl SignalLatencyRepository {
+    pub fn new(connection: Arc<ClickhouseConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitSignalLatencyRepository for SignalLatencyRepository {
+    async fn insert_latencies(&self, latencies: &[DbSignalLatency]) -> Result<(), clickhouse::error::Error> {
+        if latencies.is_empty() {
+            return Ok(());
+        }
+
+        let client = self.connection.get_client();
+
+        let mut insert = client.insert("market_data.tinkoff_signal_latency")?;
+        for latency in latencies {
+            insert.write(latency).await?;
+        }
+        insert.end().await?;
+
+        debug!("Recorded latency of {} signals", latencies.len());
+
+        Ok(())
+    }
+
+    async fn get_stage_latencies(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<DbStageLatency>, clickhouse::error::Error> {
+        let client = self.connection.get_client();
+
+        let levels = LATENCY_QUANTILES.map(|level| level.to_string()).join(", ");
+        // One row per stage; delivery and total only for delivered signals
+        let query = format!(
+            "SELECT
+                stage,
+                count() AS count,
+                quantiles({})(toFloat64(duration_ms)) AS quantiles_ms
+            FROM market_data.tinkoff_signal_latency
+            ARRAY JOIN
+                ['fetch', 'compute', 'insert', 'emit', 'deliver', 'total'] AS stage,
+                [
+                    fetched_ms - candle_close_ms,
+                    computed_ms - fetched_ms,
+                    inserted_ms - computed_ms,
+                    emitted_ms - inserted_ms,
+                    delivered_ms - emitted_ms,
+                    delivered_ms - candle_close_ms
+                ] AS duration_ms,
+                [1, 1, 1, 1, delivered_ms > 0, delivered_ms > 0] AS measured
+            WHERE candle_time >= ? AND candle_time <= ? AND measured
+            GROUP BY stage",
+            levels
+        );
+
+        let rows = client
+            .query(&query)
+            .bind(from)
+            .bind(to)
+            .fetch_all::<DbStageLatency>()
+            .await?;
+
+        debug!("Retrieved latency of {} signal stages", rows.len());
+
+        Ok(DbStageLatency::complete(rows))
+    }
+}
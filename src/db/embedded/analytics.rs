@@ -0,0 +1,1073 @@
+// File: src/db/embedded/analytics.rs
+use super::store::EmbeddedStore;
+use crate::api::query::{Aggregation, SortOrder};
+use crate::db::clickhouse::batch_size::MAX_SELECT_ROWS;
+use crate::db::clickhouse::models::column_stats::DbColumnStats;
+use crate::db::clickhouse::models::indicator::{
+    DbCandleConverted, DbCandleRaw, DbDailyOhlc, DbIndicator, DbLabelUpdate,
+};
+use crate::db::clickhouse::models::pipeline_profile::{DbPipelineProfile, DbPipelineRunSummary};
+use crate::db::clickhouse::models::signal_counts::DbSignalCounts;
+use crate::db::clickhouse::models::signal_latency::{DbSignalLatency, DbStageLatency};
+use crate::db::clickhouse::models::volume_baseline::DbVolumeBaseline;
+use crate::db::clickhouse::repository::indicator_repository::TraitIndicatorRepository;
+use crate::db::clickhouse::repository::pipeline_profile_repository::TraitPipelineProfileRepository;
+use crate::db::clickhouse::repository::signal_latency_repository::TraitSignalLatencyRepository;
+use crate::env_config::models::app_config::Timeframe;
+use crate::services::signals::{detect_signals, SignalKind};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use clickhouse::error::Error;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::debug;
+
+/// Rows written by a time (unix ms), bound after the other conditions
+const AS_OF_CONDITION: &str = "AND json_extract(row, '$.insert_time') <= ?";
+
+const CANDLE_COLUMNS: &str = "instrument_uid, time, open_units, open_nano, high_units, high_nano,
+    low_units, low_nano, close_units, close_nano, volume";
+
+/// Reports SQLite/JSON failures through the analytics repository error type
+fn store_error(e: impl std::fmt::Display) -> Error {
+    Error::Custom(e.to_string())
+}
+
+fn parse_rows(rows: Vec<String>) -> Result<Vec<DbIndicator>, Error> {
+    rows.iter()
+        .map(|row| serde_json::from_str(row).map_err(store_error))
+        .collect()
+}
+
+/// `?, ?, ...` for an `IN` list of `count` values
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+fn price(units: i64, nano: i32) -> f64 {
+    units as f64 + nano as f64 / 1e9
+}
+
+/// Aggregates ascending 1-minute candles into bars of `seconds`
+fn resample(candles: &[DbCandleRaw], seconds: i64) -> Vec<DbCandleRaw> {
+    let mut bars: Vec<DbCandleRaw> = Vec::new();
+    for candle in candles {
+        let time = candle.time.div_euclid(seconds) * seconds;
+        match bars.last_mut() {
+            Some(bar) if bar.time == time => {
+                if price(candle.high_units, candle.high_nano) > price(bar.high_units, bar.high_nano) {
+                    (bar.high_units, bar.high_nano) = (candle.high_units, candle.high_nano);
+                }
+                if price(candle.low_units, candle.low_nano) < price(bar.low_units, bar.low_nano) {
+                    (bar.low_units, bar.low_nano) = (candle.low_units, candle.low_nano);
+                }
+                (bar.close_units, bar.close_nano) = (candle.close_units, candle.close_nano);
+                bar.volume += candle.volume;
+            }
+            _ => bars.push(DbCandleRaw {
+                time,
+                ..candle.clone()
+            }),
+        }
+    }
+    bars
+}
+
+impl EmbeddedStore {
+    /// Writes 1-minute candles in one transaction; the service only reads them, this seeds
+    /// the store of `t-indicators bench`
+    pub async fn insert_candles(&self, candles: &[DbCandleRaw]) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        let query = format!(
+            "INSERT OR REPLACE INTO candles_1min ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            CANDLE_COLUMNS
+        );
+        for candle in candles {
+            sqlx::query(&query)
+                .bind(&candle.instrument_uid)
+                .bind(candle.time)
+                .bind(candle.open_units)
+                .bind(candle.open_nano)
+                .bind(candle.high_units)
+                .bind(candle.high_nano)
+                .bind(candle.low_units)
+                .bind(candle.low_nano)
+                .bind(candle.close_units)
+                .bind(candle.close_nano)
+                .bind(candle.volume)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)?;
+
+        Ok(candles.len() as u64)
+    }
+
+    /// 1-minute candles with `from <= time < until`, in ascending time order
+    async fn fetch_candles_range(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        until: i64,
+        order: SortOrder,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, Error> {
+        let query = format!(
+            "SELECT {} FROM candles_1min WHERE instrument_uid = ? AND time >= ? AND time < ?
+            ORDER BY time {} LIMIT ?",
+            CANDLE_COLUMNS,
+            if order == SortOrder::Asc { "ASC" } else { "DESC" }
+        );
+
+        let mut candles = sqlx::query_as::<_, DbCandleRaw>(&query)
+            .bind(instrument_uid)
+            .bind(from)
+            .bind(until)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+        if order == SortOrder::Desc {
+            candles.reverse();
+        }
+
+        Ok(candles)
+    }
+
+    async fn fetch_rows_multi(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, Error> {
+        let query = format!(
+            "SELECT row FROM indicators_1min
+            WHERE instrument_uid IN ({}) AND time >= ? AND time <= ? {}
+            ORDER BY instrument_uid ASC, time ASC
+            LIMIT ?",
+            placeholders(instrument_uids.len()),
+            if as_of.is_some() { AS_OF_CONDITION } else { "" }
+        );
+
+        let mut query = sqlx::query_scalar::<_, String>(&query);
+        for uid in instrument_uids {
+            query = query.bind(uid);
+        }
+        query = query.bind(from).bind(to);
+        if let Some(as_of) = as_of {
+            query = query.bind(as_of);
+        }
+        let rows = query
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+
+        parse_rows(rows)
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorRepository for EmbeddedStore {
+    async fn ping(&self) -> Result<(), Error> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(store_error)
+    }
+
+    async fn truncate_indicators(&self) -> Result<(), Error> {
+        sqlx::query("DELETE FROM indicators_1min")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(store_error)
+    }
+
+    async fn delete_indicators_after(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+        timeframe: Option<Timeframe>,
+    ) -> Result<(), Error> {
+        let query = match timeframe {
+            Some(timeframe) => sqlx::query("DELETE FROM indicators_tf WHERE timeframe = ? AND instrument_uid = ? AND time > ?")
+                .bind(timeframe.name()),
+            None => sqlx::query("DELETE FROM indicators_1min WHERE instrument_uid = ? AND time > ?"),
+        };
+        query
+            .bind(instrument_uid)
+            .bind(time)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(store_error)
+    }
+
+    async fn get_candles_after_time(
+        &self,
+        instrument_uid: &str,
+        last_processed_time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, Error> {
+        let query = format!(
+            "SELECT {} FROM candles_1min WHERE instrument_uid = ? AND time > ? ORDER BY time ASC LIMIT ?",
+            CANDLE_COLUMNS
+        );
+
+        sqlx::query_as::<_, DbCandleRaw>(&query)
+            .bind(instrument_uid)
+            .bind(last_processed_time)
+            .bind(limit.min(MAX_SELECT_ROWS) as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn get_candles_before_time(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, Error> {
+        let query = format!(
+            "SELECT {} FROM candles_1min WHERE instrument_uid = ? AND time <= ? ORDER BY time DESC LIMIT ?",
+            CANDLE_COLUMNS
+        );
+
+        sqlx::query_as::<_, DbCandleRaw>(&query)
+            .bind(instrument_uid)
+            .bind(time)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn get_timeframe_candles_after(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        after_time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, Error> {
+        let seconds = timeframe.seconds();
+        let from = (after_time.div_euclid(seconds) + 1) * seconds;
+        let latest: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(time) FROM candles_1min WHERE instrument_uid = ?")
+                .bind(instrument_uid)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(store_error)?;
+        let Some(latest) = latest else {
+            return Ok(Vec::new());
+        };
+        // Start of the bar containing the minute after the latest candle
+        let until = (latest + 60).div_euclid(seconds) * seconds;
+
+        let minutes = (seconds / 60) as usize;
+        let candles = self
+            .fetch_candles_range(instrument_uid, from, until, SortOrder::Asc, limit.min(MAX_SELECT_ROWS) * minutes)
+            .await?;
+        let mut bars = resample(&candles, seconds);
+        // A truncated fetch may end inside a bar
+        if candles.len() == limit.min(MAX_SELECT_ROWS) * minutes {
+            bars.pop();
+        }
+        bars.truncate(limit);
+
+        Ok(bars)
+    }
+
+    async fn get_timeframe_candles_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+        limit: usize,
+    ) -> Result<Vec<DbCandleRaw>, Error> {
+        let seconds = timeframe.seconds();
+        let until = (time.div_euclid(seconds) + 1) * seconds;
+
+        let minutes = (seconds / 60) as usize;
+        let candles = self
+            .fetch_candles_range(instrument_uid, i64::MIN, until, SortOrder::Desc, limit * minutes)
+            .await?;
+        let mut bars = resample(&candles, seconds);
+        // A truncated fetch may start inside a bar
+        if candles.len() == limit * minutes && !bars.is_empty() {
+            bars.remove(0);
+        }
+        let skip = bars.len().saturating_sub(limit);
+
+        Ok(bars.split_off(skip))
+    }
+
+    /// `async_insert` has no meaning here, rows are written in one transaction
+    async fn insert_timeframe_indicators(
+        &self,
+        timeframe: Timeframe,
+        indicators: Vec<DbIndicator>,
+        _async_insert: bool,
+    ) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        let count = indicators.len() as u64;
+        let now_ms = Utc::now().timestamp_millis();
+        for indicator in indicators {
+            let indicator = indicator.sanitized().stamped(now_ms);
+            let row = serde_json::to_string(&indicator).map_err(store_error)?;
+            sqlx::query("INSERT OR REPLACE INTO indicators_tf (timeframe, instrument_uid, time, row) VALUES (?, ?, ?, ?)")
+                .bind(timeframe.name())
+                .bind(&indicator.instrument_uid)
+                .bind(indicator.time)
+                .bind(row)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)?;
+
+        Ok(count)
+    }
+
+    async fn get_timeframe_indicator_before(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        time: i64,
+    ) -> Result<Option<DbIndicator>, Error> {
+        let row = sqlx::query_scalar::<_, String>(
+            "SELECT row FROM indicators_tf WHERE timeframe = ? AND instrument_uid = ? AND time <= ?
+            ORDER BY time DESC LIMIT 1",
+        )
+        .bind(timeframe.name())
+        .bind(instrument_uid)
+        .bind(time + 60 - timeframe.seconds())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        row.map(|row| serde_json::from_str(&row).map_err(store_error)).transpose()
+    }
+
+    async fn get_indicators_page(
+        &self,
+        instrument_uid: &str,
+        after: Option<i64>,
+        order: SortOrder,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, Error> {
+        let cursor_condition = match (after, order) {
+            (None, _) => "",
+            (Some(_), SortOrder::Asc) => "AND time > ?",
+            (Some(_), SortOrder::Desc) => "AND time < ?",
+        };
+        let query = format!(
+            "SELECT row FROM indicators_1min WHERE instrument_uid = ? {} {} ORDER BY time {} LIMIT ?",
+            cursor_condition,
+            if as_of.is_some() { AS_OF_CONDITION } else { "" },
+            order.as_sql()
+        );
+
+        let mut query = sqlx::query_scalar::<_, String>(&query).bind(instrument_uid);
+        if let Some(after) = after {
+            query = query.bind(after);
+        }
+        if let Some(as_of) = as_of {
+            query = query.bind(as_of);
+        }
+        let rows = query
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+
+        parse_rows(rows)
+    }
+
+    async fn get_indicators_range(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        to: i64,
+        limit: usize,
+    ) -> Result<Vec<DbIndicator>, Error> {
+        self.fetch_rows_multi(&[instrument_uid.to_string()], from, to, limit, None)
+            .await
+    }
+
+    async fn get_indicators_multi(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        limit: usize,
+        as_of: Option<i64>,
+    ) -> Result<Vec<DbIndicator>, Error> {
+        self.fetch_rows_multi(instrument_uids, from, to, limit, as_of).await
+    }
+
+    /// Aggregates in memory over the raw rows of the range
+    async fn get_indicators_downsampled(
+        &self,
+        instrument_uids: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+        columns: &[(String, Aggregation)],
+        limit: usize,
+    ) -> Result<Vec<Map<String, Value>>, Error> {
+        let rows = self
+            .fetch_rows_multi(instrument_uids, from, to, i64::MAX as usize, None)
+            .await?;
+
+        // (instrument, bucket) -> per column (sum, count) for avg or the last value
+        let mut buckets: BTreeMap<(String, i64), Vec<(f64, usize, Value)>> = BTreeMap::new();
+        for row in rows {
+            let bucket = row.time.div_euclid(bucket_seconds) * bucket_seconds;
+            let value = serde_json::to_value(&row).map_err(store_error)?;
+            let state = buckets
+                .entry((row.instrument_uid.clone(), bucket))
+                .or_insert_with(|| vec![(0.0, 0, Value::Null); columns.len()]);
+            for ((column, _), (sum, count, last)) in columns.iter().zip(state.iter_mut()) {
+                let cell = value.get(column).cloned().unwrap_or(Value::Null);
+                if let Some(number) = cell.as_f64() {
+                    *sum += number;
+                    *count += 1;
+                }
+                *last = cell;
+            }
+        }
+
+        let result: Vec<Map<String, Value>> = buckets
+            .into_iter()
+            .take(limit)
+            .map(|((instrument_uid, bucket), state)| {
+                let mut object = Map::new();
+                object.insert("instrument_uid".to_string(), Value::from(instrument_uid));
+                object.insert("time".to_string(), Value::from(bucket));
+                for ((column, aggregation), (sum, count, last)) in columns.iter().zip(state) {
+                    let value = match aggregation {
+                        Aggregation::Avg if count > 0 => Value::from(sum / count as f64),
+                        Aggregation::Avg => Value::Null,
+                        Aggregation::Last => last,
+                    };
+                    object.insert(column.clone(), value);
+                }
+                object
+            })
+            .collect();
+
+        debug!("Downsampled {} buckets in the embedded store", result.len());
+
+        Ok(result)
+    }
+
+    /// Runs `detect_signals` over the raw rows of every instrument
+    async fn get_signal_counts(
+        &self,
+        instrument_uids: Option<&[String]>,
+        excluded: &[String],
+        from: i64,
+        to: i64,
+        bucket_seconds: i64,
+    ) -> Result<Vec<DbSignalCounts>, Error> {
+        let instrument_uids: Vec<String> = match instrument_uids {
+            Some(uids) => uids.to_vec(),
+            None => sqlx::query_scalar::<_, String>("SELECT DISTINCT instrument_uid FROM indicators_1min")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(store_error)?,
+        };
+        let instrument_uids: Vec<String> =
+            instrument_uids.into_iter().filter(|uid| !excluded.contains(uid)).collect();
+
+        let rows = self
+            .fetch_rows_multi(&instrument_uids, from, to, i64::MAX as usize, None)
+            .await?;
+
+        let mut buckets: BTreeMap<i64, (DbSignalCounts, HashSet<String>)> = BTreeMap::new();
+        for uid_rows in rows.chunk_by(|a, b| a.instrument_uid == b.instrument_uid) {
+            for row in uid_rows {
+                let time = row.time.div_euclid(bucket_seconds) * bucket_seconds;
+                buckets.entry(time).or_default().1.insert(row.instrument_uid.clone());
+            }
+            for event in detect_signals(uid_rows) {
+                let time = event.time.div_euclid(bucket_seconds) * bucket_seconds;
+                let (counts, _) = buckets.entry(time).or_default();
+                match event.kind {
+                    SignalKind::GoldenCross => counts.golden_cross += 1,
+                    SignalKind::DeathCross => counts.death_cross += 1,
+                    SignalKind::RsiOversold => counts.rsi_oversold += 1,
+                    SignalKind::RsiOverbought => counts.rsi_overbought += 1,
+                }
+            }
+        }
+
+        Ok(buckets
+            .into_iter()
+            .map(|(time, (counts, instruments))| DbSignalCounts {
+                time,
+                instruments: instruments.len() as u64,
+                ..counts
+            })
+            .collect())
+    }
+
+    /// Computed in memory over the raw rows of the range
+    async fn get_column_stats(
+        &self,
+        instrument_uid: &str,
+        column: &str,
+        from: i64,
+        to: i64,
+        buckets: usize,
+    ) -> Result<Option<DbColumnStats>, Error> {
+        let rows = self
+            .fetch_rows_multi(&[instrument_uid.to_string()], from, to, i64::MAX as usize, None)
+            .await?;
+
+        let mut values = Vec::with_capacity(rows.len());
+        for row in rows {
+            let value = serde_json::to_value(&row).map_err(store_error)?;
+            if let Some(number) = value.get(column).and_then(Value::as_f64) {
+                values.push((row.time, number));
+            }
+        }
+
+        Ok(DbColumnStats::from_values(&values, buckets))
+    }
+
+    async fn update_labels(&self, updates: &[DbLabelUpdate]) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        let now_ms = Utc::now().timestamp_millis();
+        for update in updates {
+            let row = sqlx::query_scalar::<_, String>(
+                "SELECT row FROM indicators_1min WHERE instrument_uid = ? AND time = ?",
+            )
+            .bind(&update.instrument_uid)
+            .bind(update.time)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(store_error)?;
+            let Some(row) = row else {
+                continue;
+            };
+
+            let mut indicator: DbIndicator = serde_json::from_str(&row).map_err(store_error)?;
+            indicator.price_change_15m = update.price_change_15m;
+            indicator.signal_15m = update.signal_15m;
+            indicator.target_change = update.target_change.clone();
+            indicator.target_signal = update.target_signal.clone();
+            indicator.tb_label = update.tb_label;
+            indicator.tb_hit_time = update.tb_hit_time;
+            indicator.fractal_high = update.fractal_high;
+            indicator.fractal_low = update.fractal_low;
+            indicator.labels_time = now_ms;
+            let row = serde_json::to_string(&indicator.sanitized()).map_err(store_error)?;
+
+            sqlx::query("UPDATE indicators_1min SET row = ? WHERE instrument_uid = ? AND time = ?")
+                .bind(row)
+                .bind(&update.instrument_uid)
+                .bind(update.time)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)
+    }
+
+    async fn upsert_latest_indicator(&self, indicator: DbIndicator) -> Result<(), Error> {
+        let indicator = indicator.sanitized().stamped(Utc::now().timestamp_millis());
+        let row = serde_json::to_string(&indicator).map_err(store_error)?;
+
+        sqlx::query("INSERT OR REPLACE INTO indicators_latest (instrument_uid, row) VALUES (?, ?)")
+            .bind(&indicator.instrument_uid)
+            .bind(row)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(store_error)
+    }
+
+    async fn get_latest_indicators(&self) -> Result<Vec<DbIndicator>, Error> {
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT row FROM indicators_latest ORDER BY instrument_uid",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        parse_rows(rows)
+    }
+
+    /// `async_insert` has no meaning here, rows are written in one transaction
+    async fn insert_indicators(
+        &self,
+        indicators: Vec<DbIndicator>,
+        _async_insert: bool,
+    ) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        let count = indicators.len() as u64;
+        let now_ms = Utc::now().timestamp_millis();
+        for indicator in indicators {
+            let indicator = indicator.sanitized().stamped(now_ms);
+            let row = serde_json::to_string(&indicator).map_err(store_error)?;
+            sqlx::query(
+                "INSERT OR REPLACE INTO indicators_1min (instrument_uid, time, row) VALUES (?, ?, ?)",
+            )
+            .bind(&indicator.instrument_uid)
+            .bind(indicator.time)
+            .bind(row)
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)?;
+
+        Ok(count)
+    }
+
+    /// Single tier, nothing to move
+    async fn move_to_cold(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn export_inserted(&self, _destination: &str, _after: Option<i64>, _until: i64) -> Result<u64, Error> {
+        Err(store_error("incremental export needs ClickHouse table functions"))
+    }
+
+    async fn get_all_instrument_uids(&self) -> Result<Vec<String>, Error> {
+        sqlx::query_scalar::<_, String>("SELECT DISTINCT instrument_uid FROM candles_1min")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn get_latest_candle_times(&self) -> Result<HashMap<String, i64>, Error> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT instrument_uid, MAX(time) FROM candles_1min GROUP BY instrument_uid",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn get_previous_day_ohlc(
+        &self,
+        instrument_uid: &str,
+        time: i64,
+    ) -> Result<Option<DbDailyOhlc>, Error> {
+        // Look back a week to skip weekends and holidays
+        let day_start = time.div_euclid(86_400) * 86_400;
+        let query = format!(
+            "SELECT {} FROM candles_1min
+            WHERE instrument_uid = ? AND time < ? AND time >= ?
+            ORDER BY time DESC",
+            CANDLE_COLUMNS
+        );
+        let candles: Vec<DbCandleConverted> = sqlx::query_as::<_, DbCandleRaw>(&query)
+            .bind(instrument_uid)
+            .bind(day_start)
+            .bind(day_start - 7 * 86_400)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?
+            .into_iter()
+            .map(DbCandleConverted::from)
+            .collect();
+
+        let Some(last) = candles.first() else {
+            return Ok(None);
+        };
+        let day = last.time.div_euclid(86_400);
+        let day_candles = candles.iter().take_while(|c| c.time.div_euclid(86_400) == day);
+
+        let mut ohlc = DbDailyOhlc {
+            high: f64::MIN,
+            low: f64::MAX,
+            close: last.close_price,
+        };
+        for candle in day_candles {
+            ohlc.high = ohlc.high.max(candle.high_price);
+            ohlc.low = ohlc.low.min(candle.low_price);
+        }
+
+        Ok(Some(ohlc))
+    }
+
+    async fn get_volume_baseline(&self, instrument_uid: &str) -> Result<Vec<DbVolumeBaseline>, Error> {
+        sqlx::query_as::<_, DbVolumeBaseline>(
+            "SELECT instrument_uid, weekday, minute_of_day, mean, stddev, samples, update_time, as_of
+            FROM volume_baseline_daily WHERE instrument_uid = ?",
+        )
+        .bind(instrument_uid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)
+    }
+
+    async fn refresh_volume_baseline(&self, instrument_uid: &str, as_of: i64, lookback_days: u32) -> Result<(), Error> {
+        let rows = self.compute_volume_baselines(instrument_uid, as_of, as_of, lookback_days).await?;
+
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        // Buckets without candles in the lookback go with the previous day
+        sqlx::query("DELETE FROM volume_baseline_daily WHERE instrument_uid = ?")
+            .bind(instrument_uid)
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        for row in rows {
+            sqlx::query("INSERT INTO volume_baseline_daily VALUES (?, ?, ?, ?, ?, ?, ?, ?)")
+                .bind(&row.instrument_uid)
+                .bind(row.weekday)
+                .bind(row.minute_of_day)
+                .bind(row.mean)
+                .bind(row.stddev)
+                .bind(row.samples)
+                .bind(row.update_time)
+                .bind(row.as_of)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)
+    }
+
+    async fn compute_volume_baselines(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        until: i64,
+        lookback_days: u32,
+    ) -> Result<Vec<DbVolumeBaseline>, Error> {
+        let (from_day, until_day) = (from.div_euclid(86_400), until.div_euclid(86_400));
+        let lookback_days = lookback_days as i64;
+        let candles = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT time, volume FROM candles_1min WHERE instrument_uid = ? AND time >= ? AND time < ?",
+        )
+        .bind(instrument_uid)
+        .bind((from_day - lookback_days) * 86_400)
+        .bind(until_day * 86_400)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        // (day, weekday, minute of day) -> volumes; a candle of day `d` counts towards the days
+        // `d + 1..=d + lookback`, as in ClickHouse
+        let mut buckets: HashMap<(i64, u8, u16), Vec<f64>> = HashMap::new();
+        for (time, volume) in candles {
+            let dt = DateTime::<Utc>::from_timestamp(time, 0).unwrap_or_default();
+            let weekday = dt.weekday().number_from_monday() as u8;
+            let minute_of_day = (dt.hour() * 60 + dt.minute()) as u16;
+            let candle_day = time.div_euclid(86_400);
+            for day in (candle_day + 1).max(from_day)..=(candle_day + lookback_days).min(until_day) {
+                buckets.entry((day, weekday, minute_of_day)).or_default().push(volume as f64);
+            }
+        }
+
+        let now = Utc::now().timestamp() as u32;
+        Ok(buckets
+            .into_iter()
+            .map(|((day, weekday, minute_of_day), volumes)| {
+                let n = volumes.len() as f64;
+                let mean = volumes.iter().sum::<f64>() / n;
+                let stddev = if volumes.len() > 1 {
+                    (volumes.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+                } else {
+                    0.0
+                };
+                DbVolumeBaseline {
+                    instrument_uid: instrument_uid.to_string(),
+                    weekday,
+                    minute_of_day,
+                    mean,
+                    stddev,
+                    samples: volumes.len() as u32,
+                    update_time: now,
+                    as_of: day * 86_400,
+                }
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl TraitPipelineProfileRepository for EmbeddedStore {
+    async fn insert_profiles(&self, profiles: &[DbPipelineProfile]) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        for profile in profiles {
+            let row = serde_json::to_string(profile).map_err(store_error)?;
+            sqlx::query("INSERT INTO pipeline_profile (run_id, row) VALUES (?, ?)")
+                .bind(&profile.run_id)
+                .bind(row)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)
+    }
+
+    async fn get_run_summaries(&self, limit: usize) -> Result<Vec<DbPipelineRunSummary>, Error> {
+        let rows = sqlx::query_scalar::<_, String>("SELECT row FROM pipeline_profile")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+
+        let mut runs: HashMap<String, DbPipelineRunSummary> = HashMap::new();
+        for row in rows {
+            let profile: DbPipelineProfile = serde_json::from_str(&row).map_err(store_error)?;
+            let run = runs.entry(profile.run_id.clone()).or_insert_with(|| DbPipelineRunSummary {
+                run_id: profile.run_id.clone(),
+                run_started: profile.run_time,
+                ..Default::default()
+            });
+            run.run_started = run.run_started.min(profile.run_time);
+            run.instruments += 1;
+            run.batches_total += profile.batches as u64;
+            run.candles_total += profile.candles;
+            run.fetch_ms_total += profile.fetch_ms;
+            run.compute_ms_total += profile.compute_ms;
+            run.insert_ms_total += profile.insert_ms;
+            run.status_ms_total += profile.status_ms;
+            run.wall_ms += profile.total_ms;
+            run.insert_errors_total += profile.insert_errors as u64;
+        }
+
+        let mut runs: Vec<DbPipelineRunSummary> = runs.into_values().collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.run_started));
+        runs.truncate(limit);
+
+        Ok(runs)
+    }
+}
+
+#[async_trait]
+impl TraitSignalLatencyRepository for EmbeddedStore {
+    async fn insert_latencies(&self, latencies: &[DbSignalLatency]) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        for latency in latencies {
+            let row = serde_json::to_string(latency).map_err(store_error)?;
+            sqlx::query("INSERT INTO signal_latency (candle_time, row) VALUES (?, ?)")
+                .bind(latency.candle_time as i64)
+                .bind(row)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)
+    }
+
+    /// Computed in memory over the records of the range
+    async fn get_stage_latencies(&self, from: i64, to: i64) -> Result<Vec<DbStageLatency>, Error> {
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT row FROM signal_latency WHERE candle_time >= ? AND candle_time <= ?",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        let records = rows
+            .iter()
+            .map(|row| serde_json::from_str(row).map_err(store_error))
+            .collect::<Result<Vec<DbSignalLatency>, Error>>()?;
+
+        Ok(DbStageLatency::from_records(&records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(time: i64, rsi_14: f64) -> DbIndicator {
+        DbIndicator {
+            instrument_uid: "uid".to_string(),
+            time,
+            rsi_14,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_indicator_roundtrip() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        let rows = vec![row(0, 10.0), row(60, 20.0), row(120, 40.0)];
+        assert_eq!(store.insert_indicators(rows, true).await.unwrap(), 3);
+
+        let update = DbLabelUpdate {
+            instrument_uid: "uid".to_string(),
+            time: 60,
+            signal_15m: 1,
+            ..Default::default()
+        };
+        store.update_labels(&[update]).await.unwrap();
+
+        let page = store
+            .get_indicators_page("uid", Some(0), SortOrder::Asc, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(page.iter().map(|r| r.time).collect::<Vec<_>>(), vec![60, 120]);
+        assert_eq!(page[0].signal_15m, 1);
+
+        let columns = vec![("rsi_14".to_string(), Aggregation::Avg)];
+        let buckets = store
+            .get_indicators_downsampled(&["uid".to_string()], 0, 120, 120, &columns, 10)
+            .await
+            .unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0]["rsi_14"], Value::from(15.0));
+        assert_eq!(buckets[1]["time"], Value::from(120));
+    }
+
+    #[tokio::test]
+    async fn test_volume_baselines_are_point_in_time() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+        let candle = |day: i64, volume: i64| DbCandleRaw {
+            instrument_uid: "uid".to_string(),
+            time: day * 86_400 + 600,
+            open_units: 100,
+            open_nano: 0,
+            high_units: 100,
+            high_nano: 0,
+            low_units: 100,
+            low_nano: 0,
+            close_units: 100,
+            close_nano: 0,
+            volume,
+        };
+        // Volume 100 on days 0-2, then 1000 on day 3
+        let candles: Vec<DbCandleRaw> = (0..3).map(|day| candle(day, 100)).chain([candle(3, 1000)]).collect();
+        store.insert_candles(&candles).await.unwrap();
+
+        let rows = store.compute_volume_baselines("uid", 86_400, 4 * 86_400, 2).await.unwrap();
+        // Mean over the weekday buckets of the day, and their samples
+        let mean_of = |day: i64| {
+            let rows: Vec<_> = rows.iter().filter(|row| row.as_of == day * 86_400).collect();
+            let samples: u32 = rows.iter().map(|row| row.samples).sum();
+            let total: f64 = rows.iter().map(|row| row.mean * row.samples as f64).sum();
+            (samples > 0).then(|| (total / samples as f64, samples))
+        };
+        // Each day only sees the two days before it: day 3 doesn't leak into day 3 itself
+        assert_eq!(mean_of(1), Some((100.0, 1)));
+        assert_eq!(mean_of(3), Some((100.0, 2)));
+        assert_eq!(mean_of(4), Some((550.0, 2)));
+        assert_eq!(mean_of(0), None);
+
+        store.refresh_volume_baseline("uid", 3 * 86_400, 2).await.unwrap();
+        let stored = store.get_volume_baseline("uid").await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored.iter().all(|row| row.as_of == 3 * 86_400 && row.mean == 100.0));
+    }
+
+    #[tokio::test]
+    async fn test_as_of() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        store.insert_indicators(vec![row(0, 10.0)], true).await.unwrap();
+        let first = store.get_indicators_page("uid", None, SortOrder::Asc, 10, None).await.unwrap();
+        let as_of = first[0].insert_time;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        store.insert_indicators(vec![row(60, 20.0)], true).await.unwrap();
+        let update = DbLabelUpdate { instrument_uid: "uid".to_string(), time: 0, signal_15m: 1, ..Default::default() };
+        store.update_labels(&[update]).await.unwrap();
+
+        let page = store.get_indicators_page("uid", None, SortOrder::Asc, 10, Some(as_of)).await.unwrap();
+        assert_eq!(page.iter().map(|r| r.time).collect::<Vec<_>>(), vec![0]);
+        assert!(page[0].labels_time > as_of);
+        let rows = store.get_indicators_multi(&["uid".to_string()], 0, 60, 10, Some(as_of)).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        let rows = store.get_indicators_multi(&["uid".to_string()], 0, 60, 10, None).await.unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_signal_counts() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        let signal_row = |uid: &str, time: i64, ma_cross: i8, rsi_zone: i8| DbIndicator {
+            instrument_uid: uid.to_string(),
+            time,
+            ma_cross,
+            rsi_zone,
+            ..Default::default()
+        };
+        let rows = vec![
+            signal_row("sber", 0, 1, 1),
+            // Still oversold: no new entry
+            signal_row("sber", 60, 0, 1),
+            signal_row("sber", 3600, -1, -1),
+            signal_row("gazp", 120, 1, 0),
+            signal_row("lkoh", 60, 1, 0),
+        ];
+        store.insert_indicators(rows, true).await.unwrap();
+
+        let counts = store
+            .get_signal_counts(None, &["lkoh".to_string()], 0, 7200, 3600)
+            .await
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!((counts[0].time, counts[0].instruments), (0, 2));
+        assert_eq!((counts[0].golden_cross, counts[0].rsi_oversold), (2, 1));
+        assert_eq!((counts[1].death_cross, counts[1].rsi_overbought), (1, 1));
+
+        let watchlist = ["gazp".to_string()];
+        let counts = store.get_signal_counts(Some(&watchlist), &[], 0, 7200, 86_400).await.unwrap();
+        assert_eq!(counts, vec![DbSignalCounts { time: 0, instruments: 1, golden_cross: 1, ..Default::default() }]);
+    }
+
+    fn candle(time: i64, close: i64, volume: i64) -> DbCandleRaw {
+        DbCandleRaw {
+            instrument_uid: "uid".to_string(),
+            time,
+            open_units: close,
+            open_nano: 0,
+            high_units: close,
+            high_nano: 500_000_000,
+            low_units: close - 1,
+            low_nano: 0,
+            close_units: close,
+            close_nano: 0,
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_resample() {
+        let candles: Vec<DbCandleRaw> = [(0, 10), (60, 12), (120, 11), (240, 9), (300, 8), (360, 14)]
+            .iter()
+            .map(|&(time, close)| candle(time, close, 5))
+            .collect();
+
+        // 5-minute bars; the 180 minute is missing
+        let bars = resample(&candles, 300);
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!((bars[0].time, bars[0].open_units, bars[0].close_units), (0, 10, 9));
+        assert_eq!((bars[0].high_units, bars[0].low_units, bars[0].volume), (12, 8, 20));
+        assert_eq!((bars[1].time, bars[1].open_units, bars[1].close_units), (300, 8, 14));
+        assert_eq!((bars[1].high_units, bars[1].low_units, bars[1].volume), (14, 7, 10));
+    }
+}
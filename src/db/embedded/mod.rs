@@ -0,0 +1,5 @@
+//! Embedded SQLite store for local development (`embedded.enabled`, built with
+//! `--features embedded`). Implements the ClickHouse and PostgreSQL repository traits so the
+//! updater and the API run without any database server. Not meant for production volumes.
+pub mod analytics;
+pub mod store;
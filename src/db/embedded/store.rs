@@ -0,0 +1,828 @@
+// File: src/db/embedded/store.rs
+use crate::db::postgres::repository::export_watermark_repository::TraitExportWatermarkRepository;
+use crate::db::postgres::repository::feature_flag_repository::TraitFeatureFlagRepository;
+use crate::db::postgres::repository::feature_version_repository::TraitFeatureVersionRepository;
+use crate::db::postgres::repository::health_check_repository::TraitHealthCheckRepository;
+use crate::db::postgres::repository::indicator_status_repository::TraitIndicatorStatusRepository;
+use crate::db::postgres::repository::run_lock_repository::{InstrumentLock, RunLockGuard, TraitRunLockRepository};
+use crate::db::postgres::repository::signal_cooldown_repository::TraitSignalCooldownRepository;
+use crate::db::postgres::repository::signal_suppression_repository::TraitSignalSuppressionRepository;
+use crate::db::postgres::repository::timeframe_status_repository::TraitTimeframeStatusRepository;
+use crate::db::postgres::repository::instrument_registry_repository::TraitInstrumentRegistryRepository;
+use crate::db::postgres::repository::indicator_state_repository::TraitIndicatorStateRepository;
+use crate::db::postgres::repository::instrument_filter_repository::TraitInstrumentFilterRepository;
+use crate::db::postgres::repository::indicator_failure_repository::TraitIndicatorFailureRepository;
+use crate::db::postgres::repository::indicator_run_repository::TraitIndicatorRunRepository;
+use crate::db::postgres::models::indicator_failure::{PgFailingInstrument, PgIndicatorFailure};
+use crate::db::postgres::models::indicator_run::PgIndicatorRun;
+use crate::db::postgres::models::instrument_filter::PgInstrumentFilter;
+use crate::db::postgres::models::indicator_state::PgIndicatorState;
+use crate::db::postgres::models::signal_suppression::PgSignalSuppression;
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+/// Tables of the embedded store. Indicator rows are kept as JSON of `DbIndicator`, so new
+/// columns need no schema change here.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS candles_1min (
+    instrument_uid TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    open_units INTEGER NOT NULL,
+    open_nano INTEGER NOT NULL,
+    high_units INTEGER NOT NULL,
+    high_nano INTEGER NOT NULL,
+    low_units INTEGER NOT NULL,
+    low_nano INTEGER NOT NULL,
+    close_units INTEGER NOT NULL,
+    close_nano INTEGER NOT NULL,
+    volume INTEGER NOT NULL,
+    PRIMARY KEY (instrument_uid, time)
+);
+CREATE TABLE IF NOT EXISTS indicators_1min (
+    instrument_uid TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    row TEXT NOT NULL,
+    PRIMARY KEY (instrument_uid, time)
+);
+CREATE TABLE IF NOT EXISTS indicators_latest (
+    instrument_uid TEXT PRIMARY KEY,
+    row TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS volume_baseline_daily (
+    instrument_uid TEXT NOT NULL,
+    weekday INTEGER NOT NULL,
+    minute_of_day INTEGER NOT NULL,
+    mean REAL NOT NULL,
+    stddev REAL NOT NULL,
+    samples INTEGER NOT NULL,
+    update_time INTEGER NOT NULL,
+    as_of INTEGER NOT NULL,
+    PRIMARY KEY (instrument_uid, weekday, minute_of_day)
+);
+CREATE TABLE IF NOT EXISTS pipeline_profile (
+    run_id TEXT NOT NULL,
+    row TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS signal_latency (
+    candle_time INTEGER NOT NULL,
+    row TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS indicators_status (
+    instrument_uid TEXT PRIMARY KEY,
+    last_processed_time INTEGER NOT NULL,
+    update_time INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS indicators_status_tf (
+    instrument_uid TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    last_processed_time INTEGER NOT NULL,
+    PRIMARY KEY (instrument_uid, timeframe)
+);
+CREATE TABLE IF NOT EXISTS indicators_tf (
+    timeframe TEXT NOT NULL,
+    instrument_uid TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    row TEXT NOT NULL,
+    PRIMARY KEY (timeframe, instrument_uid, time)
+);
+CREATE TABLE IF NOT EXISTS instrument_registry (
+    instrument_uid TEXT PRIMARY KEY,
+    first_seen_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS indicator_state (
+    instrument_uid TEXT PRIMARY KEY,
+    last_time INTEGER NOT NULL,
+    fingerprint TEXT NOT NULL,
+    state TEXT NOT NULL,
+    update_time INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS feature_flags (
+    name TEXT PRIMARY KEY,
+    enabled INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS feature_versions (
+    feature TEXT PRIMARY KEY,
+    version TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS export_watermarks (
+    target TEXT PRIMARY KEY,
+    watermark INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS signal_suppression (
+    instrument_uid TEXT PRIMARY KEY,
+    reason TEXT NOT NULL,
+    suppressed_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS instrument_filters (
+    instrument_uid TEXT PRIMARY KEY,
+    mode TEXT NOT NULL CHECK (mode IN ('allow', 'deny')),
+    reason TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE IF NOT EXISTS indicators_failures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    instrument_uid TEXT NOT NULL,
+    job_id TEXT NOT NULL,
+    error TEXT NOT NULL,
+    batch_from INTEGER NOT NULL,
+    batch_to INTEGER,
+    failure_time INTEGER NOT NULL,
+    resolve_time INTEGER
+);
+CREATE TABLE IF NOT EXISTS indicators_runs (
+    run_id TEXT PRIMARY KEY,
+    trigger TEXT NOT NULL,
+    cycle INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER,
+    instruments INTEGER NOT NULL DEFAULT 0,
+    instruments_failed INTEGER NOT NULL DEFAULT 0,
+    candles_processed INTEGER NOT NULL DEFAULT 0,
+    rows_inserted INTEGER NOT NULL DEFAULT 0,
+    insert_errors INTEGER NOT NULL DEFAULT 0,
+    error TEXT
+);
+CREATE TABLE IF NOT EXISTS signal_cooldown (
+    instrument_uid TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    last_emitted_time INTEGER NOT NULL,
+    PRIMARY KEY (instrument_uid, kind)
+);
+";
+
+/// Single-file SQLite database standing in for both ClickHouse and PostgreSQL
+pub struct EmbeddedStore {
+    pub(super) pool: SqlitePool,
+    // Job holding each locked instrument, the dev process being the only one
+    instrument_locks: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl EmbeddedStore {
+    /// Opens (creating if needed) the database at `path`; `:memory:` keeps it in memory
+    pub async fn open(path: &str) -> Result<Self, SqlxError> {
+        info!("Opening embedded store at {}", path);
+
+        let options = SqliteConnectOptions::from_str(path)?.create_if_missing(true);
+        // One connection: writes are serialized anyway and `:memory:` is per connection
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+
+        Ok(Self {
+            pool,
+            instrument_locks: Arc::default(),
+        })
+    }
+}
+
+/// Entry of `EmbeddedStore::instrument_locks`, removed on drop
+struct EmbeddedInstrumentLock {
+    locks: Arc<Mutex<HashMap<String, String>>>,
+    instrument_uid: String,
+}
+
+impl Drop for EmbeddedInstrumentLock {
+    fn drop(&mut self) {
+        self.locks.lock().unwrap().remove(&self.instrument_uid);
+    }
+}
+
+#[async_trait]
+impl TraitHealthCheckRepository for EmbeddedStore {
+    async fn check(&self) -> Result<bool, SqlxError> {
+        let result = sqlx::query_scalar::<_, i32>("SELECT 1")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(result == 1)
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorStatusRepository for EmbeddedStore {
+    async fn get_last_processed_time(&self, instrument_uid: &str) -> Result<Option<i64>, SqlxError> {
+        let result = sqlx::query_scalar::<_, i64>(
+            "SELECT last_processed_time FROM indicators_status WHERE instrument_uid = ?",
+        )
+        .bind(instrument_uid)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        debug!("Retrieved last processed time for {}: {:?}", instrument_uid, result);
+
+        Ok(result)
+    }
+
+    /// Run IDs are only kept in PostgreSQL, the embedded store serves a single dev process
+    async fn update_last_processed_time(&self, instrument_uid: &str, time: i64, _run_id: &str) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO indicators_status (instrument_uid, last_processed_time, update_time)
+             VALUES (?, ?, unixepoch())
+             ON CONFLICT (instrument_uid)
+             DO UPDATE SET last_processed_time = excluded.last_processed_time, update_time = unixepoch()",
+        )
+        .bind(instrument_uid)
+        .bind(time)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Updated last processed time for {}: {}", instrument_uid, time);
+
+        Ok(())
+    }
+
+    async fn count(&self) -> Result<i64, SqlxError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM indicators_status")
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_all_last_processed_times(&self) -> Result<HashMap<String, i64>, SqlxError> {
+        let rows = sqlx::query_as::<_, (String, i64)>("SELECT instrument_uid, last_processed_time FROM indicators_status")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl TraitFeatureFlagRepository for EmbeddedStore {
+    async fn get_overrides(&self) -> Result<HashMap<String, bool>, SqlxError> {
+        let rows = sqlx::query_as::<_, (String, bool)>("SELECT name, enabled FROM feature_flags")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl TraitFeatureVersionRepository for EmbeddedStore {
+    async fn get_feature_versions(&self) -> Result<HashMap<String, String>, SqlxError> {
+        let rows = sqlx::query_as::<_, (String, String)>("SELECT feature, version FROM feature_versions")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn save_feature_versions(&self, versions: &[(String, String)]) -> Result<(), SqlxError> {
+        let mut tx = self.pool.begin().await?;
+        for (feature, version) in versions {
+            sqlx::query("INSERT OR REPLACE INTO feature_versions VALUES (?, ?)")
+                .bind(feature)
+                .bind(version)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+}
+
+#[async_trait]
+impl TraitExportWatermarkRepository for EmbeddedStore {
+    async fn get_export_watermark(&self, target: &str) -> Result<Option<i64>, SqlxError> {
+        sqlx::query_scalar::<_, i64>("SELECT watermark FROM export_watermarks WHERE target = ?")
+            .bind(target)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn save_export_watermark(&self, target: &str, watermark: i64) -> Result<(), SqlxError> {
+        sqlx::query("INSERT OR REPLACE INTO export_watermarks VALUES (?, ?)")
+            .bind(target)
+            .bind(watermark)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// A single dev process: the scheduler's in-process lock already serializes the runs
+#[async_trait]
+impl TraitRunLockRepository for EmbeddedStore {
+    async fn try_lock_run(&self) -> Result<Option<RunLockGuard>, SqlxError> {
+        Ok(Some(Box::new(())))
+    }
+
+    async fn try_lock_instrument(&self, instrument_uid: &str, job_id: &str) -> Result<InstrumentLock, SqlxError> {
+        let mut locks = self.instrument_locks.lock().unwrap();
+        if let Some(holder) = locks.get(instrument_uid) {
+            return Ok(InstrumentLock::Held { job_id: Some(holder.clone()) });
+        }
+        locks.insert(instrument_uid.to_string(), job_id.to_string());
+
+        Ok(InstrumentLock::Acquired(Box::new(EmbeddedInstrumentLock {
+            locks: self.instrument_locks.clone(),
+            instrument_uid: instrument_uid.to_string(),
+        })))
+    }
+}
+
+#[async_trait]
+impl TraitInstrumentFilterRepository for EmbeddedStore {
+    async fn get_instrument_filters(&self) -> Result<Vec<PgInstrumentFilter>, SqlxError> {
+        sqlx::query_as::<_, PgInstrumentFilter>(
+            "SELECT instrument_uid, mode, reason FROM instrument_filters ORDER BY instrument_uid",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn save_instrument_filter(&self, filter: &PgInstrumentFilter) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO instrument_filters (instrument_uid, mode, reason)
+             VALUES (?, ?, ?)
+             ON CONFLICT (instrument_uid) DO UPDATE SET mode = excluded.mode, reason = excluded.reason",
+        )
+        .bind(&filter.instrument_uid)
+        .bind(&filter.mode)
+        .bind(&filter.reason)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_instrument_filter(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query("DELETE FROM instrument_filters WHERE instrument_uid = ?")
+            .bind(instrument_uid)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorFailureRepository for EmbeddedStore {
+    async fn record_failure(&self, failure: &PgIndicatorFailure) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO indicators_failures (instrument_uid, job_id, error, batch_from, batch_to, failure_time)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&failure.instrument_uid)
+        .bind(&failure.job_id)
+        .bind(&failure.error)
+        .bind(failure.batch_from)
+        .bind(failure.batch_to)
+        .bind(failure.failure_time.timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn resolve_failures(&self, instrument_uid: &str) -> Result<u64, SqlxError> {
+        let result = sqlx::query(
+            "UPDATE indicators_failures SET resolve_time = unixepoch()
+             WHERE instrument_uid = ? AND resolve_time IS NULL",
+        )
+        .bind(instrument_uid)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_failing_instruments(&self) -> Result<Vec<PgFailingInstrument>, SqlxError> {
+        // SQLite returns the bare columns of the row holding MAX()
+        sqlx::query_as::<_, PgFailingInstrument>(
+            "SELECT instrument_uid,
+                    COUNT(*) AS failures,
+                    MIN(failure_time) AS first_failure_time,
+                    MAX(failure_time) AS last_failure_time,
+                    error AS last_error
+             FROM indicators_failures
+             WHERE resolve_time IS NULL
+             GROUP BY instrument_uid
+             ORDER BY failures DESC, instrument_uid",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_failures(
+        &self,
+        instrument_uid: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<PgIndicatorFailure>, SqlxError> {
+        sqlx::query_as::<_, PgIndicatorFailure>(
+            "SELECT instrument_uid, job_id, error, batch_from, batch_to, failure_time
+             FROM indicators_failures
+             WHERE instrument_uid = ? AND resolve_time IS NULL AND failure_time < ?
+             ORDER BY failure_time DESC, id DESC
+             LIMIT ?",
+        )
+        .bind(instrument_uid)
+        .bind(before.unwrap_or(i64::MAX))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorRunRepository for EmbeddedStore {
+    async fn start_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError> {
+        sqlx::query("INSERT INTO indicators_runs (run_id, trigger, cycle, status, start_time) VALUES (?, ?, ?, ?, ?)")
+            .bind(&run.run_id)
+            .bind(&run.trigger)
+            .bind(run.cycle)
+            .bind(&run.status)
+            .bind(run.start_time.timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn finish_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError> {
+        sqlx::query(
+            "UPDATE indicators_runs
+             SET status = ?, end_time = ?, instruments = ?, instruments_failed = ?,
+                 candles_processed = ?, rows_inserted = ?, insert_errors = ?, error = ?
+             WHERE run_id = ?",
+        )
+        .bind(&run.status)
+        .bind(run.end_time.map(|time| time.timestamp()))
+        .bind(run.instruments)
+        .bind(run.instruments_failed)
+        .bind(run.candles_processed)
+        .bind(run.rows_inserted)
+        .bind(run.insert_errors)
+        .bind(&run.error)
+        .bind(&run.run_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_runs(&self, before: Option<i64>, limit: i64) -> Result<Vec<PgIndicatorRun>, SqlxError> {
+        sqlx::query_as::<_, PgIndicatorRun>(
+            "SELECT run_id, trigger, cycle, status, start_time, end_time, instruments, instruments_failed,
+                    candles_processed, rows_inserted, insert_errors, error
+             FROM indicators_runs
+             WHERE start_time < ?
+             ORDER BY start_time DESC, rowid DESC
+             LIMIT ?",
+        )
+        .bind(before.unwrap_or(i64::MAX))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+#[async_trait]
+impl TraitSignalSuppressionRepository for EmbeddedStore {
+    async fn get_suppressions(&self) -> Result<Vec<PgSignalSuppression>, SqlxError> {
+        sqlx::query_as::<_, PgSignalSuppression>(
+            "SELECT instrument_uid, reason, suppressed_at FROM signal_suppression ORDER BY instrument_uid",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn is_suppressed(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM signal_suppression WHERE instrument_uid = ?)")
+            .bind(instrument_uid)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn suppress(&self, instrument_uid: &str, reason: &str) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO signal_suppression (instrument_uid, reason, suppressed_at)
+             VALUES (?, ?, unixepoch())
+             ON CONFLICT (instrument_uid) DO UPDATE SET reason = excluded.reason",
+        )
+        .bind(instrument_uid)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query("DELETE FROM signal_suppression WHERE instrument_uid = ?")
+            .bind(instrument_uid)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl TraitSignalCooldownRepository for EmbeddedStore {
+    async fn get_last_emitted(&self, instrument_uid: &str) -> Result<HashMap<String, i64>, SqlxError> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT kind, last_emitted_time FROM signal_cooldown WHERE instrument_uid = ?",
+        )
+        .bind(instrument_uid)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn record_emitted(&self, instrument_uid: &str, emitted: &HashMap<String, i64>) -> Result<(), SqlxError> {
+        for (kind, time) in emitted {
+            sqlx::query(
+                "INSERT INTO signal_cooldown (instrument_uid, kind, last_emitted_time) VALUES (?, ?, ?)
+                 ON CONFLICT (instrument_uid, kind)
+                 DO UPDATE SET last_emitted_time = MAX(last_emitted_time, excluded.last_emitted_time)",
+            )
+            .bind(instrument_uid)
+            .bind(kind)
+            .bind(time)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TraitTimeframeStatusRepository for EmbeddedStore {
+    async fn get_last_processed_bar(&self, instrument_uid: &str, timeframe: &str) -> Result<Option<i64>, SqlxError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT last_processed_time FROM indicators_status_tf WHERE instrument_uid = ? AND timeframe = ?",
+        )
+        .bind(instrument_uid)
+        .bind(timeframe)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn update_last_processed_bar(
+        &self,
+        instrument_uid: &str,
+        timeframe: &str,
+        time: i64,
+        _run_id: &str,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("INSERT OR REPLACE INTO indicators_status_tf VALUES (?, ?, ?)")
+            .bind(instrument_uid)
+            .bind(timeframe)
+            .bind(time)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TraitInstrumentRegistryRepository for EmbeddedStore {
+    async fn get_known_instruments(&self) -> Result<Vec<String>, SqlxError> {
+        sqlx::query_scalar::<_, String>("SELECT instrument_uid FROM instrument_registry")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn register_instruments(&self, instrument_uids: &[String]) -> Result<(), SqlxError> {
+        let mut tx = self.pool.begin().await?;
+        for uid in instrument_uids {
+            sqlx::query("INSERT OR IGNORE INTO instrument_registry VALUES (?, ?)")
+                .bind(uid)
+                .bind(chrono::Utc::now().timestamp())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorStateRepository for EmbeddedStore {
+    async fn get_state(&self, instrument_uid: &str) -> Result<Option<PgIndicatorState>, SqlxError> {
+        let row = sqlx::query_as::<_, (String, i64, String, String, i64)>(
+            "SELECT instrument_uid, last_time, fingerprint, state, update_time FROM indicator_state WHERE instrument_uid = ?",
+        )
+        .bind(instrument_uid)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(instrument_uid, last_time, fingerprint, state, update_time)| PgIndicatorState {
+            instrument_uid,
+            last_time,
+            fingerprint,
+            state,
+            update_time: chrono::DateTime::from_timestamp(update_time, 0).unwrap_or_default(),
+        }))
+    }
+
+    async fn save_state(
+        &self,
+        instrument_uid: &str,
+        last_time: i64,
+        fingerprint: &str,
+        state: &str,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("INSERT OR REPLACE INTO indicator_state VALUES (?, ?, ?, ?, ?)")
+            .bind(instrument_uid)
+            .bind(last_time)
+            .bind(fingerprint)
+            .bind(state)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_roundtrip() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        assert_eq!(store.count().await.unwrap(), 0);
+        assert_eq!(store.get_last_processed_time("uid").await.unwrap(), None);
+
+        store.update_last_processed_time("uid", 60, "run").await.unwrap();
+        store.update_last_processed_time("uid", 120, "run").await.unwrap();
+
+        assert_eq!(store.count().await.unwrap(), 1);
+        assert_eq!(store.get_last_processed_time("uid").await.unwrap(), Some(120));
+        assert_eq!(store.get_all_last_processed_times().await.unwrap(), HashMap::from([("uid".to_string(), 120)]));
+        assert!(store.check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_instrument_lock() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        let InstrumentLock::Acquired(guard) = store.try_lock_instrument("uid", "run-1").await.unwrap() else {
+            panic!("lock expected to be free");
+        };
+        let InstrumentLock::Held { job_id } = store.try_lock_instrument("uid", "run-2").await.unwrap() else {
+            panic!("lock expected to be held");
+        };
+        assert_eq!(job_id.as_deref(), Some("run-1"));
+        assert!(matches!(store.try_lock_instrument("other", "run-2").await.unwrap(), InstrumentLock::Acquired(_)));
+
+        drop(guard);
+        assert!(matches!(store.try_lock_instrument("uid", "run-2").await.unwrap(), InstrumentLock::Acquired(_)));
+    }
+
+    #[tokio::test]
+    async fn test_signal_suppression() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        store.suppress("uid", "split").await.unwrap();
+        assert!(store.is_suppressed("uid").await.unwrap());
+        assert_eq!(store.get_suppressions().await.unwrap()[0].reason, "split");
+
+        assert!(store.release("uid").await.unwrap());
+        assert!(!store.release("uid").await.unwrap());
+        assert!(!store.is_suppressed("uid").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_indicator_failures() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+        let failure = |time: i64, error: &str| PgIndicatorFailure {
+            instrument_uid: "uid".to_string(),
+            job_id: "run-1".to_string(),
+            error: error.to_string(),
+            batch_from: 60,
+            batch_to: None,
+            failure_time: chrono::DateTime::from_timestamp(time, 0).unwrap(),
+        };
+
+        store.record_failure(&failure(100, "timeout")).await.unwrap();
+        store.record_failure(&failure(200, "bad candle")).await.unwrap();
+        let failing = store.get_failing_instruments().await.unwrap();
+        assert_eq!((failing[0].failures, failing[0].last_error.as_str()), (2, "bad candle"));
+        assert_eq!(store.get_failures("uid", None, 10).await.unwrap()[0].failure_time.timestamp(), 200);
+        assert_eq!(store.get_failures("uid", Some(200), 10).await.unwrap()[0].failure_time.timestamp(), 100);
+
+        assert_eq!(store.resolve_failures("uid").await.unwrap(), 2);
+        assert!(store.get_failing_instruments().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_indicator_runs() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+        let run = |run_id: &str, start: i64| PgIndicatorRun {
+            run_id: run_id.to_string(),
+            trigger: "scheduled".to_string(),
+            cycle: 1,
+            status: "running".to_string(),
+            start_time: chrono::DateTime::from_timestamp(start, 0).unwrap(),
+            end_time: None,
+            instruments: 0,
+            instruments_failed: 0,
+            candles_processed: 0,
+            rows_inserted: 0,
+            insert_errors: 0,
+            error: None,
+        };
+
+        store.start_run(&run("run-1", 100)).await.unwrap();
+        store.start_run(&run("run-2", 400)).await.unwrap();
+        let finished = PgIndicatorRun {
+            status: "completed".to_string(),
+            end_time: chrono::DateTime::from_timestamp(160, 0),
+            instruments: 3,
+            rows_inserted: 500,
+            ..run("run-1", 100)
+        };
+        store.finish_run(&finished).await.unwrap();
+
+        let runs = store.get_runs(None, 10).await.unwrap();
+        assert_eq!(runs.iter().map(|run| run.run_id.as_str()).collect::<Vec<_>>(), vec!["run-2", "run-1"]);
+        assert_eq!((runs[0].status.as_str(), runs[0].end_time), ("running", None));
+        assert_eq!((runs[1].status.as_str(), runs[1].instruments, runs[1].rows_inserted), ("completed", 3, 500));
+        assert_eq!(runs[1].end_time.map(|time| time.timestamp()), Some(160));
+        let older = store.get_runs(Some(400), 10).await.unwrap();
+        assert_eq!(older.iter().map(|run| run.run_id.as_str()).collect::<Vec<_>>(), vec!["run-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_instrument_registry() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        store.register_instruments(&["a".to_string(), "b".to_string()]).await.unwrap();
+        store.register_instruments(&["b".to_string(), "c".to_string()]).await.unwrap();
+
+        let mut known = store.get_known_instruments().await.unwrap();
+        known.sort();
+        assert_eq!(known, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_timeframe_status() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        store.update_last_processed_bar("uid", "5min", 300, "run").await.unwrap();
+        store.update_last_processed_bar("uid", "5min", 600, "run").await.unwrap();
+        store.update_last_processed_bar("uid", "1hour", 3600, "run").await.unwrap();
+
+        assert_eq!(store.get_last_processed_bar("uid", "5min").await.unwrap(), Some(600));
+        assert_eq!(store.get_last_processed_bar("uid", "1hour").await.unwrap(), Some(3600));
+        assert_eq!(store.get_last_processed_bar("uid", "1day").await.unwrap(), None);
+        // Independent of the 1-minute status
+        assert_eq!(store.get_last_processed_time("uid").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_feature_versions() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        let versions = vec![("hma".to_string(), "16".to_string()), ("roc".to_string(), "[1,5]".to_string())];
+        store.save_feature_versions(&versions).await.unwrap();
+        store.save_feature_versions(&[("hma".to_string(), "21".to_string())]).await.unwrap();
+
+        let stored = store.get_feature_versions().await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored["hma"], "21");
+        assert_eq!(stored["roc"], "[1,5]");
+    }
+
+    #[tokio::test]
+    async fn test_export_watermarks() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        assert_eq!(store.get_export_watermark("s3").await.unwrap(), None);
+        store.save_export_watermark("s3", 1_000).await.unwrap();
+        store.save_export_watermark("s3", 2_000).await.unwrap();
+        store.save_export_watermark("warehouse", 500).await.unwrap();
+
+        assert_eq!(store.get_export_watermark("s3").await.unwrap(), Some(2_000));
+        assert_eq!(store.get_export_watermark("warehouse").await.unwrap(), Some(500));
+    }
+
+    #[tokio::test]
+    async fn test_indicator_state() {
+        let store = EmbeddedStore::open(":memory:").await.unwrap();
+
+        assert!(store.get_state("uid").await.unwrap().is_none());
+        store.save_state("uid", 60, "v1", "{}").await.unwrap();
+        store.save_state("uid", 120, "v1", "{\"ema\":1.5}").await.unwrap();
+
+        let state = store.get_state("uid").await.unwrap().unwrap();
+        assert_eq!(state.last_time, 120);
+        assert_eq!(state.fingerprint, "v1");
+        assert_eq!(state.state, "{\"ema\":1.5}");
+    }
+}
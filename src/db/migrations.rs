@@ -0,0 +1,158 @@
+// File: src/db/migrations.rs
+//! `t-indicators migrate`: applies the SQL files of `migrations/clickhouse` and
+//! `migrations/postgres` in name order. Applied files are recorded in
+//! tinkoff_indicators_migrations of each database and skipped on later runs, so mutations such
+//! as `MATERIALIZE COLUMN` run once.
+use clickhouse::Client;
+use sqlx::{Pool, Postgres};
+use std::path::Path;
+use tracing::info;
+
+const CLICKHOUSE_TABLE: &str = "CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_migrations (
+    name String,
+    applied_time DateTime64(3) DEFAULT now64(3)
+) ENGINE = ReplacingMergeTree ORDER BY name";
+
+const POSTGRES_TABLE: &str = "CREATE TABLE IF NOT EXISTS market_data.tinkoff_indicators_migrations (
+    name TEXT PRIMARY KEY,
+    applied_time TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub name: String, // Имя файла, например 0043_labels_time.sql
+    pub sql: String,
+}
+
+/// `*.sql` files of `dir`, by name
+pub fn load(dir: &Path) -> Result<Vec<Migration>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut migrations = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("{}: {}", dir.display(), e))?.path();
+        if path.extension().is_none_or(|extension| extension != "sql") {
+            continue;
+        }
+        let sql = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        migrations.push(Migration { name, sql });
+    }
+    migrations.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(migrations)
+}
+
+/// Statements of a migration without `--` comments; ClickHouse runs one statement per query
+pub fn statements(sql: &str) -> Vec<String> {
+    let code: String = sql
+        .lines()
+        .map(|line| line.split_once("--").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    code.split(';')
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Applies the pending migrations, or only records them with `baseline`; returns their names
+pub async fn migrate_clickhouse(
+    client: &Client,
+    migrations: &[Migration],
+    baseline: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    client.query(CLICKHOUSE_TABLE).execute().await?;
+    let applied: Vec<String> = client
+        .query("SELECT name FROM market_data.tinkoff_indicators_migrations FINAL")
+        .fetch_all()
+        .await?;
+
+    let mut names = Vec::new();
+    for migration in migrations.iter().filter(|migration| !applied.contains(&migration.name)) {
+        if !baseline {
+            info!("Applying ClickHouse migration {}", migration.name);
+            for statement in statements(&migration.sql) {
+                client
+                    .query(&statement)
+                    .execute()
+                    .await
+                    .map_err(|e| format!("{}: {}", migration.name, e))?;
+            }
+        }
+        client
+            .query("INSERT INTO market_data.tinkoff_indicators_migrations (name) VALUES (?)")
+            .bind(&migration.name)
+            .execute()
+            .await?;
+        names.push(migration.name.clone());
+    }
+    Ok(names)
+}
+
+/// Applies the pending migrations, each in a transaction with its record, or only records
+/// them with `baseline`; returns their names
+pub async fn migrate_postgres(
+    pool: &Pool<Postgres>,
+    migrations: &[Migration],
+    baseline: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    sqlx::raw_sql(POSTGRES_TABLE).execute(pool).await?;
+    let applied: Vec<String> = sqlx::query_scalar("SELECT name FROM market_data.tinkoff_indicators_migrations")
+        .fetch_all(pool)
+        .await?;
+
+    let mut names = Vec::new();
+    for migration in migrations.iter().filter(|migration| !applied.contains(&migration.name)) {
+        let mut transaction = pool.begin().await?;
+        if !baseline {
+            info!("Applying PostgreSQL migration {}", migration.name);
+            sqlx::raw_sql(&migration.sql)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|e| format!("{}: {}", migration.name, e))?;
+        }
+        sqlx::query("INSERT INTO market_data.tinkoff_indicators_migrations (name) VALUES ($1)")
+            .bind(&migration.name)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        names.push(migration.name.clone());
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statements() {
+        let sql = "-- Comment; not a statement\nALTER TABLE t\n    ADD COLUMN IF NOT EXISTS a Float64; -- trailing\n\nALTER TABLE t MATERIALIZE COLUMN a;\n";
+        assert_eq!(
+            statements(sql),
+            vec!["ALTER TABLE t\n    ADD COLUMN IF NOT EXISTS a Float64", "ALTER TABLE t MATERIALIZE COLUMN a"]
+        );
+
+        // Every migration of the repository splits into statements
+        for database in ["clickhouse", "postgres"] {
+            let migrations = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations").join(database)).unwrap();
+            assert!(migrations.windows(2).all(|pair| pair[0].name < pair[1].name));
+            for migration in &migrations {
+                assert!(!statements(&migration.sql).is_empty(), "{}", migration.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_indicators_all_view_follows_cold_columns() {
+        // The union view keeps the columns it was created with, so it is replaced after the
+        // last migration that changes the cold table
+        let migrations = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations").join("clickhouse")).unwrap();
+        let position = |needle: &str| migrations.iter().rposition(|migration| {
+            statements(&migration.sql).iter().any(|statement| statement.starts_with(needle))
+        });
+        let altered = position("ALTER TABLE market_data.tinkoff_indicators_1min_cold").unwrap();
+        let replaced = position("CREATE OR REPLACE VIEW market_data.tinkoff_indicators_all").unwrap();
+        assert!(replaced >= altered, "{} alters the cold table after the view was last replaced", migrations[altered].name);
+    }
+}
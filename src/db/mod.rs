@@ -1,2 +1,5 @@
 pub mod clickhouse;
+#[cfg(feature = "embedded")]
+pub mod embedded;
+pub mod migrations;
 pub mod postgres;
@@ -24,15 +24,9 @@ impl PostgresConnection {
         let pool = PgPoolOptions::new()
             .max_connections(settings.app_config.postgres.max_connections)
             .min_connections(settings.app_config.postgres.min_connections)
-            .max_lifetime(std::time::Duration::from_secs(
-                settings.app_config.postgres.max_lifetime,
-            ))
-            .idle_timeout(std::time::Duration::from_secs(
-                settings.app_config.postgres.idle_timeout,
-            ))
-            .acquire_timeout(std::time::Duration::from_secs(
-                settings.app_config.postgres.timeout,
-            ))
+            .max_lifetime(settings.app_config.postgres.max_lifetime)
+            .idle_timeout(settings.app_config.postgres.idle_timeout)
+            .acquire_timeout(settings.app_config.postgres.timeout)
             .connect(&connection_string)
             .await?;
 
@@ -0,0 +1,25 @@
+// src/db/postgres/models/indicator_failure.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One failed run of an instrument
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PgIndicatorFailure {
+    pub instrument_uid: String,
+    pub job_id: String,
+    pub error: String,
+    pub batch_from: i64,
+    pub batch_to: Option<i64>,
+    pub failure_time: DateTime<Utc>,
+}
+
+/// Instrument with failures since its last successful run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PgFailingInstrument {
+    pub instrument_uid: String,
+    pub failures: i64, // consecutive, since the last success
+    pub first_failure_time: DateTime<Utc>,
+    pub last_failure_time: DateTime<Utc>,
+    pub last_error: String,
+}
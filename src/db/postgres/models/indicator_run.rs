@@ -0,0 +1,21 @@
+// src/db/postgres/models/indicator_run.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One indicators run with its totals, which are zero while it is running
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct PgIndicatorRun {
+    pub run_id: String,
+    pub trigger: String, // scheduled, manual, recalc, backfill
+    pub cycle: i64,
+    pub status: String, // running, completed, failed
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub instruments: i64,
+    pub instruments_failed: i64,
+    pub candles_processed: i64,
+    pub rows_inserted: i64,
+    pub insert_errors: i64,
+    pub error: Option<String>,
+}
@@ -0,0 +1,15 @@
+// src/db/postgres/models/indicator_state.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Indicator state of an instrument after the candle at `last_time`. `fingerprint` identifies
+/// the state layout and the settings it was built with; `state` is base64 of its bincode encoding.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PgIndicatorState {
+    pub instrument_uid: String,
+    pub last_time: i64,
+    pub fingerprint: String,
+    pub state: String,
+    pub update_time: DateTime<Utc>,
+}
@@ -0,0 +1,10 @@
+// src/db/postgres/models/instrument_filter.rs
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PgInstrumentFilter {
+    pub instrument_uid: String,
+    pub mode: String, // "allow" or "deny"
+    pub reason: String,
+}
@@ -1 +1,6 @@
 pub mod indicator_status;
+pub mod signal_suppression;
+pub mod indicator_state;
+pub mod instrument_filter;
+pub mod indicator_failure;
+pub mod indicator_run;
@@ -0,0 +1,11 @@
+// src/db/postgres/models/signal_suppression.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PgSignalSuppression {
+    pub instrument_uid: String,
+    pub reason: String,
+    pub suppressed_at: DateTime<Utc>,
+}
@@ -1,21 +1,44 @@
+use crate::db::postgres::repository::export_watermark_repository::{StructExportWatermarkRepository, TraitExportWatermarkRepository};
+use crate::db::postgres::repository::feature_flag_repository::{StructFeatureFlagRepository, TraitFeatureFlagRepository};
+use crate::db::postgres::repository::feature_version_repository::{StructFeatureVersionRepository, TraitFeatureVersionRepository};
 use crate::db::postgres::repository::health_check_repository::TraitHealthCheckRepository;
 
+use crate::db::postgres::repository::indicator_failure_repository::{StructIndicatorFailureRepository, TraitIndicatorFailureRepository};
+use crate::db::postgres::repository::indicator_run_repository::{StructIndicatorRunRepository, TraitIndicatorRunRepository};
+use crate::db::postgres::repository::instrument_filter_repository::{StructInstrumentFilterRepository, TraitInstrumentFilterRepository};
+use crate::db::postgres::repository::instrument_registry_repository::{StructInstrumentRegistryRepository, TraitInstrumentRegistryRepository};
 use crate::db::postgres::repository::indicator_status_repository::{StructIndicatorStatusRepository, TraitIndicatorStatusRepository};
+use crate::db::postgres::repository::indicator_state_repository::{StructIndicatorStateRepository, TraitIndicatorStateRepository};
+use crate::db::postgres::repository::run_lock_repository::{StructRunLockRepository, TraitRunLockRepository};
+use crate::db::postgres::repository::signal_cooldown_repository::{StructSignalCooldownRepository, TraitSignalCooldownRepository};
+use crate::db::postgres::repository::signal_suppression_repository::{StructSignalSuppressionRepository, TraitSignalSuppressionRepository};
+use crate::db::postgres::repository::timeframe_status_repository::{StructTimeframeStatusRepository, TraitTimeframeStatusRepository};
 use crate::db::postgres::{
     connection::PostgresConnection,
     repository::health_check_repository::StructHealthCheckRepository,
 };
+#[cfg(feature = "embedded")]
+use crate::db::embedded::store::EmbeddedStore;
 use crate::env_config::models::app_setting::AppSettings;
 use std::sync::Arc;
 use tracing::{error, info};
 
 pub struct PostgresService {
-    // Connection
-    pub connection: Arc<PostgresConnection>,
-
     // Operational repositories (PostgreSQL)
     pub repository_health_check: Arc<dyn TraitHealthCheckRepository + Send + Sync>,
     pub repository_indicator_status: Arc<dyn TraitIndicatorStatusRepository + Send + Sync>,
+    pub repository_feature_flag: Arc<dyn TraitFeatureFlagRepository + Send + Sync>,
+    pub repository_signal_suppression: Arc<dyn TraitSignalSuppressionRepository + Send + Sync>,
+    pub repository_signal_cooldown: Arc<dyn TraitSignalCooldownRepository + Send + Sync>,
+    pub repository_timeframe_status: Arc<dyn TraitTimeframeStatusRepository + Send + Sync>,
+    pub repository_instrument_registry: Arc<dyn TraitInstrumentRegistryRepository + Send + Sync>,
+    pub repository_indicator_state: Arc<dyn TraitIndicatorStateRepository + Send + Sync>,
+    pub repository_feature_version: Arc<dyn TraitFeatureVersionRepository + Send + Sync>,
+    pub repository_export_watermark: Arc<dyn TraitExportWatermarkRepository + Send + Sync>,
+    pub repository_run_lock: Arc<dyn TraitRunLockRepository + Send + Sync>,
+    pub repository_instrument_filter: Arc<dyn TraitInstrumentFilterRepository + Send + Sync>,
+    pub repository_indicator_failure: Arc<dyn TraitIndicatorFailureRepository + Send + Sync>,
+    pub repository_indicator_run: Arc<dyn TraitIndicatorRunRepository + Send + Sync>,
 }
 
 impl PostgresService {
@@ -48,11 +71,103 @@ impl PostgresService {
         ))
             as Arc<dyn TraitIndicatorStatusRepository + Send + Sync>;
 
+        let feature_flag_repository = Arc::new(StructFeatureFlagRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitFeatureFlagRepository + Send + Sync>;
+
+        let signal_suppression_repository = Arc::new(StructSignalSuppressionRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitSignalSuppressionRepository + Send + Sync>;
+
+        let signal_cooldown_repository = Arc::new(StructSignalCooldownRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitSignalCooldownRepository + Send + Sync>;
+
+        let timeframe_status_repository = Arc::new(StructTimeframeStatusRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitTimeframeStatusRepository + Send + Sync>;
+
+        let instrument_registry_repository = Arc::new(StructInstrumentRegistryRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitInstrumentRegistryRepository + Send + Sync>;
+
+        let indicator_state_repository = Arc::new(StructIndicatorStateRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitIndicatorStateRepository + Send + Sync>;
+
+        let feature_version_repository = Arc::new(StructFeatureVersionRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitFeatureVersionRepository + Send + Sync>;
+
+        let export_watermark_repository = Arc::new(StructExportWatermarkRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitExportWatermarkRepository + Send + Sync>;
+
+        let run_lock_repository = Arc::new(StructRunLockRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitRunLockRepository + Send + Sync>;
+
+        let instrument_filter_repository = Arc::new(StructInstrumentFilterRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitInstrumentFilterRepository + Send + Sync>;
+
+        let indicator_failure_repository = Arc::new(StructIndicatorFailureRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitIndicatorFailureRepository + Send + Sync>;
+
+        let indicator_run_repository = Arc::new(StructIndicatorRunRepository::new(
+            postgres_connection.clone(),
+        ))
+            as Arc<dyn TraitIndicatorRunRepository + Send + Sync>;
+
         info!("PostgreSQL service initialized successfully");
         Ok(Self {
-            connection: postgres_connection,
             repository_health_check: health_check_repository,
             repository_indicator_status: indicator_status_repository,
+            repository_feature_flag: feature_flag_repository,
+            repository_signal_suppression: signal_suppression_repository,
+            repository_signal_cooldown: signal_cooldown_repository,
+            repository_timeframe_status: timeframe_status_repository,
+            repository_instrument_registry: instrument_registry_repository,
+            repository_indicator_state: indicator_state_repository,
+            repository_feature_version: feature_version_repository,
+            repository_export_watermark: export_watermark_repository,
+            repository_run_lock: run_lock_repository,
+            repository_instrument_filter: instrument_filter_repository,
+            repository_indicator_failure: indicator_failure_repository,
+            repository_indicator_run: indicator_run_repository,
         })
     }
+
+    /// Operational repositories backed by the embedded dev-mode store
+    #[cfg(feature = "embedded")]
+    pub fn embedded(store: Arc<EmbeddedStore>) -> Self {
+        Self {
+            repository_health_check: store.clone(),
+            repository_indicator_status: store.clone(),
+            repository_feature_flag: store.clone(),
+            repository_signal_suppression: store.clone(),
+            repository_signal_cooldown: store.clone(),
+            repository_timeframe_status: store.clone(),
+            repository_instrument_registry: store.clone(),
+            repository_indicator_state: store.clone(),
+            repository_feature_version: store.clone(),
+            repository_export_watermark: store.clone(),
+            repository_run_lock: store.clone(),
+            repository_instrument_filter: store.clone(),
+            repository_indicator_failure: store.clone(),
+            repository_indicator_run: store,
+        }
+    }
 }
@@ -0,0 +1,60 @@
+// src/db/postgres/repository/export_watermark_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Insert time (unix ms) of the newest indicator rows shipped to each export target
+#[async_trait]
+pub trait TraitExportWatermarkRepository {
+    async fn get_export_watermark(&self, target: &str) -> Result<Option<i64>, SqlxError>;
+    async fn save_export_watermark(&self, target: &str, watermark: i64) -> Result<(), SqlxError>;
+}
+
+pub struct StructExportWatermarkRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructExportWatermarkRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitExportWatermarkRepository for StructExportWatermarkRepository {
+    async fn get_export_watermark(&self, target: &str) -> Result<Option<i64>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let watermark = sqlx::query_scalar::<_, i64>(
+            "SELECT watermark FROM market_data.tinkoff_export_watermarks WHERE target = $1"
+        )
+        .bind(target)
+        .fetch_optional(pool)
+        .await?;
+
+        debug!("Export watermark of {}: {:?}", target, watermark);
+
+        Ok(watermark)
+    }
+
+    async fn save_export_watermark(&self, target: &str, watermark: i64) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_export_watermarks (target, watermark, update_time)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (target)
+             DO UPDATE SET watermark = $2, update_time = NOW()",
+        )
+        .bind(target)
+        .bind(watermark)
+        .execute(pool)
+        .await?;
+
+        debug!("Saved export watermark of {}: {}", target, watermark);
+
+        Ok(())
+    }
+}
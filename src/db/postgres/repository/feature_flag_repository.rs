@@ -0,0 +1,39 @@
+// src/db/postgres/repository/feature_flag_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+#[async_trait]
+pub trait TraitFeatureFlagRepository {
+    async fn get_overrides(&self) -> Result<HashMap<String, bool>, SqlxError>;
+}
+
+pub struct StructFeatureFlagRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructFeatureFlagRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitFeatureFlagRepository for StructFeatureFlagRepository {
+    async fn get_overrides(&self) -> Result<HashMap<String, bool>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, (String, bool)>(
+            "SELECT name, enabled FROM market_data.tinkoff_feature_flags"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Retrieved {} feature flag overrides", rows.len());
+
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -0,0 +1,65 @@
+// src/db/postgres/repository/feature_version_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Versions (serialized parameters) of the indicator features behind the stored rows
+#[async_trait]
+pub trait TraitFeatureVersionRepository {
+    async fn get_feature_versions(&self) -> Result<HashMap<String, String>, SqlxError>;
+    async fn save_feature_versions(&self, versions: &[(String, String)]) -> Result<(), SqlxError>;
+}
+
+pub struct StructFeatureVersionRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructFeatureVersionRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitFeatureVersionRepository for StructFeatureVersionRepository {
+    async fn get_feature_versions(&self) -> Result<HashMap<String, String>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT feature, version FROM market_data.tinkoff_feature_versions"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Retrieved {} feature versions", rows.len());
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn save_feature_versions(&self, versions: &[(String, String)]) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let mut tx = pool.begin().await?;
+        for (feature, version) in versions {
+            sqlx::query(
+                "INSERT INTO market_data.tinkoff_feature_versions (feature, version, update_time)
+                 VALUES ($1, $2, NOW())
+                 ON CONFLICT (feature)
+                 DO UPDATE SET version = $2, update_time = NOW()
+                 WHERE market_data.tinkoff_feature_versions.version <> $2",
+            )
+            .bind(feature)
+            .bind(version)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        debug!("Saved {} feature versions", versions.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+// src/db/postgres/repository/indicator_failure_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use crate::db::postgres::models::indicator_failure::{PgFailingInstrument, PgIndicatorFailure};
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Dead-letter tracking: failed runs of instruments, open until the next successful run
+#[async_trait]
+pub trait TraitIndicatorFailureRepository {
+    async fn record_failure(&self, failure: &PgIndicatorFailure) -> Result<(), SqlxError>;
+    /// Closes the open failures of an instrument, returns how many there were
+    async fn resolve_failures(&self, instrument_uid: &str) -> Result<u64, SqlxError>;
+    /// Instruments with open failures, most failures first
+    async fn get_failing_instruments(&self) -> Result<Vec<PgFailingInstrument>, SqlxError>;
+    /// Up to `limit` open failures of an instrument before `before` (unix seconds) if given,
+    /// newest first
+    async fn get_failures(
+        &self,
+        instrument_uid: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<PgIndicatorFailure>, SqlxError>;
+}
+
+pub struct StructIndicatorFailureRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructIndicatorFailureRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorFailureRepository for StructIndicatorFailureRepository {
+    async fn record_failure(&self, failure: &PgIndicatorFailure) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_indicators_failures
+                 (instrument_uid, job_id, error, batch_from, batch_to, failure_time)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&failure.instrument_uid)
+        .bind(&failure.job_id)
+        .bind(&failure.error)
+        .bind(failure.batch_from)
+        .bind(failure.batch_to)
+        .bind(failure.failure_time)
+        .execute(pool)
+        .await?;
+
+        debug!("Recorded failure of {} in job {}", failure.instrument_uid, failure.job_id);
+
+        Ok(())
+    }
+
+    async fn resolve_failures(&self, instrument_uid: &str) -> Result<u64, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let result = sqlx::query(
+            "UPDATE market_data.tinkoff_indicators_failures
+             SET resolve_time = NOW()
+             WHERE instrument_uid = $1 AND resolve_time IS NULL",
+        )
+        .bind(instrument_uid)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            info!("Resolved {} failures of {}", result.rows_affected(), instrument_uid);
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_failing_instruments(&self) -> Result<Vec<PgFailingInstrument>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, PgFailingInstrument>(
+            "SELECT instrument_uid,
+                    COUNT(*) AS failures,
+                    MIN(failure_time) AS first_failure_time,
+                    MAX(failure_time) AS last_failure_time,
+                    (ARRAY_AGG(error ORDER BY failure_time DESC))[1] AS last_error
+             FROM market_data.tinkoff_indicators_failures
+             WHERE resolve_time IS NULL
+             GROUP BY instrument_uid
+             ORDER BY failures DESC, instrument_uid",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Retrieved {} failing instruments", rows.len());
+
+        Ok(rows)
+    }
+
+    async fn get_failures(
+        &self,
+        instrument_uid: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<PgIndicatorFailure>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_as::<_, PgIndicatorFailure>(
+            "SELECT instrument_uid, job_id, error, batch_from, batch_to, failure_time
+             FROM market_data.tinkoff_indicators_failures
+             WHERE instrument_uid = $1 AND resolve_time IS NULL
+               AND ($2::BIGINT IS NULL OR failure_time < to_timestamp($2))
+             ORDER BY failure_time DESC
+             LIMIT $3",
+        )
+        .bind(instrument_uid)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,94 @@
+// src/db/postgres/repository/indicator_run_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use crate::db::postgres::models::indicator_run::PgIndicatorRun;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::debug;
+
+/// History of indicators runs
+#[async_trait]
+pub trait TraitIndicatorRunRepository {
+    /// Inserts a run as it starts
+    async fn start_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError>;
+    /// Stores the status, end time and totals of a finished run
+    async fn finish_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError>;
+    /// The last `limit` runs started before `before` (unix seconds) if given, newest first
+    async fn get_runs(&self, before: Option<i64>, limit: i64) -> Result<Vec<PgIndicatorRun>, SqlxError>;
+}
+
+pub struct StructIndicatorRunRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructIndicatorRunRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorRunRepository for StructIndicatorRunRepository {
+    async fn start_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_indicators_runs (run_id, trigger, cycle, status, start_time)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&run.run_id)
+        .bind(&run.trigger)
+        .bind(run.cycle)
+        .bind(&run.status)
+        .bind(run.start_time)
+        .execute(pool)
+        .await?;
+
+        debug!("Recorded start of run {}", run.run_id);
+
+        Ok(())
+    }
+
+    async fn finish_run(&self, run: &PgIndicatorRun) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "UPDATE market_data.tinkoff_indicators_runs
+             SET status = $2, end_time = $3, instruments = $4, instruments_failed = $5,
+                 candles_processed = $6, rows_inserted = $7, insert_errors = $8, error = $9
+             WHERE run_id = $1",
+        )
+        .bind(&run.run_id)
+        .bind(&run.status)
+        .bind(run.end_time)
+        .bind(run.instruments)
+        .bind(run.instruments_failed)
+        .bind(run.candles_processed)
+        .bind(run.rows_inserted)
+        .bind(run.insert_errors)
+        .bind(&run.error)
+        .execute(pool)
+        .await?;
+
+        debug!("Recorded end of run {}", run.run_id);
+
+        Ok(())
+    }
+
+    async fn get_runs(&self, before: Option<i64>, limit: i64) -> Result<Vec<PgIndicatorRun>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_as::<_, PgIndicatorRun>(
+            "SELECT run_id, trigger, cycle, status, start_time, end_time, instruments, instruments_failed,
+                    candles_processed, rows_inserted, insert_errors, error
+             FROM market_data.tinkoff_indicators_runs
+             WHERE $1::BIGINT IS NULL OR start_time < to_timestamp($1)
+             ORDER BY start_time DESC
+             LIMIT $2",
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+}
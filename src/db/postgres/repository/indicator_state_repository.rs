@@ -0,0 +1,73 @@
+// src/db/postgres/repository/indicator_state_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use crate::db::postgres::models::indicator_state::PgIndicatorState;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Persisted indicator state per instrument, one row replaced after every batch
+#[async_trait]
+pub trait TraitIndicatorStateRepository {
+    async fn get_state(&self, instrument_uid: &str) -> Result<Option<PgIndicatorState>, SqlxError>;
+    async fn save_state(
+        &self,
+        instrument_uid: &str,
+        last_time: i64,
+        fingerprint: &str,
+        state: &str,
+    ) -> Result<(), SqlxError>;
+}
+
+pub struct StructIndicatorStateRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructIndicatorStateRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitIndicatorStateRepository for StructIndicatorStateRepository {
+    async fn get_state(&self, instrument_uid: &str) -> Result<Option<PgIndicatorState>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_as::<_, PgIndicatorState>(
+            "SELECT instrument_uid, last_time, fingerprint, state, update_time
+             FROM market_data.tinkoff_indicator_state
+             WHERE instrument_uid = $1",
+        )
+        .bind(instrument_uid)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn save_state(
+        &self,
+        instrument_uid: &str,
+        last_time: i64,
+        fingerprint: &str,
+        state: &str,
+    ) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_indicator_state (instrument_uid, last_time, fingerprint, state, update_time)
+             VALUES ($1, $2, $3, $4, NOW())
+             ON CONFLICT (instrument_uid)
+             DO UPDATE SET last_time = $2, fingerprint = $3, state = $4, update_time = NOW()",
+        )
+        .bind(instrument_uid)
+        .bind(last_time)
+        .bind(fingerprint)
+        .bind(state)
+        .execute(pool)
+        .await?;
+
+        debug!("Saved indicator state of {} at {}", instrument_uid, last_time);
+
+        Ok(())
+    }
+}
@@ -3,13 +3,18 @@ use crate::db::postgres::connection::PostgresConnection;
 use crate::db::postgres::models::indicator_status::PgIndicatorStatus;
 use async_trait::async_trait;
 use sqlx::Error as SqlxError;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
 #[async_trait]
 pub trait TraitIndicatorStatusRepository {
     async fn get_last_processed_time(&self, instrument_uid: &str) -> Result<Option<i64>, SqlxError>;
-    async fn update_last_processed_time(&self, instrument_uid: &str, time: i64) -> Result<(), SqlxError>;
+    /// `run_id` identifies the indicators run that wrote the row
+    async fn update_last_processed_time(&self, instrument_uid: &str, time: i64, run_id: &str) -> Result<(), SqlxError>;
+    async fn count(&self) -> Result<i64, SqlxError>;
+    /// Last processed time of every instrument with a status row
+    async fn get_all_last_processed_times(&self) -> Result<HashMap<String, i64>, SqlxError>;
 }
 
 pub struct StructIndicatorStatusRepository {
@@ -39,17 +44,18 @@ impl TraitIndicatorStatusRepository for StructIndicatorStatusRepository {
         Ok(result)
     }
     
-    async fn update_last_processed_time(&self, instrument_uid: &str, time: i64) -> Result<(), SqlxError> {
+    async fn update_last_processed_time(&self, instrument_uid: &str, time: i64, run_id: &str) -> Result<(), SqlxError> {
         let pool = self.connection.get_pool();
         
         sqlx::query(
-            "INSERT INTO market_data.tinkoff_indicators_status (instrument_uid, last_processed_time, update_time) 
-             VALUES ($1, $2, NOW()) 
+            "INSERT INTO market_data.tinkoff_indicators_status (instrument_uid, last_processed_time, run_id, update_time) 
+             VALUES ($1, $2, $3, NOW()) 
              ON CONFLICT (instrument_uid) 
-             DO UPDATE SET last_processed_time = $2, update_time = NOW()"
+             DO UPDATE SET last_processed_time = $2, run_id = $3, update_time = NOW()"
         )
         .bind(instrument_uid)
         .bind(time)
+        .bind(run_id)
         .execute(pool)
         .await?;
         
@@ -57,4 +63,24 @@ impl TraitIndicatorStatusRepository for StructIndicatorStatusRepository {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    async fn count(&self) -> Result<i64, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_scalar("SELECT COUNT(*) FROM market_data.tinkoff_indicators_status")
+            .fetch_one(pool)
+            .await
+    }
+
+    async fn get_all_last_processed_times(&self) -> Result<HashMap<String, i64>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT instrument_uid, last_processed_time FROM market_data.tinkoff_indicators_status",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -0,0 +1,79 @@
+// src/db/postgres/repository/instrument_filter_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use crate::db::postgres::models::instrument_filter::PgInstrumentFilter;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Allow/deny entries of instruments, edited in the admin UI or with SQL
+#[async_trait]
+pub trait TraitInstrumentFilterRepository {
+    async fn get_instrument_filters(&self) -> Result<Vec<PgInstrumentFilter>, SqlxError>;
+    /// Adds an entry, replacing the mode and reason of an existing one
+    async fn save_instrument_filter(&self, filter: &PgInstrumentFilter) -> Result<(), SqlxError>;
+    /// Removes the entry, returns false if the instrument had none
+    async fn delete_instrument_filter(&self, instrument_uid: &str) -> Result<bool, SqlxError>;
+}
+
+pub struct StructInstrumentFilterRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructInstrumentFilterRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitInstrumentFilterRepository for StructInstrumentFilterRepository {
+    async fn get_instrument_filters(&self) -> Result<Vec<PgInstrumentFilter>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, PgInstrumentFilter>(
+            "SELECT instrument_uid, mode, reason
+             FROM market_data.tinkoff_instrument_filters
+             ORDER BY instrument_uid",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Retrieved {} instrument filters", rows.len());
+
+        Ok(rows)
+    }
+
+    async fn save_instrument_filter(&self, filter: &PgInstrumentFilter) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_instrument_filters (instrument_uid, mode, reason)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (instrument_uid)
+             DO UPDATE SET mode = $2, reason = $3",
+        )
+        .bind(&filter.instrument_uid)
+        .bind(&filter.mode)
+        .bind(&filter.reason)
+        .execute(pool)
+        .await?;
+
+        info!("Saved instrument filter {} {}: {}", filter.mode, filter.instrument_uid, filter.reason);
+
+        Ok(())
+    }
+
+    async fn delete_instrument_filter(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let result = sqlx::query("DELETE FROM market_data.tinkoff_instrument_filters WHERE instrument_uid = $1")
+            .bind(instrument_uid)
+            .execute(pool)
+            .await?;
+
+        info!("Deleted instrument filter of {}", instrument_uid);
+
+        Ok(result.rows_affected() > 0)
+    }
+}
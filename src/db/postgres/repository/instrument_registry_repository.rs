@@ -0,0 +1,52 @@
+// src/db/postgres/repository/instrument_registry_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Instruments the pipeline has already seen, with the time each was first seen
+#[async_trait]
+pub trait TraitInstrumentRegistryRepository {
+    async fn get_known_instruments(&self) -> Result<Vec<String>, SqlxError>;
+    /// Records instruments as seen now; already known ones keep their first-seen time
+    async fn register_instruments(&self, instrument_uids: &[String]) -> Result<(), SqlxError>;
+}
+
+pub struct StructInstrumentRegistryRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructInstrumentRegistryRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitInstrumentRegistryRepository for StructInstrumentRegistryRepository {
+    async fn get_known_instruments(&self) -> Result<Vec<String>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_scalar::<_, String>("SELECT instrument_uid FROM market_data.tinkoff_instrument_registry")
+            .fetch_all(pool)
+            .await
+    }
+
+    async fn register_instruments(&self, instrument_uids: &[String]) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_instrument_registry (instrument_uid, first_seen_at)
+             SELECT UNNEST($1::TEXT[]), NOW()
+             ON CONFLICT (instrument_uid) DO NOTHING",
+        )
+        .bind(instrument_uids)
+        .execute(pool)
+        .await?;
+
+        debug!("Registered {} instruments", instrument_uids.len());
+
+        Ok(())
+    }
+}
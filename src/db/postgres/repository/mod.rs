@@ -1,2 +1,14 @@
 pub mod health_check_repository;
 pub mod indicator_status_repository;
+pub mod feature_flag_repository;
+pub mod signal_suppression_repository;
+pub mod signal_cooldown_repository;
+pub mod timeframe_status_repository;
+pub mod instrument_registry_repository;
+pub mod indicator_state_repository;
+pub mod feature_version_repository;
+pub mod export_watermark_repository;
+pub mod run_lock_repository;
+pub mod instrument_filter_repository;
+pub mod indicator_failure_repository;
+pub mod indicator_run_repository;
@@ -0,0 +1,131 @@
+// src/db/postgres/repository/run_lock_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::{Error as SqlxError, Postgres};
+use std::any::Any;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Key of the advisory lock held for the duration of an indicators run ("tind_run")
+const RUN_LOCK_KEY: i64 = 0x7469_6e64_5f72_756e;
+
+/// First key of the per-instrument advisory locks ("tind"), the second is `hashtext(uid)`.
+/// A hash collision only makes two instruments wait for each other.
+const INSTRUMENT_LOCK_CLASS: i32 = 0x7469_6e64;
+
+/// Held while an indicators run is in progress; dropping it releases the lock
+pub type RunLockGuard = Box<dyn Any + Send>;
+
+/// Outcome of taking the lock of an instrument
+pub enum InstrumentLock {
+    /// Held until the guard is dropped
+    Acquired(RunLockGuard),
+    /// Another job is processing the instrument; its ID if it was recorded
+    Held { job_id: Option<String> },
+}
+
+/// Locks shared by the replicas so that one indicators run proceeds at a time and an
+/// instrument's status and state are written by a single job
+#[async_trait]
+pub trait TraitRunLockRepository {
+    /// Takes the lock, `None` while another replica holds it
+    async fn try_lock_run(&self) -> Result<Option<RunLockGuard>, SqlxError>;
+    /// Takes the lock of one instrument for job `job_id` (an indicators run ID)
+    async fn try_lock_instrument(&self, instrument_uid: &str, job_id: &str) -> Result<InstrumentLock, SqlxError>;
+}
+
+pub struct StructRunLockRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructRunLockRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitRunLockRepository for StructRunLockRepository {
+    async fn try_lock_run(&self) -> Result<Option<RunLockGuard>, SqlxError> {
+        // Session-level lock on a connection taken out of the pool: closing the connection
+        // releases it, also when the process dies mid-run
+        let mut connection = self.connection.get_pool().acquire().await?.detach();
+
+        let acquired = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+            .bind(RUN_LOCK_KEY)
+            .fetch_one(&mut connection)
+            .await?;
+
+        debug!("Run lock {}", if acquired { "acquired" } else { "held by another replica" });
+
+        Ok(acquired.then(|| Box::new(connection) as RunLockGuard))
+    }
+
+    async fn try_lock_instrument(&self, instrument_uid: &str, job_id: &str) -> Result<InstrumentLock, SqlxError> {
+        // Taken for every instrument of every run, so the connection goes back to the pool
+        // after an explicit unlock rather than being reopened each time
+        let mut connection = self.connection.get_pool().acquire().await?;
+
+        let acquired = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1, hashtext($2))")
+            .bind(INSTRUMENT_LOCK_CLASS)
+            .bind(instrument_uid)
+            .fetch_one(&mut *connection)
+            .await?;
+
+        if !acquired {
+            let job_id = sqlx::query_scalar::<_, String>(
+                "SELECT job_id FROM market_data.tinkoff_indicators_locks WHERE instrument_uid = $1",
+            )
+            .bind(instrument_uid)
+            .fetch_optional(self.connection.get_pool())
+            .await?;
+            debug!("Lock of {} held by job {:?}", instrument_uid, job_id);
+            return Ok(InstrumentLock::Held { job_id });
+        }
+
+        let guard = PgInstrumentLock {
+            connection: Some(connection),
+            instrument_uid: instrument_uid.to_string(),
+        };
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_indicators_locks (instrument_uid, job_id, lock_time)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (instrument_uid)
+             DO UPDATE SET job_id = $2, lock_time = NOW()",
+        )
+        .bind(instrument_uid)
+        .bind(job_id)
+        .execute(self.connection.get_pool())
+        .await?;
+
+        Ok(InstrumentLock::Acquired(Box::new(guard)))
+    }
+}
+
+/// Pooled connection holding the advisory lock of an instrument
+struct PgInstrumentLock {
+    connection: Option<PoolConnection<Postgres>>,
+    instrument_uid: String,
+}
+
+impl Drop for PgInstrumentLock {
+    fn drop(&mut self) {
+        let Some(mut connection) = self.connection.take() else {
+            return;
+        };
+        let instrument_uid = std::mem::take(&mut self.instrument_uid);
+        tokio::spawn(async move {
+            let unlocked = sqlx::query_scalar::<_, bool>("SELECT pg_advisory_unlock($1, hashtext($2))")
+                .bind(INSTRUMENT_LOCK_CLASS)
+                .bind(&instrument_uid)
+                .fetch_one(&mut *connection)
+                .await;
+            if !matches!(unlocked, Ok(true)) {
+                // Closing the session releases whatever it still holds
+                warn!("Failed to release the lock of {}, closing its connection: {:?}", instrument_uid, unlocked);
+                let _ = connection.detach();
+            }
+        });
+    }
+}
@@ -0,0 +1,62 @@
+// src/db/postgres/repository/signal_cooldown_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Last published signal time per (instrument, signal kind); kinds are snake_case names
+#[async_trait]
+pub trait TraitSignalCooldownRepository {
+    async fn get_last_emitted(&self, instrument_uid: &str) -> Result<HashMap<String, i64>, SqlxError>;
+    async fn record_emitted(&self, instrument_uid: &str, emitted: &HashMap<String, i64>) -> Result<(), SqlxError>;
+}
+
+pub struct StructSignalCooldownRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructSignalCooldownRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitSignalCooldownRepository for StructSignalCooldownRepository {
+    async fn get_last_emitted(&self, instrument_uid: &str) -> Result<HashMap<String, i64>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT kind, last_emitted_time FROM market_data.tinkoff_signal_cooldown WHERE instrument_uid = $1",
+        )
+        .bind(instrument_uid)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn record_emitted(&self, instrument_uid: &str, emitted: &HashMap<String, i64>) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        for (kind, time) in emitted {
+            sqlx::query(
+                "INSERT INTO market_data.tinkoff_signal_cooldown (instrument_uid, kind, last_emitted_time, update_time)
+                 VALUES ($1, $2, $3, NOW())
+                 ON CONFLICT (instrument_uid, kind)
+                 DO UPDATE SET last_emitted_time = GREATEST(tinkoff_signal_cooldown.last_emitted_time, $3), update_time = NOW()",
+            )
+            .bind(instrument_uid)
+            .bind(kind)
+            .bind(time)
+            .execute(pool)
+            .await?;
+        }
+
+        debug!("Recorded {} emitted signal kinds for {}", emitted.len(), instrument_uid);
+
+        Ok(())
+    }
+}
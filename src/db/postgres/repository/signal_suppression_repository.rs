@@ -0,0 +1,90 @@
+// src/db/postgres/repository/signal_suppression_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use crate::db::postgres::models::signal_suppression::PgSignalSuppression;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Per-instrument kill switch of signal publication
+#[async_trait]
+pub trait TraitSignalSuppressionRepository {
+    async fn get_suppressions(&self) -> Result<Vec<PgSignalSuppression>, SqlxError>;
+    async fn is_suppressed(&self, instrument_uid: &str) -> Result<bool, SqlxError>;
+    /// Suppresses an instrument, replacing the reason if it already is
+    async fn suppress(&self, instrument_uid: &str, reason: &str) -> Result<(), SqlxError>;
+    /// Lifts the suppression, returns false if the instrument was not suppressed
+    async fn release(&self, instrument_uid: &str) -> Result<bool, SqlxError>;
+}
+
+pub struct StructSignalSuppressionRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructSignalSuppressionRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitSignalSuppressionRepository for StructSignalSuppressionRepository {
+    async fn get_suppressions(&self) -> Result<Vec<PgSignalSuppression>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let rows = sqlx::query_as::<_, PgSignalSuppression>(
+            "SELECT instrument_uid, reason, suppressed_at
+             FROM market_data.tinkoff_signal_suppression
+             ORDER BY instrument_uid",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Retrieved {} signal suppressions", rows.len());
+
+        Ok(rows)
+    }
+
+    async fn is_suppressed(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM market_data.tinkoff_signal_suppression WHERE instrument_uid = $1)",
+        )
+        .bind(instrument_uid)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn suppress(&self, instrument_uid: &str, reason: &str) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_signal_suppression (instrument_uid, reason, suppressed_at)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (instrument_uid)
+             DO UPDATE SET reason = $2",
+        )
+        .bind(instrument_uid)
+        .bind(reason)
+        .execute(pool)
+        .await?;
+
+        info!("Suppressed signals of {}: {}", instrument_uid, reason);
+
+        Ok(())
+    }
+
+    async fn release(&self, instrument_uid: &str) -> Result<bool, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        let result = sqlx::query("DELETE FROM market_data.tinkoff_signal_suppression WHERE instrument_uid = $1")
+            .bind(instrument_uid)
+            .execute(pool)
+            .await?;
+
+        info!("Released signal suppression of {}", instrument_uid);
+
+        Ok(result.rows_affected() > 0)
+    }
+}
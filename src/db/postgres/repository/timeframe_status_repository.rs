@@ -0,0 +1,73 @@
+// src/db/postgres/repository/timeframe_status_repository.rs
+use crate::db::postgres::connection::PostgresConnection;
+use async_trait::async_trait;
+use sqlx::Error as SqlxError;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Start time of the last processed bar per (instrument, higher timeframe); timeframes are
+/// config names such as "15min"
+#[async_trait]
+pub trait TraitTimeframeStatusRepository {
+    async fn get_last_processed_bar(&self, instrument_uid: &str, timeframe: &str) -> Result<Option<i64>, SqlxError>;
+    async fn update_last_processed_bar(
+        &self,
+        instrument_uid: &str,
+        timeframe: &str,
+        time: i64,
+        run_id: &str,
+    ) -> Result<(), SqlxError>;
+}
+
+pub struct StructTimeframeStatusRepository {
+    connection: Arc<PostgresConnection>,
+}
+
+impl StructTimeframeStatusRepository {
+    pub fn new(connection: Arc<PostgresConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TraitTimeframeStatusRepository for StructTimeframeStatusRepository {
+    async fn get_last_processed_bar(&self, instrument_uid: &str, timeframe: &str) -> Result<Option<i64>, SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query_scalar::<_, i64>(
+            "SELECT last_processed_time FROM market_data.tinkoff_indicators_status_tf
+             WHERE instrument_uid = $1 AND timeframe = $2",
+        )
+        .bind(instrument_uid)
+        .bind(timeframe)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn update_last_processed_bar(
+        &self,
+        instrument_uid: &str,
+        timeframe: &str,
+        time: i64,
+        run_id: &str,
+    ) -> Result<(), SqlxError> {
+        let pool = self.connection.get_pool();
+
+        sqlx::query(
+            "INSERT INTO market_data.tinkoff_indicators_status_tf (instrument_uid, timeframe, last_processed_time, run_id, update_time)
+             VALUES ($1, $2, $3, $4, NOW())
+             ON CONFLICT (instrument_uid, timeframe)
+             DO UPDATE SET last_processed_time = $3, run_id = $4, update_time = NOW()",
+        )
+        .bind(instrument_uid)
+        .bind(timeframe)
+        .bind(time)
+        .bind(run_id)
+        .execute(pool)
+        .await?;
+
+        debug!("Updated last processed {} bar for {}: {}", timeframe, instrument_uid, time);
+
+        Ok(())
+    }
+}
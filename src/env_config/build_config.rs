@@ -13,9 +13,15 @@ impl AppConfig {
     }
 
     fn load_config(env: &Env) -> Result<AppConfig, Box<dyn std::error::Error>> {
-        let config_path = format!("config/{}.toml", env);
-        let path = Path::new(&config_path);
+        Self::from_file(Path::new(&Self::path(env)))
+    }
+
+    /// Config file of an environment
+    pub fn path(env: &Env) -> String {
+        format!("config/{}.toml", env)
+    }
 
+    pub fn from_file(path: &Path) -> Result<AppConfig, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
         let config: AppConfig = toml::from_str(&content)?;
 
@@ -1,74 +1,735 @@
-use chrono::{NaiveTime, Utc};
-use serde::Deserialize;
+use super::timing::{self, TimeOfDay};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+pub use t_indicators_core::config::{AdaptiveThresholdsConfig, HurstConfig, TripleBarrierConfig};
 #[derive(Debug, Deserialize)]
 pub struct AppConfig {
     pub log: LogConfig,
     pub clickhouse: ClickhouseConfig,
     pub postgres: PostgresConfig,
     pub indicators_updater: IndicatorsUpdaterConfig,
+    #[serde(default)]
+    pub feature_flags: HashMap<String, bool>, // Значения по умолчанию, переопределяются в tinkoff_feature_flags
+    #[serde(default)]
+    pub query_cache: QueryCacheConfig,
+    #[serde(default)]
+    pub retry: RetryConfig, // Повторы при временных ошибках ClickHouse/PostgreSQL
+    #[serde(default)]
+    pub embedded: EmbeddedConfig, // Встроенное хранилище SQLite вместо ClickHouse/PostgreSQL (dev)
+    #[serde(default)]
+    pub signals: SignalsConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig, // Рассылка сигналов по правилам маршрутизации
+    #[serde(default)]
+    pub status: StatusConfig, // Публичная страница состояния /status
+    #[serde(default)]
+    pub export: ExportConfig, // Инкрементальная выгрузка новых строк во внешнее хранилище
+    #[serde(default)]
+    pub instrument_filter: InstrumentFilterConfig, // Исключение делистингованных и тестовых инструментов
+    #[serde(default)]
+    pub admin: AdminConfig, // Веб-админка /admin
 
 }
 #[derive(Debug, Deserialize)]
 pub struct IndicatorsUpdaterConfig {
     pub enabled: bool,
-    pub interval_seconds: u64,
+    #[serde(alias = "interval_seconds", with = "timing::seconds")]
+    pub interval: Duration, // Период обновления: "5m", "90s"; число - секунды
+    #[serde(default)]
+    pub cron: Option<String>, // Расписание в формате cron, UTC ("*/5 7-15 * * MON-FRI"), заменяет interval
+    #[serde(default)]
+    pub start_time: Option<TimeOfDay>, // Время начала в UTC, "HH:MM" или "HH:MM:SS"
+    #[serde(default)]
+    pub end_time: Option<TimeOfDay>, // Время окончания в UTC
+    #[serde(default)]
+    pub spreads: Vec<SpreadConfig>, // Синтетические инструменты (спреды/отношения двух инструментов)
+    #[serde(default)]
+    pub portfolios: Vec<PortfolioConfig>, // Взвешенные портфели (вотчлисты)
+    #[serde(default)]
+    pub tiers: Vec<TierConfig>, // Приоритет и частота обновления групп инструментов
+    #[serde(default = "default_roc_lags")]
+    pub roc_lags: Vec<usize>, // Лаги (в свечах) для колонок roc/momentum
+    #[serde(default = "default_volume_baseline_days")]
+    pub volume_baseline_days: u32, // Глубина оценки сезонной базовой линии объёма, дни
+    #[serde(default = "default_hma_period")]
+    pub hma_period: usize, // Период WMA/HMA, свечи
+    #[serde(default = "default_volume_anomaly_sigma")]
+    pub volume_anomaly_sigma: f64, // Порог z-score объёма для volume_anomaly/volume_anomaly_score
+    #[serde(default)]
+    pub adaptive_thresholds: AdaptiveThresholdsConfig,
+    #[serde(default = "default_targets")]
+    pub targets: Vec<TargetConfig>, // Горизонты целевой переменной (колонки target_change/target_signal)
+    #[serde(default)]
+    pub triple_barrier: TripleBarrierConfig,
+    #[serde(default)]
+    pub calendar: CalendarConfig, // Торговый календарь биржи (колонки session_phase/minutes_since_session_open)
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize, // Свечей за один проход по инструменту, стартовое значение для советника
+    #[serde(default = "default_async_insert")]
+    pub async_insert: bool, // Вставка индикаторов через async_insert ClickHouse
+    #[serde(default = "default_max_concurrent_instruments")]
+    pub max_concurrent_instruments: usize, // Инструментов, обрабатываемых одновременно, 1 - последовательно
+    #[serde(default = "default_shutdown_timeout", alias = "shutdown_timeout_seconds", with = "timing::seconds")]
+    pub shutdown_timeout: Duration, // Ожидание текущего прохода при остановке (SIGTERM)
+    #[serde(default = "default_instrument_timeout", with = "timing::seconds")]
+    pub instrument_timeout: Duration, // Предел обработки одного инструмента, проверяется между батчами; дольше - сбой, проход идёт дальше; 0 - без предела
+    #[serde(default)]
+    pub hurst: HurstConfig, // Показатель Херста (колонка hurst)
+    #[serde(default)]
+    pub timeframes: Vec<Timeframe>, // Старшие таймфреймы, считаются в tinkoff_indicators_{tf}
+    #[serde(default)]
+    pub gaps: GapConfig, // Разрывы между свечами (выходные, праздники), колонка session_break
+    #[serde(default)]
+    pub backfill: BackfillConfig, // Глубокий догоняющий пересчёт: своё окно и ресурсы
+    #[serde(default)]
+    pub recompute: RecomputeConfig, // Пересчёт затронутых колонок после смены параметров индикаторов
+    #[serde(default)]
+    pub dead_letter: DeadLetterConfig, // Пропуск инструментов, которые падают раз за разом
+    #[serde(default = "default_plugins_dir")]
+    pub plugins_dir: String, // Каталог библиотек пользовательских индикаторов
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>, // Пользовательские индикаторы (колонка plugin_values, в том же порядке)
+}
+
+/// Catch-up of instruments far behind the latest candles, with its own operation window and
+/// resource budget so it can run full-throttle at night
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackfillConfig {
+    pub lag_hours: u32, // Отставание последней обработанной свечи, с которого инструмент в бэкфилле, 0 - без разделения
+    pub start_time: Option<TimeOfDay>, // Окно бэкфилла в UTC; вне окна такие инструменты пропускаются
+    pub end_time: Option<TimeOfDay>,
+    pub batch_size: usize,          // Свечей за проход, 0 - как у инкрементального обновления
+    #[serde(alias = "batch_pause_ms", with = "timing::millis")]
+    pub batch_pause: Duration,      // Пауза между проходами: "10ms"; число - миллисекунды
+    pub max_batches_per_run: usize, // Проходов на инструмент за запуск, 0 - без ограничения
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            lag_hours: 0,
+            start_time: None,
+            end_time: None,
+            batch_size: 0,
+            batch_pause: Duration::from_millis(10),
+            max_batches_per_run: 0,
+        }
+    }
+}
+
+impl BackfillConfig {
+    /// Checks if the current time is within the backfill window
+    pub fn is_window_open(&self) -> bool {
+        is_within_window(&self.start_time, &self.end_time)
+    }
+}
+
+/// Instruments failing `max_failures` runs in a row are skipped for `cooldown`, then retried
+/// once per cooldown until a run succeeds. Failures are kept in tinkoff_indicators_failures.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DeadLetterConfig {
+    pub max_failures: u32, // Подряд упавших запусков до пропуска, 0 - не пропускать
+    #[serde(with = "timing::seconds")]
+    pub cooldown: Duration, // Пауза перед повторной попыткой: "1h"; число - секунды
+}
+
+impl Default for DeadLetterConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 5,
+            cooldown: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl DeadLetterConfig {
+    /// Whether an instrument with `failures` open failures, the last at `last_failure_time`
+    /// (unix seconds), waits out its cooldown at `now`
+    pub fn is_cooling_down(&self, failures: i64, last_failure_time: i64, now: i64) -> bool {
+        self.max_failures > 0
+            && failures >= self.max_failures as i64
+            && now < last_failure_time + self.cooldown.as_secs() as i64
+    }
+}
+
+/// Recomputation of stored rows when indicator parameters change in config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RecomputeConfig {
+    pub enabled: bool,      // false - новые версии только запоминаются, пересчёт вручную
+    pub lookback_days: u32, // Глубина пересчёта от текущего момента, 0 - вся история
+}
+
+impl Default for RecomputeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback_days: 0,
+        }
+    }
+}
+
+/// Exchange trading calendar, MOEX by default. Times are UTC, formatted "HH:MM" or "HH:MM:SS"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub exchange: String,
+    pub pre_market: SessionWindow, // Аукцион открытия
+    pub main: SessionWindow,       // Основная сессия (с аукционом закрытия)
+    pub evening: SessionWindow,    // Вечерняя сессия
+    pub trading_weekdays: Vec<u32>, // 1 - понедельник ... 7 - воскресенье
+    pub holidays: Vec<NaiveDate>,  // Неторговые дни, формат: "YYYY-MM-DD"
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            exchange: "MOEX".to_string(),
+            pre_market: SessionWindow::new("06:50:00", "07:00:00"),
+            main: SessionWindow::new("07:00:00", "15:50:00"),
+            evening: SessionWindow::new("16:05:00", "20:50:00"),
+            trading_weekdays: vec![1, 2, 3, 4, 5],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+/// Daily session window `[start, end)`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionWindow {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+}
+
+impl SessionWindow {
+    fn new(start: &str, end: &str) -> Self {
+        Self {
+            start: start.parse().expect("valid default session time"),
+            end: end.parse().expect("valid default session time"),
+        }
+    }
+}
+
+/// Horizon and classification threshold of one target column
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TargetConfig {
+    pub horizon: usize,     // Горизонт, свечи
+    pub threshold_pct: f64, // Порог сигнала роста/падения, %
+}
+
+/// Custom indicator computed by a shared library, see `t_indicators_core::plugin`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PluginConfig {
+    pub name: String,    // Имя в сводке конфигурации и логах
+    pub library: String, // Файл библиотеки, относительно plugins_dir
     #[serde(default)]
-    pub start_time: Option<String>, // Время начала в UTC, формат: "HH:MM:SS"
+    pub params: Vec<f64>, // Параметры, передаются плагину как есть
+}
+
+/// Handling of long breaks between consecutive 1-minute candles
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GapConfig {
+    pub max_gap_minutes: u32, // Разрыв длиннее этого начинает новую сессию, 0 - без учёта разрывов
+    pub mode: GapMode,
+    pub decay_candles: usize, // Для mode = "decay": сколько свечей до разрыва остаётся в состоянии
+}
+
+impl Default for GapConfig {
+    fn default() -> Self {
+        Self {
+            max_gap_minutes: 0,
+            mode: GapMode::Flag,
+            decay_candles: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GapMode {
+    Flag,  // Только отметить session_break, состояние индикаторов сохраняется
+    Reset, // Состояние индикаторов считается заново после разрыва
+    Decay, // После разрыва в состоянии остаются только последние decay_candles свечей
+}
+
+/// Higher timeframe aggregated from 1-minute candles; bars are aligned to UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Timeframe {
+    #[serde(rename = "5min")]
+    Min5,
+    #[serde(rename = "15min")]
+    Min15,
+    #[serde(rename = "1hour")]
+    Hour1,
+    #[serde(rename = "1day")]
+    Day1,
+}
+
+impl Timeframe {
+    /// Name as in the config and the `tinkoff_indicators_{name}` table
+    pub fn name(self) -> &'static str {
+        match self {
+            Timeframe::Min5 => "5min",
+            Timeframe::Min15 => "15min",
+            Timeframe::Hour1 => "1hour",
+            Timeframe::Day1 => "1day",
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Timeframe::Min5 => 300,
+            Timeframe::Min15 => 900,
+            Timeframe::Hour1 => 3600,
+            Timeframe::Day1 => 86_400,
+        }
+    }
+
+    /// Timeframe of bars of `minutes`, if it has a table
+    pub fn from_minutes(minutes: u32) -> Option<Self> {
+        [Timeframe::Min5, Timeframe::Min15, Timeframe::Hour1, Timeframe::Day1]
+            .into_iter()
+            .find(|timeframe| timeframe.seconds() == i64::from(minutes) * 60)
+    }
+}
+
+/// Synthetic instrument built from two real instruments
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpreadConfig {
+    pub name: String,
+    pub leg_a: String,
+    pub leg_b: String,
+    #[serde(default)]
+    pub mode: SpreadMode,
+    #[serde(default = "default_hedge_ratio")]
+    pub hedge_ratio: f64, // Используется только для mode = "difference": leg_a - hedge_ratio * leg_b
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpreadMode {
+    #[default]
+    Ratio,
+    Difference,
+}
+
+/// Weighted watchlist treated as a single synthetic instrument
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioConfig {
+    pub name: String,
+    pub members: Vec<PortfolioMember>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioMember {
+    pub instrument_uid: String,
+    pub weight: f64,
+}
+
+/// Group of instruments refreshed every `every`-th scheduler cycle; unlisted instruments
+/// have priority 0 and are refreshed every cycle
+#[derive(Debug, Clone, Deserialize)]
+pub struct TierConfig {
+    pub name: String,
     #[serde(default)]
-    pub end_time: Option<String>, // Время окончания в UTC, формат: "HH:MM:SS"
+    pub priority: i32, // Больше - раньше в очереди прохода
+    #[serde(default = "default_tier_every")]
+    pub every: u32, // Обновлять каждый N-й цикл планировщика
+    pub instruments: Vec<String>, // instrument_uid; спреды и портфели - "spread:<name>", "portfolio:<name>"
+}
+
+fn default_tier_every() -> u32 {
+    1
+}
+
+fn default_roc_lags() -> Vec<usize> {
+    vec![1, 5, 15, 60]
+}
+
+fn default_volume_baseline_days() -> u32 {
+    30
+}
+
+fn default_targets() -> Vec<TargetConfig> {
+    vec![TargetConfig {
+        horizon: 15,
+        threshold_pct: 0.2,
+    }]
+}
+
+fn default_batch_size() -> usize {
+    100000
+}
+
+fn default_async_insert() -> bool {
+    true
+}
+
+fn default_max_concurrent_instruments() -> usize {
+    4
+}
+
+fn default_shutdown_timeout() -> Duration {
+    Duration::from_secs(25)
+}
+
+fn default_instrument_timeout() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_plugins_dir() -> String {
+    "plugins".to_string()
+}
+
+fn default_hma_period() -> usize {
+    20
+}
+
+fn default_sampling_level() -> String {
+    "info".to_string()
+}
+
+fn default_volume_anomaly_sigma() -> f64 {
+    2.0
+}
+
+fn default_hedge_ratio() -> f64 {
+    1.0
 }
 #[derive(Debug, Deserialize)]
 pub struct LogConfig {
     pub level: String,
     pub format: String,
+    #[serde(default)]
+    pub sampling: LogSamplingConfig, // Прореживание частых сообщений по target
+    #[serde(default)]
+    pub file: LogFileConfig, // Запись в файлы с ротацией, помимо stdout
+}
+
+/// Rolling log files for deployments without a log collector
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogFileConfig {
+    pub enabled: bool,
+    pub directory: String,
+    pub prefix: String,        // Имя файла: {prefix}.{дата}.log, для rotation = "size" - {prefix}.log
+    pub rotation: LogRotation,
+    pub max_size_mb: u64,      // Размер файла до ротации, только для rotation = "size"
+    pub max_files: usize,      // Сколько файлов хранить, включая текущий, 0 - все
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "logs".to_string(),
+            prefix: "t-indicators".to_string(),
+            rotation: LogRotation::Daily,
+            max_size_mb: 100,
+            max_files: 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Never,
+    Size, // По размеру файла, max_size_mb
+}
+
+/// Sampling of repetitive log messages, e.g. per-batch logs of a full recalculation
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogSamplingConfig {
+    #[serde(alias = "rollup_seconds", with = "timing::seconds")]
+    pub rollup: Duration, // Период сводки о пропущенных сообщениях, 0 - без сводки
+    pub rules: Vec<LogSamplingRule>,
+}
+
+impl Default for LogSamplingConfig {
+    fn default() -> Self {
+        Self {
+            rollup: Duration::from_secs(60),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogSamplingRule {
+    pub target: String, // Модуль, например "t_indicators::services::indicators::calculator", включая вложенные
+    #[serde(default = "default_sampling_level")]
+    pub level: String,  // Прореживаются сообщения этого уровня и подробнее; warn/error выше него проходят все
+    pub every: u64,     // Пишется каждое N-е сообщение
+}
+
+/// In-memory cache of API query results
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QueryCacheConfig {
+    pub capacity: usize,    // Максимум закэшированных ответов, 0 - кэш выключен
+    #[serde(alias = "ttl_seconds", with = "timing::seconds")]
+    pub ttl: Duration,      // Верхняя граница устаревания записи
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 512,
+            ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Retries of transient database errors with exponential backoff and jitter
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_attempts: u32,       // Попыток всего, 1 - без повторов
+    #[serde(alias = "initial_backoff_ms", with = "timing::millis")]
+    pub initial_backoff: Duration, // Пауза перед первым повтором, дальше удваивается; число - миллисекунды
+    #[serde(alias = "max_backoff_ms", with = "timing::millis")]
+    pub max_backoff: Duration,     // Верхняя граница паузы
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Local development store, requires building with `--features embedded`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmbeddedConfig {
+    pub enabled: bool,
+    pub path: String, // Файл SQLite, ":memory:" - в памяти
+}
+
+impl Default for EmbeddedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/dev.sqlite".to_string(),
+        }
+    }
+}
+
+/// Deduplication of repeated signal events in the feed and notifications
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SignalsConfig {
+    #[serde(alias = "cooldown_seconds", with = "timing::seconds")]
+    pub cooldown: Duration, // Минимальный интервал между сигналами одного типа по инструменту
+    #[serde(with = "timing::seconds_map")]
+    pub cooldown_by_kind: HashMap<String, Duration>, // Переопределения по типу сигнала, 0 - без паузы
+}
+
+impl Default for SignalsConfig {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::from_secs(3600),
+            cooldown_by_kind: HashMap::new(),
+        }
+    }
+}
+
+/// Public status page: freshness thresholds and announced maintenance
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatusConfig {
+    #[serde(alias = "degraded_lag_seconds", with = "timing::seconds")]
+    pub degraded_lag: Duration, // Отставание данных в торговую сессию, после которого статус yellow
+    #[serde(alias = "down_lag_seconds", with = "timing::seconds")]
+    pub down_lag: Duration,     // Отставание, после которого статус red
+    pub maintenance: Vec<MaintenanceWindow>,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            degraded_lag: Duration::from_secs(900),
+            down_lag: Duration::from_secs(3600),
+            maintenance: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>, // RFC 3339, например "2026-11-01T20:00:00Z"
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Instruments processed out of those with candles, extended by tinkoff_instrument_filters
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct InstrumentFilterConfig {
+    pub allow: Vec<String>, // Непустой список - обрабатываются только эти instrument_uid
+    pub deny: Vec<String>,  // Не обрабатываются, важнее allow
+}
+
+/// Admin UI at /admin. With a token, the UI and /api/admin require it (`Authorization: Bearer
+/// <token>`, or Basic auth with the token as password in browsers); without one both are open.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub token_env: Option<String>, // Переменная окружения с токеном, обязательна если задана
+}
+
+/// Periodic export of the 1-minute indicator rows inserted since the previous run
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    pub enabled: bool,
+    #[serde(alias = "interval_seconds", with = "timing::seconds")]
+    pub interval: Duration, // Период выгрузки
+    #[serde(alias = "lag_seconds", with = "timing::seconds")]
+    pub lag: Duration,      // Строки моложе этого ждут следующей выгрузки: асинхронные вставки ещё дописываются
+    pub targets: Vec<ExportTarget>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(300),
+            lag: Duration::from_secs(60),
+            targets: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportTarget {
+    pub name: String,        // Ключ high-watermark в tinkoff_export_watermarks
+    pub destination: String, // Табличная функция ClickHouse: s3(...), remoteSecure(...), url(...); {watermark} - верхняя граница выгрузки, мс
+}
+
+/// Outgoing signal notifications: named channels and the rules routing signals to them
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub enabled: bool,
+    #[serde(alias = "max_signal_age_seconds", with = "timing::seconds")]
+    pub max_signal_age: Duration, // Более старые сигналы не рассылаются (догоняющий пересчёт)
+    pub discovery_channels: Vec<String>, // Каналы для событий о новых инструментах
+    pub channels: HashMap<String, ChannelConfig>,
+    pub rules: Vec<RoutingRuleConfig>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_signal_age: Duration::from_secs(600),
+            discovery_channels: Vec::new(),
+            channels: HashMap::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChannelConfig {
+    Telegram {
+        bot_token_env: String, // Переменная окружения с токеном бота
+        chat_id: String,
+    },
+    Webhook {
+        url: String,
+    },
+    Kafka {
+        rest_proxy_url: String, // Kafka REST Proxy (v2 API)
+        topic: String,
+    },
+}
+
+/// Signals matching every condition of a rule go to all of its channels
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoutingRuleConfig {
+    pub name: String,
+    #[serde(default)]
+    pub signals: Vec<String>, // golden_cross, death_cross, rsi_oversold, rsi_overbought; пусто - все
+    #[serde(default)]
+    pub instruments: Vec<String>, // пусто вместе с watchlist - все инструменты
+    #[serde(default)]
+    pub watchlist: Option<String>, // Имя портфеля из indicators_updater.portfolios
+    #[serde(default)]
+    pub min_severity: Option<String>, // low, medium, high
+    pub channels: Vec<String>,
+    #[serde(default)]
+    pub quiet_hours: Option<SessionWindow>, // Окно UTC без рассылки, может переходить через полночь
+    #[serde(default)]
+    pub max_per_hour: u32, // 0 - без ограничения
+    #[serde(default)]
+    pub confirm_timeframes: Vec<u32>, // Таймфреймы в минутах, тренд которых должен совпадать с сигналом
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ClickhouseConfig {
-    pub timeout: u64,
+    #[serde(with = "timing::seconds")]
+    pub timeout: Duration,
     pub pool_min: u32,
     pub pool_max: u32,
+    #[serde(default)]
+    pub hot_days: u32, // Глубина "горячей" таблицы индикаторов, дни; 0 - без разделения на hot/cold
 }
 #[derive(Debug, Deserialize)]
 pub struct PostgresConfig {
-    pub timeout: u64,
+    #[serde(with = "timing::seconds")]
+    pub timeout: Duration,
     pub max_connections: u32,
     pub min_connections: u32,
-    pub max_lifetime: u64,
-    pub idle_timeout: u64,
+    #[serde(with = "timing::seconds")]
+    pub max_lifetime: Duration,
+    #[serde(with = "timing::seconds")]
+    pub idle_timeout: Duration,
 }
 
 
 impl IndicatorsUpdaterConfig {
     /// Checks if the current time is within the allowed operation window
     pub fn is_operation_allowed(&self) -> bool {
-        // If no time window is configured, always allow operation
-        if self.start_time.is_none() || self.end_time.is_none() {
-            return true;
-        }
-        
-        // Get current UTC time
-        let now = chrono::Utc::now().time();
-        
-        // Parse start and end times
-        if let (Some(start_str), Some(end_str)) = (&self.start_time, &self.end_time) {
-            if let (Ok(start), Ok(end)) = (
-                NaiveTime::parse_from_str(start_str, "%H:%M:%S"),
-                NaiveTime::parse_from_str(end_str, "%H:%M:%S"),
-            ) {
-                // Check if current time is within the operation window
-                if start <= end {
-                    // Simple case: start time is before end time
-                    return start <= now && now <= end;
-                } else {
-                    // Case where operation window crosses midnight
-                    // e.g., start=21:00:00, end=04:00:00
-                    return start <= now || now <= end;
-                }
-            }
-        }
-        
-        // If parsing fails, default to allowing operation
-        true
+        is_within_window(&self.start_time, &self.end_time)
+    }
+}
+
+/// Checks if the current UTC time is within a window, which may cross midnight
+fn is_within_window(start_time: &Option<TimeOfDay>, end_time: &Option<TimeOfDay>) -> bool {
+    // If no time window is configured, always allow operation
+    let (Some(start), Some(end)) = (start_time, end_time) else {
+        return true;
+    };
+    
+    // Get current UTC time
+    let now = chrono::Utc::now().time();
+    let (start, end) = (start.time(), end.time());
+    
+    // Check if current time is within the operation window
+    if start <= end {
+        // Simple case: start time is before end time
+        start <= now && now <= end
+    } else {
+        // Case where operation window crosses midnight
+        // e.g., start=21:00:00, end=04:00:00
+        start <= now || now <= end
     }
 }
\ No newline at end of file
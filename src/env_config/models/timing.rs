@@ -0,0 +1,182 @@
+// File: src/env_config/models/timing.rs
+//! Typed durations and times of day of the config, validated when it is loaded.
+//!
+//! Durations are humantime strings ("90s", "15m", "1h 30m", "250ms"). A bare integer is
+//! still accepted in the unit of the option's former `_seconds`/`_ms` name, so existing
+//! configs keep working.
+use chrono::{NaiveTime, Timelike};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// UTC time of day, written "HH:MM" or "HH:MM:SS"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay(NaiveTime);
+
+impl TimeOfDay {
+    pub fn time(self) -> NaiveTime {
+        self.0
+    }
+
+    /// Minutes since midnight
+    pub fn minute_of_day(self) -> i32 {
+        (self.0.hour() * 60 + self.0.minute()) as i32
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        NaiveTime::parse_from_str(value, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+            .map(TimeOfDay)
+            .map_err(|_| format!("invalid time of day \"{}\", expected \"HH:MM\" or \"HH:MM:SS\"", value))
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%H:%M:%S"))
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for TimeOfDay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Parses a humantime duration, naming the accepted format on error
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    humantime::parse_duration(value.trim())
+        .map_err(|e| format!("invalid duration \"{}\" ({}), expected e.g. \"90s\", \"15m\" or \"1h 30m\"", value, e))
+}
+
+/// Humantime string or an integer in `unit`
+struct DurationVisitor {
+    unit: Duration,
+}
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration such as \"90s\" or \"15m\", or a whole number")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Duration, E> {
+        let value = u32::try_from(value).map_err(|_| E::custom(format!("duration {} is too large", value)))?;
+        Ok(self.unit * value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Duration, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::custom(format!("duration {} is negative", value))),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Duration, E> {
+        parse_duration(value).map_err(E::custom)
+    }
+}
+
+/// `#[serde(with = "timing::seconds")]`: integers are seconds
+pub mod seconds {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer.deserialize_any(DurationVisitor { unit: Duration::from_secs(1) })
+    }
+}
+
+/// `#[serde(with = "timing::millis")]`: integers are milliseconds, serialized as "250ms"
+pub mod millis {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer.deserialize_any(DurationVisitor { unit: Duration::from_millis(1) })
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&humantime::format_duration(*duration))
+    }
+}
+
+/// `#[serde(with = "timing::seconds_map")]`: durations by key, integers are seconds
+pub mod seconds_map {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Seconds(#[serde(with = "super::seconds")] Duration);
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Duration>, D::Error> {
+        let map = HashMap::<String, Seconds>::deserialize(deserializer)?;
+        Ok(map.into_iter().map(|(key, Seconds(duration))| (key, duration)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Options {
+        #[serde(with = "seconds")]
+        interval: Duration,
+        #[serde(with = "millis")]
+        pause: Duration,
+        #[serde(with = "seconds_map", default)]
+        by_kind: HashMap<String, Duration>,
+        start: Option<TimeOfDay>,
+    }
+
+    fn parse(toml: &str) -> Result<Options, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_parse_durations_and_times() {
+        let options = parse("interval = \"1h 30m\"\npause = \"250ms\"\nby_kind = { rsi = \"15m\" }\nstart = \"07:00\"").unwrap();
+        assert_eq!(options.interval, Duration::from_secs(5400));
+        assert_eq!(options.pause, Duration::from_millis(250));
+        assert_eq!(options.by_kind["rsi"], Duration::from_secs(900));
+        assert_eq!(options.start.unwrap().minute_of_day(), 420);
+        assert_eq!(options.start.unwrap().to_string(), "07:00:00");
+
+        // Bare integers in the former unit of the option
+        let options = parse("interval = 300\npause = 10\nby_kind = { rsi = 0 }").unwrap();
+        assert_eq!(options.interval, Duration::from_secs(300));
+        assert_eq!(options.pause, Duration::from_millis(10));
+        assert_eq!(options.by_kind["rsi"], Duration::ZERO);
+        assert!(options.start.is_none());
+
+        let error = parse("interval = \"5 minutes later\"\npause = 0").unwrap_err();
+        assert!(error.contains("invalid duration \"5 minutes later\""), "{}", error);
+        let error = parse("interval = 60\npause = 0\nstart = \"25:00:00\"").unwrap_err();
+        assert!(error.contains("invalid time of day \"25:00:00\""), "{}", error);
+        assert!(parse("interval = -5\npause = 0").is_err());
+    }
+
+    #[test]
+    fn test_shipped_configs_parse() {
+        use crate::env_config::models::app_config::AppConfig;
+
+        for content in [include_str!("../../../config/local.toml"), include_str!("../../../config/prod.toml")] {
+            let config: AppConfig = toml::from_str(content).unwrap();
+            assert_eq!(config.indicators_updater.interval, Duration::from_secs(300));
+            assert_eq!(config.retry.initial_backoff, Duration::from_millis(200));
+        }
+    }
+}
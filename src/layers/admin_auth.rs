@@ -0,0 +1,100 @@
+use crate::api::error::ApiError;
+use crate::env_config::models::app_config::AdminConfig;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::Engine;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Токен админки (`admin.token_env`); `None` - админка и /api/admin открыты.
+#[derive(Clone, Default)]
+pub struct AdminToken(Option<Arc<str>>);
+
+impl AdminToken {
+    /// Читает токен из переменной окружения, заданной в конфиге; без неё сервис не стартует.
+    pub fn from_config(config: &AdminConfig) -> Self {
+        let Some(name) = &config.token_env else {
+            warn!("admin.token_env is not set, /admin and /api/admin are open");
+            return Self(None);
+        };
+        match std::env::var(name) {
+            Ok(token) if !token.is_empty() => Self(Some(token.into())),
+            _ => panic!("ENV -> {} is not set", name),
+        }
+    }
+
+    /// `Authorization: Bearer <token>` или Basic с токеном в качестве пароля (браузер)
+    fn accepts(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = &self.0 else {
+            return true;
+        };
+        let Some(value) = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) else {
+            return false;
+        };
+
+        let presented = match value.split_once(' ') {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim().to_string(),
+            Some((scheme, credentials)) if scheme.eq_ignore_ascii_case("basic") => {
+                let decoded = base64::engine::general_purpose::STANDARD
+                    .decode(credentials.trim())
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok());
+                match decoded.as_deref().and_then(|pair| pair.split_once(':')) {
+                    Some((_, password)) => password.to_string(),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+        constant_time_eq(presented.as_bytes(), expected.as_bytes())
+    }
+}
+
+/// Пропускает к админке и /api/admin только запросы с токеном; браузеру отвечает
+/// `WWW-Authenticate: Basic`, чтобы он запросил пароль.
+pub async fn require_admin_token(State(token): State<AdminToken>, request: Request, next: Next) -> Response {
+    if token.accepts(request.headers()) {
+        return next.run(request).await;
+    }
+
+    let mut response = ApiError::unauthorized().into_response();
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static("Basic realm=\"t-indicators admin\", charset=\"UTF-8\""),
+    );
+    response
+}
+
+/// Сравнение без раннего выхода, чтобы время ответа не выдавало совпавший префикс
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(authorization).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_accepts_bearer_and_basic() {
+        let token = AdminToken(Some("s3cret".into()));
+        let basic = base64::engine::general_purpose::STANDARD.encode("admin:s3cret");
+
+        assert!(token.accepts(&headers("Bearer s3cret")));
+        assert!(token.accepts(&headers(&format!("Basic {}", basic))));
+        assert!(!token.accepts(&headers("Bearer s3cre")));
+        assert!(!token.accepts(&headers("Basic !!!")));
+        assert!(!token.accepts(&HeaderMap::new()));
+
+        assert!(AdminToken::default().accepts(&HeaderMap::new()));
+    }
+}
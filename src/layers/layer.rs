@@ -51,8 +51,17 @@ pub fn create_trace() -> TraceLayer<
             .unwrap_or("unknown")
             .to_string();
 
+        // Идентификатор запроса (выставляется create_request_id)
+        let request_id = request
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string();
+
         tracing::info_span!(
             "request",
+            request_id = %request_id,
             method = %request.method(),
             uri = %request.uri(),
             version = ?request.version(),
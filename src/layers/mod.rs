@@ -1,2 +1,6 @@
+mod admin_auth;
 mod layer;
+mod request_id;
+pub use admin_auth::{require_admin_token, AdminToken};
 pub use layer::{create_cors, create_trace};
+pub use request_id::{create_request_id, propagate_request_id, render_api_errors};
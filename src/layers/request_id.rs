@@ -0,0 +1,44 @@
+use crate::api::error::{ApiError, Lang, REQUEST_ID_HEADER};
+use axum::{
+    extract::Request,
+    http::{header::{CONTENT_LENGTH, CONTENT_TYPE}, HeaderName},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+
+/// Присваивает запросу `x-request-id` (если клиент его не передал).
+pub fn create_request_id() -> SetRequestIdLayer<MakeRequestUuid> {
+    SetRequestIdLayer::new(HeaderName::from_static(REQUEST_ID_HEADER), MakeRequestUuid)
+}
+
+/// Возвращает `x-request-id` клиенту в заголовке ответа.
+pub fn propagate_request_id() -> PropagateRequestIdLayer {
+    PropagateRequestIdLayer::new(HeaderName::from_static(REQUEST_ID_HEADER))
+}
+
+/// Перерисовывает тело ошибок `ApiError`: добавляет request id и переводит сообщение
+/// на язык из `Accept-Language`.
+pub async fn render_api_errors(request: Request, next: Next) -> Response {
+    let lang = Lang::from_headers(request.headers());
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+
+    let Some(error) = response.extensions().get::<ApiError>().cloned() else {
+        return response;
+    };
+
+    let mut rendered = error.to_response(lang, request_id);
+    // Keep headers set by the handler or inner layers, except the ones describing the old body
+    for (name, value) in response.headers() {
+        if name != CONTENT_LENGTH && name != CONTENT_TYPE {
+            rendered.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+    rendered
+}
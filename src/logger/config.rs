@@ -1,6 +1,12 @@
+use super::file::file_writer;
+use super::sampling::LogSampler;
+use crate::env_config::models::app_config::{LogFileConfig, LogSamplingConfig};
 use std::fmt;
 use std::io::{Error, ErrorKind};
-use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
 
 /// Supported log format types
 #[derive(Debug, Clone, PartialEq)]
@@ -27,7 +33,20 @@ impl From<&str> for LogFormat {
     }
 }
 
-pub fn init_logger(log_level: &str, log_format: &str) -> Result<(), Error> {
+/// Installed logger. The sampler reports what it suppressed once its rollup is spawned; the
+/// file guard must live until shutdown, dropping it flushes the pending file writes.
+pub struct Logger {
+    pub sampler: LogSampler,
+    pub file_guard: Option<WorkerGuard>,
+}
+
+/// Installs the global subscriber: stdout, plus rolling files if enabled
+pub fn init_logger(
+    log_level: &str,
+    log_format: &str,
+    sampling: &LogSamplingConfig,
+    file: &LogFileConfig,
+) -> Result<Logger, Error> {
     // Parse and validate the log level, falling back to "info" if invalid
     let filter = EnvFilter::try_new(log_level)
         .map_err(|_| Error::new(ErrorKind::InvalidInput, "Invalid log level"))?;
@@ -36,37 +55,43 @@ pub fn init_logger(log_level: &str, log_format: &str) -> Result<(), Error> {
     let env = crate::env_config::models::app_env::AppEnv::new();
     let is_production = !env.is_local();
     
-    // Create builders with appropriate time settings
-    if is_production {
-        // Production mode without timestamps
-        let builder = tracing_subscriber::fmt()
-            .with_env_filter(filter)
-            .with_target(false)
-            .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
-            .without_time();
-            
-        // Initialize with the specified format
-        let format = LogFormat::from(log_format);
-        match format {
-            LogFormat::Json => builder.json().init(),
-            LogFormat::Plain => builder.init(),
-        }
-    } else {
-        // Development mode with timestamps
-        let builder = tracing_subscriber::fmt()
-            .with_env_filter(filter)
-            .with_target(false)
-            .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE);
-            
-        // Initialize with the specified format
-        let format = LogFormat::from(log_format);
-        match format {
-            LogFormat::Json => builder.json().init(),
-            LogFormat::Plain => builder.init(),
-        }
-    }
+    let sampler = LogSampler::new(&sampling.rules);
+    let layer = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE);
     
-    Ok(())
+    // Production mode without timestamps, in the specified format
+    let layer = match (LogFormat::from(log_format), is_production) {
+        (LogFormat::Json, true) => layer.json().without_time().boxed(),
+        (LogFormat::Json, false) => layer.json().boxed(),
+        (LogFormat::Plain, true) => layer.without_time().boxed(),
+        (LogFormat::Plain, false) => layer.boxed(),
+    };
+
+    // Files always carry timestamps: nothing else adds them on bare metal
+    let (file_layer, file_guard) = match file.enabled {
+        true => {
+            let (writer, guard) = tracing_appender::non_blocking(file_writer(file)?);
+            let file_layer = tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_target(false);
+            let file_layer = match LogFormat::from(log_format) {
+                LogFormat::Json => file_layer.json().boxed(),
+                LogFormat::Plain => file_layer.boxed(),
+            };
+            (Some(file_layer), Some(guard))
+        }
+        false => (None, None),
+    };
+
+    // One sampling decision per event for every output
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layer.and_then(file_layer).with_filter(sampler.clone()))
+        .init();
+
+    Ok(Logger { sampler, file_guard })
 }
 
 #[cfg(test)]
@@ -84,10 +109,12 @@ mod tests {
     #[test]
     fn test_init_logger() {
         // Test with valid configurations
-        assert!(init_logger("debug", "plain").is_ok());
-        assert!(init_logger("info", "json").is_ok());
+        let sampling = LogSamplingConfig::default();
+        let file = LogFileConfig::default();
+        assert!(init_logger("debug", "plain", &sampling, &file).is_ok());
+        assert!(init_logger("info", "json", &sampling, &file).is_ok());
 
         // Test with invalid log level (should fallback to info)
-        assert!(init_logger("invalid_level", "plain").is_ok());
+        assert!(init_logger("invalid_level", "plain", &sampling, &file).is_ok());
     }
 }
@@ -0,0 +1,136 @@
+// File: src/logger/file.rs
+use crate::env_config::models::app_config::{LogFileConfig, LogRotation};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// Writer of the log files. Time-based rotation and retention are left to tracing-appender,
+/// size-based rotation is done by [`SizeRollingWriter`].
+pub fn file_writer(config: &LogFileConfig) -> io::Result<Box<dyn Write + Send>> {
+    let rotation = match config.rotation {
+        LogRotation::Size => {
+            let max_bytes = config.max_size_mb.max(1) * 1024 * 1024;
+            let writer =
+                SizeRollingWriter::new(&config.directory, &config.prefix, max_bytes, config.max_files)?;
+            return Ok(Box::new(writer));
+        }
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Weekly => Rotation::WEEKLY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(&config.prefix)
+        .filename_suffix("log");
+    if config.max_files > 0 {
+        builder = builder.max_log_files(config.max_files);
+    }
+    let appender = builder.build(&config.directory).map_err(io::Error::other)?;
+
+    Ok(Box::new(appender))
+}
+
+/// Appends to `{prefix}.log` and rolls it over to `{prefix}.log.1` (older files shift to
+/// `.2`, `.3`, ...) once the next write would take it past `max_bytes`. `max_files` counts
+/// the active file, 0 keeps every file.
+pub struct SizeRollingWriter {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl SizeRollingWriter {
+    pub fn new(directory: &str, prefix: &str, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        let path = Path::new(directory).join(format!("{}.log", prefix));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.path.display(), index))
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let mut last = 0;
+        while self.rotated(last + 1).exists() {
+            last += 1;
+        }
+        let keep = match self.max_files {
+            0 => usize::MAX,
+            max_files => max_files - 1,
+        };
+        while last > 0 && last >= keep {
+            fs::remove_file(self.rotated(last))?;
+            last -= 1;
+        }
+        for index in (1..=last).rev() {
+            fs::rename(self.rotated(index), self.rotated(index + 1))?;
+        }
+        if keep > 0 {
+            fs::rename(&self.path, self.rotated(1))?;
+        }
+
+        self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+        self.written = 0;
+
+        Ok(())
+    }
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_rotation() {
+        let directory = std::env::temp_dir().join(format!("t-indicators-logs-{}", uuid::Uuid::new_v4()));
+        let directory = directory.to_str().unwrap();
+        let mut writer = SizeRollingWriter::new(directory, "app", 16, 3).unwrap();
+
+        for line in ["first  1\n", "second 2\n", "third  3\n", "fourth 4\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let read = |name: &str| fs::read_to_string(Path::new(directory).join(name)).ok();
+        assert_eq!(read("app.log").as_deref(), Some("fourth 4\n"));
+        assert_eq!(read("app.log.1").as_deref(), Some("third  3\n"));
+        assert_eq!(read("app.log.2").as_deref(), Some("second 2\n"));
+        // Over the retention of three files
+        assert_eq!(read("app.log.3"), None);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
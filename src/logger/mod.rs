@@ -1,2 +1,4 @@
 mod config;
+mod file;
+mod sampling;
 pub use config::init_logger;
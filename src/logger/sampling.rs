@@ -0,0 +1,163 @@
+use crate::env_config::models::app_config::LogSamplingRule;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn, Event, Level, Metadata};
+use tracing_subscriber::layer::{Context, Filter};
+
+/// Target of the rollup messages, never sampled
+const ROLLUP_TARGET: &str = module_path!();
+
+struct Rule {
+    target: String,
+    level: Level,
+    every: u64,
+    seen: AtomicU64,
+    suppressed: AtomicU64,
+}
+
+impl Rule {
+    /// The target itself or one of its submodules, at the rule level or more verbose
+    fn matches(&self, target: &str, level: Level) -> bool {
+        level >= self.level
+            && target
+                .strip_prefix(self.target.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    }
+}
+
+/// Per-target log sampling: of the matching events only every Nth is written, the rest are
+/// counted and reported by a periodic rollup. Warnings and errors above the rule level always
+/// pass. Clones share the counters.
+#[derive(Clone)]
+pub struct LogSampler {
+    rules: Arc<[Rule]>,
+}
+
+impl LogSampler {
+    pub fn new(config: &[LogSamplingRule]) -> Self {
+        let mut rules: Vec<Rule> = config
+            .iter()
+            .filter_map(|rule| {
+                let level = match rule.level.parse::<Level>() {
+                    Ok(level) => level,
+                    Err(_) => {
+                        warn!("Log sampling rule {}: invalid level {}, ignoring it", rule.target, rule.level);
+                        return None;
+                    }
+                };
+                Some(Rule {
+                    target: rule.target.clone(),
+                    level,
+                    every: rule.every.max(1),
+                    seen: AtomicU64::new(0),
+                    suppressed: AtomicU64::new(0),
+                })
+            })
+            .collect();
+        // The most specific target wins
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.target.len()));
+
+        Self { rules: rules.into() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether an event is written; the first event of a rule and every Nth after it pass
+    fn admit(&self, target: &str, level: Level) -> bool {
+        if target == ROLLUP_TARGET {
+            return true;
+        }
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(target, level)) else {
+            return true;
+        };
+
+        if rule.seen.fetch_add(1, Ordering::Relaxed) % rule.every == 0 {
+            true
+        } else {
+            rule.suppressed.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    /// Suppressed events per rule target since the previous call, non-zero counts only
+    pub fn take_suppressed(&self) -> Vec<(&str, u64)> {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                let count = rule.suppressed.swap(0, Ordering::Relaxed);
+                (count > 0).then_some((rule.target.as_str(), count))
+            })
+            .collect()
+    }
+
+    /// Logs the suppressed counts every `interval`
+    pub fn spawn_rollup(&self, interval: Duration) {
+        let sampler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                for (target, count) in sampler.take_suppressed() {
+                    info!(
+                        "Log sampling: {} messages from {} suppressed in the last {}s",
+                        count,
+                        target,
+                        interval.as_secs()
+                    );
+                }
+            }
+        });
+    }
+}
+
+impl<S> Filter<S> for LogSampler {
+    fn enabled(&self, _meta: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        true
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, _cx: &Context<'_, S>) -> bool {
+        let meta = event.metadata();
+        self.admit(meta.target(), *meta.level())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(target: &str, level: &str, every: u64) -> LogSamplingRule {
+        LogSamplingRule {
+            target: target.to_string(),
+            level: level.to_string(),
+            every,
+        }
+    }
+
+    #[test]
+    fn test_sampling() {
+        let sampler = LogSampler::new(&[
+            rule("t_indicators::services", "info", 10),
+            rule("t_indicators::services::indicators::calculator", "debug", 100),
+        ]);
+        let calculator = "t_indicators::services::indicators::calculator";
+
+        let written = (0..250).filter(|_| sampler.admit(calculator, Level::DEBUG)).count();
+        assert_eq!(written, 3);
+        // Info is more severe than the calculator rule level, the parent rule applies
+        let written = (0..25).filter(|_| sampler.admit(calculator, Level::INFO)).count();
+        assert_eq!(written, 3);
+        // Warnings, other targets and name-prefix lookalikes pass
+        assert!((0..5).all(|_| sampler.admit(calculator, Level::WARN)));
+        assert!((0..5).all(|_| sampler.admit("t_indicators::api", Level::DEBUG)));
+        assert!((0..5).all(|_| sampler.admit("t_indicators::services_extra", Level::DEBUG)));
+
+        let mut suppressed = sampler.take_suppressed();
+        suppressed.sort();
+        assert_eq!(suppressed, vec![("t_indicators::services", 22), (calculator, 247)]);
+        assert!(sampler.take_suppressed().is_empty());
+    }
+}
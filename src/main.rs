@@ -1,6 +1,7 @@
 
 mod api;
 mod app_state;
+mod cli;
 mod db;
 mod env_config;
 mod layers;
@@ -10,26 +11,67 @@ mod utils;
 
 
 use app_state::models::AppState;
-use axum::{Router, routing::get};
+use clap::Parser;
+use cli::{BenchArgs, Cli, Command, MigrateArgs, ValidateConfigArgs};
+use services::indicators::calculator::RecalcRange;
+use axum::{Router, routing::{get, post, put}};
 use db::{
-    clickhouse::clickhouse_service::{self, ClickhouseService},
-    postgres::postgres_service::PostgresService,
+    clickhouse::{clickhouse_service::ClickhouseService, connection::ClickhouseConnection},
+    migrations,
+    postgres::{connection::PostgresConnection, postgres_service::PostgresService},
 };
-use env_config::models::{app_config::AppConfig, app_env::AppEnv, app_setting::AppSettings};
-use layers::{create_cors, create_trace};
+use env_config::models::{app_config::AppConfig, app_env::{AppEnv, Env}, app_setting::AppSettings};
+use layers::{
+    create_cors, create_request_id, create_trace, propagate_request_id, render_api_errors, require_admin_token, AdminToken,
+};
+use services::config_summary::VersionInfo;
+use services::export::IncrementalExport;
+use services::feature_flags::FeatureFlags;
+use services::notifications::Notifier;
+use services::query_cache::QueryCache;
+use services::shutdown::Shutdown;
+use services::tuning::PipelineTuning;
+use services::indicators::plugins::IndicatorPlugins;
 use services::indicators::scheduler::IndicatorsScheduler;
-use std::{net::SocketAddr, sync::Arc};
-use tokio::{net::TcpListener, signal};
-use tracing::{debug, error, info};
+use std::{net::SocketAddr, path::Path, str::FromStr, sync::Arc};
+use tokio::net::TcpListener;
+use tracing::{debug, error, info, warn};
+use tracing_appender::non_blocking::WorkerGuard;
 
 #[tokio::main]
 async fn main() {
+    let command = Cli::parse().command.unwrap_or(Command::Serve);
+
+    // Проверка конфига не требует переменных окружения баз данных и логгера
+    if let Command::ValidateConfig(args) = &command {
+        std::process::exit(validate_config(args));
+    }
+    let recalc_range = match &command {
+        Command::Recalc(args) => Some(args.range().unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        })),
+        _ => None,
+    };
+
     // Инициализация приложения
-    let settings: Arc<AppSettings> = Arc::new(initialize_application().await);
-    
-    // Подключение к базам данных
-    let (clickhouse_service, postgres_service) =
-        initialize_database_connections(settings.clone()).await;
+    // Охранник файлового лога живёт до завершения процесса
+    let (settings, _log_file_guard) = initialize_application().await;
+
+    match command {
+        Command::Serve => serve(settings).await,
+        Command::Recalc(_) => run_recalc(settings, recalc_range.expect("parsed above")).await,
+        Command::Backfill(args) => run_backfill(settings, args.instruments).await,
+        Command::ValidateConfig(_) => unreachable!("handled before initialization"),
+        Command::Migrate(args) => run_migrate(settings, &args).await,
+        Command::Export => run_export(settings).await,
+        Command::Bench(args) => run_bench(settings, &args).await,
+    }
+}
+
+/// HTTP сервер и фоновое обновление индикаторов до сигнала остановки
+async fn serve(settings: AppSettings) {
+    let settings: Arc<AppSettings> = Arc::new(settings);
     
     // Настройка адреса сервера
     let server_address: SocketAddr = format!(
@@ -40,28 +82,246 @@ async fn main() {
     .expect("Invalid server address configuration");
     
     info!("Server will listen on: {}", server_address);
+
+    let app_state = build_app_state(settings.clone()).await;
     
-    // Создание глобального состояния приложения
-    let app_state: Arc<AppState> = Arc::new(AppState {
-        settings: settings.clone(),
-        clickhouse_service: Arc::new(clickhouse_service),
-        postgres_service: Arc::new(postgres_service),
+    // Сводка действующей конфигурации (включая производные значения и флаги)
+    VersionInfo::collect(&app_state).await.log_banner();
+
+    // Остановка по SIGTERM/SIGINT
+    let shutdown = app_state.service::<Shutdown>().cloned().expect("Shutdown is registered");
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move { shutdown.listen_for_signals().await }
     });
-    
+
     // Инициализация и запуск фоновых сервисов
-    initialize_background_services(app_state.clone()).await;
+    let indicators_scheduler = initialize_background_services(app_state.clone()).await;
     
     // Создание API роутера
     let app_router = create_application_router(app_state.clone());
     
-    // Запуск HTTP сервера
-    start_http_server(app_router, server_address).await;
+    // Запуск HTTP сервера до сигнала остановки
+    start_http_server(app_router, server_address, shutdown).await;
+
+    // Текущий проход дописывает пакет в работе и сохраняет статус
+    let shutdown_timeout = settings.app_config.indicators_updater.shutdown_timeout;
+    if !indicators_scheduler.wait_idle(shutdown_timeout).await {
+        warn!(
+            "Indicators update did not stop within {:?}, the next run resumes from its last stored batch",
+            shutdown_timeout
+        );
+    }
     
-    info!("Application started successfully!");
+    info!("Application stopped");
+}
+
+/// Один пересчёт и выход, без HTTP сервера и планировщика (`t-indicators recalc`)
+async fn run_recalc(settings: AppSettings, range: RecalcRange) {
+    let app_state = build_job_state(settings).await;
+
+    info!("Recalculating indicators: {:?}", range);
+    match IndicatorsScheduler::new(app_state).recalculate(range).await {
+        Ok(count) => info!("Recalculation completed: {} candles processed", count),
+        Err(e) => {
+            error!("Recalculation failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Догоняющий пересчёт отстающих инструментов вне окна и бюджета бэкфилла (`t-indicators backfill`)
+async fn run_backfill(settings: AppSettings, instrument_uids: Vec<String>) {
+    let app_state = build_job_state(settings).await;
+
+    match instrument_uids.is_empty() {
+        true => info!("Backfilling indicators of all instruments"),
+        false => info!("Backfilling indicators of {}", instrument_uids.join(", ")),
+    }
+    match IndicatorsScheduler::new(app_state).backfill(instrument_uids).await {
+        Ok(count) => info!("Backfill completed: {} candles processed", count),
+        Err(e) => {
+            error!("Backfill failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Один проход выгрузки по всем целям [export] (`t-indicators export`)
+async fn run_export(settings: AppSettings) {
+    if settings.app_config.export.targets.is_empty() {
+        error!("No export targets in config");
+        std::process::exit(1);
+    }
+    let app_state = build_job_state(settings).await;
+
+    let failed = IncrementalExport::new(app_state).run().await;
+    if failed > 0 {
+        error!("Export failed for {} targets", failed);
+        std::process::exit(1);
+    }
+    info!("Export completed");
+}
+
+/// Применяет миграции ClickHouse и PostgreSQL (`t-indicators migrate`)
+async fn run_migrate(settings: AppSettings, args: &MigrateArgs) {
+    if settings.app_config.embedded.enabled {
+        info!("Embedded store creates its schema on open, nothing to migrate");
+        return;
+    }
+    let settings = Arc::new(settings);
+    let dir = Path::new(&args.dir);
+    let result: Result<(), Box<dyn std::error::Error>> = async {
+        let clickhouse = ClickhouseConnection::new(settings.clone()).await?;
+        let applied = migrations::migrate_clickhouse(
+            &clickhouse.get_client(),
+            &migrations::load(&dir.join("clickhouse"))?,
+            args.baseline,
+        )
+        .await?;
+        info!("ClickHouse: {} migrations {}", applied.len(), if args.baseline { "recorded" } else { "applied" });
+
+        let postgres = PostgresConnection::new(settings.clone()).await?;
+        let applied =
+            migrations::migrate_postgres(postgres.get_pool(), &migrations::load(&dir.join("postgres"))?, args.baseline)
+                .await?;
+        info!("PostgreSQL: {} migrations {}", applied.len(), if args.baseline { "recorded" } else { "applied" });
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        error!("Migration failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Разбирает конфиг, cron и плагины без подключения к базам (`t-indicators validate-config`).
+/// Возвращает код выхода процесса.
+fn validate_config(args: &ValidateConfigArgs) -> i32 {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => match std::env::var("ENV").map_err(|e| e.to_string()).and_then(|env| Env::from_str(&env)) {
+            Ok(env) => AppConfig::path(&env),
+            Err(e) => {
+                eprintln!("error: ENV: {} (or pass --config)", e);
+                return 2;
+            }
+        },
+    };
+
+    let config = match AppConfig::from_file(Path::new(&path)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return 1;
+        }
+    };
+    let updater = &config.indicators_updater;
+    let mut errors = Vec::new();
+    let cron = updater.cron.as_deref().map(|cron| {
+        services::indicators::scheduler::parse_cron(cron)
+            .map_err(|e| format!("indicators_updater.cron '{}': {}", cron, e))
+    });
+    if let Some(Err(e)) = cron {
+        errors.push(e);
+    }
+    if let Err(e) = IndicatorPlugins::load(&updater.plugins_dir, &updater.plugins) {
+        errors.push(format!("indicators_updater.plugins: {}", e));
+    }
+
+    if errors.is_empty() {
+        println!("{}: OK", path);
+        return 0;
+    }
+    for error in &errors {
+        eprintln!("{}: {}", path, error);
+    }
+    1
+}
+
+/// Состояние приложения для разовой команды: по SIGTERM/SIGINT проход дописывает пакет в
+/// работе и завершается
+async fn build_job_state(settings: AppSettings) -> Arc<AppState> {
+    let app_state = build_app_state(Arc::new(settings)).await;
+    let shutdown = app_state.service::<Shutdown>().cloned().expect("Shutdown is registered");
+    tokio::spawn(async move { shutdown.listen_for_signals().await });
+    app_state
+}
+
+/// Подключения к базам данных и глобальное состояние приложения
+async fn build_app_state(settings: Arc<AppSettings>) -> Arc<AppState> {
+    // Подключение к базам данных
+    let (clickhouse_service, postgres_service) =
+        initialize_database_connections(settings.clone()).await;
+
+    // Флаги экспериментальных возможностей (конфиг + переопределения в PostgreSQL)
+    let feature_flags = FeatureFlags::new(
+        settings.app_config.feature_flags.clone(),
+        postgres_service.repository_feature_flag.clone(),
+    );
+
+    // Кэш результатов запросов API
+    let query_cache = QueryCache::new(
+        settings.app_config.query_cache.capacity,
+        settings.app_config.query_cache.ttl,
+    );
+
+    // Настройки обновления, изменяемые советником без перезапуска
+    let pipeline_tuning = PipelineTuning::new(&settings.app_config.indicators_updater);
+
+    // Рассылка сигналов по правилам маршрутизации
+    let notifier = Notifier::new(
+        &settings.app_config.notifications,
+        &settings.app_config.indicators_updater.portfolios,
+    );
+
+    // Пользовательские индикаторы из библиотек плагинов (indicators_updater.plugins)
+    let indicator_plugins = IndicatorPlugins::load(
+        &settings.app_config.indicators_updater.plugins_dir,
+        &settings.app_config.indicators_updater.plugins,
+    )
+    .unwrap_or_else(|e| panic!("Failed to load indicator plugins: {}", e));
+
+    // Создание глобального состояния приложения
+    Arc::new(
+        AppState::builder(settings)
+            .with_service(Arc::new(feature_flags))
+            .with_service(Arc::new(query_cache))
+            .with_service(Arc::new(pipeline_tuning))
+            .with_service(Arc::new(notifier))
+            .with_service(Arc::new(indicator_plugins))
+            .with_service(Arc::new(clickhouse_service))
+            .with_service(Arc::new(postgres_service))
+            .with_service(Arc::new(Shutdown::new()))
+            .build()
+            .expect("Failed to build application state"),
+    )
+}
+
+/// Прогоняет синтетические свечи через конвейер и пишет отчёт (`t-indicators bench`)
+#[cfg(feature = "embedded")]
+async fn run_bench(settings: AppSettings, args: &BenchArgs) {
+    let report = services::bench::run(settings, args)
+        .await
+        .unwrap_or_else(|e| panic!("Benchmark failed: {}", e));
+    report
+        .write(&args.output)
+        .unwrap_or_else(|e| panic!("Failed to write the benchmark report: {}", e));
+
+    info!(
+        "Benchmark finished: {} candles in {:.0} ms ({:.0} candles/s), report in {}.json and {}.md",
+        report.candles_processed, report.wall_ms, report.candles_per_second, args.output, args.output
+    );
+}
+
+#[cfg(not(feature = "embedded"))]
+async fn run_bench(_settings: AppSettings, _args: &BenchArgs) {
+    panic!("bench requires a build with `--features embedded`");
 }
 
 /// Инициализирует настройки и логирование приложения
-async fn initialize_application() -> AppSettings {
+async fn initialize_application() -> (AppSettings, Option<WorkerGuard>) {
     // Загрузка переменных окружения и конфигурации
     let environment = AppEnv::new();
     let config = AppConfig::new(&environment.env);
@@ -71,11 +331,19 @@ async fn initialize_application() -> AppSettings {
     };
     
     // Настройка логирования с уровнем и форматом из конфигурации
-    logger::init_logger(
+    let logger = logger::init_logger(
         &app_settings.app_config.log.level,
         &app_settings.app_config.log.format,
+        &app_settings.app_config.log.sampling,
+        &app_settings.app_config.log.file,
     )
     .expect("Failed to initialize logger");
+
+    // Сводка о прореженных сообщениях
+    let rollup = app_settings.app_config.log.sampling.rollup;
+    if !logger.sampler.is_empty() && !rollup.is_zero() {
+        logger.sampler.spawn_rollup(rollup);
+    }
     
     info!("Starting Indicators Service application...");
     info!("Current environment: {}", app_settings.app_env.env);
@@ -88,7 +356,7 @@ async fn initialize_application() -> AppSettings {
         info!("Running in production mode");
     }
     
-    app_settings
+    (app_settings, logger.file_guard)
 }
 
 /// Устанавливает соединения с базами данных
@@ -96,6 +364,10 @@ async fn initialize_database_connections(
     settings: Arc<AppSettings>,
 ) -> (ClickhouseService, PostgresService) {
     info!("Initializing database connections...");
+
+    if settings.app_config.embedded.enabled {
+        return initialize_embedded_store(&settings).await;
+    }
     
     // Инициализация подключения к ClickHouse
     let clickhouse_service = match ClickhouseService::new(&settings).await {
@@ -124,18 +396,84 @@ async fn initialize_database_connections(
     (clickhouse_service, postgres_service)
 }
 
+/// Открывает встроенное хранилище вместо ClickHouse/PostgreSQL (режим разработки)
+#[cfg(feature = "embedded")]
+async fn initialize_embedded_store(settings: &AppSettings) -> (ClickhouseService, PostgresService) {
+    use db::embedded::store::EmbeddedStore;
+
+    let path = &settings.app_config.embedded.path;
+    let store = match EmbeddedStore::open(path).await {
+        Ok(store) => Arc::new(store),
+        Err(err) => {
+            error!("Failed to open embedded store at {}: {}", path, err);
+            panic!("Cannot continue without a database");
+        }
+    };
+
+    (ClickhouseService::embedded(store.clone()), PostgresService::embedded(store))
+}
+
+#[cfg(not(feature = "embedded"))]
+async fn initialize_embedded_store(_settings: &AppSettings) -> (ClickhouseService, PostgresService) {
+    panic!("embedded.enabled requires a build with `--features embedded`");
+}
+
 /// Создает API роутер со всеми эндпоинтами и middleware
 fn create_application_router(app_state: Arc<AppState>) -> Router {
+    // Админка и /api/admin - за токеном admin.token_env
+    let admin_token = AdminToken::from_config(&app_state.settings.app_config.admin);
+    let admin_router = Router::new()
+        .route("/admin", get(api::admin_ui))
+        .route("/admin/admin.js", get(api::admin_js))
+        .route("/admin/admin.css", get(api::admin_css))
+        .route("/api/admin/tuning-recommendations", get(api::tuning_recommendations))
+        .route("/api/admin/sample-export", post(api::sample_export))
+        .route("/api/admin/signal-suppressions", get(api::signal_suppressions))
+        .route(
+            "/api/admin/signal-suppressions/{instrument_uid}",
+            put(api::suppress_signals).delete(api::release_signals),
+        )
+        .route("/api/admin/instrument-filters", get(api::instrument_filters))
+        .route(
+            "/api/admin/instrument-filters/{instrument_uid}",
+            put(api::save_instrument_filter).delete(api::delete_instrument_filter),
+        )
+        .route("/api/admin/dead-letters", get(api::dead_letters))
+        .route(
+            "/api/admin/dead-letters/{instrument_uid}",
+            get(api::instrument_failures).delete(api::resolve_dead_letter),
+        )
+        .route("/api/recalculate/{instrument_uid}", post(api::recalculate))
+        .route_layer(axum::middleware::from_fn_with_state(admin_token, require_admin_token));
+
     Router::new()
         .layer(create_cors())
         .route("/api-health", get(api::health_api))
         .route("/db-health", get(api::health_db))
+        .route("/status", get(api::status))
+        .route("/runs", get(api::runs))
+        .route("/api/openapi.json", get(api::openapi))
+        .route("/api/version", get(api::version))
+        .route("/api/feature-flags", get(api::feature_flags))
+        .route("/api/compute", post(api::compute_indicators))
+        .route("/api/indicators/latest", get(api::latest_indicators))
+        .route("/api/indicators/query", post(api::query_indicators))
+        .route("/api/indicators/{instrument_uid}", get(api::indicators))
+        .route("/api/indicators/{instrument_uid}/stats", get(api::column_stats))
+        .route("/api/signals/counts", get(api::signal_counts))
+        .route("/api/signals/latency", get(api::signal_latency))
+        .route("/api/signals/{instrument_uid}/annotations", get(api::signal_annotations))
+        .merge(admin_router)
+        .fallback(api::not_found)
+        .layer(axum::middleware::from_fn(render_api_errors))
         .layer(axum::Extension(app_state.clone()))
         .layer(create_trace())
+        .layer(propagate_request_id())
+        .layer(create_request_id())
 }
 
 /// Запускает HTTP сервер на указанном адресе
-async fn start_http_server(app: Router, addr: SocketAddr) {
+async fn start_http_server(app: Router, addr: SocketAddr, shutdown: Arc<Shutdown>) {
     info!("Starting HTTP server on {}", addr);
     
     let listener = match TcpListener::bind(addr).await {
@@ -148,28 +486,42 @@ async fn start_http_server(app: Router, addr: SocketAddr) {
     
     info!("Server started successfully, now accepting connections");
     
-    if let Err(err) = axum::serve(listener, app).await {
+    // Новые соединения не принимаются, текущие запросы дорабатывают
+    let graceful = axum::serve(listener, app).with_graceful_shutdown(async move { shutdown.wait().await });
+    if let Err(err) = graceful.await {
         error!("Server error: {}", err);
         panic!("Server failed: {}", err);
     }
+    
+    info!("HTTP server stopped");
 }
 
 /// Инициализирует и запускает все фоновые сервисы
-async fn initialize_background_services(app_state: Arc<AppState>) {
+async fn initialize_background_services(app_state: Arc<AppState>) -> IndicatorsScheduler {
+    // Инкрементальная выгрузка новых строк во внешнее хранилище
+    if app_state.settings.app_config.export.enabled {
+        IncrementalExport::new(app_state.clone()).start();
+    }
+
     // Инициализация планировщика индикаторов
     let indicators_scheduler = IndicatorsScheduler::new(app_state.clone());
-    
-    // Выполнение начального обновления индикаторов
-    match indicators_scheduler.trigger_update().await {
-        Ok(count) => info!("Initial indicators update completed: {} instruments processed", count),
-        Err(err) => error!("Failed to perform initial indicators update: {}", err),
-    }
-    
-    // Запуск планировщика для регулярных обновлений
-    match indicators_scheduler.trigger_update().await {
-        Ok(count) => info!("Scheduled indicators update completed: {} instruments processed", count),
-        Err(err) => error!("Failed to perform scheduled indicators update: {}", err),
+
+    if app_state.settings.app_config.indicators_updater.enabled {
+        // Начальное обновление в фоне, чтобы не задерживать запуск HTTP сервера
+        let initial_scheduler = indicators_scheduler.clone();
+        tokio::spawn(async move {
+            match initial_scheduler.trigger_update().await {
+                Ok(count) => info!("Initial indicators update completed: {} candles processed", count),
+                Err(err) => error!("Failed to perform initial indicators update: {}", err),
+            }
+        });
+
+        // Запуск планировщика для регулярных обновлений
+        indicators_scheduler.start_scheduled_updates().await;
+    } else {
+        info!("Indicator updates are disabled in config");
     }
     
     info!("Background services initialized successfully");
+    indicators_scheduler
 }
\ No newline at end of file
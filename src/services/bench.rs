@@ -0,0 +1,298 @@
+// File: src/services/bench.rs
+//! `t-indicators bench`: seeds an in-memory embedded store with synthetic 1-minute candles,
+//! runs one full indicators update over them and reports throughput, peak memory and the
+//! time of each pipeline stage. Candles are the same for the same arguments, so reports of
+//! two branches or two machines compare directly.
+use crate::app_state::models::AppState;
+use crate::cli::BenchArgs;
+use crate::db::clickhouse::clickhouse_service::ClickhouseService;
+use crate::db::clickhouse::models::indicator::DbCandleRaw;
+use crate::db::embedded::store::EmbeddedStore;
+use crate::db::postgres::postgres_service::PostgresService;
+use crate::env_config::models::app_config::{EmbeddedConfig, InstrumentFilterConfig};
+use crate::env_config::models::app_setting::AppSettings;
+use crate::services::feature_flags::FeatureFlags;
+use crate::services::indicators::plugins::IndicatorPlugins;
+use crate::services::indicators::scheduler::IndicatorsScheduler;
+use crate::services::shutdown::Shutdown;
+use crate::services::tuning::PipelineTuning;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub version: &'static str,
+    pub started_at: DateTime<Utc>,
+    pub host: BenchHost,
+    pub instruments: usize,
+    pub days: u32,
+    pub batch_size: usize,
+    pub max_concurrent_instruments: usize,
+    pub candles_seeded: u64,
+    pub candles_processed: u64,
+    pub rows_inserted: u64,
+    pub insert_errors: u64,
+    pub instruments_failed: u64,
+    pub seed_ms: f64,
+    pub wall_ms: f64,
+    pub candles_per_second: f64,
+    /// Peak resident set of the process (VmHWM), seeding included; `None` off Linux
+    pub peak_rss_kb: Option<u64>,
+    /// Summed over instruments, so with concurrency the total exceeds `wall_ms`
+    pub stages: Vec<BenchStage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchHost {
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub cpus: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchStage {
+    pub stage: &'static str,
+    pub total_ms: f64,
+    pub share: f64, // Доля от суммы стадий, %
+}
+
+/// Seeds the store, runs the update and collects the report. Signals are detected but not
+/// delivered, and config spreads, portfolios and instrument filters are ignored: they name
+/// real instruments.
+pub async fn run(mut settings: AppSettings, args: &BenchArgs) -> Result<BenchReport, String> {
+    let started_at = Utc::now();
+    settings.app_config.embedded = EmbeddedConfig {
+        enabled: true,
+        path: ":memory:".to_string(),
+    };
+    settings.app_config.notifications.enabled = false;
+    settings.app_config.instrument_filter = InstrumentFilterConfig::default();
+    settings.app_config.indicators_updater.spreads.clear();
+    settings.app_config.indicators_updater.portfolios.clear();
+    let settings = Arc::new(settings);
+
+    let store = Arc::new(EmbeddedStore::open(":memory:").await.map_err(|e| e.to_string())?);
+
+    let seeding = Instant::now();
+    // The last candle closes a minute before the run, as with live data
+    let end = started_at.timestamp() / 60 * 60 - 60;
+    let mut candles_seeded = 0;
+    for instrument in 0..args.instruments {
+        let candles = synthetic_candles(instrument, args.days, end);
+        candles_seeded += store.insert_candles(&candles).await.map_err(|e| e.to_string())?;
+    }
+    let seed_ms = seeding.elapsed().as_secs_f64() * 1000.0;
+    info!("Seeded {} candles of {} instruments in {:.0} ms", candles_seeded, args.instruments, seed_ms);
+
+    let clickhouse_service = ClickhouseService::embedded(store.clone());
+    let postgres_service = PostgresService::embedded(store);
+    let feature_flags = FeatureFlags::new(
+        settings.app_config.feature_flags.clone(),
+        postgres_service.repository_feature_flag.clone(),
+    );
+    let updater_config = &settings.app_config.indicators_updater;
+    let indicator_plugins = IndicatorPlugins::load(&updater_config.plugins_dir, &updater_config.plugins)?;
+    let app_state = Arc::new(
+        AppState::builder(settings.clone())
+            .with_service(Arc::new(feature_flags))
+            .with_service(Arc::new(PipelineTuning::new(updater_config)))
+            .with_service(Arc::new(indicator_plugins))
+            .with_service(Arc::new(clickhouse_service))
+            .with_service(Arc::new(postgres_service))
+            .with_service(Arc::new(Shutdown::new()))
+            .build()?,
+    );
+
+    let running = Instant::now();
+    IndicatorsScheduler::new(app_state.clone())
+        .trigger_update()
+        .await
+        .map_err(|e| e.to_string())?;
+    let wall_ms = running.elapsed().as_secs_f64() * 1000.0;
+
+    let run = app_state
+        .postgres_service()
+        .repository_indicator_run
+        .get_runs(None, 1)
+        .await
+        .map_err(|e| e.to_string())?
+        .pop()
+        .ok_or("the run was not recorded")?;
+    let summary = app_state
+        .clickhouse_service()
+        .repository_pipeline_profile
+        .get_run_summaries(1)
+        .await
+        .map_err(|e| e.to_string())?
+        .pop()
+        .unwrap_or_default();
+
+    let candles_processed = run.candles_processed as u64;
+    Ok(BenchReport {
+        version: env!("CARGO_PKG_VERSION"),
+        started_at,
+        host: BenchHost {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        },
+        instruments: args.instruments,
+        days: args.days,
+        batch_size: updater_config.batch_size,
+        max_concurrent_instruments: updater_config.max_concurrent_instruments,
+        candles_seeded,
+        candles_processed,
+        rows_inserted: run.rows_inserted as u64,
+        insert_errors: run.insert_errors as u64,
+        instruments_failed: run.instruments_failed as u64,
+        seed_ms,
+        wall_ms,
+        candles_per_second: candles_processed as f64 / (wall_ms / 1000.0).max(f64::EPSILON),
+        peak_rss_kb: peak_rss_kb(),
+        stages: stages(&[
+            ("fetch", summary.fetch_ms_total),
+            ("compute", summary.compute_ms_total),
+            ("insert", summary.insert_ms_total),
+            ("status", summary.status_ms_total),
+        ]),
+    })
+}
+
+impl BenchReport {
+    /// Writes `<output>.json` and `<output>.md`
+    pub fn write(&self, output: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(format!("{}.json", output), json + "\n").map_err(|e| format!("{}.json: {}", output, e))?;
+        std::fs::write(format!("{}.md", output), self.to_markdown()).map_err(|e| format!("{}.md: {}", output, e))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# t-indicators bench\n\n| | |\n|---|---|\n");
+        let peak_rss = match self.peak_rss_kb {
+            Some(kb) => format!("{:.1} MiB", kb as f64 / 1024.0),
+            None => "n/a".to_string(),
+        };
+        let rows = [
+            ("Version", self.version.to_string()),
+            ("Started", self.started_at.to_rfc3339()),
+            ("Host", format!("{} {}, {} CPUs", self.host.os, self.host.arch, self.host.cpus)),
+            ("Instruments x days", format!("{} x {}", self.instruments, self.days)),
+            ("Batch size", self.batch_size.to_string()),
+            ("Concurrent instruments", self.max_concurrent_instruments.to_string()),
+            ("Candles seeded", self.candles_seeded.to_string()),
+            ("Candles processed", self.candles_processed.to_string()),
+            ("Rows inserted", self.rows_inserted.to_string()),
+            ("Insert errors", self.insert_errors.to_string()),
+            ("Failed instruments", self.instruments_failed.to_string()),
+            ("Seeding", format!("{:.0} ms", self.seed_ms)),
+            ("Pipeline wall time", format!("{:.0} ms", self.wall_ms)),
+            ("Throughput", format!("{:.0} candles/s", self.candles_per_second)),
+            ("Peak RSS", peak_rss),
+        ];
+        for (name, value) in rows {
+            let _ = writeln!(md, "| {} | {} |", name, value);
+        }
+
+        md.push_str("\n## Stages\n\nSummed over instruments.\n\n| Stage | Total, ms | Share |\n|---|---:|---:|\n");
+        for stage in &self.stages {
+            let _ = writeln!(md, "| {} | {:.1} | {:.1}% |", stage.stage, stage.total_ms, stage.share);
+        }
+        md
+    }
+}
+
+fn stages(totals: &[(&'static str, f64)]) -> Vec<BenchStage> {
+    let sum: f64 = totals.iter().map(|(_, ms)| ms).sum();
+    totals
+        .iter()
+        .map(|&(stage, total_ms)| BenchStage {
+            stage,
+            total_ms,
+            share: if sum > 0.0 { total_ms / sum * 100.0 } else { 0.0 },
+        })
+        .collect()
+}
+
+/// Random walk of `days` of 1-minute candles ending at `end`, the same for the same
+/// `instrument` index
+pub(crate) fn synthetic_candles(instrument: usize, days: u32, end: i64) -> Vec<DbCandleRaw> {
+    let count = days as i64 * 1440;
+    // xorshift64, seeded by the index
+    let mut state = 0x9E37_79B9_7F4A_7C15u64 ^ (instrument as u64 + 1).wrapping_mul(0x2545_F491_4F6C_DD1D);
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    let mut close = 100.0 + instrument as f64;
+    (0..count)
+        .map(|i| {
+            let open = close;
+            close = (open * (1.0 + (next() - 0.5) * 0.004)).max(0.01);
+            let high = open.max(close) * (1.0 + next() * 0.001);
+            let low = open.min(close) * (1.0 - next() * 0.001);
+            let (open_units, open_nano) = units_nano(open);
+            let (high_units, high_nano) = units_nano(high);
+            let (low_units, low_nano) = units_nano(low);
+            let (close_units, close_nano) = units_nano(close);
+            DbCandleRaw {
+                instrument_uid: format!("bench-{:04}", instrument),
+                time: end - (count - 1 - i) * 60,
+                open_units,
+                open_nano,
+                high_units,
+                high_nano,
+                low_units,
+                low_nano,
+                close_units,
+                close_nano,
+                volume: 100 + (next() * 1000.0) as i64,
+            }
+        })
+        .collect()
+}
+
+fn units_nano(price: f64) -> (i64, i32) {
+    let units = price.trunc();
+    (units as i64, ((price - units) * 1e9) as i32)
+}
+
+/// VmHWM of /proc/self/status
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::clickhouse::models::indicator::DbCandleConverted;
+
+    #[test]
+    fn test_synthetic_candles() {
+        let candles = synthetic_candles(3, 2, 86_400 * 10);
+
+        assert_eq!(candles.len(), 2 * 1440);
+        assert_eq!(candles.last().unwrap().time, 86_400 * 10);
+        assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == 60));
+        assert!(candles.iter().all(|c| c.instrument_uid == "bench-0003"));
+        for candle in candles.iter().cloned().map(DbCandleConverted::from) {
+            assert!(candle.low_price <= candle.open_price.min(candle.close_price));
+            assert!(candle.high_price >= candle.open_price.max(candle.close_price));
+        }
+
+        // Reproducible, and different per instrument
+        assert_eq!(synthetic_candles(3, 2, 0)[100].close_nano, candles[100].close_nano);
+        assert_ne!(synthetic_candles(4, 2, 0)[100].close_nano, candles[100].close_nano);
+
+        let stages = stages(&[("fetch", 30.0), ("compute", 90.0)]);
+        assert_eq!((stages[0].share, stages[1].share), (25.0, 75.0));
+    }
+}
@@ -0,0 +1,278 @@
+// File: src/services/config_summary.rs
+use crate::app_state::models::AppState;
+use crate::env_config::models::app_config::{
+    AdaptiveThresholdsConfig, BackfillConfig, GapConfig, HurstConfig, RecomputeConfig, RetryConfig,
+    TargetConfig, TripleBarrierConfig,
+};
+use crate::services::feature_flags::{FeatureFlagSnapshot, FeatureFlags};
+use crate::services::indicators::calculator::{warmup_window, FIXED_PERIODS};
+use crate::services::tuning::{PipelineTuning, TuningSettings};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+/// Build version and the effective configuration: config values after defaults, plus
+/// derived values, runtime tuning and feature flag overrides
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub environment: String,
+    pub config: ConfigSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigSummary {
+    pub storage: StorageSummary,
+    pub scheduler: SchedulerSummary,
+    pub pipeline: TuningSettings,
+    pub indicators: IndicatorsSummary,
+    pub feature_flags: FeatureFlagSnapshot,
+    pub signals: SignalsSummary,
+    pub notifications: NotificationsSummary,
+    pub export: ExportSummary,
+    pub instrument_filter: InstrumentFilterSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageSummary {
+    pub embedded: bool,
+    pub hot_days: u32,
+    pub query_cache_capacity: usize,
+    pub query_cache_ttl_seconds: u64,
+    pub retry: RetryConfig,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulerSummary {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub cron: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub exchange: String,
+    pub backfill: BackfillConfig,
+    pub shutdown_timeout_seconds: u64,
+    pub instrument_timeout_seconds: u64,
+    pub tiers: Vec<TierSummary>,
+    pub dead_letter_max_failures: u32,
+    pub dead_letter_cooldown_seconds: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TierSummary {
+    pub name: String,
+    pub priority: i32,
+    pub every: u32,
+    pub instruments: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndicatorsSummary {
+    pub warmup_candles: usize,
+    pub fixed_periods: BTreeMap<&'static str, usize>,
+    pub roc_lags: Vec<usize>,
+    pub hma_period: usize,
+    pub volume_baseline_days: u32,
+    pub volume_anomaly_sigma: f64,
+    pub hurst: HurstConfig,
+    pub adaptive_thresholds: AdaptiveThresholdsConfig,
+    pub targets: Vec<TargetConfig>,
+    pub triple_barrier: TripleBarrierConfig,
+    pub gaps: GapConfig,
+    pub recompute: RecomputeConfig,
+    pub spreads: Vec<String>,
+    pub portfolios: Vec<String>,
+    pub plugins: Vec<String>, // plugin_values order
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignalsSummary {
+    pub cooldown_seconds: i64,
+    pub cooldown_by_kind: HashMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationsSummary {
+    pub enabled: bool,
+    pub channels: Vec<String>,
+    pub rules: Vec<String>,
+}
+
+/// Sizes of the config lists; tinkoff_instrument_filters is read per run
+#[derive(Debug, Serialize)]
+pub struct InstrumentFilterSummary {
+    pub allow: usize,
+    pub deny: usize,
+}
+
+/// Destinations are left out, they carry credentials
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub lag_seconds: u64,
+    pub targets: Vec<String>,
+}
+
+impl VersionInfo {
+    pub async fn collect(app_state: &AppState) -> Self {
+        let config = &app_state.settings.app_config;
+        let updater = &config.indicators_updater;
+
+        let pipeline = match app_state.service::<PipelineTuning>() {
+            Some(tuning) => tuning.settings(),
+            None => TuningSettings {
+                batch_size: updater.batch_size,
+                concurrency: updater.max_concurrent_instruments.max(1),
+                async_insert: updater.async_insert,
+            },
+        };
+        let feature_flags = match app_state.service::<FeatureFlags>() {
+            Some(flags) => flags.snapshot().await,
+            None => FeatureFlagSnapshot::default(),
+        };
+        let mut channels: Vec<String> = config.notifications.channels.keys().cloned().collect();
+        channels.sort_unstable();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            environment: app_state.settings.app_env.env.to_string(),
+            config: ConfigSummary {
+                storage: StorageSummary {
+                    embedded: config.embedded.enabled,
+                    hot_days: config.clickhouse.hot_days,
+                    query_cache_capacity: config.query_cache.capacity,
+                    query_cache_ttl_seconds: config.query_cache.ttl.as_secs(),
+                    retry: config.retry.clone(),
+                },
+                scheduler: SchedulerSummary {
+                    enabled: updater.enabled,
+                    interval_seconds: updater.interval.as_secs(),
+                    cron: updater.cron.clone(),
+                    start_time: updater.start_time.map(|time| time.to_string()),
+                    end_time: updater.end_time.map(|time| time.to_string()),
+                    exchange: updater.calendar.exchange.clone(),
+                    backfill: updater.backfill.clone(),
+                    shutdown_timeout_seconds: updater.shutdown_timeout.as_secs(),
+                    instrument_timeout_seconds: updater.instrument_timeout.as_secs(),
+                    tiers: updater
+                        .tiers
+                        .iter()
+                        .map(|tier| TierSummary {
+                            name: tier.name.clone(),
+                            priority: tier.priority,
+                            every: tier.every,
+                            instruments: tier.instruments.len(),
+                        })
+                        .collect(),
+                    dead_letter_max_failures: updater.dead_letter.max_failures,
+                    dead_letter_cooldown_seconds: updater.dead_letter.cooldown.as_secs(),
+                },
+                pipeline,
+                indicators: IndicatorsSummary {
+                    warmup_candles: warmup_window(updater),
+                    fixed_periods: FIXED_PERIODS.into_iter().collect(),
+                    roc_lags: updater.roc_lags.clone(),
+                    hma_period: updater.hma_period,
+                    volume_baseline_days: updater.volume_baseline_days,
+                    volume_anomaly_sigma: updater.volume_anomaly_sigma,
+                    hurst: updater.hurst.clone(),
+                    adaptive_thresholds: updater.adaptive_thresholds.clone(),
+                    targets: updater.targets.clone(),
+                    triple_barrier: updater.triple_barrier.clone(),
+                    gaps: updater.gaps.clone(),
+                    recompute: updater.recompute.clone(),
+                    spreads: updater.spreads.iter().map(|spread| spread.name.clone()).collect(),
+                    portfolios: updater.portfolios.iter().map(|portfolio| portfolio.name.clone()).collect(),
+                    plugins: updater.plugins.iter().map(|plugin| plugin.name.clone()).collect(),
+                },
+                feature_flags,
+                signals: SignalsSummary {
+                    cooldown_seconds: config.signals.cooldown.as_secs() as i64,
+                    cooldown_by_kind: config
+                        .signals
+                        .cooldown_by_kind
+                        .iter()
+                        .map(|(kind, cooldown)| (kind.clone(), cooldown.as_secs() as i64))
+                        .collect(),
+                },
+                notifications: NotificationsSummary {
+                    enabled: config.notifications.enabled,
+                    channels,
+                    rules: config.notifications.rules.iter().map(|rule| rule.name.clone()).collect(),
+                },
+                export: ExportSummary {
+                    enabled: config.export.enabled,
+                    interval_seconds: config.export.interval.as_secs(),
+                    lag_seconds: config.export.lag.as_secs(),
+                    targets: config.export.targets.iter().map(|target| target.name.clone()).collect(),
+                },
+                instrument_filter: InstrumentFilterSummary {
+                    allow: config.instrument_filter.allow.len(),
+                    deny: config.instrument_filter.deny.len(),
+                },
+            },
+        }
+    }
+
+    /// Logs a short startup banner; the full summary is served by `/api/version`
+    pub fn log_banner(&self) {
+        let config = &self.config;
+        let scheduler = &config.scheduler;
+        let indicators = &config.indicators;
+
+        info!("t-indicators {} ({})", self.version, self.environment);
+        info!(
+            "Storage: {}, hot tier {} days, query cache {} entries / {}s",
+            if config.storage.embedded { "embedded SQLite" } else { "ClickHouse + PostgreSQL" },
+            config.storage.hot_days,
+            config.storage.query_cache_capacity,
+            config.storage.query_cache_ttl_seconds
+        );
+        info!(
+            "Scheduler: {}, {}, window {}-{} UTC, {} calendar",
+            if scheduler.enabled { "enabled" } else { "disabled" },
+            match &scheduler.cron {
+                Some(cron) => format!("cron \"{}\"", cron),
+                None => format!("every {}s", scheduler.interval_seconds),
+            },
+            scheduler.start_time.as_deref().unwrap_or("any"),
+            scheduler.end_time.as_deref().unwrap_or("any"),
+            scheduler.exchange
+        );
+        info!(
+            "Pipeline: batch size {}, concurrency {}, async insert {}",
+            config.pipeline.batch_size, config.pipeline.concurrency, config.pipeline.async_insert
+        );
+        info!(
+            "Indicators: warm-up {} candles, ROC lags {:?}, HMA {}, volume anomaly {}σ, Hurst {:?}/{}, targets {:?}, triple barrier {}",
+            indicators.warmup_candles,
+            indicators.roc_lags,
+            indicators.hma_period,
+            indicators.volume_anomaly_sigma,
+            indicators.hurst.method,
+            indicators.hurst.window,
+            indicators.targets.iter().map(|target| target.horizon).collect::<Vec<_>>(),
+            if indicators.triple_barrier.enabled { "on" } else { "off" }
+        );
+        info!(
+            "Synthetic instruments: {} spreads, {} portfolios",
+            indicators.spreads.len(),
+            indicators.portfolios.len()
+        );
+        info!("Feature flags enabled: {:?}", config.feature_flags.enabled_flags());
+        info!(
+            "Notifications: {}, {} channels, {} rules, signal cooldown {}s",
+            if config.notifications.enabled { "enabled" } else { "disabled" },
+            config.notifications.channels.len(),
+            config.notifications.rules.len(),
+            config.signals.cooldown_seconds
+        );
+        info!(
+            "Export: {}, {} targets every {}s",
+            if config.export.enabled { "enabled" } else { "disabled" },
+            config.export.targets.len(),
+            config.export.interval_seconds
+        );
+    }
+}
@@ -0,0 +1,90 @@
+// File: src/services/export.rs
+use crate::app_state::models::AppState;
+use crate::env_config::models::app_config::ExportTarget;
+use crate::services::shutdown::{wait_shutdown, Shutdown};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info};
+
+/// Ships the 1-minute indicator rows inserted since the previous run to every export target.
+/// Each target keeps its own high-watermark (insert time, unix ms), advanced only after its
+/// rows were written, so a failed run is repeated in full by the next one.
+pub struct IncrementalExport {
+    app_state: Arc<AppState>,
+}
+
+impl IncrementalExport {
+    pub fn new(app_state: Arc<AppState>) -> Self {
+        Self { app_state }
+    }
+
+    /// Runs the export every `export.interval`
+    pub fn start(self) {
+        let config = &self.app_state.settings.app_config.export;
+        info!(
+            "Starting incremental export of indicators to {} targets every {:?}",
+            config.targets.len(),
+            config.interval
+        );
+
+        let period = config.interval.max(Duration::from_secs(1));
+        let shutdown = self.app_state.service::<Shutdown>().cloned();
+        tokio::spawn(async move {
+            let mut interval = time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        self.run().await;
+                    }
+                    _ = wait_shutdown(shutdown.as_deref()) => {
+                        info!("Incremental export stopped");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Exports every target once; a failing target does not hold back the others. Returns the
+    /// number of failed targets.
+    pub async fn run(&self) -> usize {
+        let config = &self.app_state.settings.app_config.export;
+        // Rows of in-flight async inserts get an insert_time slightly in the past
+        let until = chrono::Utc::now().timestamp_millis() - config.lag.as_millis() as i64;
+
+        let mut failed = 0;
+        for target in &config.targets {
+            match self.export_target(target, until).await {
+                Ok(0) => debug!("Export {}: no new rows", target.name),
+                Ok(count) => info!("Export {}: shipped {} rows up to {}", target.name, count, until),
+                Err(e) => {
+                    error!("Export {} failed, will retry from the same watermark: {}", target.name, e);
+                    failed += 1;
+                }
+            }
+        }
+        failed
+    }
+
+    async fn export_target(&self, target: &ExportTarget, until: i64) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let postgres = self.app_state.postgres_service();
+        let clickhouse = self.app_state.clickhouse_service();
+
+        let after = postgres.repository_export_watermark.get_export_watermark(&target.name).await?;
+        if after.is_some_and(|after| after >= until) {
+            return Ok(0);
+        }
+
+        // A new object per run for file destinations such as s3(...)
+        let destination = target.destination.replace("{watermark}", &until.to_string());
+        let count = clickhouse
+            .repository_indicator
+            .export_inserted(&destination, after, until)
+            .await?;
+
+        postgres.repository_export_watermark.save_export_watermark(&target.name, until).await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,68 @@
+// File: src/services/feature_flags.rs
+use crate::db::postgres::repository::feature_flag_repository::TraitFeatureFlagRepository;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Use the seasonal (weekday × minute-of-day) volume baseline for volume_norm
+pub const SEASONAL_VOLUME_BASELINE: &str = "seasonal_volume_baseline";
+
+/// Runtime feature flags: defaults from config, optionally overridden per environment
+/// through the `tinkoff_feature_flags` table.
+///
+/// Flags are evaluated per run or per request via `snapshot`, so an override takes effect
+/// on the next run without a restart.
+pub struct FeatureFlags {
+    defaults: HashMap<String, bool>,
+    repository: Arc<dyn TraitFeatureFlagRepository + Send + Sync>,
+}
+
+/// Flag values resolved at one point in time
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeatureFlagSnapshot {
+    flags: HashMap<String, bool>,
+}
+
+impl FeatureFlagSnapshot {
+    /// Unknown flags are disabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags.get(name).copied().unwrap_or(false)
+    }
+
+    /// Names of the enabled flags, sorted
+    pub fn enabled_flags(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .flags
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl FeatureFlags {
+    pub fn new(
+        defaults: HashMap<String, bool>,
+        repository: Arc<dyn TraitFeatureFlagRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            defaults,
+            repository,
+        }
+    }
+
+    /// Resolves all flags, falling back to config defaults if overrides can't be loaded
+    pub async fn snapshot(&self) -> FeatureFlagSnapshot {
+        let mut flags = self.defaults.clone();
+
+        match self.repository.get_overrides().await {
+            Ok(overrides) => flags.extend(overrides),
+            Err(e) => warn!("Failed to load feature flag overrides, using config defaults: {}", e),
+        }
+
+        FeatureFlagSnapshot { flags }
+    }
+}
@@ -1,39 +1,533 @@
 // File: src/services/indicators/calculator.rs
+use super::adaptive::AdaptiveThresholds;
+use super::changepoint::ChangepointDetector;
+use super::quality::{self, QualityTracker, StalePriceTracker};
+use super::pivots::{last_day_before, DailyOhlc, PivotTracker};
+use super::regime::RegimeClassifier;
+use super::hurst::RollingHurst;
+use super::feature_versions::{self, Recompute};
+use super::higher_timeframe::{HourlyTrend, HOURLY_SEED_BARS};
+use super::labels::TripleBarrier;
+use super::rolling::{distance_pct, Ema, RollingExtrema, RollingRegression, RollingStats, Sma};
+use super::seasonal::{day_start, VolumeBaseline};
+use super::session::ExchangeCalendar;
+use super::features::{
+    calculate_candle_shape, calculate_future_price_change, calculate_gap, calculate_log_return, calculate_roc,
+    calculate_rsi, determine_ma_cross,
+};
+use super::oscillators::{
+    HeikinAshi, HullMovingAverage, RealizedVolatility, ReturnStructure, StochRsi, Trix, UltimateOscillator, Vortex,
+    Vwma,
+};
+use super::plugins::IndicatorPlugins;
+use super::{portfolio, priority, spread};
 use crate::app_state::models::AppState;
-use crate::db::clickhouse::models::indicator::{DbCandleConverted, DbCandleRaw, DbIndicator};
-use crate::db::clickhouse::repository::indicator_repository::IndicatorRepository;
+use crate::services::feature_flags::{self, FeatureFlagSnapshot, FeatureFlags};
+use crate::services::instrument_filter::InstrumentFilter;
+use crate::services::latency::{now_ms, BatchStages, TracedSignal};
+use crate::services::notifications::Notifier;
+use crate::services::query_cache::QueryCache;
+use crate::services::shutdown::Shutdown;
+use crate::services::signals::{
+    detect_signals, ma_trend, timeframe_trend, SignalCooldown, SignalEvent, SignalKind, TREND_SLOW_PERIOD,
+};
+use crate::services::tuning::PipelineTuning;
+use crate::utils::retry::with_retry;
+use crate::db::clickhouse::models::indicator::{DbCandleConverted, DbIndicator, DbLabelUpdate};
+use crate::db::clickhouse::batch_size::{is_resource_error, AdaptiveBatchSize, MAX_SELECT_ROWS};
+use crate::db::clickhouse::models::pipeline_profile::DbPipelineProfile;
+use crate::db::clickhouse::models::signal_latency::DbSignalLatency;
+use crate::db::clickhouse::repository::indicator_repository::{TraitIndicatorRepository, LABEL_UPDATE_ROWS};
+use crate::db::postgres::models::indicator_failure::{PgFailingInstrument, PgIndicatorFailure};
+use crate::db::postgres::repository::run_lock_repository::InstrumentLock;
+use crate::env_config::models::app_config::{
+    BackfillConfig, GapConfig, GapMode, HurstConfig, IndicatorsUpdaterConfig, PortfolioConfig, RetryConfig,
+    SpreadConfig, TargetConfig, Timeframe,
+};
 use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc, Weekday};
-use std::collections::VecDeque;
-use std::sync::Arc;
-use tracing::{debug, error, info, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn, Instrument};
+
+/// Lookbacks (in candles) of the rolling support/resistance levels
+const SUPPORT_RESISTANCE_SHORT: usize = 60;
+const SUPPORT_RESISTANCE_LONG: usize = 240;
+/// Period of the volume-weighted moving average
+const VWMA_PERIOD: usize = 20;
+/// EMA period of Elder-Ray Bull/Bear Power
+const ELDER_RAY_PERIOD: usize = 13;
+/// Period of the Vortex indicator
+const VORTEX_PERIOD: usize = 14;
+/// Window of the rolling linear regression of closes
+const LINREG_PERIOD: usize = 30;
+/// Candles on each side of a fractal; it is confirmed this many candles later
+const FRACTAL_SIDE: usize = 2;
+/// Window of the return autocorrelation and sign entropy
+const RETURN_STRUCTURE_WINDOW: usize = 60;
+/// Floor of the candle select size when ClickHouse reports resource limits
+const MIN_SELECT_BATCH_SIZE: usize = 1_000;
+/// Queued label updates written ahead of the end of the run once there are this many
+const LABEL_FLUSH_ROWS: usize = 50_000;
+/// Upper bound of `volume_anomaly_score`, so a single huge print doesn't dominate the column
+const VOLUME_ANOMALY_SCORE_CAP: f64 = 10.0;
+
+/// Indicators with fixed parameters and their periods, reported in the configuration summary
+pub const FIXED_PERIODS: [(&str, usize); 11] = [
+    ("ma_fast", 10),
+    ("ma_slow", 30),
+    ("rsi", 14),
+    ("support_resistance_short", SUPPORT_RESISTANCE_SHORT),
+    ("support_resistance_long", SUPPORT_RESISTANCE_LONG),
+    ("vwma", VWMA_PERIOD),
+    ("elder_ray", ELDER_RAY_PERIOD),
+    ("vortex", VORTEX_PERIOD),
+    ("linreg", LINREG_PERIOD),
+    ("fractal_side", FRACTAL_SIDE),
+    ("return_structure", RETURN_STRUCTURE_WINDOW),
+];
+
+/// Version of the persisted `IndicatorState` layout; bump it when the state changes so saved
+/// states are warmed up again instead of misread
+const INDICATOR_STATE_VERSION: u32 = 4;
+
+/// Candles loaded before each batch: enough for moving averages and RSI, the longest ROC lag,
+/// support/resistance lookback and the Hurst window of returns
+pub fn warmup_window(config: &IndicatorsUpdaterConfig) -> usize {
+    let max_lag = config.roc_lags.iter().copied().max().unwrap_or(0);
+    50.max(max_lag + 1).max(SUPPORT_RESISTANCE_LONG).max(config.hurst.window + 1)
+}
+
+/// Source of candles for a single calculation stream
+enum CandleSource {
+    Instrument(String),
+    Spread(SpreadConfig),
+    Portfolio(PortfolioConfig),
+}
+
+impl CandleSource {
+    fn uid(&self) -> String {
+        match self {
+            CandleSource::Instrument(uid) => uid.clone(),
+            CandleSource::Spread(spread) => spread.uid(),
+            CandleSource::Portfolio(portfolio) => portfolio.uid(),
+        }
+    }
+
+    /// Real instruments whose candles make up this source
+    fn legs(&self) -> Vec<&str> {
+        match self {
+            CandleSource::Instrument(uid) => vec![uid.as_str()],
+            CandleSource::Spread(spread) => vec![&spread.leg_a, &spread.leg_b],
+            CandleSource::Portfolio(portfolio) => portfolio
+                .members
+                .iter()
+                .map(|member| member.instrument_uid.as_str())
+                .collect(),
+        }
+    }
+
+    /// Combine aligned leg candles into the series used for calculation
+    fn combine(&self, mut legs: Vec<Vec<DbCandleConverted>>) -> Vec<DbCandleConverted> {
+        match self {
+            CandleSource::Instrument(_) => legs.pop().unwrap_or_default(),
+            CandleSource::Spread(spread) => {
+                spread::build_spread_candles(spread, &legs[0], &legs[1])
+            }
+            CandleSource::Portfolio(portfolio) => {
+                portfolio::build_portfolio_candles(portfolio, &legs)
+            }
+        }
+    }
+}
+
+/// Batch of candles fetched for calculation
+struct CandleBatch {
+    candles: Vec<DbCandleConverted>,
+    // Number of raw candles fetched (largest leg for synthetic sources), used to detect the last batch
+    fetched: usize,
+    latest_time: Option<i64>,
+    // Candle times that had duplicate rows removed
+    duplicate_times: HashSet<i64>,
+}
+
+/// Candles of the batch a source was at, recorded with its failure
+#[derive(Debug, Clone, Copy, Default)]
+struct BatchRange {
+    from: i64,       // last processed candle time
+    to: Option<i64>, // latest candle time of the batch, once fetched
+}
+
+/// Per-batch inputs of the calculation that come from outside the candle series
+struct CalculationContext {
+    volume_baseline: VolumeBaseline,
+    seasonal_volume_norm: bool,
+    previous_day: Option<DailyOhlc>,
+    // Closes of the complete hours before the series, oldest first
+    hourly_closes: Vec<f64>,
+    duplicate_times: HashSet<i64>,
+    // Gap handling applies to 1-minute series only; bars of higher timeframes span breaks anyway
+    session_gaps: bool,
+    // Synthetic spreads fill spread_zscore
+    spread: bool,
+}
+
+/// State of every rolling and recursive indicator after the last calculated candle. It is
+/// persisted per instrument after each batch, so the next run continues exactly where the
+/// previous one stopped instead of re-warming on a historical window. The encoding keeps
+/// every float bit for bit, NaN and infinities included.
+#[derive(Serialize, Deserialize)]
+struct IndicatorState {
+    prices_window: VecDeque<f64>,
+    rsi_gains: VecDeque<f64>,
+    rsi_losses: VecDeque<f64>,
+    stoch_rsi: StochRsi,
+    adaptive_thresholds: AdaptiveThresholds,
+    sma_10: Sma,
+    sma_30: Sma,
+    prev_ma_10: f64,
+    prev_ma_30: f64,
+    volume_stats: RollingStats,
+    close_stats: RollingStats,
+    // Over `window_size` closes, for spread_zscore
+    spread_stats: RollingStats,
+    regime_classifier: RegimeClassifier,
+    heikin_ashi: HeikinAshi,
+    pivot_tracker: PivotTracker,
+    hourly_trend: HourlyTrend,
+    extrema_short: RollingExtrema,
+    extrema_long: RollingExtrema,
+    trix: Trix,
+    ultimate_oscillator: UltimateOscillator,
+    volatility: RealizedVolatility,
+    hull: HullMovingAverage,
+    regression: RollingRegression,
+    return_structure: ReturnStructure,
+    hurst: RollingHurst,
+    vortex: Vortex,
+    elder_ema: Ema,
+    vwma: Vwma,
+    stale_tracker: StalePriceTracker,
+    quality_tracker: QualityTracker,
+    changepoint_detector: ChangepointDetector,
+    // Last candle, for the RSI change and gap of the next one
+    prev_candle: Option<DbCandleConverted>,
+}
+
+impl IndicatorState {
+    /// Base64 of the bincode encoding, which writes floats as their raw bits
+    fn encode(&self) -> Result<String, bincode::Error> {
+        Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(self)?))
+    }
+
+    fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| e.to_string())?;
+        bincode::deserialize(&bytes).map_err(|e| e.to_string())
+    }
+}
+
+/// Totals of one run, recorded in tinkoff_indicators_runs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunStats {
+    pub instruments: u64, // Sources processed, failed ones included
+    pub instruments_failed: u64,
+    pub candles_processed: u64, // 1-minute candles read
+    pub rows_inserted: u64,
+    pub insert_errors: u64,
+}
+
+/// Why `recalculate_instrument` did not recalculate
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstrumentRecalcError {
+    /// No candles, no such spread or portfolio, or filtered out
+    NotFound,
+    /// Another job is processing the instrument; its ID if it was recorded
+    Held { job_id: Option<String> },
+    /// Logged and, past the lock, recorded as a failure of the instrument
+    Failed(String),
+}
+
+/// Scope of a one-shot recalculation (`t-indicators recalc`, `POST /api/recalculate`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecalcRange {
+    pub instrument_uids: Vec<String>, // Пусто - все источники
+    pub from: Option<i64>,            // Строки после from удаляются и считаются заново
+    pub to: Option<i64>,              // Последняя свеча прохода; дальше догонит обычное обновление
+}
 
 pub struct IndicatorCalculator {
     app_state: Arc<AppState>,
     batch_size: usize,
+    async_insert: bool,
     window_size: usize,
+    roc_lags: Vec<usize>,
+    hma_period: usize,
+    volume_anomaly_sigma: f64,
+    targets: Vec<TargetConfig>,
+    calendar: ExchangeCalendar,
+    hurst: HurstConfig,
+    timeframes: Vec<Timeframe>,
+    gaps: GapConfig,
+    backfill: BackfillConfig,
+    // Scheduled runs keep to the operation and backfill windows, manual runs ignore them
+    enforce_windows: bool,
+    // Identifies the settings a persisted indicator state was built with
+    state_fingerprint: String,
+    retry: RetryConfig,
+    // Attached to the run's log lines, status rows and pipeline profiles
+    run_id: String,
+    // Scheduler cycle, picks the tiers due in this run
+    cycle: u64,
+    recalc: Option<RecalcRange>,
+    // `t-indicators backfill`: lagging instruments run to the latest candle in one go
+    catch_up: bool,
+    stats: Mutex<RunStats>,
+    // Label updates of all sources, written with one mutation per run
+    label_updates: Mutex<Vec<DbLabelUpdate>>,
 }
 
 impl IndicatorCalculator {
     pub fn new(app_state: Arc<AppState>) -> Self {
-        // Use moderate batch size to avoid memory issues entirely
-        let batch_size = 100000; // Balanced batch size to avoid memory errors
-        let window_size = 50;  // Size of window for moving averages and RSI
+        // Batch size and insert mode may have been changed by the tuning advisor
+        let updater_config = &app_state.settings.app_config.indicators_updater;
+        let (batch_size, async_insert) = match app_state.service::<PipelineTuning>() {
+            Some(tuning) => (tuning.batch_size(), tuning.async_insert()),
+            None => (updater_config.batch_size, updater_config.async_insert),
+        };
+        let roc_lags = app_state.settings.app_config.indicators_updater.roc_lags.clone();
+        let hma_period = app_state.settings.app_config.indicators_updater.hma_period;
+        let volume_anomaly_sigma = app_state.settings.app_config.indicators_updater.volume_anomaly_sigma;
+        let targets = app_state.settings.app_config.indicators_updater.targets.clone();
+        let calendar = ExchangeCalendar::new(&app_state.settings.app_config.indicators_updater.calendar);
+        let hurst = app_state.settings.app_config.indicators_updater.hurst.clone();
+        let window_size = warmup_window(updater_config);
+        let timeframes = updater_config.timeframes.clone();
+        let gaps = updater_config.gaps.clone();
+        let backfill = updater_config.backfill.clone();
+        let retry = app_state.settings.app_config.retry.clone();
+        let state_fingerprint = serde_json::json!({
+            "version": INDICATOR_STATE_VERSION,
+            "window_size": window_size,
+            "hma_period": hma_period,
+            "hurst": hurst,
+            "adaptive_thresholds": updater_config.adaptive_thresholds,
+        })
+        .to_string();
 
         Self {
             app_state,
             batch_size,
+            async_insert,
             window_size,
+            roc_lags,
+            hma_period,
+            volume_anomaly_sigma,
+            targets,
+            calendar,
+            hurst,
+            timeframes,
+            gaps,
+            backfill,
+            enforce_windows: false,
+            state_fingerprint,
+            retry,
+            run_id: uuid::Uuid::new_v4().to_string(),
+            cycle: 0,
+            recalc: None,
+            catch_up: false,
+            stats: Mutex::new(RunStats::default()),
+            label_updates: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Runs on a scheduler tick keep to the operation windows, manual ones don't
+    pub fn trigger(&self) -> &'static str {
+        match (self.enforce_windows, self.catch_up, &self.recalc) {
+            (true, _, _) => "scheduled",
+            (false, true, _) => "backfill",
+            (false, false, Some(_)) => "recalc",
+            (false, false, None) => "manual",
         }
     }
 
+    /// Totals so far; final once `process_all_instruments` returns
+    pub fn run_stats(&self) -> RunStats {
+        *self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update_stats(&self, update: impl FnOnce(&mut RunStats)) {
+        update(&mut self.stats.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// Queues label updates for the flush at the end of the run; every update is a
+    /// ClickHouse mutation, so they aren't written per source and batch. Updates a flush
+    /// ahead of the end fails to write stay queued for the next one.
+    async fn queue_labels(&self, updates: Vec<DbLabelUpdate>) {
+        let queued = {
+            let mut label_updates = self.label_updates.lock().unwrap_or_else(|e| e.into_inner());
+            label_updates.extend(updates);
+            label_updates.len()
+        };
+        if queued < LABEL_FLUSH_ROWS {
+            return;
+        }
+        if let Err(e) = self.flush_labels().await {
+            warn!("Failed to update labels ahead of the end of run {}, kept queued: {}", self.run_id, e);
+        }
+    }
+
+    /// Writes the queued label updates of all sources, one mutation per `LABEL_UPDATE_ROWS`
+    /// neighbouring rows. A failed mutation counts as an insert error of the run rather than
+    /// of a source; the updates not written yet go back to the queue.
+    async fn flush_labels(&self) -> Result<(), clickhouse::error::Error> {
+        let mut updates = std::mem::take(&mut *self.label_updates.lock().unwrap_or_else(|e| e.into_inner()));
+        if updates.is_empty() {
+            return Ok(());
+        }
+        // Rows of a source in time order keep the time range each mutation rewrites narrow
+        updates.sort_by(|a, b| a.instrument_uid.cmp(&b.instrument_uid).then(a.time.cmp(&b.time)));
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let mut written = 0;
+        while written < updates.len() {
+            let chunk = &updates[written..(written + LABEL_UPDATE_ROWS).min(updates.len())];
+            let update = || indicator_repo.update_labels(chunk);
+            if let Err(e) = with_retry(&self.retry, "Label update", update).await {
+                self.update_stats(|stats| stats.insert_errors += 1);
+                let mut label_updates = self.label_updates.lock().unwrap_or_else(|e| e.into_inner());
+                label_updates.splice(0..0, updates.drain(written..));
+                return Err(e);
+            }
+            let instrument_uids: HashSet<&str> = chunk.iter().map(|update| update.instrument_uid.as_str()).collect();
+            for instrument_uid in instrument_uids {
+                self.invalidate_cached_queries(instrument_uid);
+            }
+            written += chunk.len();
+        }
+        debug!("Updated labels of {} rows", written);
+        Ok(())
+    }
+
+    /// Records the sources whose queued labels could not be written as failed and moves each
+    /// back before its first unlabeled row, so the next run rewrites those rows with labels
+    async fn rewrite_unlabeled(&self, error: &str) {
+        let updates = std::mem::take(&mut *self.label_updates.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut ranges: HashMap<String, (i64, i64)> = HashMap::new();
+        for update in updates {
+            let range = ranges.entry(update.instrument_uid).or_insert((update.time, update.time));
+            *range = (range.0.min(update.time), range.1.max(update.time));
+        }
+        self.update_stats(|stats| stats.instruments_failed += ranges.len() as u64);
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+        for (instrument_uid, (first, last)) in ranges {
+            let batch = BatchRange { from: first - 1, to: Some(last) };
+            self.record_failure(&instrument_uid, error, batch).await;
+            if let Err(e) = indicator_repo.delete_indicators_after(&instrument_uid, batch.from, None).await {
+                error!("Failed to remove the unlabeled rows of {}: {}", instrument_uid, e);
+                continue;
+            }
+            if let Err(e) = status_repo.update_last_processed_time(&instrument_uid, batch.from, &self.run_id).await {
+                error!("Failed to move back the status of {}: {}", instrument_uid, e);
+            }
+            warn!("Labels of {} after {} were not written, rewriting them next run", instrument_uid, batch.from);
+        }
+    }
+
+    /// Skips instruments whose window (operation or backfill) is closed
+    pub fn with_operation_windows(mut self) -> Self {
+        self.enforce_windows = true;
+        self
+    }
+
+    /// Runs as scheduler cycle `cycle`: tiers refreshed every N-th cycle are due when N divides
+    /// it. Cycle 0, the default, refreshes every tier.
+    pub fn with_cycle(mut self, cycle: u64) -> Self {
+        self.cycle = cycle;
+        self
+    }
+
+    /// Limits the run to `range`: its sources only, dead-lettered ones included, rewritten
+    /// after `range.from` and processed up to `range.to`
+    pub fn with_recalc(mut self, range: RecalcRange) -> Self {
+        self.recalc = Some(range);
+        self
+    }
+
+    /// Lifts `backfill.max_batches_per_run`, so lagging instruments catch up within this run
+    pub fn with_catch_up(mut self) -> Self {
+        self.catch_up = true;
+        self
+    }
+
+    /// Last candle time a recalculation processes
+    fn until(&self) -> Option<i64> {
+        self.recalc.as_ref().and_then(|range| range.to)
+    }
+
+    /// Rows a select is asked for; the repositories return at most `MAX_SELECT_ROWS`, so a
+    /// shorter batch means the series is exhausted only against this limit
+    fn select_limit(&self) -> usize {
+        self.batch_size.min(MAX_SELECT_ROWS)
+    }
+
+    /// Fewest candles `compute` returns rows for
+    pub fn min_candles(&self) -> usize {
+        self.window_size + 1
+    }
+
+    /// Calculates indicators of caller-supplied candles (ascending, unique times) with the
+    /// pipeline's math, without touching storage. There is no seasonal volume baseline,
+    /// previous day or hourly history, so the columns built on them start empty as for a new
+    /// instrument.
+    pub fn compute(&self, candles: &[DbCandleConverted]) -> Vec<DbIndicator> {
+        let context = CalculationContext {
+            volume_baseline: VolumeBaseline::default(),
+            seasonal_volume_norm: false,
+            previous_day: None,
+            hourly_closes: Vec::new(),
+            duplicate_times: HashSet::new(),
+            session_gaps: true,
+            spread: false,
+        };
+        let (rows, _) = self.calculate_indicators(candles, 0, &context, None);
+        rows.into_iter().map(DbIndicator::sanitized).collect()
+    }
+
+    /// Set on SIGTERM/SIGINT: no new sources are started and running ones stop after the
+    /// batch in flight, its rows and status stored
+    fn is_shutting_down(&self) -> bool {
+        self.app_state.service::<Shutdown>().is_some_and(|shutdown| shutdown.is_triggered())
+    }
+
+    /// Instruments whose last processed candle lags more than `backfill.lag_hours` behind now
+    fn is_backfill(&self, last_processed_time: i64) -> bool {
+        self.backfill.lag_hours > 0
+            && last_processed_time < Utc::now().timestamp() - self.backfill.lag_hours as i64 * 3600
+    }
+
     /// Clear indicators table before recalculation
     pub async fn truncate_indicators_table(&self) -> Result<(), Box<dyn std::error::Error>> {
         info!("Clearing indicators table before update");
-        let client = self.app_state.clickhouse_service.connection.get_client();
-        let query = "TRUNCATE TABLE market_data.tinkoff_indicators_1min";
-        
-        match client.query(query).execute().await {
-            Ok(_) => {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+
+        match indicator_repo.truncate_indicators().await {
+            Ok(()) => {
                 info!("Indicators table successfully cleared");
                 Ok(())
             }
@@ -44,19 +538,21 @@ impl IndicatorCalculator {
         }
     }
 
-    /// Process all instruments and calculate technical indicators
-    pub async fn process_all_instruments(&self) -> Result<usize, Box<dyn std::error::Error>> {
+    /// Process all instruments and calculate technical indicators. Up to
+    /// `max_concurrent_instruments` sources run at once; a failing source is logged and
+    /// doesn't stop the others.
+    pub async fn process_all_instruments(self: Arc<Self>) -> Result<usize, Box<dyn std::error::Error>> {
         info!("Starting processing for all instruments from last processed time");
 
         // Очищаем таблицу индикаторов перед обновлением
         // self.truncate_indicators_table().await?;
 
                 // Get repositories
-        let indicator_repo = &self.app_state.clickhouse_service.repository_indicator;
-        let status_repo = &self.app_state.postgres_service.repository_indicator_status;
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
 
-        // Get all instruments with candles
+        // Get all instruments with candles, less the denied ones
         let instrument_uids = indicator_repo.get_all_instrument_uids().await?;
+        let instrument_uids = InstrumentFilter::load(&self.app_state).await.apply(instrument_uids);
         if instrument_uids.is_empty() {
             info!("No instruments found for processing");
             return Ok(0);
@@ -72,154 +568,1274 @@ impl IndicatorCalculator {
             info!("Status table has records, continuing from last processed times");
         }
 
+        // New instruments are announced and bootstrapped ahead of the known ones
+        let new_uids = self.discover_instruments(&instrument_uids).await;
+
+        let updater_config = &self.app_state.settings.app_config.indicators_updater;
+        let sources: Vec<CandleSource> = instrument_uids
+            .into_iter()
+            .map(CandleSource::Instrument)
+            .chain(updater_config.spreads.iter().cloned().map(CandleSource::Spread))
+            .chain(updater_config.portfolios.iter().cloned().map(CandleSource::Portfolio))
+            .collect();
+
+        // A recalculation of named sources runs them even if they are dead-lettered
+        let selected = self.recalc.as_ref().map_or(&[][..], |range| &range.instrument_uids[..]);
+        let sources: Vec<CandleSource> = match selected {
+            [] => sources,
+            _ => {
+                let sources: Vec<CandleSource> =
+                    sources.into_iter().filter(|source| selected.contains(&source.uid())).collect();
+                let unknown: Vec<&String> =
+                    selected.iter().filter(|uid| !sources.iter().any(|source| source.uid() == **uid)).collect();
+                if !unknown.is_empty() {
+                    return Err(format!("No candles or filtered out: {:?}", unknown).into());
+                }
+                sources
+            }
+        };
+
+        // Sources failing run after run wait out the dead-letter cooldown
+        let failing = self.load_failing_instruments().await;
+        let dead_letter = &updater_config.dead_letter;
+        let now = Utc::now().timestamp();
+        let source_total = sources.len();
+        let sources: Vec<CandleSource> = sources
+            .into_iter()
+            .filter(|source| {
+                !selected.is_empty()
+                    || failing.get(&source.uid()).is_none_or(|failing| {
+                        !dead_letter.is_cooling_down(failing.failures, failing.last_failure_time.timestamp(), now)
+                    })
+            })
+            .collect();
+        if sources.len() < source_total {
+            warn!(
+                "{} sources skipped after {} failed runs in a row, see /api/admin/dead-letters",
+                source_total - sources.len(),
+                dead_letter.max_failures
+            );
+        }
+        let failing: Arc<HashSet<String>> = Arc::new(failing.into_keys().collect());
+
+        // Tiers set the order and how often a source is refreshed; the most stale go first
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+        let last_processed = status_repo.get_all_last_processed_times().await.unwrap_or_else(|e| {
+            warn!("Failed to fetch last processed times, ordering by tier only: {}", e);
+            HashMap::new()
+        });
+        let (sources, deferred) = priority::plan_sources(
+            sources,
+            CandleSource::uid,
+            &new_uids,
+            &updater_config.tiers,
+            &last_processed,
+            self.cycle,
+        );
+        if deferred > 0 {
+            info!("Cycle {}: {} sources wait for a later cycle of their tier", self.cycle, deferred);
+        }
+
+        // Evaluate feature flags once per run
+        let flags = match self.app_state.service::<FeatureFlags>() {
+            Some(flags) => flags.snapshot().await,
+            None => FeatureFlagSnapshot::default(),
+        };
+
+        // Rows written with indicator parameters that changed since are recomputed first
+        if let Err(e) = self.apply_feature_changes(&sources, is_status_table_empty).await {
+            error!("Failed to recompute features with changed parameters: {}", e);
+        }
+
         let mut total_processed = 0;
 
-        // Process each instrument sequentially - no parallelism
-        for (index, instrument_uid) in instrument_uids.iter().enumerate() {
-            info!(
-                "Processing instrument {}/{}: {}",
-                index + 1,
-                instrument_uids.len(),
-                instrument_uid
-            );
+        // Per-stage timings of every source, written to tinkoff_pipeline_profile after the run
+        let run_time = Utc::now().timestamp() as u32;
+        let mut profiles = Vec::with_capacity(sources.len());
+        let mut failed = 0;
+
+        // One grouped max(time) query spares instruments without new candles their candle SELECT
+        let latest_candle_times = match indicator_repo.get_latest_candle_times().await {
+            Ok(times) => Some(times),
+            Err(e) => {
+                warn!("Failed to fetch latest candle times, checking every instrument: {}", e);
+                None
+            }
+        };
+        let latest_candle_times = Arc::new(latest_candle_times);
+
+        let source_count = sources.len();
+        let concurrency = updater_config.max_concurrent_instruments.max(1);
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let flags = Arc::new(flags);
+        let mut tasks = JoinSet::new();
+        info!("Processing {} sources, up to {} at a time", source_count, concurrency);
+
+        for (index, source) in sources.into_iter().enumerate() {
+            // Taking the permit before spawning keeps the start order: new instruments first
+            let permit = semaphore.clone().acquire_owned().await?;
+            if self.is_shutting_down() {
+                info!("Shutting down, {} sources left for the next run", source_count - index);
+                break;
+            }
+            let calculator = Arc::clone(&self);
+            let flags = Arc::clone(&flags);
+            let latest_candle_times = Arc::clone(&latest_candle_times);
+            let failing = Arc::clone(&failing);
+            let profile = DbPipelineProfile {
+                run_id: self.run_id.clone(),
+                run_time,
+                instrument_uid: source.uid(),
+                ..Default::default()
+            };
+            tasks.spawn(async move {
+                let _permit = permit;
+                info!("Processing instrument {}/{}: {}", index + 1, source_count, profile.instrument_uid);
+                calculator
+                    .process_instrument(&source, &flags, (*latest_candle_times).as_ref(), &failing, profile)
+                    .await
+            }.in_current_span());
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(Some((processed_count, profile))) => {
+                    total_processed += processed_count;
+                    self.update_stats(|stats| {
+                        stats.instruments += 1;
+                        stats.rows_inserted += processed_count as u64;
+                        stats.insert_errors += profile.insert_errors as u64;
+                    });
+                    profiles.push(profile);
+                }
+                Ok(None) => failed += 1,
+                Err(e) => {
+                    error!("Instrument processing task aborted: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+        self.update_stats(|stats| {
+            stats.instruments += failed;
+            stats.instruments_failed += failed;
+        });
+
+        info!(
+            "All instrument processing completed. Total processed: {} candles, {} sources failed",
+            total_processed, failed
+        );
+
+        if let Err(e) = self.flush_labels().await {
+            error!("Failed to update labels of run {}: {}", self.run_id, e);
+            self.rewrite_unlabeled(&e.to_string()).await;
+        }
+
+        if let Err(e) = indicator_repo.move_to_cold().await {
+            error!("Failed to move old indicators to the cold table: {}", e);
+        }
+
+        let profile_repo = &self.app_state.clickhouse_service().repository_pipeline_profile;
+        if let Err(e) = profile_repo.insert_profiles(&profiles).await {
+            error!("Failed to record pipeline profile of run {}: {}", self.run_id, e);
+        }
+
+        Ok(total_processed)
+    }
+
+    /// Calculates one source and, for a real instrument, its higher timeframes. Errors are
+    /// logged here so they stay with the source and recorded as its failure; `None` marks a
+    /// failed source. `failing` holds the sources with open failures, closed by a processed batch.
+    /// Processing longer than `instrument_timeout` stops before its next batch and counts as a
+    /// failure; the next run continues after the last batch written.
+    async fn process_instrument(
+        &self,
+        source: &CandleSource,
+        flags: &FeatureFlagSnapshot,
+        latest_candle_times: Option<&HashMap<String, i64>>,
+        failing: &HashSet<String>,
+        profile: DbPipelineProfile,
+    ) -> Option<(usize, DbPipelineProfile)> {
+        // Status and state of the instrument are written by one job at a time
+        let run_lock_repo = &self.app_state.postgres_service().repository_run_lock;
+        let _instrument_lock = match run_lock_repo.try_lock_instrument(&profile.instrument_uid, &self.run_id).await {
+            Ok(InstrumentLock::Acquired(guard)) => guard,
+            Ok(InstrumentLock::Held { job_id }) => {
+                info!(
+                    "Instrument {} is being processed by job {}, skipping",
+                    profile.instrument_uid,
+                    job_id.as_deref().unwrap_or("unknown")
+                );
+                return Some((0, profile));
+            }
+            Err(e) => {
+                error!("Failed to take the lock of instrument {}: {}", profile.instrument_uid, e);
+                return None;
+            }
+        };
+        self.process_locked(source, flags, latest_candle_times, failing, profile).await.ok()
+    }
+
+    /// Body of `process_instrument` once the instrument lock is taken. A recalculation first
+    /// rewrites the source after its `from`. The error is already logged and recorded.
+    async fn process_locked(
+        &self,
+        source: &CandleSource,
+        flags: &FeatureFlagSnapshot,
+        latest_candle_times: Option<&HashMap<String, i64>>,
+        failing: &HashSet<String>,
+        mut profile: DbPipelineProfile,
+    ) -> Result<(usize, DbPipelineProfile), String> {
+        let started = Instant::now();
+        if let Some(from) = self.recalc.as_ref().and_then(|range| range.from) {
+            info!("Recalculating {} after {}", profile.instrument_uid, from);
+            let rewritten = self.rewrite_after(source, from).await.map_err(|e| e.to_string());
+            if let Err(e) = rewritten {
+                error!("Failed to rewrite instrument {}: {}", profile.instrument_uid, e);
+                self.record_failure(&profile.instrument_uid, &e, BatchRange::default()).await;
+                return Err(e);
+            }
+        }
+        let deadline = Deadline::after(self.app_state.settings.app_config.indicators_updater.instrument_timeout);
+
+        let mut batch = BatchRange::default();
+        // The error is not Send, only its message is kept across the await below
+        let processed_count = self
+            .process_source(source, flags, latest_candle_times, deadline, &mut profile, &mut batch)
+            .await
+            .map_err(|e| e.to_string());
+        let processed_count = match processed_count {
+            Ok(count) => count,
+            Err(e) => {
+                error!("Failed to process instrument {}: {}", profile.instrument_uid, e);
+                self.record_failure(&profile.instrument_uid, &e, batch).await;
+                return Err(e);
+            }
+        };
+        if profile.batches > 0 && failing.contains(&profile.instrument_uid) {
+            let failure_repo = &self.app_state.postgres_service().repository_indicator_failure;
+            if let Err(e) = failure_repo.resolve_failures(&profile.instrument_uid).await {
+                error!("Failed to resolve failures of {}: {}", profile.instrument_uid, e);
+            }
+        }
+
+        // Higher timeframes are aggregated from the real instrument's 1-minute candles
+        if let CandleSource::Instrument(uid) = source {
+            for &timeframe in &self.timeframes {
+                let processed = self
+                    .process_timeframe(uid, timeframe, deadline)
+                    .await
+                    .map_err(|e| (e.is::<InstrumentTimeout>(), e.to_string()));
+                match processed {
+                    Ok(_) => {}
+                    Err((true, e)) => {
+                        error!("Failed to process higher timeframes of {}: {}", uid, e);
+                        self.record_failure(uid, &e, BatchRange::default()).await;
+                        return Err(e);
+                    }
+                    Err((false, e)) => error!("Failed to process {} bars for {}: {}", timeframe.name(), uid, e),
+                }
+            }
+        }
+        profile.total_ms = elapsed_ms(started);
+
+        info!(
+            "Completed processing for instrument {}, processed {} candles",
+            profile.instrument_uid, processed_count
+        );
+        Ok((processed_count, profile))
+    }
+
+    /// Recalculates one source under its lock, outside of any run: rewrites it after the
+    /// `from` of `with_recalc` and processes it up to `to` (`POST /api/recalculate`)
+    pub async fn recalculate_instrument(&self, instrument_uid: &str) -> Result<usize, InstrumentRecalcError> {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let updater_config = &self.app_state.settings.app_config.indicators_updater;
+
+        let instrument_uids = indicator_repo
+            .get_all_instrument_uids()
+            .await
+            .map_err(|e| InstrumentRecalcError::Failed(e.to_string()))?;
+        let source = InstrumentFilter::load(&self.app_state)
+            .await
+            .apply(instrument_uids)
+            .into_iter()
+            .map(CandleSource::Instrument)
+            .chain(updater_config.spreads.iter().cloned().map(CandleSource::Spread))
+            .chain(updater_config.portfolios.iter().cloned().map(CandleSource::Portfolio))
+            .find(|source| source.uid() == instrument_uid)
+            .ok_or(InstrumentRecalcError::NotFound)?;
+
+        let run_lock_repo = &self.app_state.postgres_service().repository_run_lock;
+        let _instrument_lock = match run_lock_repo.try_lock_instrument(instrument_uid, &self.run_id).await {
+            Ok(InstrumentLock::Acquired(guard)) => guard,
+            Ok(InstrumentLock::Held { job_id }) => return Err(InstrumentRecalcError::Held { job_id }),
+            Err(e) => return Err(InstrumentRecalcError::Failed(e.to_string())),
+        };
+
+        let flags = match self.app_state.service::<FeatureFlags>() {
+            Some(flags) => flags.snapshot().await,
+            None => FeatureFlagSnapshot::default(),
+        };
+        let failing: HashSet<String> = self.load_failing_instruments().await.into_keys().collect();
+        let profile = DbPipelineProfile {
+            run_id: self.run_id.clone(),
+            run_time: Utc::now().timestamp() as u32,
+            instrument_uid: instrument_uid.to_string(),
+            ..Default::default()
+        };
+        let (processed_count, profile) = self
+            .process_locked(&source, &flags, None, &failing, profile)
+            .await
+            .map_err(InstrumentRecalcError::Failed)?;
+        if let Err(e) = self.flush_labels().await {
+            error!("Failed to update labels of {}: {}", instrument_uid, e);
+            self.rewrite_unlabeled(&e.to_string()).await;
+            return Err(InstrumentRecalcError::Failed(e.to_string()));
+        }
+
+        let profile_repo = &self.app_state.clickhouse_service().repository_pipeline_profile;
+        if let Err(e) = profile_repo.insert_profiles(&[profile]).await {
+            error!("Failed to record pipeline profile of {}: {}", self.run_id, e);
+        }
+        Ok(processed_count)
+    }
+
+    /// Compares the feature versions of the stored rows with the current config. Changed
+    /// label features are recomputed in place, any other change rewrites the recompute range
+    /// of every source. Versions are recorded once that succeeded; a full recalculation
+    /// only records them.
+    async fn apply_feature_changes(
+        &self,
+        sources: &[CandleSource],
+        full_recalculation: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let version_repo = &self.app_state.postgres_service().repository_feature_version;
+        let recompute = &self.app_state.settings.app_config.indicators_updater.recompute;
+
+        let features = feature_versions::registry(&self.app_state.settings.app_config.indicators_updater);
+        let stored = version_repo.get_feature_versions().await?;
+        let changed = feature_versions::changed(&features, &stored);
+
+        if !changed.is_empty() && !full_recalculation {
+            let names: Vec<&str> = changed.iter().map(|feature| feature.name).collect();
+            let columns: Vec<&str> = changed.iter().flat_map(|feature| feature.columns.iter().copied()).collect();
+            match recompute.enabled {
+                true => {
+                    let from = match recompute.lookback_days {
+                        0 => 0,
+                        days => Utc::now().timestamp() - days as i64 * 86_400,
+                    };
+                    let mode = changed.iter().map(|feature| feature.recompute).max().unwrap_or(Recompute::Labels);
+                    info!(
+                        "Parameters of {:?} changed, recomputing columns {:?} after {} ({:?})",
+                        names, columns, from, mode
+                    );
+                    for source in sources {
+                        match mode {
+                            Recompute::Labels => self.recompute_labels(source, from).await?,
+                            Recompute::Rewrite => self.rewrite_after(source, from).await?,
+                        }
+                    }
+                    self.flush_labels().await?;
+                }
+                false => warn!(
+                    "Parameters of {:?} changed, columns {:?} are stale until a manual recalculation",
+                    names, columns
+                ),
+            }
+        }
+
+        version_repo.save_feature_versions(&feature_versions::versions(&features)).await?;
+
+        Ok(())
+    }
+
+    /// Deletes the rows of a source after `from` and moves its status back, so the regular
+    /// batches rewrite the range with the current parameters
+    async fn rewrite_after(&self, source: &CandleSource, from: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+        let timeframe_status_repo = &self.app_state.postgres_service().repository_timeframe_status;
+        let instrument_uid = source.uid();
+
+        let last_processed_time = status_repo.get_last_processed_time(&instrument_uid).await?.unwrap_or(0);
+        if last_processed_time > from {
+            indicator_repo.delete_indicators_after(&instrument_uid, from, None).await?;
+            status_repo.update_last_processed_time(&instrument_uid, from, &self.run_id).await?;
+            debug!("Rewriting {} after {}", instrument_uid, from);
+        }
+
+        if let CandleSource::Instrument(uid) = source {
+            for &timeframe in &self.timeframes {
+                let last_bar = timeframe_status_repo.get_last_processed_bar(uid, timeframe.name()).await?;
+                if last_bar.is_some_and(|time| time > from) {
+                    indicator_repo.delete_indicators_after(uid, from, Some(timeframe)).await?;
+                    timeframe_status_repo.update_last_processed_bar(uid, timeframe.name(), from, &self.run_id).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recomputes the label columns of the rows of a source after `from` in place, batch by
+    /// batch, queued for the next label flush. Each batch keeps its last candles for the next
+    /// one, so every row is labeled with its whole horizon and both fractal sides loaded.
+    async fn recompute_labels(&self, source: &CandleSource, from: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+        let instrument_uid = source.uid();
+
+        let Some(last_processed_time) = status_repo.get_last_processed_time(&instrument_uid).await? else {
+            return Ok(());
+        };
+        let horizon = self.label_horizon();
+        let mut after = from;
+        let mut candles: Vec<DbCandleConverted> = Vec::new();
+        // Rows without their left fractal side are skipped, unless the series starts there
+        let mut start = if from > 0 { FRACTAL_SIDE } else { 0 };
+        let mut updated = 0;
+
+        while after < last_processed_time {
+            let batch = self.fetch_candles_after(source, after, self.select_limit()).await?;
+            let Some(latest_time) = batch.latest_time else {
+                break;
+            };
+            let last_batch = batch.fetched < self.select_limit() || latest_time >= last_processed_time;
+            candles.extend(batch.candles);
+
+            let end = match last_batch {
+                true => candles.len(),
+                false => candles.len().saturating_sub(horizon),
+            };
+            let triple_barrier = self.triple_barrier(&candles);
+            let updates: Vec<DbLabelUpdate> = (start.min(end)..end)
+                .filter(|&i| candles[i].time <= last_processed_time)
+                .map(|i| self.calculate_labels(&candles, i, triple_barrier.as_ref()))
+                .collect();
+            updated += updates.len();
+            self.queue_labels(updates).await;
+
+            let keep_from = end.max(start).saturating_sub(FRACTAL_SIDE);
+            candles.drain(..keep_from);
+            start = end.max(start) - keep_from;
+            after = latest_time;
+            if last_batch {
+                break;
+            }
+        }
+
+        if updated > 0 {
+            debug!("Recomputed labels of {} rows for {}", updated, instrument_uid);
+        }
+
+        Ok(())
+    }
+
+    /// Registers instrument UIDs missing from the registry and announces them as new. The
+    /// first run against an empty registry only records the current instruments.
+    async fn discover_instruments(&self, instrument_uids: &[String]) -> HashSet<String> {
+        let registry = &self.app_state.postgres_service().repository_instrument_registry;
+
+        let known: HashSet<String> = match registry.get_known_instruments().await {
+            Ok(known) => known.into_iter().collect(),
+            Err(e) => {
+                error!("Failed to load the instrument registry: {}", e);
+                return HashSet::new();
+            }
+        };
+        let new_uids: Vec<String> = instrument_uids
+            .iter()
+            .filter(|uid| !known.contains(*uid))
+            .cloned()
+            .collect();
+        if new_uids.is_empty() {
+            return HashSet::new();
+        }
+
+        if let Err(e) = registry.register_instruments(&new_uids).await {
+            error!("Failed to register {} new instruments: {}", new_uids.len(), e);
+            return HashSet::new();
+        }
+        if known.is_empty() {
+            info!("Instrument registry initialized with {} instruments", new_uids.len());
+            return HashSet::new();
+        }
+
+        info!("Discovered {} new instruments: {:?}", new_uids.len(), new_uids);
+        if let Some(notifier) = self.app_state.service::<Notifier>() {
+            notifier.announce_instruments(&new_uids).await;
+        }
+
+        new_uids.into_iter().collect()
+    }
+
+    /// Process a single instrument (or synthetic spread) from its last processed time
+    async fn process_source(
+        &self,
+        source: &CandleSource,
+        flags: &FeatureFlagSnapshot,
+        latest_candle_times: Option<&HashMap<String, i64>>,
+        deadline: Option<Deadline>,
+        profile: &mut DbPipelineProfile,
+        batch_range: &mut BatchRange,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+        let instrument_uid = source.uid();
+
+        // Get the last processed time for this instrument
+        let mut last_processed_time =
+            with_retry(&self.retry, "Status read", || status_repo.get_last_processed_time(&instrument_uid))
+                .await?
+                .unwrap_or(0); // If no record exists, start from the beginning (time 0)
+
+        info!(
+            "Last processed time for instrument {}: {}",
+            instrument_uid, last_processed_time
+        );
+
+        // No leg has a candle after the last processed one: nothing to fetch
+        if let Some(times) = latest_candle_times {
+            let latest_time = source.legs().iter().filter_map(|leg| times.get(*leg)).max().copied();
+            if latest_time.is_none_or(|time| time <= last_processed_time) {
+                debug!("Skipping {}: no candles after {}", instrument_uid, last_processed_time);
+                return Ok(0);
+            }
+        }
+
+        // Instruments far behind catch up with the backfill budget, within the backfill window
+        let backfill = self.is_backfill(last_processed_time);
+        if self.enforce_windows {
+            let window_open = match backfill {
+                true => self.backfill.is_window_open(),
+                false => self.app_state.settings.app_config.indicators_updater.is_operation_allowed(),
+            };
+            if !window_open {
+                debug!(
+                    "Skipping {}: outside the {} window",
+                    instrument_uid,
+                    if backfill { "backfill" } else { "operation" }
+                );
+                return Ok(0);
+            }
+        }
+        let (batch_size, batch_pause, max_batches) = match backfill {
+            true => (
+                match self.backfill.batch_size {
+                    0 => self.batch_size,
+                    batch_size => batch_size,
+                },
+                self.backfill.batch_pause,
+                if self.catch_up { 0 } else { self.backfill.max_batches_per_run },
+            ),
+            false => (self.batch_size, Duration::from_millis(10), 0),
+        };
+        // Candles per select, halved while ClickHouse reports resource limits
+        let select_batch = AdaptiveBatchSize::new(batch_size, MIN_SELECT_BATCH_SIZE.min(batch_size));
+
+        let started = Instant::now();
+        let mut context = CalculationContext {
+            // Models of the days of each batch, loaded for rel_volume whatever the flag, which
+            // only switches volume_norm to them
+            volume_baseline: VolumeBaseline::default(),
+            seasonal_volume_norm: flags.is_enabled(feature_flags::SEASONAL_VOLUME_BASELINE),
+            previous_day: None,
+            hourly_closes: Vec::new(),
+            duplicate_times: HashSet::new(),
+            session_gaps: true,
+            spread: matches!(source, CandleSource::Spread(_)),
+        };
+        // Continue the indicators exactly where the previous run stopped, if it saved its state
+        let mut state = self.load_state(&instrument_uid, last_processed_time).await;
+        profile.fetch_ms += elapsed_ms(started);
+
+        let mut processed_count = 0;
+
+        loop {
+            // Fetch candles after the last processed time
+            *batch_range = BatchRange {
+                from: last_processed_time,
+                to: None,
+            };
+            let started = Instant::now();
+            let batch_size = select_batch.get().min(MAX_SELECT_ROWS);
+            let fetch = || self.fetch_candles_after(source, last_processed_time, batch_size);
+            let batch = match with_retry(&self.retry, "Candle fetch", fetch).await {
+                Ok(batch) => batch,
+                Err(e) if is_resource_error(&e) && select_batch.shrink() => {
+                    warn!(
+                        "Candle select for {} hit a resource limit, retrying with {} candles: {}",
+                        instrument_uid,
+                        select_batch.get(),
+                        e
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            profile.fetch_ms += elapsed_ms(started);
+
+            // Update the latest time for this batch
+            let latest_time = match batch.latest_time {
+                Some(time) => time,
+                None => {
+                    debug!(
+                        "No more candles found for instrument {} after time {}",
+                        instrument_uid, last_processed_time
+                    );
+                    break;
+                }
+            };
+
+            debug!("Latest time in current batch: {}", latest_time);
+            batch_range.to = Some(latest_time);
+
+            let batch_candles = batch.candles;
+            self.update_stats(|stats| stats.candles_processed += batch_candles.len() as u64);
+            context.duplicate_times = batch.duplicate_times;
+
+            profile.batches += 1;
+            let mut stages = BatchStages::default();
+
+            let (indicators, label_updates) = {
+                // Calculate indicators for the batch
+                let started = Instant::now();
+                let window_data = if processed_count == 0 && last_processed_time > 0 {
+                    // We need historical data for the first batch to calculate indicators correctly
+                    self.fetch_historical_window(source, last_processed_time)
+                        .await?
+                } else {
+                    Vec::new()
+                };
+
+                // Get window size before moving window_data
+                let window_end_idx = if !window_data.is_empty() {
+                    window_data.len()
+                } else {
+                    0
+                };
+
+                // Prepend the historical window if needed; the batch is moved, not copied
+                let calculation_data = if !window_data.is_empty() {
+                    let mut combined = window_data;
+                    combined.extend(batch_candles);
+                    combined
+                } else {
+                    batch_candles
+                };
+                
+                context.previous_day = match calculation_data.first() {
+                    Some(first) => self.fetch_previous_day(source, first.time).await,
+                    None => None,
+                };
+                context.hourly_closes = match calculation_data.first() {
+                    Some(first) => self.fetch_hourly_closes(source, first.time).await,
+                    None => Vec::new(),
+                };
+                self.load_volume_baseline(source, &calculation_data[window_end_idx..], &mut context.volume_baseline)
+                    .await;
+                profile.fetch_ms += elapsed_ms(started);
+                stages.fetched_ms = now_ms();
+
+                let started = Instant::now();
+                let (mut indicators, next_state) =
+                    self.calculate_indicators(&calculation_data, window_end_idx, &context, state.take());
+                state = next_state;
+
+                self.apply_plugins(&mut indicators, &calculation_data);
+
+                // Rows at the tail of the previous run were written before their horizon elapsed
+                let label_updates = self.backfill_labels(&calculation_data, window_end_idx);
+                profile.compute_ms += elapsed_ms(started);
+                stages.computed_ms = now_ms();
+
+                (indicators, label_updates)
+            };
+            
+            // Insert calculated indicators
+            let started = Instant::now();
+            if !indicators.is_empty() {
+                let latest_row = indicators[indicators.len() - 1].clone();
+                let batch_len = indicators.len();
+                let events = batch_signals(&indicators);
+                match indicator_repo.insert_indicators(indicators, self.async_insert).await {
+                    Ok(inserted) => {
+                        stages.inserted_ms = now_ms();
+                        if (inserted as usize) < batch_len {
+                            profile.insert_errors += 1;
+                        }
+                        processed_count += inserted as usize;
+                        debug!("Inserted {} indicators for {}", inserted, instrument_uid);
+
+                        self.invalidate_cached_queries(&instrument_uid);
+
+                        // Keep the one-row-per-instrument table in sync with the batch
+                        if let Err(e) = indicator_repo.upsert_latest_indicator(latest_row).await {
+                            profile.insert_errors += 1;
+                            error!("Failed to update latest indicators for {}: {}", instrument_uid, e);
+                        }
+
+                        self.publish_signals(&instrument_uid, events, &stages).await;
+                    }
+                    Err(e) => {
+                        // Stop before the status moves past the batch; rows of it that made it
+                        // in are removed so the next run rewrites it whole
+                        profile.insert_errors += 1;
+                        error!("Failed to insert indicators for {}: {}", instrument_uid, e);
+                        if let Err(e) = indicator_repo
+                            .delete_indicators_after(&instrument_uid, last_processed_time, None)
+                            .await
+                        {
+                            error!("Failed to remove the partial batch of {}: {}", instrument_uid, e);
+                        }
+                        return Err(Box::new(e));
+                    }
+                }
+            }
+            
+            // Second pass: fill in labels of previously written rows whose horizon has elapsed,
+            // written with the labels of the other sources at the end of the run
+            if !label_updates.is_empty() {
+                debug!("Backfilling labels of {} rows for {}", label_updates.len(), instrument_uid);
+                self.queue_labels(label_updates).await;
+            }
+
+            profile.insert_ms += elapsed_ms(started);
+
+            // Update last processed time
+            let started = Instant::now();
+            let update = || status_repo.update_last_processed_time(&instrument_uid, latest_time, &self.run_id);
+            if let Err(e) = with_retry(&self.retry, "Status update", update).await {
+                // The next run starts from the stored time again and rewrites what follows
+                error!("Failed to update last processed time for {}: {}", instrument_uid, e);
+                return Err(Box::new(e));
+            }
+            if let Some(state) = &state {
+                self.save_state(&instrument_uid, latest_time, state).await;
+            }
+            profile.status_ms += elapsed_ms(started);
+            
+            // Update last processed time for next iteration
+            last_processed_time = latest_time;
+            select_batch.record_success();
+            
+            // If we received fewer candles than batch size, we're done with this instrument
+            if batch.fetched < batch_size {
+                break;
+            }
+
+            // The stored status lets the next run resume after this batch
+            if self.is_shutting_down() {
+                info!("Shutting down, stopped {} after the batch up to {}", instrument_uid, latest_time);
+                break;
+            }
+
+            // The rest waits for the next run once the backfill budget is spent
+            if max_batches > 0 && profile.batches as usize >= max_batches {
+                info!("Backfill budget of {} batches spent for {}", max_batches, instrument_uid);
+                break;
+            }
+
+            // Only between batches: a written batch always has its status and state saved
+            deadline.map_or(Ok(()), Deadline::check)?;
+            
+            // Very short pause between batches
+            tokio::time::sleep(batch_pause).await;
+        }
+
+        profile.candles = processed_count as u64;
+
+        Ok(processed_count)
+    }
+
+    /// Sources with open failures by UID; none if they can't be loaded, so nothing is skipped
+    async fn load_failing_instruments(&self) -> HashMap<String, PgFailingInstrument> {
+        let failure_repo = &self.app_state.postgres_service().repository_indicator_failure;
+        match failure_repo.get_failing_instruments().await {
+            Ok(failing) => failing.into_iter().map(|failing| (failing.instrument_uid.clone(), failing)).collect(),
+            Err(e) => {
+                warn!("Failed to load failing instruments, processing all of them: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    async fn record_failure(&self, instrument_uid: &str, error: &str, batch: BatchRange) {
+        let failure = PgIndicatorFailure {
+            instrument_uid: instrument_uid.to_string(),
+            job_id: self.run_id.clone(),
+            error: error.to_string(),
+            batch_from: batch.from,
+            batch_to: batch.to,
+            failure_time: Utc::now(),
+        };
+        let failure_repo = &self.app_state.postgres_service().repository_indicator_failure;
+        if let Err(e) = failure_repo.record_failure(&failure).await {
+            error!("Failed to record the failure of {}: {}", instrument_uid, e);
+        }
+    }
+
+    /// Calculates indicators of complete `timeframe` bars of an instrument since its last
+    /// processed bar. Labels aren't backfilled and signals aren't published for higher
+    /// timeframes; their horizons are counted in 1-minute candles.
+    async fn process_timeframe(
+        &self,
+        instrument_uid: &str,
+        timeframe: Timeframe,
+        deadline: Option<Deadline>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let status_repo = &self.app_state.postgres_service().repository_timeframe_status;
+
+        let mut last_processed_time = status_repo
+            .get_last_processed_bar(instrument_uid, timeframe.name())
+            .await?
+            .unwrap_or(0);
+
+        let mut context = CalculationContext {
+            volume_baseline: VolumeBaseline::default(),
+            seasonal_volume_norm: false,
+            previous_day: None,
+            hourly_closes: Vec::new(),
+            duplicate_times: HashSet::new(),
+            session_gaps: false,
+            spread: false,
+        };
+        let mut processed_count = 0;
+
+        loop {
+            let bars: Vec<DbCandleConverted> = indicator_repo
+                .get_timeframe_candles_after(instrument_uid, timeframe, last_processed_time, self.select_limit())
+                .await?
+                .into_iter()
+                .filter(|raw| self.until().is_none_or(|until| raw.time <= until))
+                .map(|raw| raw.into())
+                .collect();
+            let Some(latest_time) = bars.last().map(|bar| bar.time) else {
+                break;
+            };
+            let fetched = bars.len();
+
+            let window_data: Vec<DbCandleConverted> = if processed_count == 0 && last_processed_time > 0 {
+                indicator_repo
+                    .get_timeframe_candles_before(instrument_uid, timeframe, last_processed_time, self.window_size)
+                    .await?
+                    .into_iter()
+                    .map(|raw| raw.into())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let window_end_idx = window_data.len();
+            let mut calculation_data = window_data;
+            calculation_data.extend(bars);
+
+            // A young instrument has too few bars to warm up; retry from the start next run
+            if calculation_data.len() <= self.window_size {
+                debug!(
+                    "Not enough {} bars for {} yet: {}",
+                    timeframe.name(),
+                    instrument_uid,
+                    calculation_data.len()
+                );
+                break;
+            }
+
+            context.previous_day = match calculation_data.first() {
+                Some(first) => self.fetch_previous_day(&CandleSource::Instrument(instrument_uid.to_string()), first.time).await,
+                None => None,
+            };
+            let (mut indicators, _) = self.calculate_indicators(&calculation_data, window_end_idx, &context, None);
+            self.apply_plugins(&mut indicators, &calculation_data);
+            let inserted = indicator_repo
+                .insert_timeframe_indicators(timeframe, indicators, self.async_insert)
+                .await?;
+            processed_count += inserted as usize;
+
+            status_repo
+                .update_last_processed_bar(instrument_uid, timeframe.name(), latest_time, &self.run_id)
+                .await?;
+            last_processed_time = latest_time;
+
+            if fetched < self.select_limit() || self.is_shutting_down() {
+                break;
+            }
+            deadline.map_or(Ok(()), Deadline::check)?;
+        }
+
+        if processed_count > 0 {
+            debug!(
+                "Processed {} {} bars for {}",
+                processed_count,
+                timeframe.name(),
+                instrument_uid
+            );
+        }
+
+        Ok(processed_count)
+    }
+
+    /// Sends signal notifications of an instrument unless its signals are suppressed, skipping
+    /// repeats within the cooldown of the last published event of the same kind. The stage
+    /// times of every published signal are recorded for the end-to-end latency report.
+    async fn publish_signals(&self, instrument_uid: &str, events: Vec<SignalEvent>, stages: &BatchStages) {
+        let Some(notifier) = self.app_state.service::<Notifier>() else {
+            return;
+        };
+        if events.is_empty() || !notifier.is_enabled() {
+            return;
+        }
+
+        let postgres = self.app_state.postgres_service();
+        match postgres.repository_signal_suppression.is_suppressed(instrument_uid).await {
+            Ok(false) => {}
+            Ok(true) => {
+                debug!("Signals of {} are suppressed, not publishing", instrument_uid);
+                return;
+            }
+            Err(e) => {
+                error!("Failed to check signal suppression of {}: {}", instrument_uid, e);
+                return;
+            }
+        }
+
+        let mut last_emitted = match postgres.repository_signal_cooldown.get_last_emitted(instrument_uid).await {
+            Ok(last_emitted) => last_emitted
+                .into_iter()
+                .filter_map(|(name, time)| Some((SignalKind::from_name(&name)?, time)))
+                .collect(),
+            Err(e) => {
+                error!("Failed to load signal cooldown state of {}: {}", instrument_uid, e);
+                return;
+            }
+        };
+
+        let cooldown = SignalCooldown::new(&self.app_state.settings.app_config.signals);
+        let events = cooldown.apply(events, &mut last_emitted);
+        if events.is_empty() {
+            return;
+        }
+
+        let trends = self.timeframe_trends(instrument_uid, &events, notifier).await;
+        let mut signals: Vec<TracedSignal> = events
+            .into_iter()
+            .map(|event| TracedSignal::new(&self.run_id, instrument_uid, event, stages))
+            .collect();
+        notifier.publish(instrument_uid, &mut signals, &trends).await;
+        self.record_latencies(&signals).await;
+
+        let emitted = last_emitted.into_iter().map(|(kind, time)| (kind.name().to_string(), time)).collect();
+        if let Err(e) = postgres.repository_signal_cooldown.record_emitted(instrument_uid, &emitted).await {
+            error!("Failed to record signal cooldown state of {}: {}", instrument_uid, e);
+        }
+    }
+
+    async fn record_latencies(&self, signals: &[TracedSignal]) {
+        let latencies: Vec<DbSignalLatency> = signals
+            .iter()
+            .filter(|signal| signal.is_emitted())
+            .map(|signal| signal.latency.clone())
+            .collect();
+        let repository = &self.app_state.clickhouse_service().repository_signal_latency;
+        if let Err(e) = repository.insert_latencies(&latencies).await {
+            error!("Failed to record signal latency of run {}: {}", self.run_id, e);
+        }
+    }
+
+    /// Trend of every confirmation timeframe at each fresh event: MA(10) against MA(30) of the
+    /// latest bar in the timeframe's `tinkoff_indicators_{tf}` table closed by the event.
+    /// Timeframes without a calculated table are resampled from 1-minute candles up to the
+    /// event. A failed read leaves the trend unknown, which fails the confirmation rather than
+    /// letting the signal through unchecked.
+    async fn timeframe_trends(
+        &self,
+        instrument_uid: &str,
+        events: &[SignalEvent],
+        notifier: &Notifier,
+    ) -> HashMap<(i64, u32), i8> {
+        let mut trends = HashMap::new();
+        let (tables, resampled): (Vec<u32>, Vec<u32>) =
+            notifier.confirmation_timeframes().iter().partition(|&&minutes| {
+                Timeframe::from_minutes(minutes).is_some_and(|timeframe| self.timeframes.contains(&timeframe))
+            });
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let now = Utc::now().timestamp();
+        for event in events.iter().filter(|event| notifier.is_fresh(event, now)) {
+            for &minutes in &tables {
+                let Some(timeframe) = Timeframe::from_minutes(minutes) else {
+                    continue;
+                };
+                match indicator_repo.get_timeframe_indicator_before(instrument_uid, timeframe, event.time).await {
+                    // No bar calculated yet reads as flat, like too few resampled bars
+                    Ok(row) => {
+                        let trend = row.map_or(0, |row| ma_trend(row.ma_10, row.ma_30));
+                        trends.insert((event.time, minutes), trend);
+                    }
+                    Err(e) => error!("Failed to fetch the {} trend of {}: {}", timeframe.name(), instrument_uid, e),
+                }
+            }
+
+            let Some(&longest) = resampled.iter().max() else {
+                continue;
+            };
+            let limit = longest as usize * TREND_SLOW_PERIOD;
+            let candles = match indicator_repo.get_candles_before_time(instrument_uid, event.time, limit).await {
+                Ok(candles) => candles,
+                Err(e) => {
+                    error!("Failed to fetch trend candles of {}: {}", instrument_uid, e);
+                    continue;
+                }
+            };
+
+            let closes: Vec<(i64, f64)> = candles
+                .into_iter()
+                .rev()
+                .map(|raw| {
+                    let candle = DbCandleConverted::from(raw);
+                    (candle.time, candle.close_price)
+                })
+                .collect();
+            for &minutes in &resampled {
+                trends.insert((event.time, minutes), timeframe_trend(&closes, minutes));
+            }
+        }
+
+        trends
+    }
 
-            // Get the last processed time for this instrument
-            let mut last_processed_time = status_repo
-                .get_last_processed_time(instrument_uid)
-                .await?
-                .unwrap_or(0); // If no record exists, start from the beginning (time 0)
+    /// Drops cached API results that depend on this instrument's rows
+    fn invalidate_cached_queries(&self, instrument_uid: &str) {
+        if let Some(cache) = self.app_state.service::<QueryCache>() {
+            cache.invalidate(instrument_uid);
+        }
+    }
 
-            info!(
-                "Last processed time for instrument {}: {}",
-                instrument_uid, last_processed_time
-            );
+    /// Loads into `baseline` the seasonal volume models of the days of `candles` it lacks and
+    /// drops those of earlier days. Each day's model comes from the candles before that day:
+    /// today's is stored once a day, past days' are estimated for the batch. Synthetic sources
+    /// and failures get empty models (flat rolling normalization).
+    async fn load_volume_baseline(
+        &self,
+        source: &CandleSource,
+        candles: &[DbCandleConverted],
+        baseline: &mut VolumeBaseline,
+    ) {
+        let CandleSource::Instrument(uid) = source else {
+            return;
+        };
+        let (Some(first), Some(last)) = (candles.first(), candles.last()) else {
+            return;
+        };
+        let (from, until) = (day_start(first.time), day_start(last.time));
+        baseline.retain_from(from);
+        let days: Vec<i64> = (from..=until).step_by(86_400).filter(|&day| !baseline.has_day(day)).collect();
+        let today = day_start(Utc::now().timestamp());
+        let (past, current): (Vec<i64>, Vec<i64>) = days.into_iter().partition(|&day| day < today);
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        let lookback_days = self.app_state.settings.app_config.indicators_updater.volume_baseline_days;
+
+        if let (Some(&first_day), Some(&last_day)) = (past.first(), past.last()) {
+            let rows = match indicator_repo.compute_volume_baselines(uid, first_day, last_day, lookback_days).await {
+                Ok(rows) => rows,
+                Err(e) => {
+                    warn!("Failed to estimate volume baselines for {}: {}", uid, e);
+                    Vec::new()
+                }
+            };
+            baseline.insert_days(&past, &rows);
+        }
 
-            let mut processed_count = 0;
+        if current.is_empty() {
+            return;
+        }
+        let stored = match indicator_repo.get_volume_baseline(uid).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("Failed to load volume baseline for {}: {}", uid, e);
+                Vec::new()
+            }
+        };
+        if stored.iter().any(|row| row.as_of == today) {
+            baseline.insert_days(&current, &stored);
+            return;
+        }
+        if let Err(e) = indicator_repo.refresh_volume_baseline(uid, today, lookback_days).await {
+            warn!("Failed to refresh volume baseline for {}: {}", uid, e);
+        }
+        match indicator_repo.get_volume_baseline(uid).await {
+            Ok(rows) => baseline.insert_days(&current, &rows),
+            Err(e) => {
+                warn!("Failed to reload volume baseline for {}: {}", uid, e);
+                baseline.insert_days(&current, &[]);
+            }
+        }
+    }
 
-            loop {
-                // Fetch candles after the last processed time
-                let raw_candles = indicator_repo
-                    .get_candles_after_time(instrument_uid, last_processed_time, self.batch_size)
-                    .await?;
+    /// Daily OHLC of the day preceding `time`, used to seed pivot points.
+    /// Synthetic sources derive pivots from their own stream only.
+    async fn fetch_previous_day(&self, source: &CandleSource, time: i64) -> Option<DailyOhlc> {
+        let CandleSource::Instrument(uid) = source else {
+            return None;
+        };
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        match indicator_repo.get_previous_day_ohlc(uid, time).await {
+            Ok(day) => day.map(|day| DailyOhlc {
+                high: day.high,
+                low: day.low,
+                close: day.close,
+            }),
+            Err(e) => {
+                warn!("Failed to fetch previous day OHLC for {}: {}", uid, e);
+                None
+            }
+        }
+    }
 
-                if raw_candles.is_empty() {
-                    debug!(
-                        "No more candles found for instrument {} after time {}",
-                        instrument_uid, last_processed_time
-                    );
-                    break;
-                }
+    /// Closes of the complete hours before the hour of `time`, used to seed the hourly trend.
+    /// Synthetic sources build their hourly trend from their own stream only.
+    async fn fetch_hourly_closes(&self, source: &CandleSource, time: i64) -> Vec<f64> {
+        let CandleSource::Instrument(uid) = source else {
+            return Vec::new();
+        };
+
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+        match indicator_repo
+            .get_timeframe_candles_before(uid, Timeframe::Hour1, time - 3600, HOURLY_SEED_BARS)
+            .await
+        {
+            Ok(bars) => bars.into_iter().map(|raw| DbCandleConverted::from(raw).close_price).collect(),
+            Err(e) => {
+                warn!("Failed to fetch hourly closes for {}: {}", uid, e);
+                Vec::new()
+            }
+        }
+    }
 
-                let batch_count = raw_candles.len();
+    /// Indicator state saved after `last_processed_time` with the current settings. Any other
+    /// state (older, newer after a reset, or from different settings) is ignored and the
+    /// batch warms up on the historical window instead.
+    async fn load_state(&self, instrument_uid: &str, last_processed_time: i64) -> Option<IndicatorState> {
+        if last_processed_time == 0 {
+            return None;
+        }
+        let state_repo = &self.app_state.postgres_service().repository_indicator_state;
+        let saved = match state_repo.get_state(instrument_uid).await {
+            Ok(saved) => saved?,
+            Err(e) => {
+                warn!("Failed to load indicator state of {}: {}", instrument_uid, e);
+                return None;
+            }
+        };
+        if saved.last_time != last_processed_time || saved.fingerprint != self.state_fingerprint {
+            debug!("Saved indicator state of {} doesn't match, warming up", instrument_uid);
+            return None;
+        }
 
-                // Update the latest time for this batch
-                let latest_time = if let Some(last_candle) = raw_candles.last() {
-                    last_candle.time
-                } else {
-                    continue; // Should never happen as we just checked if empty, but just in case
-                };
+        match IndicatorState::decode(&saved.state) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                warn!("Failed to parse indicator state of {}: {}", instrument_uid, e);
+                None
+            }
+        }
+    }
 
-                debug!("Latest time in current batch: {}", latest_time);
-
-                // Convert raw candles to a more convenient format
-                let converted_candles: Vec<DbCandleConverted> =
-                    raw_candles.into_iter().map(|raw| raw.into()).collect();
-
-                let indicators = {
-                    // Calculate indicators for the batch
-                    let window_data = if processed_count == 0 && last_processed_time > 0 {
-                        // We need historical data for the first batch to calculate indicators correctly
-                        self.fetch_historical_window(
-                            indicator_repo,
-                            instrument_uid,
-                            last_processed_time,
-                        )
-                        .await?
-                    } else {
-                        Vec::new()
-                    };
+    async fn save_state(&self, instrument_uid: &str, last_time: i64, state: &IndicatorState) {
+        let state_repo = &self.app_state.postgres_service().repository_indicator_state;
+        let encoded = match state.encode() {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                error!("Failed to serialize indicator state of {}: {}", instrument_uid, e);
+                return;
+            }
+        };
+        if let Err(e) = state_repo.save_state(instrument_uid, last_time, &self.state_fingerprint, &encoded).await {
+            error!("Failed to save indicator state of {}: {}", instrument_uid, e);
+        }
+    }
 
-                    // Get window size before moving window_data
-                    let window_end_idx = if !window_data.is_empty() {
-                        window_data.len()
-                    } else {
-                        0
-                    };
+    /// Fetches the next batch of candles for an instrument or a synthetic spread
+    async fn fetch_candles_after(
+        &self,
+        source: &CandleSource,
+        last_processed_time: i64,
+        batch_size: usize,
+    ) -> Result<CandleBatch, Box<dyn std::error::Error>> {
+        let indicator_repo = &self.app_state.clickhouse_service().repository_indicator;
+
+        let mut legs = Vec::new();
+        let mut fetched = 0;
+        let mut duplicate_times = HashSet::new();
+        for uid in source.legs() {
+            let raw_candles = indicator_repo
+                .get_candles_after_time(uid, last_processed_time, batch_size)
+                .await?;
+            fetched = fetched.max(raw_candles.len());
+
+            // Convert raw candles to a more convenient format
+            let mut candles: Vec<DbCandleConverted> =
+                raw_candles.into_iter().map(|raw| raw.into()).collect();
+            if let Some(until) = self.until() {
+                candles.retain(|candle| candle.time <= until);
+            }
 
-                    // Combine historical window with new data if needed
-                    let calculation_data = if !window_data.is_empty() {
-                        let mut combined = window_data;
-                        combined.extend(converted_candles.iter().cloned());
-                        combined
-                    } else {
-                        converted_candles.clone()
-                    };
-                    
-                    self.calculate_indicators(&calculation_data, window_end_idx)
-                };
-                
-                // Insert calculated indicators
-                if !indicators.is_empty() {
-                    match indicator_repo.insert_indicators(indicators).await {
-                        Ok(inserted) => {
-                            processed_count += inserted as usize;
-                            debug!("Inserted {} indicators for {}", inserted, instrument_uid);
-                        }
-                        Err(e) => {
-                            // Just log the error and continue with the next batch
-                            error!("Failed to insert indicators for {}: {}", instrument_uid, e);
-                        }
-                    }
-                }
-                
-                // Update last processed time
-                if let Err(e) = status_repo.update_last_processed_time(instrument_uid, latest_time).await {
-                    error!("Failed to update last processed time for {}: {}", instrument_uid, e);
-                }
-                
-                // Update last processed time for next iteration
-                last_processed_time = latest_time;
-                
-                // If we received fewer candles than batch size, we're done with this instrument
-                if batch_count < self.batch_size {
-                    break;
-                }
-                
-                // Very short pause between batches
-                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+            let duplicates = quality::dedup_candles(&mut candles);
+            if !duplicates.is_empty() {
+                debug!("Removed duplicate candles for {} at {} times", uid, duplicates.len());
+                duplicate_times.extend(duplicates);
             }
-            
-            total_processed += processed_count;
-            
-            info!(
-                "Completed processing for instrument {}/{}: {}, processed {} candles",
-                index + 1, instrument_uids.len(), instrument_uid, processed_count
-            );
+
+            legs.push(candles);
         }
-        
-        info!(
-            "All instrument processing completed. Total processed: {} candles",
-            total_processed
-        );
 
-        Ok(total_processed)
+        // Synthetic series are only complete up to the earliest of the legs' last candles
+        let latest_time = legs
+            .iter()
+            .map(|candles| candles.last().map(|candle| candle.time))
+            .collect::<Option<Vec<i64>>>()
+            .and_then(|times| times.into_iter().min());
+
+        let candles = source
+            .combine(legs)
+            .into_iter()
+            .filter(|candle| latest_time.is_some_and(|time| candle.time <= time))
+            .collect();
+
+        Ok(CandleBatch { candles, fetched, latest_time, duplicate_times })
     }
     
     /// Checks if the tinkoff_indicators_status table is empty
     async fn is_status_table_empty(&self) -> Result<bool, Box<dyn std::error::Error>> {
-        let pool = self.app_state.postgres_service.connection.get_pool();
-        
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM market_data.tinkoff_indicators_status")
-            .fetch_one(pool)
-            .await?;
-        
-        Ok(count == 0)
+        let status_repo = &self.app_state.postgres_service().repository_indicator_status;
+
+        Ok(status_repo.count().await? == 0)
     }
-    
+
     /// Fetches historical data for calculating indicators
     async fn fetch_historical_window(
         &self,
-        repo: &Arc<IndicatorRepository>,
+        source: &CandleSource,
+        current_time: i64,
+    ) -> Result<Vec<DbCandleConverted>, Box<dyn std::error::Error>> {
+        let repo = &self.app_state.clickhouse_service().repository_indicator;
+
+        let mut legs = Vec::new();
+        for uid in source.legs() {
+            let mut candles = self.fetch_instrument_window(repo, uid, current_time).await?;
+            quality::dedup_candles(&mut candles);
+            legs.push(candles);
+        }
+
+        Ok(source.combine(legs))
+    }
+    
+    /// Fetches historical candles of a single real instrument
+    async fn fetch_instrument_window(
+        &self,
+        repo: &Arc<dyn TraitIndicatorRepository + Send + Sync>,
         instrument_uid: &str,
         current_time: i64,
     ) -> Result<Vec<DbCandleConverted>, Box<dyn std::error::Error>> {
@@ -230,29 +1846,10 @@ impl IndicatorCalculator {
             window_size, instrument_uid, current_time
         );
         
-        // Query to get the last N candles before the current time
-        let query = format!(
-            "SELECT 
-                instrument_uid,
-                time,
-                open_units,
-                open_nano,
-                high_units,
-                high_nano,
-                low_units,
-                low_nano,
-                close_units,
-                close_nano,
-                volume
-            FROM market_data.tinkoff_candles_1min
-            WHERE instrument_uid = '{}' AND time <= {}
-            ORDER BY time DESC
-            LIMIT {}",
-            instrument_uid, current_time, window_size
-        );
-        
-        let client = repo.connection.get_client();
-        let result = client.query(&query).fetch_all::<DbCandleRaw>().await?;
+        // Get the last N candles before the current time
+        let result = repo
+            .get_candles_before_time(instrument_uid, current_time, window_size)
+            .await?;
         
         debug!(
             "Retrieved {} historical candles for instrument {} before time {}",
@@ -269,22 +1866,200 @@ impl IndicatorCalculator {
         Ok(converted)
     }
 
-    /// Calculate technical indicators for candles
+    /// Target variables of candle `i` for every configured horizon, 0 where the future
+    /// candle isn't in `candles` yet
+    fn calculate_labels(
+        &self,
+        candles: &[DbCandleConverted],
+        i: usize,
+        triple_barrier: Option<&TripleBarrier>,
+    ) -> DbLabelUpdate {
+        let (target_change, target_signal): (Vec<f64>, Vec<i8>) = self
+            .targets
+            .iter()
+            .map(|target| match candles.get(i + target.horizon) {
+                Some(future) if target.horizon > 0 => calculate_future_price_change(
+                    candles[i].close_price,
+                    future.close_price,
+                    target.threshold_pct,
+                ),
+                _ => (0.0, 0),
+            })
+            .unzip();
+
+        // Legacy 15-candle target mirrors the configured 15 horizon
+        let (price_change_15m, signal_15m) = self
+            .targets
+            .iter()
+            .position(|target| target.horizon == 15)
+            .map_or((0.0, 0), |idx| (target_change[idx], target_signal[idx]));
+
+        let barrier = triple_barrier.map(|tb| tb.label(candles, i)).unwrap_or_default();
+        let (fractal_high, fractal_low) = calculate_fractals(candles, i);
+
+        DbLabelUpdate {
+            instrument_uid: candles[i].instrument_uid.clone(),
+            time: candles[i].time,
+            price_change_15m,
+            signal_15m,
+            target_change,
+            target_signal,
+            tb_label: barrier.label,
+            tb_hit_time: barrier.hit_time,
+            fractal_high,
+            fractal_low,
+        }
+    }
+
+    /// Longest number of following candles any label column looks at
+    fn label_horizon(&self) -> usize {
+        let triple_barrier = &self.app_state.settings.app_config.indicators_updater.triple_barrier;
+        self.targets
+            .iter()
+            .map(|target| target.horizon)
+            .chain(triple_barrier.enabled.then_some(triple_barrier.max_horizon))
+            .chain([FRACTAL_SIDE])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Triple-barrier labeler over `candles` if enabled in config
+    fn triple_barrier<'a>(&'a self, candles: &[DbCandleConverted]) -> Option<TripleBarrier<'a>> {
+        let config = &self.app_state.settings.app_config.indicators_updater.triple_barrier;
+        config.enabled.then(|| TripleBarrier::new(config, candles))
+    }
+
+    /// Recomputes labels of the historical window rows that the previous run wrote without
+    /// their full horizon, now that the following candles are loaded
+    fn backfill_labels(&self, candles: &[DbCandleConverted], window_end_idx: usize) -> Vec<DbLabelUpdate> {
+        let triple_barrier = self.triple_barrier(candles);
+        let max_horizon = self.label_horizon();
+
+        (window_end_idx.saturating_sub(max_horizon)..window_end_idx)
+            .map(|i| self.calculate_labels(candles, i, triple_barrier.as_ref()))
+            .collect()
+    }
+
+    /// Adds the values of the custom indicator plugins to the rows of the tail of `candles`
+    fn apply_plugins(&self, indicators: &mut [DbIndicator], candles: &[DbCandleConverted]) {
+        if let Some(plugins) = self.app_state.service::<IndicatorPlugins>() {
+            plugins.apply(indicators, candles);
+        }
+    }
+
+    /// Calculate technical indicators for candles. With gap handling on, breaks longer than
+    /// `gaps.max_gap_minutes` split the series into sessions whose state starts over (or
+    /// keeps the last `gaps.decay_candles` candles before the break), and the first row of
+    /// each session gets `session_break`. A `resume` state continues the series without
+    /// warm-up; the state after the last candle is returned for the next batch.
     fn calculate_indicators(
         &self,
         candles: &[DbCandleConverted],
         window_end_idx: usize,
-    ) -> Vec<DbIndicator> {
-        if candles.len() <= self.window_size {
+        context: &CalculationContext,
+        resume: Option<IndicatorState>,
+    ) -> (Vec<DbIndicator>, Option<IndicatorState>) {
+        let enough = match resume {
+            Some(_) => candles.len() > window_end_idx,
+            None => candles.len() > self.window_size,
+        };
+        if !enough {
             debug!("Not enough candles for indicator calculation");
-            return Vec::new();
+            return (Vec::new(), None);
         }
-        
-        let mut result = Vec::with_capacity(candles.len() - window_end_idx);
+
+        let max_gap_seconds = self.gaps.max_gap_minutes as i64 * 60;
+        let breaks: Vec<usize> = match context.session_gaps && max_gap_seconds > 0 {
+            true => (1..candles.len())
+                .filter(|&i| candles[i].time - candles[i - 1].time > max_gap_seconds)
+                .collect(),
+            false => Vec::new(),
+        };
+        let keep = match self.gaps.mode {
+            GapMode::Flag | GapMode::Reset => 0,
+            GapMode::Decay => self.gaps.decay_candles,
+        };
+
+        let (mut result, state) = match self.gaps.mode {
+            GapMode::Flag => self.calculate_segment(
+                candles,
+                window_end_idx,
+                context,
+                context.previous_day,
+                &context.hourly_closes,
+                resume,
+            ),
+            GapMode::Reset | GapMode::Decay => {
+                let mut rows = Vec::with_capacity(candles.len() - window_end_idx);
+                // Rows are written for candles[out_start..], state is built from candles[in_start..]
+                let (mut in_start, mut out_start) = (0, window_end_idx);
+                let mut previous_day = context.previous_day;
+                let mut hourly_closes = context.hourly_closes.as_slice();
+                // The resumed state already covers breaks in the historical window
+                let mut resume = resume;
+                for &b in &breaks {
+                    if b > out_start {
+                        let (segment, _) = self.calculate_segment(
+                            &candles[in_start..b],
+                            out_start - in_start,
+                            context,
+                            previous_day,
+                            hourly_closes,
+                            resume.take(),
+                        );
+                        rows.extend(segment);
+                        out_start = b;
+                    }
+                    if b >= window_end_idx {
+                        resume = None;
+                    }
+                    // Pivots come from the last day before the break, the hourly trend starts over
+                    in_start = b.saturating_sub(keep);
+                    previous_day = last_day_before(candles, candles[b].time).or(previous_day);
+                    hourly_closes = &[];
+                }
+                let (segment, state) = self.calculate_segment(
+                    &candles[in_start..],
+                    out_start - in_start,
+                    context,
+                    previous_day,
+                    hourly_closes,
+                    resume,
+                );
+                rows.extend(segment);
+                (rows, state)
+            }
+        };
+
+        let break_times: HashSet<i64> = breaks.iter().map(|&b| candles[b].time).collect();
+        for row in &mut result {
+            if break_times.contains(&row.time) {
+                row.session_break = 1;
+            }
+        }
+
+        (result, Some(state))
+    }
+
+    /// Builds the indicator state by running every tracker over `candles[..window_end_idx]`
+    fn warm_up(
+        &self,
+        candles: &[DbCandleConverted],
+        window_end_idx: usize,
+        context: &CalculationContext,
+        previous_day: Option<DailyOhlc>,
+        hourly_closes: &[f64],
+    ) -> IndicatorState {
         // Windows for moving averages and RSI calculation
         let mut prices_window: VecDeque<f64> = VecDeque::with_capacity(self.window_size);
         let mut rsi_gains: VecDeque<f64> = VecDeque::with_capacity(14);
         let mut rsi_losses: VecDeque<f64> = VecDeque::with_capacity(14);
+        let mut stoch_rsi = StochRsi::new(14, 3, 3);
+        let mut adaptive_thresholds =
+            AdaptiveThresholds::new(&self.app_state.settings.app_config.indicators_updater.adaptive_thresholds);
+        let mut sma_10 = Sma::new(10);
+        let mut sma_30 = Sma::new(30);
+        let (mut prev_ma_10, mut prev_ma_30) = (0.0, 0.0);
         
         // Pre-fill windows with data for calculation
         for i in 0..window_end_idx {
@@ -303,31 +2078,262 @@ impl IndicatorCalculator {
                     rsi_gains.pop_front();
                     rsi_losses.pop_front();
                 }
+
+                // Warm up the StochRSI window once RSI is defined
+                if rsi_gains.len() >= 14 {
+                    stoch_rsi.update(calculate_rsi(&rsi_gains, &rsi_losses));
+                }
             }
             
             prices_window.push_back(candles[i].close_price);
             if prices_window.len() > self.window_size {
                 prices_window.pop_front();
             }
+
+            // The last moving averages are kept for crossing detection
+            prev_ma_10 = sma_10.update(candles[i].close_price);
+            prev_ma_30 = sma_30.update(candles[i].close_price);
+
+            // Warm up adaptive thresholds on the historical window
+            if rsi_gains.len() >= 14 {
+                adaptive_thresholds.update(
+                    calculate_rsi(&rsi_gains, &rsi_losses),
+                    candles[i].volume as f64,
+                    prev_ma_10 - prev_ma_30,
+                );
+            }
         }
         
-        // Save previous ma_10 and ma_30 for crossing detection
-        let mut prev_ma_10 = calculate_sma(prices_window.iter().cloned().collect::<Vec<f64>>(), 10);
-        let mut prev_ma_30 = calculate_sma(prices_window.iter().cloned().collect::<Vec<f64>>(), 30);
-        
         // Calculate volume standard deviation for anomaly detection
-        let mut volume_stats = VolumeStatistics::new(50);
-        for i in 0..window_end_idx {
-            volume_stats.add(candles[i].volume as f64);
+        let mut volume_stats = RollingStats::new(50);
+        for candle in &candles[..window_end_idx] {
+            volume_stats.add(candle.volume as f64);
         }
-        
+
+        // Rolling mean/stddev of close for the close z-score
+        let mut close_stats = RollingStats::new(50);
+        for candle in &candles[..window_end_idx] {
+            close_stats.add(candle.close_price);
+        }
+
+        // Same over the warm-up window for the spread z-score
+        let mut spread_stats = RollingStats::new(self.window_size);
+        for candle in &candles[..window_end_idx] {
+            spread_stats.add(candle.close_price);
+        }
+
+        // Warm up the regime classifier on the historical window
+        let mut regime_classifier = RegimeClassifier::new();
+        for candle in &candles[..window_end_idx] {
+            regime_classifier.update(candle.close_price);
+        }
+
+        // Seed Heikin-Ashi state from the historical window so resumed batches continue the recursion
+        let mut heikin_ashi = HeikinAshi::new();
+        for candle in &candles[..window_end_idx] {
+            heikin_ashi.update(candle);
+        }
+
+        // Track daily OHLC for pivot points, seeded with the day before the series starts
+        let mut pivot_tracker = PivotTracker::new(previous_day);
+        for candle in &candles[..window_end_idx] {
+            pivot_tracker.update(candle);
+        }
+
+        // Hourly EMA slope and RSI, seeded with the hours before the series starts
+        let mut hourly_trend = HourlyTrend::new(hourly_closes);
+        for candle in &candles[..window_end_idx] {
+            hourly_trend.update(candle);
+        }
+
+        // Rolling highs/lows for support/resistance distances
+        let mut extrema_short = RollingExtrema::new(SUPPORT_RESISTANCE_SHORT);
+        let mut extrema_long = RollingExtrema::new(SUPPORT_RESISTANCE_LONG);
+        for candle in &candles[..window_end_idx] {
+            extrema_short.update(candle.high_price, candle.low_price);
+            extrema_long.update(candle.high_price, candle.low_price);
+        }
+
+        // Triple-smoothed EMA for TRIX
+        let mut trix = Trix::new(15, 9);
+        for candle in &candles[..window_end_idx] {
+            trix.update(candle.close_price);
+        }
+
+        // Buying pressure / true range sums for the Ultimate Oscillator
+        let mut ultimate_oscillator = UltimateOscillator::new();
+        for candle in &candles[..window_end_idx] {
+            ultimate_oscillator.update(candle);
+        }
+
+        // Stddev of 1-minute log returns for realized volatility
+        let mut volatility = RealizedVolatility::new();
+        for candle in &candles[..window_end_idx] {
+            volatility.update(candle.close_price);
+        }
+
+        // Weighted and Hull moving averages
+        let mut hull = HullMovingAverage::new(self.hma_period);
+        for candle in &candles[..window_end_idx] {
+            hull.update(candle.close_price);
+        }
+
+        // Linear regression of closes over the last 30 candles
+        let mut regression = RollingRegression::new(LINREG_PERIOD);
+        for candle in &candles[..window_end_idx] {
+            regression.update(candle.close_price);
+        }
+
+        // Returns of the autocorrelation/entropy window
+        let mut return_structure = ReturnStructure::new(RETURN_STRUCTURE_WINDOW);
+        for candle in &candles[..window_end_idx] {
+            return_structure.update(candle.close_price);
+        }
+
+        // Log returns of the Hurst window
+        let mut hurst = RollingHurst::new(self.hurst.window, self.hurst.method);
+        for candle in &candles[..window_end_idx] {
+            hurst.update(candle.close_price);
+        }
+
+        // Vortex movement and true range sums
+        let mut vortex = Vortex::new(VORTEX_PERIOD);
+        for candle in &candles[..window_end_idx] {
+            vortex.update(candle);
+        }
+
+        // EMA(13) of closes for Elder-Ray
+        let mut elder_ema = Ema::new(ELDER_RAY_PERIOD);
+        for candle in &candles[..window_end_idx] {
+            elder_ema.update(candle.close_price);
+        }
+
+        // Volume-weighted moving average
+        let mut vwma = Vwma::new(VWMA_PERIOD);
+        for candle in &candles[..window_end_idx] {
+            vwma.update(candle);
+        }
+
+        // Count consecutive stale prices, continuing runs that started in the historical window
+        let mut stale_tracker = StalePriceTracker::new();
+        for candle in &candles[..window_end_idx] {
+            stale_tracker.update(candle);
+        }
+
+        // Track data quality of the rolling window
+        let mut quality_tracker = QualityTracker::new(self.window_size);
+        for candle in &candles[..window_end_idx] {
+            quality_tracker.update(candle, context.duplicate_times.contains(&candle.time));
+        }
+
+        // Warm up the changepoint detector on the historical window
+        let mut changepoint_detector = ChangepointDetector::new();
+        for candle in &candles[..window_end_idx] {
+            changepoint_detector.update(candle.time, candle.close_price);
+        }
+
+        IndicatorState {
+            prices_window,
+            rsi_gains,
+            rsi_losses,
+            stoch_rsi,
+            adaptive_thresholds,
+            sma_10,
+            sma_30,
+            prev_ma_10,
+            prev_ma_30,
+            volume_stats,
+            close_stats,
+            spread_stats,
+            regime_classifier,
+            heikin_ashi,
+            pivot_tracker,
+            hourly_trend,
+            extrema_short,
+            extrema_long,
+            trix,
+            ultimate_oscillator,
+            volatility,
+            hull,
+            regression,
+            return_structure,
+            hurst,
+            vortex,
+            elder_ema,
+            vwma,
+            stale_tracker,
+            quality_tracker,
+            changepoint_detector,
+            prev_candle: window_end_idx.checked_sub(1).map(|i| candles[i].clone()),
+        }
+    }
+
+    /// Calculates rows of `candles[window_end_idx..]` over one continuous series. The state
+    /// is continued from `resume` when given, otherwise warmed up on the candles before
+    /// `window_end_idx`. Returns the rows and the state after the last candle.
+    fn calculate_segment(
+        &self,
+        candles: &[DbCandleConverted],
+        window_end_idx: usize,
+        context: &CalculationContext,
+        previous_day: Option<DailyOhlc>,
+        hourly_closes: &[f64],
+        resume: Option<IndicatorState>,
+    ) -> (Vec<DbIndicator>, IndicatorState) {
+        let mut result = Vec::with_capacity(candles.len() - window_end_idx);
+        let IndicatorState {
+            mut prices_window,
+            mut rsi_gains,
+            mut rsi_losses,
+            mut stoch_rsi,
+            mut adaptive_thresholds,
+            mut sma_10,
+            mut sma_30,
+            mut prev_ma_10,
+            mut prev_ma_30,
+            mut volume_stats,
+            mut close_stats,
+            mut spread_stats,
+            mut regime_classifier,
+            mut heikin_ashi,
+            mut pivot_tracker,
+            mut hourly_trend,
+            mut extrema_short,
+            mut extrema_long,
+            mut trix,
+            mut ultimate_oscillator,
+            mut volatility,
+            mut hull,
+            mut regression,
+            mut return_structure,
+            mut hurst,
+            mut vortex,
+            mut elder_ema,
+            mut vwma,
+            mut stale_tracker,
+            mut quality_tracker,
+            mut changepoint_detector,
+            prev_candle,
+        } = match resume {
+            Some(state) => state,
+            None => self.warm_up(candles, window_end_idx, context, previous_day, hourly_closes),
+        };
+
+        // ATR-scaled triple-barrier labeler over the whole series
+        let triple_barrier = self.triple_barrier(candles);
+
         // Main indicator calculation for each candle
         for i in window_end_idx..candles.len() {
             let candle = &candles[i];
+            // The state carries the previous candle into the first candle of a resumed series
+            let prev = match i.checked_sub(1) {
+                Some(prev) => Some(&candles[prev]),
+                None => prev_candle.as_ref(),
+            };
             
             // RSI calculation
-            if i > 0 {
-                let price_change = candle.close_price - candles[i - 1].close_price;
+            if let Some(prev) = prev {
+                let price_change = candle.close_price - prev.close_price;
                 if price_change >= 0.0 {
                     rsi_gains.push_back(price_change);
                     rsi_losses.push_back(0.0);
@@ -351,14 +2357,101 @@ impl IndicatorCalculator {
             // Update volume statistics
             volume_stats.add(candle.volume as f64);
 
+            // Update close statistics
+            close_stats.add(candle.close_price);
+            spread_stats.add(candle.close_price);
+
             // Calculate moving averages
-            let prices_vec = prices_window.iter().cloned().collect::<Vec<f64>>();
-            let ma_10 = calculate_sma(prices_vec.clone(), 10);
-            let ma_30 = calculate_sma(prices_vec, 30);
+            let ma_10 = sma_10.update(candle.close_price);
+            let ma_30 = sma_30.update(candle.close_price);
 
             // Calculate RSI
             let rsi_14 = calculate_rsi(&rsi_gains, &rsi_losses);
 
+            // Calculate StochRSI with smoothed %K/%D
+            let (stoch_rsi_value, stoch_rsi_k, stoch_rsi_d) = if rsi_gains.len() >= 14 {
+                stoch_rsi.update(rsi_14)
+            } else {
+                (50.0, 50.0, 50.0)
+            };
+
+            // Calculate rate of change and momentum for the configured lags
+            let (roc, momentum) = calculate_roc(&prices_window, &self.roc_lags);
+
+            // Backward log returns at fixed horizons
+            let log_return_1m = calculate_log_return(&prices_window, 1);
+            let log_return_5m = calculate_log_return(&prices_window, 5);
+            let log_return_15m = calculate_log_return(&prices_window, 15);
+            let log_return_60m = calculate_log_return(&prices_window, 60);
+
+            // Classify market regime
+            let regime = regime_classifier.update(candle.close_price);
+
+            // Heikin-Ashi candle
+            let (ha_open, ha_high, ha_low, ha_close) = heikin_ashi.update(candle);
+
+            // Data quality of the inputs behind this row
+            let quality_score =
+                quality_tracker.update(candle, context.duplicate_times.contains(&candle.time));
+
+            // TRIX and its signal line cross
+            let (trix_value, trix_signal, trix_cross) = trix.update(candle.close_price);
+
+            // Ultimate Oscillator (7/14/28)
+            let ultimate_osc = ultimate_oscillator.update(candle);
+
+            // Regression slope as % of the mean price per candle, and its R²
+            let (slope, linreg_r2_30) = regression.update(candle.close_price);
+            let linreg_slope_30 = match regression.mean() {
+                mean if mean > 0.0 => slope / mean * 100.0,
+                _ => 0.0,
+            };
+
+            // Lag-1 autocorrelation and sign entropy of returns: choppy vs trending
+            let (return_autocorr_60, sign_entropy_60) = return_structure.update(candle.close_price);
+
+            // Hurst exponent: >0.5 trending, <0.5 mean-reverting
+            let hurst_value = hurst.update(candle.close_price);
+
+            // Vortex VI+/VI- and their cross
+            let (vortex_pos, vortex_neg, vortex_cross) = vortex.update(candle);
+
+            // Elder-Ray: extremes relative to EMA(13)
+            let elder_ema_value = elder_ema.update(candle.close_price);
+            let bull_power = candle.high_price - elder_ema_value;
+            let bear_power = candle.low_price - elder_ema_value;
+
+            // Price and time gap to the previous candle
+            let (gap_pct, gap_minutes) = match prev {
+                Some(prev) => calculate_gap(prev, candle),
+                None => (0.0, 0),
+            };
+
+            // High-low range and body/wick proportions
+            let [range_pct, body_pct, upper_wick_pct, lower_wick_pct] = calculate_candle_shape(candle);
+
+            // Realized volatility over 30/60/240 candles
+            let [volatility_30, volatility_60, volatility_240] = volatility.update(candle.close_price);
+
+            // WMA, Hull MA and its slope
+            let (wma, hma, hma_slope) = hull.update(candle.close_price);
+
+            // Stale price flag and run length
+            let (stale_price, stale_count) = stale_tracker.update(candle);
+
+            // Support/resistance from rolling extremes
+            let (high_60, low_60) = extrema_short.update(candle.high_price, candle.low_price);
+            let (high_240, low_240) = extrema_long.update(candle.high_price, candle.low_price);
+
+            // Pivot points of the previous day
+            let pivots = pivot_tracker.update(candle);
+
+            // Trend of the completed hours
+            let hourly = hourly_trend.update(candle);
+
+            // Detect changepoints in price and volatility
+            let changepoints = changepoint_detector.update(candle.time, candle.close_price);
+
             // Calculate derived metrics
             let ma_diff = ma_10 - ma_30;
 
@@ -379,16 +2472,43 @@ impl IndicatorCalculator {
             };
 
             // Check volume anomaly
-            let volume_norm = volume_stats.normalize(candle.volume as f64);
-            let volume_anomaly = if volume_norm > 2.0 { 1 } else { 0 };
-
-            // Calculate target variable (will be updated on next pass)
-            let (price_change_15m, signal_15m) = if i + 15 < candles.len() {
-                calculate_future_price_change(candle.close_price, candles[i + 15].close_price)
-            } else {
-                (0.0, 0)
+            // Prefer the seasonal baseline so the open/close volume spikes aren't always anomalies
+            let seasonal_norm = match context.seasonal_volume_norm {
+                true => context.volume_baseline.normalize(candle.time, candle.volume as f64),
+                false => None,
+            };
+            let volume_norm =
+                seasonal_norm.unwrap_or_else(|| volume_stats.normalize(candle.volume as f64));
+
+            // VWMA(20) and volume relative to the usual volume at this time of day
+            let vwma_20 = vwma.update(candle);
+            let rel_volume = context
+                .volume_baseline
+                .relative_volume(candle.time, candle.volume as f64)
+                .unwrap_or(0.0);
+            // Binary flag kept for existing consumers, the score grades how far above the threshold
+            let volume_anomaly = if volume_norm > self.volume_anomaly_sigma { 1 } else { 0 };
+            let volume_anomaly_score = match volume_anomaly {
+                1 => volume_norm.min(VOLUME_ANOMALY_SCORE_CAP),
+                _ => 0.0,
             };
 
+            // Adaptive (per-instrument EWMA) versions of the zone/anomaly/cross flags
+            let adaptive_flags = adaptive_thresholds.update(rsi_14, candle.volume as f64, ma_diff);
+
+            // Calculate target variables (backfilled by the next run once the horizon elapses)
+            let DbLabelUpdate {
+                price_change_15m,
+                signal_15m,
+                target_change,
+                target_signal,
+                tb_label,
+                tb_hit_time,
+                fractal_high,
+                fractal_low,
+                ..
+            } = self.calculate_labels(candles, i, triple_barrier.as_ref());
+
             // Get time features
             let dt = DateTime::<Utc>::from_timestamp(candle.time, 0).unwrap_or_default();
             let hour_of_day = dt.hour() as i8;
@@ -402,6 +2522,13 @@ impl IndicatorCalculator {
                 Weekday::Sun => 7,
             };
 
+            // Cyclical encodings: 23:59 is next to 00:00 and Sunday next to Monday
+            let minute_of_day = (candle.time.rem_euclid(86_400) / 60) as i32;
+            let day_angle = std::f64::consts::TAU * minute_of_day as f64 / 1440.0;
+            let week_angle = std::f64::consts::TAU * (day_of_week - 1) as f64 / 7.0;
+            let session_phase = self.calendar.phase(candle.time) as i8;
+            let minutes_since_session_open = self.calendar.minutes_since_open(candle.time);
+
             // Create indicator record
             let indicator = DbIndicator {
                 instrument_uid: candle.instrument_uid.clone(),
@@ -419,150 +2546,447 @@ impl IndicatorCalculator {
                 ma_cross,
                 rsi_zone,
                 volume_anomaly,
+                volume_anomaly_score,
                 hour_of_day,
                 day_of_week,
+                hour_sin: day_angle.sin(),
+                hour_cos: day_angle.cos(),
+                dow_sin: week_angle.sin(),
+                dow_cos: week_angle.cos(),
+                minutes_since_session_open,
+                session_phase,
                 price_change_15m,
                 signal_15m,
+                target_change,
+                target_signal,
+                tb_label,
+                tb_hit_time,
+                fractal_high,
+                fractal_low,
+                spread_zscore: match context.spread {
+                    true => spread_stats.normalize(candle.close_price),
+                    false => 0.0,
+                },
+                roc,
+                momentum,
+                regime,
+                stoch_rsi: stoch_rsi_value,
+                stoch_rsi_k,
+                stoch_rsi_d,
+                ha_open,
+                ha_high,
+                ha_low,
+                ha_close,
+                pivot_p: pivots.p,
+                pivot_r1: pivots.r1,
+                pivot_r2: pivots.r2,
+                pivot_r3: pivots.r3,
+                pivot_s1: pivots.s1,
+                pivot_s2: pivots.s2,
+                pivot_s3: pivots.s3,
+                pivot_dist_pct: distance_pct(candle.close_price, pivots.p),
+                pivot_r1_dist_pct: distance_pct(candle.close_price, pivots.r1),
+                pivot_s1_dist_pct: distance_pct(candle.close_price, pivots.s1),
+                rsi_zone_adaptive: adaptive_flags.rsi_zone,
+                volume_anomaly_adaptive: adaptive_flags.volume_anomaly,
+                ma_cross_adaptive: adaptive_flags.ma_cross,
+                quality_score,
+                high_60,
+                low_60,
+                high_240,
+                low_240,
+                dist_high_60_pct: distance_pct(candle.close_price, high_60),
+                dist_low_60_pct: distance_pct(candle.close_price, low_60),
+                dist_high_240_pct: distance_pct(candle.close_price, high_240),
+                dist_low_240_pct: distance_pct(candle.close_price, low_240),
+                stale_price,
+                stale_count,
+                trix: trix_value,
+                trix_signal,
+                trix_cross,
+                ultimate_osc,
+                bull_power,
+                bear_power,
+                vortex_pos,
+                vortex_neg,
+                vortex_cross,
+                linreg_slope_30,
+                linreg_r2_30,
+                hurst: hurst_value,
+                htf_1h_ema_slope: hourly.ema_slope,
+                htf_1h_rsi: hourly.rsi,
+                session_break: 0, // set per session by calculate_indicators
+                return_autocorr_60,
+                sign_entropy_60,
+                wma,
+                vwma_20,
+                rel_volume,
+                hma,
+                hma_slope,
+                close_zscore_50: close_stats.normalize(candle.close_price),
+                volatility_30,
+                volatility_60,
+                volatility_240,
+                gap_pct,
+                gap_minutes,
+                range_pct,
+                body_pct,
+                upper_wick_pct,
+                lower_wick_pct,
+                log_return_1m,
+                log_return_5m,
+                log_return_15m,
+                log_return_60m,
+                price_changepoint: changepoints.price_changepoint,
+                vol_changepoint: changepoints.vol_changepoint,
+                minutes_since_price_cp: changepoints.minutes_since_price_cp,
+                minutes_since_vol_cp: changepoints.minutes_since_vol_cp,
+                plugin_values: Vec::new(), // filled by IndicatorPlugins::apply
+                // Stamped by the repository on write
+                insert_time: 0,
+                labels_time: 0,
             };
 
             result.push(indicator);
         }
 
-        result
+        let state = IndicatorState {
+            prices_window,
+            rsi_gains,
+            rsi_losses,
+            stoch_rsi,
+            adaptive_thresholds,
+            sma_10,
+            sma_30,
+            prev_ma_10,
+            prev_ma_30,
+            volume_stats,
+            close_stats,
+            spread_stats,
+            regime_classifier,
+            heikin_ashi,
+            pivot_tracker,
+            hourly_trend,
+            extrema_short,
+            extrema_long,
+            trix,
+            ultimate_oscillator,
+            volatility,
+            hull,
+            regression,
+            return_structure,
+            hurst,
+            vortex,
+            elder_ema,
+            vwma,
+            stale_tracker,
+            quality_tracker,
+            changepoint_detector,
+            prev_candle: candles.last().cloned().or(prev_candle),
+        };
+        (result, state)
     }
 }
 
-/// Helper structure for volume statistics
-struct VolumeStatistics {
-    volumes: VecDeque<f64>,
-    window_size: usize,
-    sum: f64,
-    sum_sq: f64,
+/// Wall time since `started`, milliseconds
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
 }
 
-impl VolumeStatistics {
-    fn new(window_size: usize) -> Self {
-        Self {
-            volumes: VecDeque::with_capacity(window_size),
-            window_size,
-            sum: 0.0,
-            sum_sq: 0.0,
-        }
-    }
-
-    fn add(&mut self, volume: f64) {
-        // Add new value
-        self.volumes.push_back(volume);
-        self.sum += volume;
-        self.sum_sq += volume * volume;
+/// End of the `instrument_timeout` of a source, checked between its batches
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
 
-        // Remove old value if window size is exceeded
-        if self.volumes.len() > self.window_size {
-            let old_value = self.volumes.pop_front().unwrap_or(0.0);
-            self.sum -= old_value;
-            self.sum_sq -= old_value * old_value;
-        }
+impl Deadline {
+    /// `None` for a zero timeout, which disables the watchdog
+    fn after(timeout: Duration) -> Option<Self> {
+        (!timeout.is_zero()).then(|| Self {
+            at: Instant::now() + timeout,
+            timeout,
+        })
     }
 
-    fn mean(&self) -> f64 {
-        if self.volumes.is_empty() {
-            return 0.0;
+    fn check(self) -> Result<(), InstrumentTimeout> {
+        match Instant::now() >= self.at {
+            true => Err(InstrumentTimeout(self.timeout)),
+            false => Ok(()),
         }
-        self.sum / self.volumes.len() as f64
     }
+}
 
-    fn stddev(&self) -> f64 {
-        if self.volumes.len() <= 1 {
-            return 0.0;
-        }
-
-        let n = self.volumes.len() as f64;
-        let variance = (self.sum_sq - (self.sum * self.sum) / n) / (n - 1.0);
-
-        if variance <= 0.0 {
-            return 0.0;
-        }
+/// Processing of a source ran past its `instrument_timeout`
+#[derive(Debug)]
+struct InstrumentTimeout(Duration);
 
-        variance.sqrt()
+impl std::fmt::Display for InstrumentTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "processing took longer than instrument_timeout ({:?})", self.0)
     }
+}
 
-    fn normalize(&self, value: f64) -> f64 {
-        let mean = self.mean();
-        let stddev = self.stddev();
-
-        if stddev == 0.0 {
-            return 0.0;
-        }
-
-        (value - mean) / stddev
-    }
+impl std::error::Error for InstrumentTimeout {}
+
+/// Signal events of a freshly calculated batch. The RSI zone before the batch is unknown, so
+/// zone entries on its first row can't be told from a continuing stretch and are skipped.
+fn batch_signals(indicators: &[DbIndicator]) -> Vec<SignalEvent> {
+    let first_time = indicators.first().map_or(0, |row| row.time);
+    detect_signals(indicators)
+        .into_iter()
+        .filter(|event| {
+            event.time != first_time
+                || matches!(event.kind, SignalKind::GoldenCross | SignalKind::DeathCross)
+        })
+        .collect()
 }
 
-/// Calculate Simple Moving Average (SMA)
-fn calculate_sma(prices: Vec<f64>, period: usize) -> f64 {
-    if prices.is_empty() || period == 0 || prices.len() < period {
-        return 0.0;
+/// Bill Williams fractals of candle `i`: (high, low) flags, set when its high (low) is strictly
+/// above (below) those of the `FRACTAL_SIDE` candles on both sides. 0 until the later candles
+/// are loaded.
+fn calculate_fractals(candles: &[DbCandleConverted], i: usize) -> (i8, i8) {
+    if i < FRACTAL_SIDE || i + FRACTAL_SIDE >= candles.len() {
+        return (0, 0);
     }
 
-    let start_idx = prices.len() - period;
-    let sum: f64 = prices[start_idx..].iter().sum();
+    let candle = &candles[i];
+    let neighbours = candles[i - FRACTAL_SIDE..=i + FRACTAL_SIDE]
+        .iter()
+        .enumerate()
+        .filter(|(offset, _)| *offset != FRACTAL_SIDE)
+        .map(|(_, neighbour)| neighbour);
+
+    let (mut is_high, mut is_low) = (true, true);
+    for neighbour in neighbours {
+        is_high &= candle.high_price > neighbour.high_price;
+        is_low &= candle.low_price < neighbour.low_price;
+    }
 
-    sum / period as f64
+    (is_high as i8, is_low as i8)
 }
 
-/// Calculate RSI (Relative Strength Index)
-fn calculate_rsi(gains: &VecDeque<f64>, losses: &VecDeque<f64>) -> f64 {
-    if gains.len() < 14 || losses.len() < 14 {
-        return 50.0; // Return neutral value if insufficient data
+// The calculator runs over the embedded store here
+// Over the embedded store, so CI runs them with `cargo test --features embedded`
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+    use crate::api::query::SortOrder;
+    use crate::db::clickhouse::clickhouse_service::ClickhouseService;
+    use crate::db::clickhouse::models::indicator::DbCandleRaw;
+    use crate::db::embedded::store::EmbeddedStore;
+    use crate::db::postgres::postgres_service::PostgresService;
+    use crate::env_config::models::app_config::{AppConfig, NotificationsConfig, RoutingRuleConfig};
+    use crate::env_config::models::app_env::{AppEnv, Env};
+    use crate::env_config::models::app_setting::AppSettings;
+    use crate::services::bench::synthetic_candles;
+    use crate::services::signals::Severity;
+
+    /// App state of the local config over an in-memory embedded store
+    async fn test_app_state(configure: impl FnOnce(&mut AppConfig)) -> (Arc<AppState>, Arc<EmbeddedStore>) {
+        let mut app_config: AppConfig = toml::from_str(include_str!("../../../config/local.toml")).unwrap();
+        configure(&mut app_config);
+        let app_env = AppEnv {
+            env: Env::Local,
+            clickhouse_url: String::new(),
+            clickhouse_user: String::new(),
+            clickhouse_password: String::new(),
+            clickhouse_database: String::new(),
+            postgres_host: String::new(),
+            postgres_user: String::new(),
+            postgres_password: String::new(),
+            postgres_database: String::new(),
+            server_port: 0,
+            server_address: String::new(),
+        };
+        let store = Arc::new(EmbeddedStore::open(":memory:").await.unwrap());
+        let app_state = AppState::builder(Arc::new(AppSettings { app_config, app_env }))
+            .with_service(Arc::new(ClickhouseService::embedded(store.clone())))
+            .with_service(Arc::new(PostgresService::embedded(store.clone())))
+            .build()
+            .unwrap();
+        (Arc::new(app_state), store)
     }
 
-    let avg_gain: f64 = gains.iter().sum::<f64>() / 14.0;
-    let avg_loss: f64 = losses.iter().sum::<f64>() / 14.0;
-
-    if avg_loss == 0.0 {
-        return 100.0;
+    fn test_candles(days: u32) -> Vec<DbCandleConverted> {
+        synthetic_candles(0, days, 86_400 * 30).into_iter().map(DbCandleConverted::from).collect()
     }
 
-    let rs = avg_gain / avg_loss;
-    100.0 - (100.0 / (1.0 + rs))
-}
-
-/// Determine moving average crossing
-fn determine_ma_cross(
-    prev_ma_fast: f64,
-    prev_ma_slow: f64,
-    curr_ma_fast: f64,
-    curr_ma_slow: f64,
-) -> i8 {
-    // Crossing from below (golden cross)
-    if prev_ma_fast <= prev_ma_slow && curr_ma_fast > curr_ma_slow {
-        return 1;
+    #[tokio::test]
+    async fn test_indicator_state_roundtrip() {
+        let (app_state, _) = test_app_state(|_| {}).await;
+        let calculator = IndicatorCalculator::new(app_state);
+        let candles = test_candles(1);
+        let context = CalculationContext {
+            volume_baseline: VolumeBaseline::default(),
+            seasonal_volume_norm: false,
+            previous_day: None,
+            hourly_closes: Vec::new(),
+            duplicate_times: HashSet::new(),
+            session_gaps: true,
+            spread: false,
+        };
+        let (_, state) = calculator.calculate_indicators(&candles[..1000], 0, &context, None);
+        let mut state = state.unwrap();
+        // Non-finite values JSON can't carry, and a value JSON would round in its last bit
+        state.prev_ma_10 = f64::NAN;
+        state.prev_ma_30 = f64::NEG_INFINITY;
+        state.prices_window.push_back(0.1 + 0.2);
+        state.prices_window.push_back(-0.0);
+
+        let encoded = state.encode().unwrap();
+        let decoded = IndicatorState::decode(&encoded).unwrap();
+
+        assert!(decoded.prev_ma_10.is_nan());
+        assert_eq!(decoded.prev_ma_30, f64::NEG_INFINITY);
+        assert_eq!(decoded.prices_window.back().unwrap().to_bits(), (-0.0f64).to_bits());
+        assert_eq!(decoded.prices_window[decoded.prices_window.len() - 2].to_bits(), (0.1f64 + 0.2).to_bits());
+        assert_eq!(decoded.encode().unwrap(), encoded);
+        assert!(IndicatorState::decode("{}").is_err());
+
+        // The resumed state continues exactly like the one it was saved from
+        let (_, state) = calculator.calculate_indicators(&candles[..1000], 0, &context, None);
+        let state = state.unwrap();
+        let resumed = IndicatorState::decode(&state.encode().unwrap()).unwrap();
+        let (expected, _) = calculator.calculate_indicators(&candles[999..], 1, &context, Some(state));
+        let (actual, _) = calculator.calculate_indicators(&candles[999..], 1, &context, Some(resumed));
+        assert_eq!(serde_json::to_string(&actual).unwrap(), serde_json::to_string(&expected).unwrap());
+        assert!(!actual.is_empty());
     }
 
-    // Crossing from above (death cross)
-    if prev_ma_fast >= prev_ma_slow && curr_ma_fast < curr_ma_slow {
-        return -1;
+    #[tokio::test]
+    async fn test_rel_volume_ignores_later_candles() {
+        let (app_state, store) = test_app_state(|_| {}).await;
+        let uid = "bench-0000";
+        let recalculate = || async {
+            IndicatorCalculator::new(app_state.clone())
+                .with_recalc(RecalcRange { instrument_uids: vec![uid.to_string()], from: Some(0), to: None })
+                .recalculate_instrument(uid)
+                .await
+                .unwrap();
+            let repo = &app_state.clickhouse_service().repository_indicator;
+            repo.get_indicators_page(uid, None, SortOrder::Asc, 100_000, None).await.unwrap()
+        };
+
+        let end = 86_400 * 30;
+        store.insert_candles(&synthetic_candles(0, 6, end)).await.unwrap();
+        let before = recalculate().await;
+        // A day of tenfold volume after the history
+        let later: Vec<DbCandleRaw> = synthetic_candles(0, 1, end + 86_400)
+            .into_iter()
+            .map(|candle| DbCandleRaw { volume: candle.volume * 10, ..candle })
+            .collect();
+        store.insert_candles(&later).await.unwrap();
+        let after = recalculate().await;
+
+        assert!(after.len() > before.len());
+        assert!(before.iter().any(|row| row.rel_volume > 0.0));
+        for (old, new) in before.iter().zip(&after) {
+            assert_eq!(old.time, new.time);
+            assert_eq!(old.rel_volume, new.rel_volume);
+            assert_eq!(old.volume_norm, new.volume_norm);
+        }
     }
 
-    // No crossing
-    0
-}
+    #[tokio::test]
+    async fn test_timeframe_trends_read_timeframe_tables() {
+        let (app_state, _) = test_app_state(|_| {}).await;
+        let calculator = IndicatorCalculator::new(app_state.clone());
+        let rule = RoutingRuleConfig {
+            name: "confirmed".to_string(),
+            signals: Vec::new(),
+            instruments: Vec::new(),
+            watchlist: None,
+            min_severity: None,
+            channels: Vec::new(),
+            quiet_hours: None,
+            max_per_hour: 0,
+            confirm_timeframes: vec![60, 1440],
+        };
+        let notifier = Notifier::new(&NotificationsConfig { rules: vec![rule], ..Default::default() }, &[]);
+
+        let uid = "uid";
+        let time = Utc::now().timestamp() / 60 * 60;
+        let bar = |timeframe: Timeframe, closed: bool, ma_10: f64| {
+            let seconds = timeframe.seconds();
+            let last_closed = (time + 60 - seconds).div_euclid(seconds) * seconds;
+            DbIndicator {
+                instrument_uid: uid.to_string(),
+                time: if closed { last_closed } else { last_closed + seconds },
+                ma_10,
+                ma_30: 100.0,
+                ..Default::default()
+            }
+        };
+        // Hourly bars trend up and daily bars down; the bars still open trend the other way
+        let repo = &app_state.clickhouse_service().repository_indicator;
+        for (timeframe, ma_10) in [(Timeframe::Hour1, 101.0), (Timeframe::Day1, 99.0)] {
+            let rows = vec![bar(timeframe, true, ma_10), bar(timeframe, false, 200.0 - ma_10)];
+            repo.insert_timeframe_indicators(timeframe, rows, false).await.unwrap();
+        }
 
-/// Calculate future price change and determine signal
-fn calculate_future_price_change(current_price: f64, future_price: f64) -> (f64, i8) {
-    if current_price == 0.0 {
-        return (0.0, 0);
-    }
+        let event = SignalEvent { time, kind: SignalKind::GoldenCross, score: 1.0, severity: Severity::High };
+        let trends = calculator.timeframe_trends(uid, &[event], &notifier).await;
 
-    let price_change = ((future_price / current_price) - 1.0) * 100.0;
+        assert_eq!(trends.get(&(time, 60)), Some(&1));
+        assert_eq!(trends.get(&(time, 1440)), Some(&-1));
+    }
 
-    let signal = if price_change > 0.2 {
-        1 // Rise >0.2%
-    } else if price_change < -0.2 {
-        -1 // Fall >0.2%
-    } else {
-        0 // Sideways
-    };
+    #[tokio::test]
+    async fn test_timeout_keeps_rows_and_status_together() {
+        // The deadline passes while the first batch is inserted, before its status is saved
+        let (app_state, store) = test_app_state(|config| {
+            config.indicators_updater.instrument_timeout = Duration::from_nanos(1);
+            config.indicators_updater.batch_size = 1000;
+        })
+        .await;
+        let uid = "bench-0000";
+        store.insert_candles(&synthetic_candles(0, 2, 86_400 * 30)).await.unwrap();
+
+        let indicator_repo = &app_state.clickhouse_service().repository_indicator;
+        let status_repo = &app_state.postgres_service().repository_indicator_status;
+        let mut written = 0;
+        for _ in 0..2 {
+            let result = IndicatorCalculator::new(app_state.clone())
+                .with_recalc(RecalcRange { instrument_uids: vec![uid.to_string()], from: None, to: None })
+                .recalculate_instrument(uid)
+                .await;
+            assert!(matches!(result, Err(InstrumentRecalcError::Failed(e)) if e.contains("instrument_timeout")));
+
+            // Every run writes one whole batch and moves the status to its last row
+            let rows = indicator_repo.get_indicators_page(uid, None, SortOrder::Asc, 100_000, None).await.unwrap();
+            let status = status_repo.get_last_processed_time(uid).await.unwrap();
+            assert!(rows.len() > written);
+            assert_eq!(status, rows.last().map(|row| row.time));
+            written = rows.len();
+        }
+    }
 
-    (price_change, signal)
+    #[tokio::test]
+    async fn test_spread_zscore_continues_across_batches() {
+        let (app_state, _) = test_app_state(|_| {}).await;
+        let calculator = IndicatorCalculator::new(app_state);
+        let candles = test_candles(2);
+        let context = CalculationContext {
+            volume_baseline: VolumeBaseline::default(),
+            seasonal_volume_norm: false,
+            previous_day: None,
+            hourly_closes: Vec::new(),
+            duplicate_times: HashSet::new(),
+            session_gaps: true,
+            spread: true,
+        };
+
+        let (whole, _) = calculator.calculate_indicators(&candles, 0, &context, None);
+        // The second batch starts with a window too short for a z-score of its own
+        let (_, state) = calculator.calculate_indicators(&candles[..2000], 0, &context, None);
+        let (resumed, _) = calculator.calculate_indicators(&candles[1999..], 1, &context, state);
+
+        let tail = &whole[whole.len() - resumed.len()..];
+        assert_eq!(tail[0].time, candles[2000].time);
+        assert!(resumed[0].spread_zscore != 0.0);
+        for (expected, actual) in tail.iter().zip(&resumed) {
+            assert_eq!(actual.spread_zscore.to_bits(), expected.spread_zscore.to_bits());
+        }
+    }
 }
@@ -0,0 +1,112 @@
+// File: src/services/indicators/feature_versions.rs
+use super::calculator::FIXED_PERIODS;
+use crate::env_config::models::app_config::IndicatorsUpdaterConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How stored rows are brought up to date after a feature's parameters change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Recompute {
+    /// Label columns depend on each row's future candles only and are updated in place
+    Labels,
+    /// Indicator columns depend on the state of the whole series, so the range is rewritten
+    Rewrite,
+}
+
+/// Configurable feature: the columns it fills and the version of the parameters behind them
+#[derive(Debug)]
+pub struct Feature {
+    pub name: &'static str,
+    pub columns: &'static [&'static str],
+    pub recompute: Recompute,
+    pub version: String,
+}
+
+impl Feature {
+    fn new(name: &'static str, columns: &'static [&'static str], recompute: Recompute, params: impl Serialize) -> Self {
+        Self {
+            name,
+            columns,
+            recompute,
+            // Serialized parameters; config values always serialize
+            version: serde_json::to_string(&params).unwrap_or_default(),
+        }
+    }
+}
+
+/// Registry of the features whose parameters can change between runs. Fixed periods are
+/// versioned too, so changing one in code recomputes its columns like a config change.
+pub fn registry(config: &IndicatorsUpdaterConfig) -> Vec<Feature> {
+    use Recompute::{Labels, Rewrite};
+
+    vec![
+        Feature::new("fixed_periods", &["*"], Rewrite, FIXED_PERIODS),
+        Feature::new("roc", &["roc", "momentum"], Rewrite, &config.roc_lags),
+        Feature::new("hma", &["wma", "hma", "hma_slope"], Rewrite, config.hma_period),
+        Feature::new("hurst", &["hurst"], Rewrite, &config.hurst),
+        Feature::new(
+            "volume_anomaly",
+            &["volume_anomaly", "volume_anomaly_score"],
+            Rewrite,
+            config.volume_anomaly_sigma,
+        ),
+        Feature::new(
+            "adaptive_thresholds",
+            &["rsi_zone_adaptive", "volume_anomaly_adaptive", "ma_cross_adaptive"],
+            Rewrite,
+            &config.adaptive_thresholds,
+        ),
+        Feature::new("gaps", &["*"], Rewrite, &config.gaps),
+        Feature::new(
+            "targets",
+            &["price_change_15m", "signal_15m", "target_change", "target_signal"],
+            Labels,
+            &config.targets,
+        ),
+        Feature::new("triple_barrier", &["tb_label", "tb_hit_time"], Labels, &config.triple_barrier),
+        Feature::new("plugins", &["plugin_values"], Rewrite, &config.plugins),
+    ]
+}
+
+/// Features whose stored version differs from the current one. A feature without a stored
+/// version is new to the registry; its rows are taken as current and only recorded.
+pub fn changed<'a>(features: &'a [Feature], stored: &HashMap<String, String>) -> Vec<&'a Feature> {
+    features
+        .iter()
+        .filter(|feature| stored.get(feature.name).is_some_and(|version| version != &feature.version))
+        .collect()
+}
+
+/// Versions to record after the rows are brought up to date
+pub fn versions(features: &[Feature]) -> Vec<(String, String)> {
+    features
+        .iter()
+        .map(|feature| (feature.name.to_string(), feature.version.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env_config::models::app_config::TargetConfig;
+
+    fn config() -> IndicatorsUpdaterConfig {
+        toml::from_str("enabled = true\ninterval_seconds = 60").unwrap()
+    }
+
+    #[test]
+    fn test_changed_features() {
+        let stored: HashMap<String, String> = versions(&registry(&config())).into_iter().collect();
+        assert!(changed(&registry(&config()), &stored).is_empty());
+
+        let mut updated = config();
+        updated.hma_period += 5;
+        updated.targets.push(TargetConfig { horizon: 120, threshold_pct: 1.0 });
+        let features = registry(&updated);
+        let names: Vec<&str> = changed(&features, &stored).iter().map(|feature| feature.name).collect();
+        assert_eq!(names, vec!["hma", "targets"]);
+
+        // Nothing stored yet: recorded as current
+        assert!(changed(&features, &HashMap::new()).is_empty());
+    }
+}
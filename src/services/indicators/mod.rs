@@ -1,3 +1,13 @@
 // File: src/services/indicators/mod.rs
 pub mod calculator;
 pub mod scheduler;
+pub mod spread;
+pub mod portfolio;
+pub mod priority;
+pub mod seasonal;
+pub mod session;
+pub mod feature_versions;
+pub mod plugins;
+pub use t_indicators_core::{
+    adaptive, changepoint, features, higher_timeframe, hurst, labels, oscillators, pivots, quality, regime, rolling,
+};
@@ -0,0 +1,142 @@
+// File: src/services/indicators/plugins.rs
+use crate::db::clickhouse::models::indicator::{DbCandleConverted, DbIndicator};
+use crate::env_config::models::app_config::PluginConfig;
+use libloading::Library;
+use std::path::Path;
+use t_indicators_core::plugin::{
+    AbiVersionFn, ComputeFn, PluginCandle, ABI_VERSION_SYMBOL, COMPUTE_SYMBOL, PLUGIN_ABI_VERSION,
+};
+use tracing::{info, warn};
+
+/// Custom indicator of `indicators_updater.plugins` with its loaded library
+struct Plugin {
+    name: String,
+    params: Vec<f64>,
+    compute: ComputeFn,
+    // Unloaded on drop, so it has to outlive every call through `compute`
+    _library: Library,
+}
+
+impl Plugin {
+    fn load(dir: &Path, config: &PluginConfig) -> Result<Self, String> {
+        let path = dir.join(&config.library);
+        let error = |e: libloading::Error| format!("plugin {} ({}): {}", config.name, path.display(), e);
+
+        // Loading runs the library's initializers; plugins_dir is as trusted as the binary
+        let library = unsafe { Library::new(&path) }.map_err(error)?;
+        let version = unsafe { library.get::<AbiVersionFn>(ABI_VERSION_SYMBOL) }.map_err(error)?;
+        let version = unsafe { version() };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "plugin {} ({}): ABI version {}, expected {}",
+                config.name,
+                path.display(),
+                version,
+                PLUGIN_ABI_VERSION
+            ));
+        }
+        let compute = *unsafe { library.get::<ComputeFn>(COMPUTE_SYMBOL) }.map_err(error)?;
+
+        Ok(Self {
+            name: config.name.clone(),
+            params: config.params.clone(),
+            compute,
+            _library: library,
+        })
+    }
+
+    /// A value per candle; `None` if the plugin reports a failure
+    fn compute(&self, candles: &[PluginCandle]) -> Option<Vec<f64>> {
+        let mut out = vec![f64::NAN; candles.len()];
+        let status = unsafe {
+            (self.compute)(
+                candles.as_ptr(),
+                candles.len(),
+                self.params.as_ptr(),
+                self.params.len(),
+                out.as_mut_ptr(),
+            )
+        };
+        match status {
+            0 => Some(out),
+            _ => {
+                warn!("Indicator plugin {} failed with status {}", self.name, status);
+                None
+            }
+        }
+    }
+}
+
+/// Custom indicators loaded from the shared libraries of `indicators_updater.plugins`
+#[derive(Default)]
+pub struct IndicatorPlugins {
+    plugins: Vec<Plugin>,
+}
+
+impl IndicatorPlugins {
+    /// Loads every configured library from `dir`; a missing library or symbol or another ABI
+    /// version fails the whole set, so rows never get a shifted `plugin_values`
+    pub fn load(dir: &str, configs: &[PluginConfig]) -> Result<Self, String> {
+        let plugins = configs
+            .iter()
+            .map(|config| Plugin::load(Path::new(dir), config))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !plugins.is_empty() {
+            info!(
+                "Loaded indicator plugins: {:?}",
+                plugins.iter().map(|plugin| plugin.name.as_str()).collect::<Vec<_>>()
+            );
+        }
+        Ok(Self { plugins })
+    }
+
+    /// Fills `plugin_values` of `indicators`, the rows of the tail of `candles`. A failed
+    /// plugin leaves its value at 0 for the batch.
+    pub fn apply(&self, indicators: &mut [DbIndicator], candles: &[DbCandleConverted]) {
+        if self.plugins.is_empty() || indicators.is_empty() {
+            return;
+        }
+        let input: Vec<PluginCandle> = candles.iter().map(PluginCandle::from).collect();
+        let series: Vec<Vec<f64>> = self
+            .plugins
+            .iter()
+            .map(|plugin| plugin.compute(&input).unwrap_or_else(|| vec![0.0; input.len()]))
+            .collect();
+        fill_plugin_values(indicators, &series);
+    }
+}
+
+/// Sets every row's `plugin_values` from the per-plugin `series`, aligned at the tail
+fn fill_plugin_values(indicators: &mut [DbIndicator], series: &[Vec<f64>]) {
+    for values in series {
+        let offset = values.len() - indicators.len();
+        for (row, value) in indicators.iter_mut().zip(&values[offset..]) {
+            row.plugin_values.push(*value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_plugin_values() {
+        let mut rows = vec![DbIndicator::default(); 2];
+        fill_plugin_values(&mut rows, &[vec![1.0, 2.0, 3.0], vec![f64::NAN, 20.0, 30.0]]);
+        assert_eq!(rows[0].plugin_values, vec![2.0, 20.0]);
+        assert_eq!(rows[1].plugin_values, vec![3.0, 30.0]);
+    }
+
+    #[test]
+    fn test_missing_library() {
+        let config = PluginConfig {
+            name: "custom".to_string(),
+            library: "libmissing.so".to_string(),
+            params: Vec::new(),
+        };
+        let error = IndicatorPlugins::load("/nonexistent", &[config]).err().unwrap();
+        assert!(error.starts_with("plugin custom (/nonexistent/libmissing.so)"));
+        assert!(IndicatorPlugins::load("/nonexistent", &[]).unwrap().plugins.is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+// File: src/services/indicators/portfolio.rs
+use crate::db::clickhouse::models::indicator::DbCandleConverted;
+use crate::env_config::models::app_config::PortfolioConfig;
+use std::collections::HashMap;
+
+impl PortfolioConfig {
+    /// Synthetic instrument UID under which portfolio indicators are stored
+    pub fn uid(&self) -> String {
+        format!("portfolio:{}", self.name)
+    }
+}
+
+/// Build the weighted portfolio price series from member candles.
+///
+/// `members` must be in the same order as `portfolio.members`. Only minutes where every
+/// member has a candle are kept, so the series never mixes stale and fresh prices.
+pub fn build_portfolio_candles(
+    portfolio: &PortfolioConfig,
+    members: &[Vec<DbCandleConverted>],
+) -> Vec<DbCandleConverted> {
+    let Some((first, rest)) = members.split_first() else {
+        return Vec::new();
+    };
+
+    let uid = portfolio.uid();
+    let rest_by_time: Vec<HashMap<i64, &DbCandleConverted>> = rest
+        .iter()
+        .map(|candles| candles.iter().map(|candle| (candle.time, candle)).collect())
+        .collect();
+
+    first
+        .iter()
+        .filter_map(|head| {
+            let mut row = Vec::with_capacity(members.len());
+            row.push(head);
+            for by_time in &rest_by_time {
+                row.push(*by_time.get(&head.time)?);
+            }
+
+            let weighted = |price: fn(&DbCandleConverted) -> f64| -> f64 {
+                row.iter()
+                    .zip(&portfolio.members)
+                    .map(|(candle, member)| member.weight * price(candle))
+                    .sum()
+            };
+
+            let open = weighted(|c| c.open_price);
+            let close = weighted(|c| c.close_price);
+            let high = weighted(|c| c.high_price);
+            let low = weighted(|c| c.low_price);
+
+            Some(DbCandleConverted {
+                instrument_uid: uid.clone(),
+                time: head.time,
+                open_price: open,
+                // Member extremes don't line up in time, so keep OHLC consistent
+                high_price: high.max(open).max(close),
+                low_price: low.min(open).min(close),
+                close_price: close,
+                volume: row.iter().map(|candle| candle.volume).sum(),
+            })
+        })
+        .collect()
+}
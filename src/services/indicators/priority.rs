@@ -0,0 +1,100 @@
+// File: src/services/indicators/priority.rs
+use crate::env_config::models::app_config::TierConfig;
+use std::collections::{HashMap, HashSet};
+
+/// Priority and refresh frequency of one source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tier {
+    priority: i32,
+    every: u64,
+}
+
+impl Default for Tier {
+    fn default() -> Self {
+        Self { priority: 0, every: 1 }
+    }
+}
+
+/// Sources due in scheduler cycle `cycle`, in processing order: new instruments, then by tier
+/// priority (highest first), then the most stale first. Returns them with the number of
+/// sources whose tier skips this cycle; new instruments are never skipped.
+pub fn plan_sources<T>(
+    sources: Vec<T>,
+    uid: impl Fn(&T) -> String,
+    new_uids: &HashSet<String>,
+    tiers: &[TierConfig],
+    last_processed: &HashMap<String, i64>,
+    cycle: u64,
+) -> (Vec<T>, usize) {
+    let mut tier_by_uid = HashMap::new();
+    // The first tier listing an instrument wins
+    for tier in tiers.iter().rev() {
+        for instrument in &tier.instruments {
+            let every = u64::from(tier.every.max(1));
+            tier_by_uid.insert(instrument.as_str(), Tier { priority: tier.priority, every });
+        }
+    }
+
+    let total = sources.len();
+    let mut due: Vec<(bool, i32, Option<i64>, T)> = sources
+        .into_iter()
+        .filter_map(|source| {
+            let uid = uid(&source);
+            let is_new = new_uids.contains(&uid);
+            let tier = tier_by_uid.get(uid.as_str()).copied().unwrap_or_default();
+            if !is_new && !cycle.is_multiple_of(tier.every) {
+                return None;
+            }
+            Some((is_new, tier.priority, last_processed.get(&uid).copied(), source))
+        })
+        .collect();
+    let skipped = total - due.len();
+
+    // Stable: equally ranked sources keep their order. `None` (never processed) sorts first.
+    due.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+    (due.into_iter().map(|(_, _, _, source)| source).collect(), skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_sources() {
+        let tiers = vec![
+            TierConfig {
+                name: "liquid".to_string(),
+                priority: 10,
+                every: 1,
+                instruments: vec!["sber".to_string(), "gazp".to_string()],
+            },
+            TierConfig {
+                name: "illiquid".to_string(),
+                priority: -10,
+                every: 3,
+                instruments: vec!["rare".to_string(), "fresh".to_string()],
+            },
+        ];
+        let last_processed = HashMap::from([
+            ("sber".to_string(), 300),
+            ("gazp".to_string(), 100),
+            ("rare".to_string(), 0),
+            ("other".to_string(), 500),
+            ("spread:pair".to_string(), 50),
+        ]);
+        let new_uids = HashSet::from(["fresh".to_string()]);
+        let sources: Vec<String> = ["other", "rare", "sber", "spread:pair", "fresh", "gazp"].map(String::from).to_vec();
+        let plan = |cycle| plan_sources(sources.clone(), |uid| uid.clone(), &new_uids, &tiers, &last_processed, cycle);
+
+        // Cycle 0 runs every tier
+        let (order, skipped) = plan(0);
+        assert_eq!(order, vec!["fresh", "gazp", "sber", "spread:pair", "other", "rare"]);
+        assert_eq!(skipped, 0);
+
+        // The illiquid tier waits for cycle 3, a new instrument does not
+        let (order, skipped) = plan(1);
+        assert_eq!(order, vec!["fresh", "gazp", "sber", "spread:pair", "other"]);
+        assert_eq!(skipped, 1);
+    }
+}
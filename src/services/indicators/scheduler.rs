@@ -1,55 +1,223 @@
 // File: src/services/indicators/scheduler.rs
-use super::calculator::IndicatorCalculator;
+use super::calculator::{IndicatorCalculator, RecalcRange};
 use crate::app_state::models::AppState;
+use crate::db::postgres::models::indicator_run::PgIndicatorRun;
+use crate::services::shutdown::{wait_shutdown, Shutdown};
+use chrono::{DateTime, Utc};
+use croner::Cron;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time;
-use tracing::{debug, error, info, warn};
+use tokio::sync::Mutex;
+use tokio::time::{self, Instant, Interval, MissedTickBehavior};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
+/// Cron pattern of `indicators_updater.cron`, seconds optional
+pub fn parse_cron(pattern: &str) -> Result<Cron, croner::errors::CronError> {
+    Cron::new(pattern).with_seconds_optional().parse()
+}
+
+/// When scheduled updates run: every `interval_seconds` or at the times of `cron`
+enum Schedule {
+    Interval(Interval),
+    Cron(Cron),
+}
+
+impl Schedule {
+    /// Parses `cron` (UTC, 5 fields or 6 with seconds) or falls back to a fixed interval
+    fn from_config(cron: Option<&str>, period: Duration) -> Result<Self, croner::errors::CronError> {
+        match cron {
+            Some(pattern) => Ok(Self::Cron(parse_cron(pattern)?)),
+            None => {
+                // The first tick comes one interval after startup, the startup update covers
+                // the time until then
+                let period = period.max(Duration::from_secs(1));
+                let mut interval = time::interval_at(Instant::now() + period, period);
+                // A long update is not followed by a burst of catch-up ticks
+                interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                Ok(Self::Interval(interval))
+            }
+        }
+    }
+
+    /// Waits for the next run. Cron times missed during a long update are skipped, as are the
+    /// interval ticks.
+    async fn tick(&mut self) {
+        match self {
+            Self::Interval(interval) => {
+                interval.tick().await;
+            }
+            Self::Cron(cron) => match next_cron_time(cron, Utc::now()) {
+                Some(next) => {
+                    debug!("Next scheduled indicators update at {}", next);
+                    let delay = (next - Utc::now()).to_std().unwrap_or_default();
+                    time::sleep(delay).await;
+                }
+                None => {
+                    warn!("Cron schedule {} has no upcoming runs", cron.pattern);
+                    std::future::pending::<()>().await;
+                }
+            },
+        }
+    }
+}
+
+/// First time of `cron` strictly after `after`
+fn next_cron_time(cron: &Cron, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    cron.find_next_occurrence(&after, false).ok()
+}
+
+#[derive(Clone)]
 pub struct IndicatorsScheduler {
     app_state: Arc<AppState>,
+    // Held for the duration of an update, shared by the clones
+    run_lock: Arc<Mutex<()>>,
+    // Scheduled ticks so far; the startup update is cycle 0
+    cycle: Arc<AtomicU64>,
 }
 
 impl IndicatorsScheduler {
     pub fn new(app_state: Arc<AppState>) -> Self {
-        Self { app_state }
+        Self {
+            app_state,
+            run_lock: Arc::new(Mutex::new(())),
+            cycle: Arc::new(AtomicU64::new(0)),
+        }
     }
 
     // Simplified implementation without unnecessary retries
     pub async fn trigger_update(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        info!("Starting indicators update for all instruments");
-        
-        // Create indicator calculator with conservative batch sizes
-        let calculator = IndicatorCalculator::new(self.app_state.clone());
-        
+        self.run_update(IndicatorCalculator::new(self.app_state.clone())).await
+    }
+
+    /// One-shot update limited to `range`, skipped like any other while a run is in progress
+    pub async fn recalculate(&self, range: RecalcRange) -> Result<usize, Box<dyn std::error::Error>> {
+        self.run_update(IndicatorCalculator::new(self.app_state.clone()).with_recalc(range)).await
+    }
+
+    /// One-shot update running lagging instruments of `instrument_uids` (all if empty) to the
+    /// latest candle, outside the backfill window and budget
+    pub async fn backfill(&self, instrument_uids: Vec<String>) -> Result<usize, Box<dyn std::error::Error>> {
+        let range = RecalcRange { instrument_uids, ..Default::default() };
+        self.run_update(IndicatorCalculator::new(self.app_state.clone()).with_recalc(range).with_catch_up()).await
+    }
+
+    /// Update of a scheduled tick: instruments whose window is closed wait for the next tick
+    async fn trigger_scheduled_update(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let cycle = self.cycle.fetch_add(1, Ordering::Relaxed) + 1;
+        let calculator = IndicatorCalculator::new(self.app_state.clone())
+            .with_operation_windows()
+            .with_cycle(cycle);
+        self.run_update(calculator).await
+    }
+
+    /// Runs one update; skipped with 0 while another update is running in this process or on
+    /// another replica. Log lines of the update carry its run ID; the run and its totals are
+    /// recorded in tinkoff_indicators_runs.
+    async fn run_update(&self, calculator: IndicatorCalculator) -> Result<usize, Box<dyn std::error::Error>> {
+        let Ok(_running) = self.run_lock.try_lock() else {
+            warn!("Previous indicators update is still running, skipping this one");
+            return Ok(0);
+        };
+        let run_lock_repo = &self.app_state.postgres_service().repository_run_lock;
+        let _replica_lock = match run_lock_repo.try_lock_run().await {
+            Ok(Some(guard)) => guard,
+            Ok(None) => {
+                warn!("Indicators update is running on another replica, skipping this one");
+                return Ok(0);
+            }
+            Err(e) => {
+                error!("Failed to take the indicators run lock: {}", e);
+                return Err(Box::new(e));
+            }
+        };
+
+        let span = info_span!("indicators_run", run_id = %calculator.run_id());
+        info!(parent: &span, "Starting indicators update for all instruments");
+
+        let run_repo = &self.app_state.postgres_service().repository_indicator_run;
+        let mut run = PgIndicatorRun {
+            run_id: calculator.run_id().to_string(),
+            trigger: calculator.trigger().to_string(),
+            cycle: calculator.cycle() as i64,
+            status: "running".to_string(),
+            start_time: Utc::now(),
+            ..Default::default()
+        };
+        if let Err(e) = run_repo.start_run(&run).await {
+            error!(parent: &span, "Failed to record the start of the run: {}", e);
+        }
+
         // Process all instruments - no retries on memory errors since we use smaller batches by default
-        match calculator.process_all_instruments().await {
+        let calculator = Arc::new(calculator);
+        // The error is not Send, only its message is kept across the await below
+        let result = calculator
+            .clone()
+            .process_all_instruments()
+            .instrument(span.clone())
+            .await
+            .map_err(|e| e.to_string());
+
+        let stats = calculator.run_stats();
+        run.end_time = Some(Utc::now());
+        run.status = if result.is_ok() { "completed" } else { "failed" }.to_string();
+        run.instruments = stats.instruments as i64;
+        run.instruments_failed = stats.instruments_failed as i64;
+        run.candles_processed = stats.candles_processed as i64;
+        run.rows_inserted = stats.rows_inserted as i64;
+        run.insert_errors = stats.insert_errors as i64;
+        run.error = result.as_ref().err().cloned();
+        if let Err(e) = run_repo.finish_run(&run).await {
+            error!(parent: &span, "Failed to record the end of the run: {}", e);
+        }
+
+        match result {
             Ok(count) => {
-                info!("Indicators update completed successfully. Processed {} candles", count);
+                info!(parent: &span, "Indicators update completed successfully. Processed {} candles", count);
                 Ok(count)
             },
             Err(e) => {
-                error!("Error during indicators update: {}", e);
-                Err(e)
+                error!(parent: &span, "Error during indicators update: {}", e);
+                Err(e.into())
             }
         }
     }
     
+    /// Waits up to `timeout` for a running update to finish its batch in flight; false when
+    /// the update is still running
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        time::timeout(timeout, self.run_lock.lock()).await.is_ok()
+    }
+
     // Start a regular scheduled update process
     pub async fn start_scheduled_updates(&self) {
         info!("Starting scheduled indicator updates");
         
-        // Get the update interval from settings
-        let interval_seconds = self.app_state.settings.app_config.indicators_updater.interval_seconds;
-        info!("Update interval set to {} seconds", interval_seconds);
+        // Get the update schedule from settings
+        let updater_config = &self.app_state.settings.app_config.indicators_updater;
+        let mut schedule = match Schedule::from_config(updater_config.cron.as_deref(), updater_config.interval) {
+            Ok(schedule) => schedule,
+            Err(e) => panic!("Invalid indicators_updater.cron {:?}: {}", updater_config.cron, e),
+        };
+        match &updater_config.cron {
+            Some(cron) => info!("Update schedule set to cron \"{}\" (UTC)", cron),
+            None => info!("Update interval set to {:?}", updater_config.interval),
+        }
         
         // Create a new task for the scheduler
-        let app_state = self.app_state.clone();
+        let scheduler = self.clone();
         tokio::spawn(async move {
-            let mut interval = time::interval(Duration::from_secs(interval_seconds));
+            let app_state = scheduler.app_state.clone();
+            let shutdown = app_state.service::<Shutdown>().cloned();
             
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = schedule.tick() => {}
+                    _ = wait_shutdown(shutdown.as_deref()) => {
+                        info!("Scheduled indicator updates stopped");
+                        break;
+                    }
+                }
                 
                 // Check if updates are enabled in config
                 if !app_state.settings.app_config.indicators_updater.enabled {
@@ -57,17 +225,17 @@ impl IndicatorsScheduler {
                     continue;
                 }
                 
-                // Check if current time is within the allowed operation window
-                if !app_state.settings.app_config.indicators_updater.is_operation_allowed() {
-                    debug!("Outside operation window, skipping update");
+                // Check if current time is within the allowed operation or backfill window
+                let updater_config = &app_state.settings.app_config.indicators_updater;
+                let backfill_open = updater_config.backfill.lag_hours > 0 && updater_config.backfill.is_window_open();
+                if !updater_config.is_operation_allowed() && !backfill_open {
+                    debug!("Outside operation and backfill windows, skipping update");
                     continue;
                 }
                 
                 info!("Executing scheduled indicator update");
                 
-                // Create a new scheduler and trigger the update
-                let scheduler = IndicatorsScheduler::new(app_state.clone());
-                match scheduler.trigger_update().await {
+                match scheduler.trigger_scheduled_update().await {
                     Ok(count) => {
                         info!("Scheduled indicators update completed: {} candles processed", count);
                     }
@@ -80,4 +248,28 @@ impl IndicatorsScheduler {
         
         info!("Scheduled update task started");
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_cron_time_skips_weekend() {
+        let Ok(Schedule::Cron(cron)) = Schedule::from_config(Some("*/5 7-15 * * MON-FRI"), Duration::from_secs(300)) else {
+            panic!("cron schedule expected");
+        };
+
+        // Friday 2025-03-14
+        let next = next_cron_time(&cron, Utc.with_ymd_and_hms(2025, 3, 14, 12, 2, 30).unwrap());
+        assert_eq!(next, Some(Utc.with_ymd_and_hms(2025, 3, 14, 12, 5, 0).unwrap()));
+        // Strictly after a matching time
+        let next = next_cron_time(&cron, Utc.with_ymd_and_hms(2025, 3, 14, 12, 5, 0).unwrap());
+        assert_eq!(next, Some(Utc.with_ymd_and_hms(2025, 3, 14, 12, 10, 0).unwrap()));
+        // After the last run of the week: Monday morning
+        let next = next_cron_time(&cron, Utc.with_ymd_and_hms(2025, 3, 14, 15, 56, 0).unwrap());
+        assert_eq!(next, Some(Utc.with_ymd_and_hms(2025, 3, 17, 7, 0, 0).unwrap()));
+
+        assert!(Schedule::from_config(Some("*/5 25 * * *"), Duration::from_secs(300)).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+// File: src/services/indicators/seasonal.rs
+use crate::db::clickhouse::models::volume_baseline::DbVolumeBaseline;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::collections::HashMap;
+
+/// Minimum number of observations for a seasonal bucket to be trusted
+const MIN_SAMPLES: u32 = 3;
+
+/// Seasonal volume model: expected volume per (weekday, minute of day), one per UTC day. The
+/// model of a day is estimated from the candles before that day only, so historical rows never
+/// see the volume that followed them.
+#[derive(Default)]
+pub struct VolumeBaseline {
+    days: HashMap<i64, DayBaseline>,
+}
+
+#[derive(Default)]
+struct DayBaseline {
+    buckets: HashMap<(u8, u16), (f64, f64)>, // (mean, stddev)
+    time_of_day: HashMap<u16, f64>,          // mean over all weekdays
+}
+
+impl DayBaseline {
+    fn from_rows(rows: &[&DbVolumeBaseline]) -> Self {
+        // Sample-weighted mean of the weekday buckets of each minute
+        let mut totals: HashMap<u16, (f64, u32)> = HashMap::new();
+        for row in rows {
+            let total = totals.entry(row.minute_of_day).or_default();
+            total.0 += row.mean * row.samples as f64;
+            total.1 += row.samples;
+        }
+        let time_of_day = totals
+            .into_iter()
+            .filter(|(_, (_, samples))| *samples >= MIN_SAMPLES)
+            .map(|(minute, (sum, samples))| (minute, sum / samples as f64))
+            .collect();
+
+        let buckets = rows
+            .iter()
+            .filter(|row| row.samples >= MIN_SAMPLES)
+            .map(|row| ((row.weekday, row.minute_of_day), (row.mean, row.stddev)))
+            .collect();
+
+        Self { buckets, time_of_day }
+    }
+}
+
+/// Start of the UTC day of `time`
+pub fn day_start(time: i64) -> i64 {
+    time.div_euclid(86_400) * 86_400
+}
+
+impl VolumeBaseline {
+    /// Whether the model of the day starting at `as_of` is loaded, possibly empty
+    pub fn has_day(&self, as_of: i64) -> bool {
+        self.days.contains_key(&as_of)
+    }
+
+    /// Loads the models of `days` (day starts) from the rows of their `as_of`; a day without
+    /// rows has no history and stays empty
+    pub fn insert_days(&mut self, days: &[i64], rows: &[DbVolumeBaseline]) {
+        for &day in days {
+            let day_rows: Vec<&DbVolumeBaseline> = rows.iter().filter(|row| row.as_of == day).collect();
+            self.days.insert(day, DayBaseline::from_rows(&day_rows));
+        }
+    }
+
+    /// Drops the models of the days before `as_of`
+    pub fn retain_from(&mut self, as_of: i64) {
+        self.days.retain(|&day, _| day >= as_of);
+    }
+
+    /// Z-score of a volume against the seasonal bucket of its candle time,
+    /// `None` if the bucket is missing or has no dispersion
+    pub fn normalize(&self, time: i64, volume: f64) -> Option<f64> {
+        let dt = DateTime::<Utc>::from_timestamp(time, 0)?;
+        let weekday = dt.weekday().number_from_monday() as u8;
+        let minute_of_day = (dt.hour() * 60 + dt.minute()) as u16;
+
+        let day = self.days.get(&day_start(time))?;
+        let &(mean, stddev) = day.buckets.get(&(weekday, minute_of_day))?;
+        if stddev <= 0.0 || !stddev.is_finite() {
+            return None;
+        }
+
+        Some((volume - mean) / stddev)
+    }
+
+    /// Volume relative to the average volume at the same minute of day over the baseline
+    /// lookback before the candle's day, `None` if the minute has no history
+    pub fn relative_volume(&self, time: i64, volume: f64) -> Option<f64> {
+        let minute_of_day = (time.rem_euclid(86_400) / 60) as u16;
+
+        let day = self.days.get(&day_start(time))?;
+        let &mean = day.time_of_day.get(&minute_of_day)?;
+        if mean <= 0.0 {
+            return None;
+        }
+
+        Some(volume / mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(as_of: i64, minute_of_day: u16, mean: f64) -> DbVolumeBaseline {
+        DbVolumeBaseline {
+            instrument_uid: "uid".to_string(),
+            weekday: 1,
+            minute_of_day,
+            mean,
+            stddev: 10.0,
+            samples: 4,
+            update_time: 0,
+            as_of,
+        }
+    }
+
+    #[test]
+    fn test_rows_use_the_baseline_of_their_day() {
+        // 1970-01-05 is a Monday
+        let (monday, tuesday) = (4 * 86_400, 5 * 86_400);
+        let mut baseline = VolumeBaseline::default();
+        baseline.insert_days(&[monday, tuesday], &[row(monday, 600, 100.0), row(tuesday, 600, 200.0)]);
+
+        assert_eq!(baseline.relative_volume(monday + 600 * 60, 150.0), Some(1.5));
+        assert_eq!(baseline.normalize(monday + 600 * 60, 150.0), Some(5.0));
+        assert_eq!(baseline.relative_volume(tuesday + 600 * 60, 150.0), Some(0.75));
+        // No model loaded for the day, and no history for the minute
+        assert_eq!(baseline.relative_volume(tuesday + 86_400 + 600 * 60, 150.0), None);
+        assert_eq!(baseline.relative_volume(monday + 601 * 60, 150.0), None);
+
+        baseline.retain_from(tuesday);
+        assert!(!baseline.has_day(monday));
+        assert!(baseline.has_day(tuesday));
+        assert_eq!(baseline.relative_volume(monday + 600 * 60, 150.0), None);
+    }
+}
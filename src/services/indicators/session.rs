@@ -0,0 +1,102 @@
+// File: src/services/indicators/session.rs
+use crate::env_config::models::app_config::{CalendarConfig, SessionWindow};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use std::collections::HashSet;
+
+/// Trading phase of a candle, stored as `session_phase`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionPhase {
+    Closed = 0,
+    PreMarket = 1,
+    Main = 2,
+    Evening = 3,
+}
+
+/// Exchange trading calendar: daily session windows (UTC), trading weekdays and holidays
+pub struct ExchangeCalendar {
+    pre_market: (i32, i32),
+    main: (i32, i32),
+    evening: (i32, i32),
+    trading_weekdays: Vec<u32>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl ExchangeCalendar {
+    pub fn new(config: &CalendarConfig) -> Self {
+        Self {
+            pre_market: window_minutes(&config.pre_market),
+            main: window_minutes(&config.main),
+            evening: window_minutes(&config.evening),
+            trading_weekdays: config.trading_weekdays.clone(),
+            holidays: config.holidays.iter().copied().collect(),
+        }
+    }
+
+    /// Phase of the minute starting at `time` (unix seconds)
+    pub fn phase(&self, time: i64) -> SessionPhase {
+        let dt = DateTime::<Utc>::from_timestamp(time, 0).unwrap_or_default();
+        let weekday = dt.weekday().number_from_monday();
+        if !self.trading_weekdays.contains(&weekday) || self.holidays.contains(&dt.date_naive()) {
+            return SessionPhase::Closed;
+        }
+
+        let minute = (dt.hour() * 60 + dt.minute()) as i32;
+        let within = |(start, end): (i32, i32)| start <= minute && minute < end;
+        if within(self.main) {
+            SessionPhase::Main
+        } else if within(self.pre_market) {
+            SessionPhase::PreMarket
+        } else if within(self.evening) {
+            SessionPhase::Evening
+        } else {
+            SessionPhase::Closed
+        }
+    }
+
+    /// Minutes since the main session opened, counted from the last open time of day
+    pub fn minutes_since_open(&self, time: i64) -> i32 {
+        let minute_of_day = (time.rem_euclid(86_400) / 60) as i32;
+        (minute_of_day - self.main.0).rem_euclid(1440)
+    }
+}
+
+/// `[start, end)` of a window as minutes of the UTC day
+pub(crate) fn window_minutes(window: &SessionWindow) -> (i32, i32) {
+    (window.start.minute_of_day(), window.end.minute_of_day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn at(date: &str, time: &str) -> i64 {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M:%S").unwrap())
+            .and_utc()
+            .timestamp()
+    }
+
+    #[test]
+    fn test_moex_phases() {
+        let config = CalendarConfig {
+            holidays: vec![NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()],
+            ..Default::default()
+        };
+        let calendar = ExchangeCalendar::new(&config);
+
+        // 2025-03-03 is a Monday
+        assert_eq!(calendar.phase(at("2025-03-03", "06:30:00")), SessionPhase::Closed);
+        assert_eq!(calendar.phase(at("2025-03-03", "06:55:00")), SessionPhase::PreMarket);
+        assert_eq!(calendar.phase(at("2025-03-03", "07:00:00")), SessionPhase::Main);
+        assert_eq!(calendar.phase(at("2025-03-03", "16:00:00")), SessionPhase::Closed);
+        assert_eq!(calendar.phase(at("2025-03-03", "18:00:00")), SessionPhase::Evening);
+        assert_eq!(calendar.phase(at("2025-03-01", "10:00:00")), SessionPhase::Closed);
+        assert_eq!(calendar.phase(at("2025-01-01", "10:00:00")), SessionPhase::Closed);
+
+        assert_eq!(calendar.minutes_since_open(at("2025-03-03", "07:00:00")), 0);
+        assert_eq!(calendar.minutes_since_open(at("2025-03-03", "08:30:00")), 90);
+        assert_eq!(calendar.minutes_since_open(at("2025-03-03", "06:59:00")), 1439);
+    }
+}
@@ -0,0 +1,60 @@
+// File: src/services/indicators/spread.rs
+use crate::db::clickhouse::models::indicator::DbCandleConverted;
+use crate::env_config::models::app_config::{SpreadConfig, SpreadMode};
+use std::collections::HashMap;
+
+impl SpreadConfig {
+    /// Synthetic instrument UID under which spread indicators are stored
+    pub fn uid(&self) -> String {
+        format!("spread:{}", self.name)
+    }
+}
+
+/// Align candles of both legs by time and build the synthetic spread series.
+///
+/// Only minutes present in both legs are kept; the result is in ascending time order.
+pub fn build_spread_candles(
+    spread: &SpreadConfig,
+    leg_a: &[DbCandleConverted],
+    leg_b: &[DbCandleConverted],
+) -> Vec<DbCandleConverted> {
+    let uid = spread.uid();
+    let leg_b_by_time: HashMap<i64, &DbCandleConverted> =
+        leg_b.iter().map(|candle| (candle.time, candle)).collect();
+
+    leg_a
+        .iter()
+        .filter_map(|a| {
+            let b = leg_b_by_time.get(&a.time)?;
+            let open = combine(spread, a.open_price, b.open_price)?;
+            let close = combine(spread, a.close_price, b.close_price)?;
+            let high = combine(spread, a.high_price, b.high_price)?;
+            let low = combine(spread, a.low_price, b.low_price)?;
+
+            Some(DbCandleConverted {
+                instrument_uid: uid.clone(),
+                time: a.time,
+                open_price: open,
+                // Leg extremes don't line up in time, so keep OHLC consistent
+                high_price: high.max(open).max(close),
+                low_price: low.min(open).min(close),
+                close_price: close,
+                volume: a.volume.min(b.volume),
+            })
+        })
+        .collect()
+}
+
+/// Combine two leg prices according to the spread mode
+fn combine(spread: &SpreadConfig, a: f64, b: f64) -> Option<f64> {
+    match spread.mode {
+        SpreadMode::Ratio => {
+            if b == 0.0 {
+                None
+            } else {
+                Some(a / b)
+            }
+        }
+        SpreadMode::Difference => Some(a - spread.hedge_ratio * b),
+    }
+}
@@ -0,0 +1,91 @@
+// File: src/services/instrument_filter.rs
+use crate::app_state::models::AppState;
+use crate::db::postgres::models::instrument_filter::PgInstrumentFilter;
+use crate::env_config::models::app_config::InstrumentFilterConfig;
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+/// Allow and deny lists of instruments from config and tinkoff_instrument_filters. With any
+/// allow entry only allowed instruments pass; a deny entry always wins.
+#[derive(Debug, Default)]
+pub struct InstrumentFilter {
+    allow: HashSet<String>,
+    deny: HashSet<String>,
+}
+
+impl InstrumentFilter {
+    pub fn new(config: &InstrumentFilterConfig, stored: &[PgInstrumentFilter]) -> Self {
+        let mut filter = Self {
+            allow: config.allow.iter().cloned().collect(),
+            deny: config.deny.iter().cloned().collect(),
+        };
+        for entry in stored {
+            match entry.mode.as_str() {
+                "allow" => filter.allow.insert(entry.instrument_uid.clone()),
+                "deny" => filter.deny.insert(entry.instrument_uid.clone()),
+                mode => {
+                    warn!("Unknown instrument filter mode {} for {}, ignoring it", mode, entry.instrument_uid);
+                    false
+                }
+            };
+        }
+        filter
+    }
+
+    /// Config lists with the stored entries; only the config ones if the table can't be read
+    pub async fn load(app_state: &AppState) -> Self {
+        let config = &app_state.settings.app_config.instrument_filter;
+        let repository = &app_state.postgres_service().repository_instrument_filter;
+        let stored = repository.get_instrument_filters().await.unwrap_or_else(|e| {
+            warn!("Failed to load instrument filters, using the config lists only: {}", e);
+            Vec::new()
+        });
+        Self::new(config, &stored)
+    }
+
+    pub fn is_allowed(&self, instrument_uid: &str) -> bool {
+        !self.deny.contains(instrument_uid) && (self.allow.is_empty() || self.allow.contains(instrument_uid))
+    }
+
+    /// Keeps the allowed instruments, logging how many were filtered out
+    pub fn apply(&self, instrument_uids: Vec<String>) -> Vec<String> {
+        let total = instrument_uids.len();
+        let allowed: Vec<String> = instrument_uids.into_iter().filter(|uid| self.is_allowed(uid)).collect();
+        if allowed.len() < total {
+            info!("Instrument filters excluded {} of {} instruments", total - allowed.len(), total);
+        }
+        allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(instrument_uid: &str, mode: &str) -> PgInstrumentFilter {
+        PgInstrumentFilter {
+            instrument_uid: instrument_uid.to_string(),
+            mode: mode.to_string(),
+            reason: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_allow_and_deny() {
+        let uids = || ["sber", "gazp", "test-1", "delisted"].map(String::from).to_vec();
+
+        // Deny only
+        let config = InstrumentFilterConfig {
+            allow: Vec::new(),
+            deny: vec!["test-1".to_string()],
+        };
+        let filter = InstrumentFilter::new(&config, &[stored("delisted", "deny")]);
+        assert_eq!(filter.apply(uids()), vec!["sber", "gazp"]);
+
+        // An allow list restricts to its entries, deny still wins
+        let filter = InstrumentFilter::new(&config, &[stored("sber", "allow"), stored("test-1", "allow")]);
+        assert_eq!(filter.apply(uids()), vec!["sber"]);
+
+        assert_eq!(InstrumentFilter::default().apply(uids()), uids());
+    }
+}
@@ -0,0 +1,66 @@
+// File: src/services/latency.rs
+use crate::db::clickhouse::models::signal_latency::DbSignalLatency;
+use crate::services::signals::SignalEvent;
+use chrono::Utc;
+
+/// Length of the 1-minute candles the signals are computed from, ms
+const CANDLE_MS: i64 = 60_000;
+
+pub fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+/// Wall-clock times (unix ms) at which a batch of candles passed each updater stage
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchStages {
+    pub fetched_ms: i64,
+    pub computed_ms: i64,
+    pub inserted_ms: i64,
+}
+
+/// Signal event with its latency record; `Notifier::publish` fills in the emission and
+/// delivery stages and sends the trace id along with the notification
+#[derive(Debug, Clone)]
+pub struct TracedSignal {
+    pub event: SignalEvent,
+    pub latency: DbSignalLatency,
+}
+
+impl TracedSignal {
+    /// The collector doesn't record when it ingested a candle, so the trace starts at the
+    /// candle close, the earliest the signal could exist
+    pub fn new(run_id: &str, instrument_uid: &str, event: SignalEvent, stages: &BatchStages) -> Self {
+        let kind = event.kind.name();
+        let latency = DbSignalLatency {
+            trace_id: format!("{}:{}:{}:{}", run_id, instrument_uid, event.time, kind),
+            run_id: run_id.to_string(),
+            instrument_uid: instrument_uid.to_string(),
+            kind: kind.to_string(),
+            candle_time: event.time as u32,
+            candle_close_ms: event.time * 1000 + CANDLE_MS,
+            fetched_ms: stages.fetched_ms,
+            computed_ms: stages.computed_ms,
+            inserted_ms: stages.inserted_ms,
+            ..Default::default()
+        };
+        Self { event, latency }
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.latency.trace_id
+    }
+
+    /// Published: fresh and past the cooldown
+    pub fn is_emitted(&self) -> bool {
+        self.latency.emitted_ms > 0
+    }
+
+    pub fn record_delivery(&mut self, delivered: bool) {
+        if delivered {
+            self.latency.deliveries += 1;
+            self.latency.delivered_ms = now_ms();
+        } else {
+            self.latency.delivery_failures += 1;
+        }
+    }
+}
@@ -1,3 +1,16 @@
 
 pub mod indicators;
 
+#[cfg(feature = "embedded")]
+pub mod bench;
+pub mod config_summary;
+pub mod export;
+pub mod feature_flags;
+pub mod instrument_filter;
+pub mod latency;
+pub mod notifications;
+pub mod query_cache;
+pub mod sample_export;
+pub mod shutdown;
+pub mod signals;
+pub mod tuning;
@@ -0,0 +1,116 @@
+// File: src/services/notifications/channels.rs
+use crate::env_config::models::app_config::ChannelConfig;
+use crate::services::signals::{Severity, SignalKind};
+use serde::Serialize;
+use serde_json::json;
+use tracing::warn;
+
+/// Payload of one notification
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub trace_id: String, // correlates with tinkoff_signal_latency
+    pub instrument_uid: String,
+    pub time: i64,
+    pub kind: SignalKind,
+    pub direction: i8,
+    pub severity: Severity,
+    pub rule: String,
+}
+
+/// Event about a UID that appeared in the candles for the first time
+#[derive(Debug, Clone, Serialize)]
+pub struct InstrumentDiscovered {
+    pub instrument_uid: String,
+    pub kind: &'static str,
+    pub first_seen_at: i64,
+}
+
+impl InstrumentDiscovered {
+    pub fn new(instrument_uid: &str, first_seen_at: i64) -> Self {
+        Self {
+            instrument_uid: instrument_uid.to_string(),
+            kind: "new_instrument",
+            first_seen_at,
+        }
+    }
+}
+
+/// Anything a channel can deliver: JSON for webhooks and Kafka, text for Telegram
+pub trait Message: Serialize + Sync {
+    fn instrument_uid(&self) -> &str;
+    fn text(&self) -> String;
+}
+
+impl Message for Notification {
+    fn instrument_uid(&self) -> &str {
+        &self.instrument_uid
+    }
+
+    fn text(&self) -> String {
+        let arrow = if self.direction > 0 { "▲" } else { "▼" };
+        format!(
+            "{} {:?} on {} at {} ({:?}, rule {})",
+            arrow, self.kind, self.instrument_uid, self.time, self.severity, self.rule
+        )
+    }
+}
+
+impl Message for InstrumentDiscovered {
+    fn instrument_uid(&self) -> &str {
+        &self.instrument_uid
+    }
+
+    fn text(&self) -> String {
+        format!("New instrument {} seen at {}", self.instrument_uid, self.first_seen_at)
+    }
+}
+
+/// Delivery target resolved from `ChannelConfig`
+pub enum Channel {
+    Telegram { url: String, chat_id: String },
+    Webhook { url: String },
+    Kafka { url: String },
+}
+
+impl Channel {
+    /// `None` if the Telegram token variable is not set
+    pub fn from_config(name: &str, config: &ChannelConfig) -> Option<Self> {
+        match config {
+            ChannelConfig::Telegram { bot_token_env, chat_id } => match std::env::var(bot_token_env) {
+                Ok(token) => Some(Channel::Telegram {
+                    url: format!("https://api.telegram.org/bot{}/sendMessage", token),
+                    chat_id: chat_id.clone(),
+                }),
+                Err(_) => {
+                    warn!("Channel {}: {} is not set, disabling it", name, bot_token_env);
+                    None
+                }
+            },
+            ChannelConfig::Webhook { url } => Some(Channel::Webhook { url: url.clone() }),
+            ChannelConfig::Kafka { rest_proxy_url, topic } => Some(Channel::Kafka {
+                url: format!("{}/topics/{}", rest_proxy_url.trim_end_matches('/'), topic),
+            }),
+        }
+    }
+
+    pub async fn send(&self, http: &reqwest::Client, notification: &impl Message) -> Result<(), reqwest::Error> {
+        let request = match self {
+            Channel::Telegram { url, chat_id } => http
+                .post(url)
+                .json(&json!({ "chat_id": chat_id, "text": notification.text() })),
+            Channel::Webhook { url } => http.post(url).json(notification),
+            Channel::Kafka { url } => http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/vnd.kafka.json.v2+json")
+                .body(
+                    json!({
+                        "records": [{ "key": notification.instrument_uid(), "value": notification }]
+                    })
+                    .to_string(),
+                ),
+        };
+
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+// File: src/services/notifications/mod.rs
+pub mod channels;
+pub mod routing;
+
+use crate::env_config::models::app_config::{NotificationsConfig, PortfolioConfig};
+use crate::services::latency::{now_ms, TracedSignal};
+use crate::services::signals::SignalEvent;
+use channels::{Channel, InstrumentDiscovered, Notification};
+use chrono::Utc;
+use routing::RoutingTable;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends signal events to the channels selected by the routing rules
+pub struct Notifier {
+    enabled: bool,
+    max_signal_age_seconds: i64,
+    discovery_channels: Vec<String>,
+    http: reqwest::Client,
+    channels: HashMap<String, Channel>,
+    confirmation_timeframes: Vec<u32>,
+    routing: Mutex<RoutingTable>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig, portfolios: &[PortfolioConfig]) -> Self {
+        let channels: HashMap<String, Channel> = config
+            .channels
+            .iter()
+            .filter_map(|(name, channel)| Some((name.clone(), Channel::from_config(name, channel)?)))
+            .collect();
+
+        if config.enabled {
+            info!(
+                "Notifications enabled: {} channels, {} routing rules",
+                channels.len(),
+                config.rules.len()
+            );
+        }
+
+        let routing = RoutingTable::new(&config.rules, portfolios);
+
+        Self {
+            enabled: config.enabled,
+            max_signal_age_seconds: config.max_signal_age.as_secs() as i64,
+            discovery_channels: config.discovery_channels.clone(),
+            http: reqwest::Client::builder()
+                .timeout(SEND_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            channels,
+            confirmation_timeframes: routing.confirmation_timeframes(),
+            routing: Mutex::new(routing),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Timeframes (minutes) whose trend at each event `publish` needs for rule confirmation
+    pub fn confirmation_timeframes(&self) -> &[u32] {
+        &self.confirmation_timeframes
+    }
+
+    /// Catch-up runs recompute old candles, their signals are history
+    pub fn is_fresh(&self, event: &SignalEvent, now: i64) -> bool {
+        event.time >= now - self.max_signal_age_seconds
+    }
+
+    /// Sends a "new instrument" event per UID to the discovery channels
+    pub async fn announce_instruments(&self, instrument_uids: &[String]) {
+        if !self.enabled || self.discovery_channels.is_empty() {
+            return;
+        }
+
+        let now = Utc::now().timestamp();
+        for instrument_uid in instrument_uids {
+            let message = InstrumentDiscovered::new(instrument_uid, now);
+            for name in &self.discovery_channels {
+                let Some(channel) = self.channels.get(name) else {
+                    warn!("Discovery channel {} is not configured", name);
+                    continue;
+                };
+                if let Err(e) = channel.send(&self.http, &message).await {
+                    error!("Failed to announce {} to {}: {}", instrument_uid, name, e);
+                }
+            }
+        }
+    }
+
+    /// Routes and sends fresh events of an instrument, recording emission and delivery in their
+    /// latency; delivery failures are only logged. `trends` holds the trend per (event time,
+    /// timeframe) for confirmation.
+    pub async fn publish(
+        &self,
+        instrument_uid: &str,
+        signals: &mut [TracedSignal],
+        trends: &HashMap<(i64, u32), i8>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let now = Utc::now().timestamp();
+        for signal in signals.iter_mut() {
+            let event = signal.event;
+            if !self.is_fresh(&event, now) {
+                continue;
+            }
+            signal.latency.emitted_ms = now_ms();
+            let event_trends: HashMap<u32, i8> = self
+                .confirmation_timeframes
+                .iter()
+                .filter_map(|&minutes| Some((minutes, *trends.get(&(event.time, minutes))?)))
+                .collect();
+
+            let routes = self
+                .routing
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .route(instrument_uid, &event, &event_trends, now);
+
+            for route in routes {
+                let Some(channel) = self.channels.get(&route.channel) else {
+                    warn!("Rule {} routes to unknown channel {}", route.rule, route.channel);
+                    continue;
+                };
+
+                let notification = Notification {
+                    trace_id: signal.trace_id().to_string(),
+                    instrument_uid: instrument_uid.to_string(),
+                    time: event.time,
+                    kind: event.kind,
+                    direction: event.kind.direction(),
+                    severity: event.severity,
+                    rule: route.rule,
+                };
+                let sent = channel.send(&self.http, &notification).await;
+                match &sent {
+                    Ok(()) => debug!("Sent {} to {}", notification.trace_id, route.channel),
+                    Err(e) => error!("Failed to send {} to {}: {}", notification.trace_id, route.channel, e),
+                }
+                signal.record_delivery(sent.is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+// File: src/services/notifications/routing.rs
+use crate::env_config::models::app_config::{PortfolioConfig, RoutingRuleConfig};
+use crate::services::indicators::session::window_minutes;
+use crate::services::signals::{Severity, SignalEvent, SignalKind};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use tracing::warn;
+
+const RATE_WINDOW_SECONDS: i64 = 3600;
+
+/// Routing rule with its rate limit state
+struct Rule {
+    name: String,
+    signals: HashSet<SignalKind>, // empty - every kind
+    instruments: HashSet<String>, // empty - every instrument
+    min_severity: Severity,
+    channels: Vec<String>,
+    quiet_hours: Option<(i32, i32)>,
+    max_per_hour: u32,
+    confirm_timeframes: Vec<u32>, // minutes
+    sent: VecDeque<i64>, // times of notifications within the rate window
+}
+
+impl Rule {
+    fn new(config: &RoutingRuleConfig, portfolios: &[PortfolioConfig]) -> Self {
+        let mut instruments: HashSet<String> = config.instruments.iter().cloned().collect();
+        if let Some(watchlist) = &config.watchlist {
+            match portfolios.iter().find(|portfolio| &portfolio.name == watchlist) {
+                Some(portfolio) => instruments
+                    .extend(portfolio.members.iter().map(|member| member.instrument_uid.clone())),
+                None => warn!("Rule {}: unknown watchlist {}", config.name, watchlist),
+            }
+        }
+
+        Self {
+            name: config.name.clone(),
+            signals: config
+                .signals
+                .iter()
+                .filter_map(|name| parse(&config.name, name))
+                .collect(),
+            instruments,
+            min_severity: config
+                .min_severity
+                .as_deref()
+                .and_then(|name| parse(&config.name, name))
+                .unwrap_or_default(),
+            channels: config.channels.clone(),
+            quiet_hours: config.quiet_hours.as_ref().map(window_minutes),
+            max_per_hour: config.max_per_hour,
+            confirm_timeframes: config.confirm_timeframes.clone(),
+            sent: VecDeque::new(),
+        }
+    }
+
+    fn matches(&self, instrument_uid: &str, event: &SignalEvent) -> bool {
+        (self.signals.is_empty() || self.signals.contains(&event.kind))
+            && (self.instruments.is_empty() || self.instruments.contains(instrument_uid))
+            && event.severity >= self.min_severity
+    }
+
+    /// Every confirmation timeframe trends in the signal's direction; an unknown trend fails
+    fn is_confirmed(&self, event: &SignalEvent, trends: &HashMap<u32, i8>) -> bool {
+        self.confirm_timeframes
+            .iter()
+            .all(|minutes| trends.get(minutes) == Some(&event.kind.direction()))
+    }
+
+    fn is_quiet(&self, now: i64) -> bool {
+        let Some((start, end)) = self.quiet_hours else {
+            return false;
+        };
+        let minute = (now.rem_euclid(86_400) / 60) as i32;
+        if start <= end {
+            start <= minute && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    }
+
+    /// Takes one notification from the hourly budget, false if it is exhausted
+    fn take_budget(&mut self, now: i64) -> bool {
+        if self.max_per_hour == 0 {
+            return true;
+        }
+        while self.sent.front().is_some_and(|&time| time <= now - RATE_WINDOW_SECONDS) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.max_per_hour as usize {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// Unknown names are logged and ignored rather than failing startup
+fn parse<T: DeserializeOwned>(rule: &str, name: &str) -> Option<T> {
+    match serde_json::from_value(serde_json::Value::String(name.to_string())) {
+        Ok(value) => Some(value),
+        Err(_) => {
+            warn!("Rule {}: unknown value {}, ignoring it", rule, name);
+            None
+        }
+    }
+}
+
+/// Matches signal events against the routing rules
+pub struct RoutingTable {
+    rules: Vec<Rule>,
+}
+
+/// Channel selected for an event and the rule that selected it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub rule: String,
+    pub channel: String,
+}
+
+impl RoutingTable {
+    pub fn new(rules: &[RoutingRuleConfig], portfolios: &[PortfolioConfig]) -> Self {
+        Self {
+            rules: rules.iter().map(|rule| Rule::new(rule, portfolios)).collect(),
+        }
+    }
+
+    /// Timeframes (minutes) whose trend any rule needs to confirm a signal
+    pub fn confirmation_timeframes(&self) -> Vec<u32> {
+        let timeframes: BTreeSet<u32> =
+            self.rules.iter().flat_map(|rule| rule.confirm_timeframes.iter().copied()).collect();
+        timeframes.into_iter().collect()
+    }
+
+    /// Channels an event goes to at `now` (unix seconds), each at most once. `trends` holds
+    /// the trend per timeframe at the event. Rules in quiet hours or over their hourly limit
+    /// drop the event.
+    pub fn route(
+        &mut self,
+        instrument_uid: &str,
+        event: &SignalEvent,
+        trends: &HashMap<u32, i8>,
+        now: i64,
+    ) -> Vec<Route> {
+        let mut routes: Vec<Route> = Vec::new();
+
+        for rule in &mut self.rules {
+            if !rule.matches(instrument_uid, event)
+                || !rule.is_confirmed(event, trends)
+                || rule.is_quiet(now)
+                || !rule.take_budget(now)
+            {
+                continue;
+            }
+            for channel in &rule.channels {
+                if routes.iter().all(|route| &route.channel != channel) {
+                    routes.push(Route {
+                        rule: rule.name.clone(),
+                        channel: channel.clone(),
+                    });
+                }
+            }
+        }
+
+        routes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env_config::models::app_config::{PortfolioMember, SessionWindow};
+
+    fn rule(name: &str, channels: &[&str]) -> RoutingRuleConfig {
+        RoutingRuleConfig {
+            name: name.to_string(),
+            signals: Vec::new(),
+            instruments: Vec::new(),
+            watchlist: None,
+            min_severity: None,
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+            quiet_hours: None,
+            max_per_hour: 0,
+            confirm_timeframes: Vec::new(),
+        }
+    }
+
+    fn event(kind: SignalKind, severity: Severity) -> SignalEvent {
+        SignalEvent {
+            time: 0,
+            kind,
+            score: 0.0,
+            severity,
+        }
+    }
+
+    fn channels(routes: Vec<Route>) -> Vec<String> {
+        routes.into_iter().map(|route| route.channel).collect()
+    }
+
+    #[test]
+    fn test_routing_rules() {
+        let portfolios = vec![PortfolioConfig {
+            name: "core".to_string(),
+            members: vec![PortfolioMember {
+                instrument_uid: "sber".to_string(),
+                weight: 1.0,
+            }],
+        }];
+        let crosses = RoutingRuleConfig {
+            signals: vec!["golden_cross".to_string(), "death_cross".to_string()],
+            watchlist: Some("core".to_string()),
+            ..rule("crosses", &["telegram", "kafka"])
+        };
+        let important = RoutingRuleConfig {
+            min_severity: Some("medium".to_string()),
+            max_per_hour: 1,
+            ..rule("important", &["webhook", "kafka"])
+        };
+        let overnight = RoutingRuleConfig {
+            quiet_hours: Some(SessionWindow {
+                start: "22:00:00".parse().unwrap(),
+                end: "06:00:00".parse().unwrap(),
+            }),
+            ..rule("overnight", &["pager"])
+        };
+        let mut table = RoutingTable::new(&[crosses, important, overnight], &portfolios);
+        let no_trends = HashMap::new();
+
+        // 12:00 UTC
+        let noon = 12 * 3600;
+        assert_eq!(
+            channels(table.route("sber", &event(SignalKind::GoldenCross, Severity::Medium), &no_trends, noon)),
+            vec!["telegram", "kafka", "webhook", "pager"]
+        );
+        // Hourly limit of "important" is used up, the watchlist excludes "gazp"
+        assert_eq!(channels(table.route("gazp", &event(SignalKind::DeathCross, Severity::High), &no_trends, noon + 60)), vec!["pager"]);
+        // Below the severity threshold and at 23:00 UTC
+        assert!(table.route("gazp", &event(SignalKind::RsiOversold, Severity::Low), &no_trends, 23 * 3600).is_empty());
+        // Budget is back after an hour
+        assert_eq!(
+            channels(table.route("gazp", &event(SignalKind::DeathCross, Severity::Medium), &no_trends, noon + 3600)),
+            vec!["webhook", "kafka", "pager"]
+        );
+    }
+
+    #[test]
+    fn test_trend_confirmation() {
+        let confirmed = RoutingRuleConfig {
+            confirm_timeframes: vec![15, 60],
+            ..rule("confirmed", &["telegram"])
+        };
+        let mut table = RoutingTable::new(&[confirmed, rule("all", &["kafka"])], &[]);
+        assert_eq!(table.confirmation_timeframes(), vec![15, 60]);
+
+        let golden = event(SignalKind::GoldenCross, Severity::Low);
+        let agreeing = HashMap::from([(15, 1), (60, 1)]);
+        let mixed = HashMap::from([(15, 1), (60, -1)]);
+        let partial = HashMap::from([(15, 1)]);
+
+        assert_eq!(channels(table.route("sber", &golden, &agreeing, 0)), vec!["telegram", "kafka"]);
+        assert_eq!(channels(table.route("sber", &golden, &mixed, 0)), vec!["kafka"]);
+        assert_eq!(channels(table.route("sber", &golden, &partial, 0)), vec!["kafka"]);
+        // A death cross needs the trends down
+        let death = event(SignalKind::DeathCross, Severity::Low);
+        assert_eq!(channels(table.route("sber", &death, &agreeing, 0)), vec!["kafka"]);
+    }
+}
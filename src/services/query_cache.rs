@@ -0,0 +1,207 @@
+// File: src/services/query_cache.rs
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Dependency marker for results that depend on every instrument (invalidated by any insert)
+pub const ALL_INSTRUMENTS: &str = "*";
+
+/// In-memory cache of API query results keyed by query parameters.
+///
+/// Every entry records the instruments it was computed from; inserting indicator rows for an
+/// instrument drops those entries, the TTL only bounds staleness of anything missed. A result
+/// whose instruments were invalidated while it was computed isn't stored.
+pub struct QueryCache {
+    state: Mutex<CacheState>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    by_instrument: HashMap<String, HashSet<String>>,
+    // Invalidations per instrument, and of any instrument for `ALL_INSTRUMENTS`
+    generations: HashMap<String, u64>,
+    invalidations: u64,
+}
+
+/// Invalidation counters of the instruments of a result, read before it is computed
+#[derive(Debug, PartialEq)]
+pub struct Generation(Vec<u64>);
+
+struct CacheEntry {
+    value: Arc<Value>,
+    instruments: Vec<String>,
+    inserted_at: Instant,
+}
+
+impl CacheState {
+    fn generation(&self, instruments: &[String]) -> Generation {
+        Generation(
+            instruments
+                .iter()
+                .map(|uid| match uid.as_str() {
+                    ALL_INSTRUMENTS => self.invalidations,
+                    uid => self.generations.get(uid).copied().unwrap_or(0),
+                })
+                .collect(),
+        )
+    }
+
+    fn remove(&mut self, key: &str) {
+        let Some(entry) = self.entries.remove(key) else {
+            return;
+        };
+        for uid in entry.instruments {
+            if let Some(keys) = self.by_instrument.get_mut(&uid) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.by_instrument.remove(&uid);
+                }
+            }
+        }
+    }
+}
+
+impl QueryCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            state: Mutex::new(CacheState::default()),
+            capacity,
+            ttl,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<Arc<Value>> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let expired = state.entries.get(key)?.inserted_at.elapsed() > self.ttl;
+        if expired {
+            state.remove(key);
+            return None;
+        }
+
+        state.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    /// Current generation of `instruments`, to be passed to `insert` of the result computed next
+    pub fn generation(&self, instruments: &[String]) -> Generation {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).generation(instruments)
+    }
+
+    /// Stores a result computed from `instruments` (use `ALL_INSTRUMENTS` for cross-instrument
+    /// results), unless one of them was invalidated since `generation` was read
+    pub fn insert(
+        &self,
+        key: String,
+        instruments: Vec<String>,
+        generation: Generation,
+        value: Value,
+    ) -> Arc<Value> {
+        let value = Arc::new(value);
+        if self.capacity == 0 {
+            return value;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.generation(&instruments) != generation {
+            debug!("Not caching {}: its instruments changed while it was computed", key);
+            return value;
+        }
+        state.remove(&key);
+
+        // Evict the oldest entry when full
+        if state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.remove(&oldest);
+            }
+        }
+
+        for uid in &instruments {
+            state.by_instrument.entry(uid.clone()).or_default().insert(key.clone());
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                value: value.clone(),
+                instruments,
+                inserted_at: Instant::now(),
+            },
+        );
+
+        value
+    }
+
+    /// Drops results depending on `instrument_uid`, called after its rows change
+    pub fn invalidate(&self, instrument_uid: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state.generations.entry(instrument_uid.to_string()).or_default() += 1;
+        state.invalidations += 1;
+
+        let mut keys: Vec<String> = Vec::new();
+        for uid in [instrument_uid, ALL_INSTRUMENTS] {
+            if let Some(dependent) = state.by_instrument.get(uid) {
+                keys.extend(dependent.iter().cloned());
+            }
+        }
+
+        for key in &keys {
+            state.remove(key);
+        }
+
+        if !keys.is_empty() {
+            debug!("Invalidated {} cached query results for {}", keys.len(), instrument_uid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidate_drops_dependent_entries() {
+        let cache = QueryCache::new(10, Duration::from_secs(60));
+        for (key, uid, value) in [("a", "uid-1", 1), ("b", "uid-2", 2), ("all", ALL_INSTRUMENTS, 3)] {
+            let instruments = vec![uid.to_string()];
+            let generation = cache.generation(&instruments);
+            cache.insert(key.to_string(), instruments, generation, Value::from(value));
+        }
+
+        cache.invalidate("uid-1");
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("all").is_none());
+        assert_eq!(cache.get("b").as_deref(), Some(&Value::from(2)));
+    }
+
+    #[test]
+    fn test_insert_after_invalidate_is_skipped() {
+        let cache = QueryCache::new(10, Duration::from_secs(60));
+        let instruments = vec!["uid-1".to_string()];
+        let all = vec![ALL_INSTRUMENTS.to_string()];
+
+        // Rows of uid-1 change while both results are being computed
+        let generation = cache.generation(&instruments);
+        let all_generation = cache.generation(&all);
+        cache.invalidate("uid-1");
+        cache.insert("a".to_string(), instruments.clone(), generation, Value::from(1));
+        cache.insert("all".to_string(), all.clone(), all_generation, Value::from(2));
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("all").is_none());
+
+        // Invalidating another instrument leaves a result of uid-1 cacheable
+        let generation = cache.generation(&instruments);
+        cache.invalidate("uid-2");
+        cache.insert("a".to_string(), instruments, generation, Value::from(3));
+        assert_eq!(cache.get("a").as_deref(), Some(&Value::from(3)));
+    }
+}
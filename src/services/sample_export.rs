@@ -0,0 +1,109 @@
+// File: src/services/sample_export.rs
+use crate::db::clickhouse::models::indicator::DbIndicator;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Picks up to `count` instruments at random
+pub fn sample_instruments(mut instrument_uids: Vec<String>, count: usize) -> Vec<String> {
+    instrument_uids.sort_by_cached_key(|_| Uuid::new_v4());
+    instrument_uids.truncate(count);
+    instrument_uids
+}
+
+/// Random alias for every instrument; a new export never reuses aliases of a previous one
+pub fn scramble_uids(instrument_uids: &[String]) -> HashMap<String, String> {
+    instrument_uids
+        .iter()
+        .map(|uid| (uid.clone(), Uuid::new_v4().to_string()))
+        .collect()
+}
+
+/// Replaces instrument UIDs by their aliases and multiplies every price-denominated column by
+/// `price_scale`. Returns, ratios and oscillators are scale-invariant and kept as is.
+pub fn anonymize(
+    rows: Vec<DbIndicator>,
+    aliases: &HashMap<String, String>,
+    price_scale: f64,
+) -> Vec<DbIndicator> {
+    rows.into_iter()
+        .map(|mut row| {
+            if let Some(alias) = aliases.get(&row.instrument_uid) {
+                row.instrument_uid = alias.clone();
+            }
+            if price_scale != 1.0 {
+                scale_prices(&mut row, price_scale);
+            }
+            row
+        })
+        .collect()
+}
+
+fn scale_prices(row: &mut DbIndicator, scale: f64) {
+    for value in [
+        &mut row.open_price,
+        &mut row.high_price,
+        &mut row.low_price,
+        &mut row.close_price,
+        &mut row.ma_10,
+        &mut row.ma_30,
+        &mut row.ma_diff,
+        &mut row.ha_open,
+        &mut row.ha_high,
+        &mut row.ha_low,
+        &mut row.ha_close,
+        &mut row.pivot_p,
+        &mut row.pivot_r1,
+        &mut row.pivot_r2,
+        &mut row.pivot_r3,
+        &mut row.pivot_s1,
+        &mut row.pivot_s2,
+        &mut row.pivot_s3,
+        &mut row.high_60,
+        &mut row.low_60,
+        &mut row.high_240,
+        &mut row.low_240,
+        &mut row.wma,
+        &mut row.hma,
+        &mut row.vwma_20,
+        &mut row.bull_power,
+        &mut row.bear_power,
+    ] {
+        *value *= scale;
+    }
+    // Price differences too: momentum with roc would give the close back
+    row.momentum.iter_mut().for_each(|value| *value *= scale);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymize() {
+        let row = DbIndicator {
+            instrument_uid: "secret".to_string(),
+            close_price: 250.0,
+            ma_10: 200.0,
+            rsi_14: 55.0,
+            gap_pct: 1.5,
+            hma_slope: 0.2,
+            roc: vec![2.0],
+            momentum: vec![5.0],
+            ..Default::default()
+        };
+        let aliases = scramble_uids(&["secret".to_string()]);
+
+        let rows = anonymize(vec![row], &aliases, 0.5);
+
+        assert_eq!(rows[0].instrument_uid, aliases["secret"]);
+        assert_ne!(rows[0].instrument_uid, "secret");
+        assert_eq!(rows[0].close_price, 125.0);
+        assert_eq!(rows[0].ma_10, 100.0);
+        assert_eq!(rows[0].rsi_14, 55.0);
+        assert_eq!(rows[0].gap_pct, 1.5);
+        // Percent slope kept, price-unit momentum scaled
+        assert_eq!(rows[0].hma_slope, 0.2);
+        assert_eq!(rows[0].roc, vec![2.0]);
+        assert_eq!(rows[0].momentum, vec![2.5]);
+    }
+}
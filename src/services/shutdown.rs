@@ -0,0 +1,99 @@
+// File: src/services/shutdown.rs
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// Process-wide shutdown signal, set once on SIGTERM/SIGINT. The HTTP server stops accepting
+/// connections, background loops exit and indicator runs stop after the batch in flight.
+pub struct Shutdown {
+    sender: watch::Sender<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            sender: watch::Sender::new(false),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once shutdown is triggered
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives in `self`, so the channel cannot close while waiting
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Triggers shutdown on SIGINT or, on Unix, SIGTERM
+    pub async fn listen_for_signals(&self) {
+        let ctrl_c = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                error!("Failed to listen for SIGINT: {}", e);
+                std::future::pending::<()>().await;
+            }
+        };
+
+        #[cfg(unix)]
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut signal) => {
+                    signal.recv().await;
+                }
+                Err(e) => {
+                    error!("Failed to listen for SIGTERM: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = ctrl_c => info!("Received SIGINT, shutting down"),
+            _ = terminate => info!("Received SIGTERM, shutting down"),
+        }
+        self.trigger();
+    }
+}
+
+/// Waits for shutdown of background loops; never resolves without a registered `Shutdown`
+pub async fn wait_shutdown(shutdown: Option<&Shutdown>) {
+    match shutdown {
+        Some(shutdown) => shutdown.wait().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_resolves_after_trigger() {
+        let shutdown = std::sync::Arc::new(Shutdown::new());
+        assert!(!shutdown.is_triggered());
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+        shutdown.trigger();
+        waiter.await.unwrap();
+
+        assert!(shutdown.is_triggered());
+        // Waiting after the fact returns at once
+        shutdown.wait().await;
+    }
+}
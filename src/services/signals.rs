@@ -0,0 +1,302 @@
+// File: src/services/signals.rs
+use crate::db::clickhouse::models::indicator::DbIndicator;
+use crate::env_config::models::app_config::SignalsConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Kind of a discrete signal event derived from the indicator columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    GoldenCross,
+    DeathCross,
+    RsiOversold,
+    RsiOverbought,
+}
+
+impl SignalKind {
+    /// snake_case name, as in the API and config
+    pub fn name(self) -> &'static str {
+        match self {
+            SignalKind::GoldenCross => "golden_cross",
+            SignalKind::DeathCross => "death_cross",
+            SignalKind::RsiOversold => "rsi_oversold",
+            SignalKind::RsiOverbought => "rsi_overbought",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
+
+    /// 1 - bullish, -1 - bearish
+    pub fn direction(self) -> i8 {
+        match self {
+            SignalKind::GoldenCross | SignalKind::RsiOversold => 1,
+            SignalKind::DeathCross | SignalKind::RsiOverbought => -1,
+        }
+    }
+}
+
+/// Importance of a signal event, used to route notifications
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn from_score(score: f64) -> Self {
+        if score >= 0.66 {
+            Severity::High
+        } else if score >= 0.33 {
+            Severity::Medium
+        } else {
+            Severity::Low
+        }
+    }
+}
+
+/// RSI this far past the 30/70 zone threshold counts as a full-strength extreme
+const RSI_FULL_DEPTH: f64 = 10.0;
+/// Volume z-score counted as full confirmation
+const VOLUME_FULL_ZSCORE: f64 = 3.0;
+/// Share of the score taken by the signal itself, the rest is volume confirmation
+const STRENGTH_WEIGHT: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalEvent {
+    pub time: i64,
+    pub kind: SignalKind,
+    pub score: f64, // 0..1, see `signal_score`
+    pub severity: Severity,
+}
+
+impl SignalEvent {
+    pub fn new(row: &DbIndicator, kind: SignalKind) -> Self {
+        let score = signal_score(row, kind);
+        Self {
+            time: row.time,
+            kind,
+            score,
+            severity: Severity::from_score(score),
+        }
+    }
+}
+
+/// How decisive a signal is, from 0 to 1: the MA spread at a cross in units of the 30-minute
+/// volatility, or how far RSI went past the zone threshold, blended with the volume z-score
+pub fn signal_score(row: &DbIndicator, kind: SignalKind) -> f64 {
+    let strength = match kind {
+        SignalKind::GoldenCross | SignalKind::DeathCross => {
+            if row.close_price > 0.0 && row.volatility_30 > 0.0 {
+                row.ma_diff.abs() / row.close_price / row.volatility_30
+            } else {
+                0.0
+            }
+        }
+        SignalKind::RsiOversold => (30.0 - row.rsi_14) / RSI_FULL_DEPTH,
+        SignalKind::RsiOverbought => (row.rsi_14 - 70.0) / RSI_FULL_DEPTH,
+    };
+    let volume = row.volume_norm / VOLUME_FULL_ZSCORE;
+
+    STRENGTH_WEIGHT * strength.clamp(0.0, 1.0) + (1.0 - STRENGTH_WEIGHT) * volume.clamp(0.0, 1.0)
+}
+
+/// Extracts signal events from rows ordered by time.
+///
+/// MA crosses are taken as is; RSI extremes fire only when the zone is entered, so a long
+/// overbought stretch produces one event rather than one per candle.
+pub fn detect_signals(rows: &[DbIndicator]) -> Vec<SignalEvent> {
+    let mut events = Vec::new();
+    let mut prev_rsi_zone = 0;
+
+    for row in rows {
+        match row.ma_cross {
+            1 => events.push(SignalEvent::new(row, SignalKind::GoldenCross)),
+            -1 => events.push(SignalEvent::new(row, SignalKind::DeathCross)),
+            _ => {}
+        }
+
+        if row.rsi_zone != prev_rsi_zone {
+            match row.rsi_zone {
+                1 => events.push(SignalEvent::new(row, SignalKind::RsiOversold)),
+                -1 => events.push(SignalEvent::new(row, SignalKind::RsiOverbought)),
+                _ => {}
+            }
+        }
+        prev_rsi_zone = row.rsi_zone;
+    }
+
+    events
+}
+
+/// Bars of the higher timeframe averaged by the fast and slow MA of `timeframe_trend`
+pub const TREND_FAST_PERIOD: usize = 10;
+pub const TREND_SLOW_PERIOD: usize = 30;
+
+/// Trend of `minutes`-minute bars resampled from 1-minute (time, close) pairs ordered by time:
+/// 1 if MA(10) of the bar closes is above MA(30), -1 if below, 0 if flat or too few bars
+pub fn timeframe_trend(closes: &[(i64, f64)], minutes: u32) -> i8 {
+    let bar_seconds = i64::from(minutes.max(1)) * 60;
+    let mut bars: Vec<(i64, f64)> = Vec::new();
+    for &(time, close) in closes {
+        let bar = time.div_euclid(bar_seconds);
+        match bars.last_mut() {
+            Some(last) if last.0 == bar => last.1 = close,
+            _ => bars.push((bar, close)),
+        }
+    }
+    if bars.len() < TREND_SLOW_PERIOD {
+        return 0;
+    }
+
+    let recent = &bars[bars.len() - TREND_SLOW_PERIOD..];
+    let mean = |bars: &[(i64, f64)]| bars.iter().map(|bar| bar.1).sum::<f64>() / bars.len() as f64;
+    ma_trend(mean(&recent[TREND_SLOW_PERIOD - TREND_FAST_PERIOD..]), mean(recent))
+}
+
+/// 1 if the fast moving average is above the slow one, -1 if below, 0 if flat
+pub fn ma_trend(fast: f64, slow: f64) -> i8 {
+    let spread = fast - slow;
+    if spread > 0.0 {
+        1
+    } else if spread < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Minimum time between two emitted events of the same kind on one instrument
+pub struct SignalCooldown {
+    default_seconds: i64,
+    by_kind: HashMap<SignalKind, i64>,
+}
+
+impl SignalCooldown {
+    pub fn new(config: &SignalsConfig) -> Self {
+        let by_kind = config
+            .cooldown_by_kind
+            .iter()
+            .filter_map(|(name, cooldown)| match SignalKind::from_name(name) {
+                Some(kind) => Some((kind, cooldown.as_secs() as i64)),
+                None => {
+                    warn!("Unknown signal kind {} in cooldown_by_kind, ignoring it", name);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            default_seconds: config.cooldown.as_secs() as i64,
+            by_kind,
+        }
+    }
+
+    pub fn seconds(&self, kind: SignalKind) -> i64 {
+        self.by_kind.get(&kind).copied().unwrap_or(self.default_seconds)
+    }
+
+    /// Drops events closer than the cooldown to the previous emitted event of the same kind.
+    /// `last_emitted` holds the time of the last emitted event per kind and is updated.
+    pub fn apply(
+        &self,
+        events: Vec<SignalEvent>,
+        last_emitted: &mut HashMap<SignalKind, i64>,
+    ) -> Vec<SignalEvent> {
+        events
+            .into_iter()
+            .filter(|event| {
+                let cooled_down = last_emitted
+                    .get(&event.kind)
+                    .is_none_or(|&last| event.time - last >= self.seconds(event.kind));
+                if cooled_down {
+                    last_emitted.insert(event.kind, event.time);
+                }
+                cooled_down
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cooldown() {
+        let config = SignalsConfig {
+            cooldown: Duration::from_secs(3600),
+            cooldown_by_kind: HashMap::from([("golden_cross".to_string(), Duration::ZERO)]),
+        };
+        let cooldown = SignalCooldown::new(&config);
+        let event = |time, kind| SignalEvent {
+            time,
+            kind,
+            score: 0.0,
+            severity: Severity::Low,
+        };
+
+        let mut last_emitted = HashMap::from([(SignalKind::RsiOversold, 0)]);
+        let events = vec![
+            event(600, SignalKind::RsiOversold),
+            event(1200, SignalKind::GoldenCross),
+            event(1260, SignalKind::GoldenCross),
+            event(3600, SignalKind::RsiOversold),
+            event(4000, SignalKind::RsiOversold),
+        ];
+
+        let emitted: Vec<i64> = cooldown.apply(events, &mut last_emitted).iter().map(|e| e.time).collect();
+        assert_eq!(emitted, vec![1200, 1260, 3600]);
+        assert_eq!(last_emitted[&SignalKind::RsiOversold], 3600);
+    }
+
+    #[test]
+    fn test_signal_score() {
+        let row = DbIndicator {
+            close_price: 100.0,
+            ma_diff: 0.1,
+            volatility_30: 0.002,
+            rsi_14: 25.0,
+            volume_norm: 1.5,
+            ..Default::default()
+        };
+
+        // Spread of half a volatility unit, volume z-score of half the full confirmation
+        let cross = SignalEvent::new(&row, SignalKind::GoldenCross);
+        assert!((cross.score - 0.5).abs() < 1e-9);
+        assert_eq!(cross.severity, Severity::Medium);
+
+        // RSI 5 points into the zone scores like the cross; the wrong zone adds nothing
+        assert!((signal_score(&row, SignalKind::RsiOversold) - 0.5).abs() < 1e-9);
+        assert!((signal_score(&row, SignalKind::RsiOverbought) - 0.2).abs() < 1e-9);
+
+        let quiet = DbIndicator { volume_norm: -1.0, ma_diff: 0.0, ..row.clone() };
+        assert_eq!(SignalEvent::new(&quiet, SignalKind::DeathCross).severity, Severity::Low);
+        let loud = DbIndicator { volume_norm: 4.0, rsi_14: 15.0, ..row };
+        assert_eq!(SignalEvent::new(&loud, SignalKind::RsiOversold).severity, Severity::High);
+    }
+
+    #[test]
+    fn test_timeframe_trend() {
+        // Rising for 10 hours, then falling for the last 2
+        let closes: Vec<(i64, f64)> = (0..720)
+            .map(|minute| {
+                let price = if minute < 600 { minute as f64 } else { 1200.0 - minute as f64 };
+                (minute * 60, price)
+            })
+            .collect();
+
+        // 15-minute bars still trend up, 1-minute bars already turned
+        assert_eq!(timeframe_trend(&closes, 15), 1);
+        assert_eq!(timeframe_trend(&closes, 1), -1);
+        // 60-minute bars: only 12 of the 30 needed
+        assert_eq!(timeframe_trend(&closes, 60), 0);
+    }
+}
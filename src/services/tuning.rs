@@ -0,0 +1,217 @@
+// File: src/services/tuning.rs
+use crate::db::clickhouse::models::pipeline_profile::DbPipelineRunSummary;
+use crate::env_config::models::app_config::IndicatorsUpdaterConfig;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Share of batches with a failed insert above which the batch size is halved
+const MAX_ERROR_RATE: f64 = 0.05;
+/// Share of wall time spent waiting on the databases above which more concurrency pays off
+const IO_BOUND_SHARE: f64 = 0.7;
+/// Rows per insert above which ClickHouse async insert buffering stops helping
+const LARGE_INSERT_ROWS: f64 = 10_000.0;
+/// Rows per insert below which async insert avoids creating too many parts
+const SMALL_INSERT_ROWS: f64 = 1_000.0;
+const MIN_BATCH_SIZE: usize = 1_000;
+const RECOMMENDED_CONCURRENCY: usize = 4;
+
+/// Updater settings the advisor may change at runtime, seeded from `indicators_updater`
+pub struct PipelineTuning {
+    batch_size: AtomicUsize,
+    async_insert: AtomicBool,
+    configured_batch_size: usize,
+    concurrency: usize,
+}
+
+/// Snapshot of the tunable settings
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TuningSettings {
+    pub batch_size: usize,
+    pub concurrency: usize,
+    pub async_insert: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TuningSetting {
+    BatchSize,
+    Concurrency,
+    AsyncInsert,
+}
+
+/// Suggested change of one setting; booleans are reported as 0/1
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub setting: TuningSetting,
+    pub current: u64,
+    pub recommended: u64,
+    pub reason: String,
+    pub applied: bool,
+}
+
+impl PipelineTuning {
+    pub fn new(config: &IndicatorsUpdaterConfig) -> Self {
+        Self {
+            batch_size: AtomicUsize::new(config.batch_size),
+            async_insert: AtomicBool::new(config.async_insert),
+            configured_batch_size: config.batch_size,
+            concurrency: config.max_concurrent_instruments.max(1),
+        }
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.load(Ordering::Relaxed)
+    }
+
+    pub fn async_insert(&self) -> bool {
+        self.async_insert.load(Ordering::Relaxed)
+    }
+
+    pub fn settings(&self) -> TuningSettings {
+        TuningSettings {
+            batch_size: self.batch_size(),
+            concurrency: self.concurrency,
+            async_insert: self.async_insert(),
+        }
+    }
+
+    /// Applies a recommendation from the next run on; returns false for settings
+    /// that can only be changed in the config
+    pub fn apply(&self, recommendation: &Recommendation) -> bool {
+        match recommendation.setting {
+            TuningSetting::BatchSize => {
+                self.batch_size
+                    .store(recommendation.recommended as usize, Ordering::Relaxed);
+                true
+            }
+            TuningSetting::AsyncInsert => {
+                self.async_insert
+                    .store(recommendation.recommended != 0, Ordering::Relaxed);
+                true
+            }
+            TuningSetting::Concurrency => false,
+        }
+    }
+
+    /// Derives recommendations from the recent runs (any order)
+    pub fn recommend(&self, runs: &[DbPipelineRunSummary]) -> Vec<Recommendation> {
+        recommend(&self.settings(), self.configured_batch_size, runs)
+    }
+}
+
+fn recommend(
+    current: &TuningSettings,
+    configured_batch_size: usize,
+    runs: &[DbPipelineRunSummary],
+) -> Vec<Recommendation> {
+    let batches: u64 = runs.iter().map(|run| run.batches_total).sum();
+    if batches == 0 {
+        return Vec::new();
+    }
+
+    let errors: u64 = runs.iter().map(|run| run.insert_errors_total).sum();
+    let candles: u64 = runs.iter().map(|run| run.candles_total).sum();
+    let wall_ms: f64 = runs.iter().map(|run| run.wall_ms).sum();
+    let io_ms: f64 = runs
+        .iter()
+        .map(|run| run.fetch_ms_total + run.insert_ms_total + run.status_ms_total)
+        .sum();
+
+    let error_rate = errors as f64 / batches as f64;
+    let rows_per_insert = candles as f64 / batches as f64;
+    let io_share = if wall_ms > 0.0 { io_ms / wall_ms } else { 0.0 };
+
+    let mut recommendations = Vec::new();
+
+    if error_rate > MAX_ERROR_RATE && current.batch_size > MIN_BATCH_SIZE {
+        recommendations.push(Recommendation {
+            setting: TuningSetting::BatchSize,
+            current: current.batch_size as u64,
+            recommended: (current.batch_size / 2).max(MIN_BATCH_SIZE) as u64,
+            reason: format!("{:.1}% of batches had a failed insert", error_rate * 100.0),
+            applied: false,
+        });
+    } else if errors == 0 && current.batch_size < configured_batch_size {
+        recommendations.push(Recommendation {
+            setting: TuningSetting::BatchSize,
+            current: current.batch_size as u64,
+            recommended: (current.batch_size * 2).min(configured_batch_size) as u64,
+            reason: format!("no failed inserts in {} batches", batches),
+            applied: false,
+        });
+    }
+
+    if io_share > IO_BOUND_SHARE && error_rate <= MAX_ERROR_RATE && current.concurrency < RECOMMENDED_CONCURRENCY {
+        recommendations.push(Recommendation {
+            setting: TuningSetting::Concurrency,
+            current: current.concurrency as u64,
+            recommended: RECOMMENDED_CONCURRENCY as u64,
+            reason: format!("{:.0}% of wall time is spent waiting on the databases", io_share * 100.0),
+            applied: false,
+        });
+    }
+
+    if current.async_insert && rows_per_insert >= LARGE_INSERT_ROWS {
+        recommendations.push(Recommendation {
+            setting: TuningSetting::AsyncInsert,
+            current: 1,
+            recommended: 0,
+            reason: format!("inserts average {:.0} rows, large enough for synchronous inserts", rows_per_insert),
+            applied: false,
+        });
+    } else if !current.async_insert && rows_per_insert < SMALL_INSERT_ROWS {
+        recommendations.push(Recommendation {
+            setting: TuningSetting::AsyncInsert,
+            current: 0,
+            recommended: 1,
+            reason: format!("inserts average {:.0} rows, async insert avoids small parts", rows_per_insert),
+            applied: false,
+        });
+    }
+
+    recommendations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(batches: u64, candles: u64, insert_errors: u64, io_ms: f64, wall_ms: f64) -> DbPipelineRunSummary {
+        DbPipelineRunSummary {
+            batches_total: batches,
+            candles_total: candles,
+            insert_errors_total: insert_errors,
+            insert_ms_total: io_ms,
+            wall_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recommend() {
+        let current = TuningSettings {
+            batch_size: 100_000,
+            concurrency: 1,
+            async_insert: true,
+        };
+
+        assert!(recommend(&current, 100_000, &[]).is_empty());
+
+        // Failing inserts halve the batch, concurrency is not raised while inserts fail
+        let failing = recommend(&current, 100_000, &[run(10, 5_000, 3, 900.0, 1000.0)]);
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].setting, TuningSetting::BatchSize);
+        assert_eq!(failing[0].recommended, 50_000);
+
+        // Healthy, I/O-bound runs with large inserts
+        let healthy = recommend(&current, 100_000, &[run(10, 200_000, 0, 900.0, 1000.0)]);
+        let settings: Vec<TuningSetting> = healthy.iter().map(|r| r.setting).collect();
+        assert_eq!(settings, vec![TuningSetting::Concurrency, TuningSetting::AsyncInsert]);
+
+        // A previously reduced batch grows back towards the configured size
+        let reduced = TuningSettings { batch_size: 30_000, ..current };
+        let grow = recommend(&reduced, 100_000, &[run(10, 5_000, 0, 100.0, 1000.0)]);
+        assert_eq!(grow[0].setting, TuningSetting::BatchSize);
+        assert_eq!(grow[0].recommended, 60_000);
+    }
+}
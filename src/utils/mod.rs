@@ -1 +1,2 @@
+pub mod retry;
 pub mod utils_http;
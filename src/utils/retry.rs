@@ -0,0 +1,109 @@
+// File: src/utils/retry.rs
+use crate::env_config::models::app_config::RetryConfig;
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tracing::warn;
+
+/// Error messages of failures that may pass on their own: dropped connections, timeouts and
+/// ClickHouse overload. Resource limits are not here, a smaller batch is what helps with them.
+const TRANSIENT_MARKERS: [&str; 10] = [
+    "connection refused",
+    "connection reset",
+    "connection closed",
+    "broken pipe",
+    "unexpected eof",
+    "timed out",
+    "timeout",
+    "network",
+    "too_many_simultaneous_queries",
+    "temporarily unavailable",
+];
+
+/// Whether a failed database call is worth retrying
+pub fn is_transient(error: &impl Display) -> bool {
+    let message = error.to_string().to_lowercase();
+    TRANSIENT_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Runs `call` until it succeeds, fails with a non-transient error or `max_attempts` are
+/// used up. The pause doubles after each attempt up to `max_backoff`, with random jitter
+/// of up to half of it so concurrent instruments don't retry in lockstep.
+pub async fn with_retry<T, E, F, Fut>(config: &RetryConfig, operation: &str, mut call: F) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut backoff_ms = config.initial_backoff.as_millis() as u64;
+    let mut attempt = 1;
+    loop {
+        // Errors such as Box<dyn Error> aren't Send, so none is held across the pause
+        let pause_ms = match call().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < config.max_attempts && is_transient(&e) => {
+                let pause_ms = backoff_ms - jitter(backoff_ms / 2);
+                warn!(
+                    "{} failed (attempt {}/{}), retrying in {} ms: {}",
+                    operation, attempt, config.max_attempts, pause_ms, e
+                );
+                pause_ms
+            }
+            Err(e) => return Err(e),
+        };
+        tokio::time::sleep(Duration::from_millis(pause_ms)).await;
+        backoff_ms = (backoff_ms * 2).min(config.max_backoff.as_millis() as u64);
+        attempt += 1;
+    }
+}
+
+/// Random value in `0..=max`
+fn jitter(max: u64) -> u64 {
+    RandomState::new().build_hasher().finish() % (max + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry() {
+        let config = RetryConfig {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        // Recovers after two transient failures
+        let calls = Cell::new(0);
+        let result = with_retry(&config, "fetch", || {
+            calls.set(calls.get() + 1);
+            async { if calls.get() < 3 { Err("Connection reset by peer") } else { Ok(calls.get()) } }
+        })
+        .await;
+        assert_eq!(result, Ok(3));
+
+        // Gives up after max_attempts
+        calls.set(0);
+        let result: Result<(), _> = with_retry(&config, "fetch", || {
+            calls.set(calls.get() + 1);
+            async { Err("operation timed out") }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+
+        // Permanent errors are returned at once
+        calls.set(0);
+        let result: Result<(), _> = with_retry(&config, "insert", || {
+            calls.set(calls.get() + 1);
+            async { Err("Code: 60. DB::Exception: UNKNOWN_TABLE") }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
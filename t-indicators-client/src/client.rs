@@ -0,0 +1,472 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{header, Method, Request, StatusCode, Uri};
+use hyper_util::client::legacy::{connect::HttpConnector, Client as HyperClient};
+use hyper_util::rt::TokioExecutor;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+use crate::error::ClientError;
+use crate::models::{
+    Annotation, ApiErrorBody, ColumnStats, ColumnarResponse, ComputeRequest, CountInterval, DeadLetter, FeatureFlags,
+    FilterMode, Indicator, IndicatorFailure, IndicatorPage, IndicatorsQuery, IndicatorsQueryRequest, InstrumentFilter,
+    RecalculateResult, Run, SampleExport, SampleExportRequest, SignalCounts, SignalLatency, SignalSuppression, StatusReport,
+    TuningReport, VersionInfo,
+};
+
+pub struct ClientBuilder {
+    base_url: String,
+    bearer_token: Option<String>,
+    timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl ClientBuilder {
+    /// Bearer token sent in `Authorization` with every request
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Per-attempt timeout, 30 s by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retries of transport errors and 502/503/504 responses, 3 by default
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled on each next one; 200 ms by default
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, ClientError> {
+        let base_url = self.base_url.trim_end_matches('/').to_string();
+        base_url
+            .parse::<Uri>()
+            .map_err(|_| ClientError::InvalidUrl(base_url.clone()))?;
+
+        Ok(Client {
+            http: HyperClient::builder(TokioExecutor::new()).build_http(),
+            base_url,
+            bearer_token: self.bearer_token,
+            timeout: self.timeout,
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+        })
+    }
+}
+
+/// Client of the t-indicators API over plain HTTP
+#[derive(Clone)]
+pub struct Client {
+    http: HyperClient<HttpConnector, Full<Bytes>>,
+    base_url: String,
+    bearer_token: Option<String>,
+    timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl Client {
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.into(),
+            bearer_token: None,
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(200),
+        }
+    }
+
+    /// `GET /api-health`
+    pub async fn health(&self) -> Result<(), ClientError> {
+        self.get_bytes("/api-health").await.map(|_| ())
+    }
+
+    /// `GET /db-health`
+    pub async fn db_health(&self) -> Result<(), ClientError> {
+        self.get_bytes("/db-health").await.map(|_| ())
+    }
+
+    /// `GET /status`
+    pub async fn status(&self) -> Result<StatusReport, ClientError> {
+        self.get_json("/status").await
+    }
+
+    /// `GET /runs`; the last `limit` indicators runs, newest first
+    pub async fn runs(&self, limit: Option<i64>) -> Result<Vec<Run>, ClientError> {
+        let mut path = "/runs".to_string();
+        if let Some(limit) = limit {
+            path.push_str(&format!("?limit={}", limit));
+        }
+        self.get_json(&path).await
+    }
+
+    /// `GET /api/version`
+    pub async fn version(&self) -> Result<VersionInfo, ClientError> {
+        self.get_json("/api/version").await
+    }
+
+    /// `GET /api/feature-flags`
+    pub async fn feature_flags(&self) -> Result<FeatureFlags, ClientError> {
+        self.get_json("/api/feature-flags").await
+    }
+
+    /// `GET /api/indicators/latest`, all columns if `columns` is empty
+    pub async fn latest_indicators(&self, columns: &[&str]) -> Result<Vec<Indicator>, ClientError> {
+        let mut path = "/api/indicators/latest".to_string();
+        if !columns.is_empty() {
+            path.push_str(&format!("?columns={}", encode(&columns.join(","))));
+        }
+        self.get_json(&path).await
+    }
+
+    /// `POST /api/compute`: indicators of caller-supplied candles, nothing is stored
+    pub async fn compute_indicators(&self, request: &ComputeRequest) -> Result<Vec<Indicator>, ClientError> {
+        let body = serde_json::to_vec(request).map_err(ClientError::Decode)?;
+        let response = self.send_with_retries(Method::POST, "/api/compute", Bytes::from(body)).await?;
+        serde_json::from_slice(&response).map_err(ClientError::Decode)
+    }
+
+    /// `POST /api/indicators/query`: several instruments over a time range in one round trip
+    pub async fn query_indicators(
+        &self,
+        request: &IndicatorsQueryRequest,
+) -> Result<ColumnarResponse, ClientError> {
+        let body = serde_json::to_vec(request).map_err(ClientError::Decode)?;
+        let response = self
+            .send_with_retries(Method::POST, "/api/indicators/query", Bytes::from(body))
+            .await?;
+        serde_json::from_slice(&response).map_err(ClientError::Decode)
+    }
+
+    /// `GET /api/indicators/{instrument_uid}`
+    pub async fn indicators(
+        &self,
+        instrument_uid: &str,
+        query: &IndicatorsQuery,
+    ) -> Result<IndicatorPage, ClientError> {
+        let mut path = format!("/api/indicators/{}", encode(instrument_uid));
+        let query = query.to_query_string();
+        if !query.is_empty() {
+            path.push('?');
+            path.push_str(&query);
+        }
+        self.get_json(&path).await
+    }
+
+    /// `GET /api/indicators/{instrument_uid}/stats`; `buckets` defaults to 20 on the server
+    pub async fn column_stats(
+        &self,
+        instrument_uid: &str,
+        column: &str,
+        from: i64,
+        to: i64,
+        buckets: Option<usize>,
+    ) -> Result<ColumnStats, ClientError> {
+        let mut path = format!(
+            "/api/indicators/{}/stats?column={}&from={}&to={}",
+            encode(instrument_uid),
+            encode(column),
+            from,
+            to
+        );
+        if let Some(buckets) = buckets {
+            path.push_str(&format!("&buckets={}", buckets));
+        }
+        self.get_json(&path).await
+    }
+
+    /// `GET /api/signals/counts`; every instrument without `watchlist`
+    pub async fn signal_counts(
+        &self,
+        from: i64,
+        to: i64,
+        interval: CountInterval,
+        watchlist: Option<&str>,
+    ) -> Result<SignalCounts, ClientError> {
+        let mut path = format!("/api/signals/counts?from={}&to={}&interval={}", from, to, interval.as_str());
+        if let Some(watchlist) = watchlist {
+            path.push_str(&format!("&watchlist={}", encode(watchlist)));
+        }
+        self.get_json(&path).await
+    }
+
+    /// `GET /api/signals/latency`; stage latency of signals of candles in `[from, to]`
+    pub async fn signal_latency(&self, from: i64, to: i64) -> Result<SignalLatency, ClientError> {
+        self.get_json(&format!("/api/signals/latency?from={}&to={}", from, to)).await
+    }
+
+    /// `GET /api/signals/{instrument_uid}/annotations`
+    pub async fn signal_annotations(
+        &self,
+        instrument_uid: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<Annotation>, ClientError> {
+        let path = format!(
+            "/api/signals/{}/annotations?from={}&to={}",
+            encode(instrument_uid),
+            from,
+            to
+        );
+        self.get_json(&path).await
+    }
+
+    /// `apply` switches the runtime-tunable recommendations on the server
+    pub async fn tuning_recommendations(
+        &self,
+        runs: Option<usize>,
+        apply: bool,
+    ) -> Result<TuningReport, ClientError> {
+        let mut path = format!("/api/admin/tuning-recommendations?apply={}", apply);
+        if let Some(runs) = runs {
+            path.push_str(&format!("&runs={}", runs));
+        }
+        self.get_json(&path).await
+    }
+
+    /// `POST /api/admin/sample-export`: anonymized rows for sharing a reproduction
+    pub async fn sample_export(&self, request: &SampleExportRequest) -> Result<SampleExport, ClientError> {
+        let body = serde_json::to_vec(request).map_err(ClientError::Decode)?;
+        let response = self
+            .send_with_retries(Method::POST, "/api/admin/sample-export", Bytes::from(body))
+            .await?;
+        serde_json::from_slice(&response).map_err(ClientError::Decode)
+    }
+
+    /// `GET /api/admin/signal-suppressions`
+    pub async fn signal_suppressions(&self) -> Result<Vec<SignalSuppression>, ClientError> {
+        self.get_json("/api/admin/signal-suppressions").await
+    }
+
+    /// Stops publishing signals of an instrument; its indicators are still calculated
+    pub async fn suppress_signals(&self, instrument_uid: &str, reason: &str) -> Result<(), ClientError> {
+        let path = format!("/api/admin/signal-suppressions/{}", encode(instrument_uid));
+        let body = serde_json::to_vec(&serde_json::json!({ "reason": reason })).map_err(ClientError::Decode)?;
+        self.send_with_retries(Method::PUT, &path, Bytes::from(body)).await?;
+        Ok(())
+    }
+
+    /// Resumes publishing signals of an instrument
+    pub async fn release_signals(&self, instrument_uid: &str) -> Result<(), ClientError> {
+        let path = format!("/api/admin/signal-suppressions/{}", encode(instrument_uid));
+        self.send_with_retries(Method::DELETE, &path, Bytes::new()).await?;
+        Ok(())
+    }
+
+    /// `GET /api/admin/instrument-filters`
+    pub async fn instrument_filters(&self) -> Result<Vec<InstrumentFilter>, ClientError> {
+        self.get_json("/api/admin/instrument-filters").await
+    }
+
+    /// Allows or denies an instrument from the next indicator run on
+    pub async fn save_instrument_filter(
+        &self,
+        instrument_uid: &str,
+        mode: FilterMode,
+        reason: &str,
+    ) -> Result<(), ClientError> {
+        let path = format!("/api/admin/instrument-filters/{}", encode(instrument_uid));
+        let body = serde_json::json!({ "mode": mode, "reason": reason });
+        let body = serde_json::to_vec(&body).map_err(ClientError::Decode)?;
+        self.send_with_retries(Method::PUT, &path, Bytes::from(body)).await?;
+        Ok(())
+    }
+
+    /// Removes the stored filter entry of an instrument
+    pub async fn delete_instrument_filter(&self, instrument_uid: &str) -> Result<(), ClientError> {
+        let path = format!("/api/admin/instrument-filters/{}", encode(instrument_uid));
+        self.send_with_retries(Method::DELETE, &path, Bytes::new()).await?;
+        Ok(())
+    }
+
+    /// `GET /api/admin/dead-letters`; `all` also lists instruments below the failure limit
+    pub async fn dead_letters(&self, all: bool) -> Result<Vec<DeadLetter>, ClientError> {
+        self.get_json(&format!("/api/admin/dead-letters?all={}", all)).await
+    }
+
+    /// `GET /api/admin/dead-letters/{instrument_uid}`, newest first
+    pub async fn instrument_failures(
+        &self,
+        instrument_uid: &str,
+        limit: Option<i64>,
+    ) -> Result<Vec<IndicatorFailure>, ClientError> {
+        let mut path = format!("/api/admin/dead-letters/{}", encode(instrument_uid));
+        if let Some(limit) = limit {
+            path.push_str(&format!("?limit={}", limit));
+        }
+        self.get_json(&path).await
+    }
+
+    /// Closes the failures of an instrument so the next run retries it
+    pub async fn resolve_dead_letter(&self, instrument_uid: &str) -> Result<(), ClientError> {
+        let path = format!("/api/admin/dead-letters/{}", encode(instrument_uid));
+        self.send_with_retries(Method::DELETE, &path, Bytes::new()).await?;
+        Ok(())
+    }
+
+    /// `POST /api/recalculate/{instrument_uid}`: rewrites the instrument after `from` and
+    /// recalculates it up to `to` (unix seconds). Answers once done, so the client timeout
+    /// must cover the range; not retried, a repeat would meet the first request's lock
+    /// (409 with its `job_id`).
+    pub async fn recalculate(
+        &self,
+        instrument_uid: &str,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<RecalculateResult, ClientError> {
+        let mut params = Vec::new();
+        if let Some(from) = from {
+            params.push(format!("from={}", from));
+        }
+        if let Some(to) = to {
+            params.push(format!("to={}", to));
+        }
+        let mut path = format!("/api/recalculate/{}", encode(instrument_uid));
+        if !params.is_empty() {
+            path.push_str(&format!("?{}", params.join("&")));
+        }
+        let response = self.send(Method::POST, &path, Bytes::new()).await?;
+        serde_json::from_slice(&response).map_err(ClientError::Decode)
+    }
+
+    /// `GET /api/openapi.json`, the spec this client is maintained against
+    pub async fn openapi(&self) -> Result<serde_json::Value, ClientError> {
+        self.get_json("/api/openapi.json").await
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let body = self.get_bytes(path).await?;
+        serde_json::from_slice(&body).map_err(ClientError::Decode)
+    }
+
+    async fn get_bytes(&self, path: &str) -> Result<Bytes, ClientError> {
+        self.send_with_retries(Method::GET, path, Bytes::new()).await
+    }
+
+    /// Sends a request, retrying transient failures. Only used for reads and idempotent
+    /// writes, so retries are safe.
+    async fn send_with_retries(
+        &self,
+        method: Method,
+        path: &str,
+        body: Bytes,
+    ) -> Result<Bytes, ClientError> {
+        let mut attempt = 0;
+        loop {
+            match self.send(method.clone(), path, body.clone()).await {
+                Err(e) if e.is_retryable() && attempt < self.max_retries => {
+                    tokio::time::sleep(self.retry_backoff * 2u32.pow(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Bytes) -> Result<Bytes, ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        let uri: Uri = url.parse().map_err(|_| ClientError::InvalidUrl(url.clone()))?;
+
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::ACCEPT, "application/json");
+        if !body.is_empty() {
+            request = request.header(header::CONTENT_TYPE, "application/json");
+        }
+        if let Some(token) = &self.bearer_token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request
+            .body(Full::new(body))
+            .map_err(|_| ClientError::InvalidUrl(url.clone()))?;
+
+        let response = tokio::time::timeout(self.timeout, async {
+            let response = self.http.request(request).await?;
+            let status = response.status();
+            let body = response.into_body().collect().await?.to_bytes();
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>((status, body))
+        })
+        .await
+        .map_err(|_| ClientError::Transport(format!("request to {} timed out", url)))?
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+
+        match response {
+            (status, body) if status.is_success() => Ok(body),
+            (status, body) => Err(api_error(status, &body)),
+        }
+    }
+}
+
+fn api_error(status: StatusCode, body: &[u8]) -> ClientError {
+    ClientError::Api {
+        status: status.as_u16(),
+        body: serde_json::from_slice::<ApiErrorBody>(body).ok(),
+    }
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters and `,`
+pub(crate) fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    /// Paths of the spec covered by this client, checked against `openapi/openapi.json`
+    const PATHS: &[&str] = &[
+        "/api-health",
+        "/db-health",
+        "/status",
+        "/runs",
+        "/api/version",
+        "/api/feature-flags",
+        "/api/compute",
+        "/api/indicators/latest",
+        "/api/indicators/query",
+        "/api/indicators/{instrument_uid}",
+        "/api/indicators/{instrument_uid}/stats",
+        "/api/signals/counts",
+        "/api/signals/latency",
+        "/api/signals/{instrument_uid}/annotations",
+        "/api/admin/tuning-recommendations",
+        "/api/admin/sample-export",
+        "/api/admin/signal-suppressions",
+        "/api/admin/signal-suppressions/{instrument_uid}",
+        "/api/admin/instrument-filters",
+        "/api/admin/instrument-filters/{instrument_uid}",
+        "/api/admin/dead-letters",
+        "/api/admin/dead-letters/{instrument_uid}",
+        "/api/recalculate/{instrument_uid}",
+        "/api/openapi.json",
+    ];
+
+    #[test]
+    fn spec_paths_are_covered() {
+        let spec: serde_json::Value =
+            serde_json::from_str(include_str!("../../openapi/openapi.json")).unwrap();
+        let mut spec_paths: Vec<&str> =
+            spec["paths"].as_object().unwrap().keys().map(String::as_str).collect();
+        let mut client_paths = PATHS.to_vec();
+        spec_paths.sort();
+        client_paths.sort();
+        assert_eq!(spec_paths, client_paths);
+    }
+}
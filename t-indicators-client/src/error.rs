@@ -0,0 +1,41 @@
+use crate::models::ApiErrorBody;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// Base URL or request path is not a valid URI
+    InvalidUrl(String),
+    /// Connection failed or the request timed out, after all retries
+    Transport(String),
+    /// The server answered with an error status
+    Api { status: u16, body: Option<ApiErrorBody> },
+    /// The response body doesn't match the expected type
+    Decode(serde_json::Error),
+}
+
+impl ClientError {
+    /// Whether repeating the same request may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Transport(_) => true,
+            ClientError::Api { status, .. } => matches!(status, 502..=504),
+            ClientError::InvalidUrl(_) | ClientError::Decode(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidUrl(url) => write!(f, "invalid url: {}", url),
+            ClientError::Transport(e) => write!(f, "transport error: {}", e),
+            ClientError::Api { status, body: Some(body) } => {
+                write!(f, "api error {} {}: {}", status, body.code, body.message)
+            }
+            ClientError::Api { status, body: None } => write!(f, "api error {}", status),
+            ClientError::Decode(e) => write!(f, "failed to decode response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
@@ -0,0 +1,29 @@
+//! Typed client for the t-indicators HTTP API.
+//!
+//! Request and response types mirror `openapi/openapi.json` at the repository root; keep
+//! them in sync when the spec changes (`spec_paths_are_covered` fails on new paths).
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), t_indicators_client::ClientError> {
+//! use t_indicators_client::{Client, IndicatorsQuery};
+//!
+//! let client = Client::builder("http://localhost:5005").bearer_token("secret").build()?;
+//! let page = client.indicators("instrument-uid", &IndicatorsQuery::default()).await?;
+//! # Ok(())
+//! # }
+//! ```
+mod client;
+mod error;
+mod models;
+
+pub use client::{Client, ClientBuilder};
+pub use error::ClientError;
+pub use models::{
+    Aggregation, Annotation, ApiErrorBody, ColumnStats, ColumnarResponse, ColumnarSeries, ComputeCandle,
+    ComputeRequest, CountInterval, DeadLetter, Extreme, FeatureFlags, FilterMode, Freshness, Health,
+    HistogramBucket, Indicator, IndicatorFailure, IndicatorPage, IndicatorsQuery, IndicatorsQueryRequest,
+    InstrumentFilter, Maintenance,
+    Recommendation, Run, SampleExport, SampleExportRequest, Severity, SignalCountBucket, SignalCounts, SignalKind,
+    SignalLatency, SignalSuppression, SortOrder, StageLatency, StatusComponents, StatusReport, TuningReport,
+    TuningSetting, TuningSettings, VersionInfo,
+};
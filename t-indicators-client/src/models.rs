@@ -0,0 +1,463 @@
+use crate::client::encode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Error body returned by every endpoint (`#/components/schemas/Error`)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiErrorBody {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: Option<Value>,
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct FeatureFlags {
+    #[serde(default)]
+    pub flags: HashMap<String, bool>,
+}
+
+impl FeatureFlags {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags.get(name).copied().unwrap_or(false)
+    }
+}
+
+/// Indicator row; only the requested columns are present when a projection is used
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Indicator {
+    #[serde(default)]
+    pub instrument_uid: Option<String>,
+    #[serde(default)]
+    pub time: Option<i64>,
+    #[serde(flatten)]
+    pub columns: Map<String, Value>,
+}
+
+impl Indicator {
+    pub fn f64(&self, column: &str) -> Option<f64> {
+        self.columns.get(column).and_then(Value::as_f64)
+    }
+
+    pub fn i64(&self, column: &str) -> Option<i64> {
+        self.columns.get(column).and_then(Value::as_i64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct IndicatorPage {
+    pub items: Vec<Indicator>,
+    #[serde(default)]
+    pub next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Query of `GET /api/indicators/{instrument_uid}`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndicatorsQuery {
+    pub after: Option<i64>,
+    pub limit: Option<usize>,
+    pub order: SortOrder,
+    pub columns: Vec<String>,
+    /// Processing time to read at, unix seconds
+    pub as_of: Option<i64>,
+}
+
+impl IndicatorsQuery {
+    pub(crate) fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(after) = self.after {
+            params.push(format!("after={}", after));
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        if self.order == SortOrder::Desc {
+            params.push("order=desc".to_string());
+        }
+        if !self.columns.is_empty() {
+            params.push(format!("columns={}", encode(&self.columns.join(","))));
+        }
+        if let Some(as_of) = self.as_of {
+            params.push(format!("as_of={}", as_of));
+        }
+        params.join("&")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    Avg,
+    #[default]
+    Last,
+}
+
+/// Body of `POST /api/compute`; empty `columns` returns all columns
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ComputeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument_uid: Option<String>,
+    pub candles: Vec<ComputeCandle>,
+    pub columns: Vec<String>,
+}
+
+/// 1-minute OHLCV candle, `time` in unix seconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ComputeCandle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+/// Body of `POST /api/indicators/query`; empty `columns` returns all columns
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IndicatorsQueryRequest {
+    pub instrument_uids: Vec<String>,
+    pub from: i64,
+    pub to: i64,
+    pub columns: Vec<String>,
+    /// Requested bucket size; the server raises it for long ranges
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution_seconds: Option<i64>,
+    pub aggregation: Aggregation,
+    /// Processing time to read at, unix seconds; native resolution only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<i64>,
+}
+
+/// Response of `POST /api/indicators/query`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColumnarResponse {
+    pub resolution_seconds: i64,
+    /// `None` at native 1-minute resolution
+    #[serde(default)]
+    pub aggregation: Option<Aggregation>,
+    pub series: Vec<ColumnarSeries>,
+}
+
+/// Time series of one instrument, one array per column aligned by index
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColumnarSeries {
+    pub instrument_uid: String,
+    pub columns: Map<String, Value>,
+}
+
+impl ColumnarSeries {
+    pub fn column_f64(&self, name: &str) -> Option<Vec<f64>> {
+        let values = self.columns.get(name)?.as_array()?;
+        values.iter().map(Value::as_f64).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    GoldenCross,
+    DeathCross,
+    RsiOversold,
+    RsiOverbought,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColumnStats {
+    pub instrument_uid: String,
+    pub column: String,
+    pub from: i64,
+    pub to: i64,
+    pub count: u64,
+    pub min: Option<Extreme>,
+    pub max: Option<Extreme>,
+    pub mean: Option<f64>,
+    pub stddev: Option<f64>,
+    pub quantiles: BTreeMap<String, f64>,
+    pub histogram: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Extreme {
+    pub value: f64,
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Annotation {
+    pub time: i64,
+    pub label: String,
+    pub color: String,
+    pub direction: i8,
+    pub kind: SignalKind,
+    pub score: f64,
+    pub severity: Severity,
+}
+
+/// Interval of `GET /api/signals/counts`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl CountInterval {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            CountInterval::Hour => "hour",
+            CountInterval::Day => "day",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SignalCounts {
+    pub interval: CountInterval,
+    pub from: i64,
+    pub to: i64,
+    pub watchlist: Option<String>,
+    pub totals: HashMap<String, u64>,
+    pub buckets: Vec<SignalCountBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SignalCountBucket {
+    pub time: i64,
+    pub instruments: u64,
+    pub golden_cross: u64,
+    pub death_cross: u64,
+    pub rsi_oversold: u64,
+    pub rsi_overbought: u64,
+}
+
+/// `stages` come in pipeline order: fetch, compute, insert, emit, deliver, total
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SignalLatency {
+    pub from: i64,
+    pub to: i64,
+    pub stages: Vec<StageLatency>,
+}
+
+/// Milliseconds; `None` without measured signals
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StageLatency {
+    pub stage: String,
+    pub count: u64,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TuningSetting {
+    BatchSize,
+    Concurrency,
+    AsyncInsert,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TuningSettings {
+    pub batch_size: u64,
+    pub concurrency: u64,
+    pub async_insert: bool,
+}
+
+/// Booleans are reported as 0/1 in `current`/`recommended`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Recommendation {
+    pub setting: TuningSetting,
+    pub current: u64,
+    pub recommended: u64,
+    pub reason: String,
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TuningReport {
+    pub runs_analyzed: usize,
+    pub current: TuningSettings,
+    pub recommendations: Vec<Recommendation>,
+}
+
+/// Body of `POST /api/admin/sample-export`; empty `instrument_uids` samples `instruments`
+/// random instruments
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SampleExportRequest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub instrument_uids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruments: Option<usize>,
+    pub from: i64,
+    pub to: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_scale: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SampleExport {
+    pub instruments: usize,
+    pub price_scaled: bool,
+    pub rows: Vec<Indicator>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SignalSuppression {
+    pub instrument_uid: String,
+    pub reason: String,
+    /// RFC 3339
+    pub suppressed_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterMode {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InstrumentFilter {
+    pub instrument_uid: String,
+    pub mode: FilterMode,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeadLetter {
+    pub instrument_uid: String,
+    /// Failed runs since the last successful one
+    pub failures: i64,
+    /// RFC 3339
+    pub first_failure_time: String,
+    pub last_failure_time: String,
+    pub last_error: String,
+    /// End of the cooldown while the instrument is skipped
+    #[serde(default)]
+    pub skipped_until: Option<String>,
+}
+
+/// Indicators run; totals are zero while it is running
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Run {
+    pub run_id: String,
+    pub trigger: String,
+    pub cycle: i64,
+    pub status: String,
+    /// RFC 3339
+    pub start_time: String,
+    #[serde(default)]
+    pub end_time: Option<String>,
+    pub instruments: i64,
+    pub instruments_failed: i64,
+    pub candles_processed: i64,
+    pub rows_inserted: i64,
+    pub insert_errors: i64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Outcome of `POST /api/recalculate/{instrument_uid}`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecalculateResult {
+    pub instrument_uid: String,
+    /// ID of the recalculation, as in the log lines, status rows and failures
+    pub job_id: String,
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+    pub candles_processed: i64,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct IndicatorFailure {
+    pub instrument_uid: String,
+    pub job_id: String,
+    pub error: String,
+    pub batch_from: i64,
+    #[serde(default)]
+    pub batch_to: Option<i64>,
+    /// RFC 3339
+    pub failure_time: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Green,
+    Yellow,
+    Red,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatusReport {
+    pub status: Health,
+    pub checked_at: i64,
+    pub components: StatusComponents,
+    pub freshness: Freshness,
+    pub suppressed_instruments: usize,
+    pub maintenance: Vec<Maintenance>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatusComponents {
+    pub clickhouse: bool,
+    pub postgres: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Freshness {
+    pub market_open: bool,
+    pub latest_time: Option<i64>,
+    pub lag_seconds: Option<i64>,
+    pub instruments: usize,
+    pub stale_instruments: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Maintenance {
+    /// RFC 3339
+    pub start: String,
+    /// RFC 3339
+    pub end: String,
+    pub description: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub environment: String,
+    /// Effective configuration summary, kept untyped as its layout follows the service config
+    pub config: serde_json::Value,
+}
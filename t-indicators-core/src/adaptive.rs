@@ -0,0 +1,152 @@
+// File: t-indicators-core/src/adaptive.rs
+use crate::config::AdaptiveThresholdsConfig;
+use serde::{Deserialize, Serialize};
+
+/// Number of observations before adaptive flags are emitted
+const WARMUP: usize = 20;
+
+/// Adaptive counterparts of `rsi_zone`, `volume_anomaly` and `ma_cross`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdaptiveFlags {
+    pub rsi_zone: i8,
+    pub volume_anomaly: i8,
+    pub ma_cross: i8,
+}
+
+/// Per-instrument thresholds derived from EWMA mean/stddev of each raw series instead
+/// of hardcoded constants (RSI 30/70, volume 2σ, MA difference sign).
+#[derive(Serialize, Deserialize)]
+pub struct AdaptiveThresholds {
+    config: AdaptiveThresholdsConfig,
+    rsi: Ewma,
+    log_volume: Ewma,
+    ma_diff: Ewma,
+    // Side of the MA difference band the series is currently on: 1 above, -1 below
+    ma_side: i8,
+}
+
+impl AdaptiveThresholds {
+    pub fn new(config: &AdaptiveThresholdsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            rsi: Ewma::new(config.alpha),
+            log_volume: Ewma::new(config.alpha),
+            ma_diff: Ewma::new(config.alpha),
+            ma_side: 0,
+        }
+    }
+
+    /// Score the raw values of a candle against the current thresholds, then update them
+    pub fn update(&mut self, rsi: f64, volume: f64, ma_diff: f64) -> AdaptiveFlags {
+        let log_volume = volume.max(0.0).ln_1p();
+        let mut flags = AdaptiveFlags::default();
+
+        if self.rsi.is_warm() {
+            let band = self.config.rsi_k * self.rsi.stddev();
+            if rsi < self.rsi.mean - band {
+                flags.rsi_zone = 1;
+            } else if rsi > self.rsi.mean + band {
+                flags.rsi_zone = -1;
+            }
+        }
+
+        if self.log_volume.is_warm()
+            && log_volume > self.log_volume.mean + self.config.volume_k * self.log_volume.stddev()
+        {
+            flags.volume_anomaly = 1;
+        }
+
+        // MA cross with a hysteresis band: the difference must clear ±k·σ to flip sides,
+        // which suppresses whipsaw crosses in flat markets
+        if self.ma_diff.is_warm() {
+            let band = self.config.ma_cross_k * self.ma_diff.stddev();
+            if ma_diff > band && self.ma_side != 1 {
+                flags.ma_cross = if self.ma_side == -1 { 1 } else { 0 };
+                self.ma_side = 1;
+            } else if ma_diff < -band && self.ma_side != -1 {
+                flags.ma_cross = if self.ma_side == 1 { -1 } else { 0 };
+                self.ma_side = -1;
+            }
+        }
+
+        self.rsi.add(rsi);
+        self.log_volume.add(log_volume);
+        self.ma_diff.add(ma_diff);
+
+        flags
+    }
+}
+
+/// Exponentially weighted mean and variance
+#[derive(Serialize, Deserialize)]
+struct Ewma {
+    alpha: f64,
+    mean: f64,
+    variance: f64,
+    count: usize,
+}
+
+impl Ewma {
+    fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            mean: 0.0,
+            variance: 0.0,
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+
+        self.count += 1;
+        if self.count == 1 {
+            self.mean = value;
+            return;
+        }
+
+        let diff = value - self.mean;
+        self.mean += self.alpha * diff;
+        self.variance = (1.0 - self.alpha) * (self.variance + self.alpha * diff * diff);
+    }
+
+    fn is_warm(&self) -> bool {
+        self.count >= WARMUP
+    }
+
+    fn stddev(&self) -> f64 {
+        self.variance.max(0.0).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(flags: AdaptiveFlags) -> (i8, i8, i8) {
+        (flags.rsi_zone, flags.volume_anomaly, flags.ma_cross)
+    }
+
+    #[test]
+    fn test_adaptive_flags_after_warmup() {
+        let mut thresholds = AdaptiveThresholds::new(&AdaptiveThresholdsConfig::default());
+        // No flags while warming up, however extreme the values
+        for i in 0..WARMUP {
+            let side = if i % 2 == 0 { 1.0 } else { -1.0 };
+            assert_eq!(flags(thresholds.update(50.0 + 5.0 * side, 100.0, side)), (0, 0, 0));
+        }
+
+        assert_eq!(flags(thresholds.update(50.0, 100.0, 0.0)), (0, 0, 0));
+        assert_eq!(flags(thresholds.update(10.0, 100.0, 0.0)), (1, 0, 0));
+        assert_eq!(flags(thresholds.update(90.0, 100.0, 0.0)), (-1, 0, 0));
+        assert_eq!(flags(thresholds.update(50.0, 10_000.0, 0.0)), (0, 1, 0));
+
+        // The first side of the band is not a cross, a small difference inside it keeps the side
+        assert_eq!(flags(thresholds.update(50.0, 100.0, 5.0)), (0, 0, 0));
+        assert_eq!(flags(thresholds.update(50.0, 100.0, 0.1)), (0, 0, 0));
+        assert_eq!(flags(thresholds.update(50.0, 100.0, -5.0)), (0, 0, -1));
+        assert_eq!(flags(thresholds.update(50.0, 100.0, 5.0)), (0, 0, 1));
+    }
+}
@@ -0,0 +1,15 @@
+// File: t-indicators-core/src/candle.rs
+use serde::{Deserialize, Serialize};
+
+/// 1-minute OHLCV candle with prices converted to floats, `time` in unix seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candle {
+    #[serde(default)]
+    pub instrument_uid: String,
+    pub time: i64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub close_price: f64,
+    pub volume: i64,
+}
@@ -0,0 +1,172 @@
+// File: t-indicators-core/src/changepoint.rs
+use serde::{Deserialize, Serialize};
+
+/// Smoothing factor of the EWMA mean/variance baseline used to standardize inputs
+const BASELINE_ALPHA: f64 = 0.02;
+/// Number of observations before the detector starts emitting changepoints
+const WARMUP: usize = 30;
+/// CUSUM slack (in standard deviations) and decision threshold
+const CUSUM_K: f64 = 0.5;
+const CUSUM_H: f64 = 5.0;
+
+/// Changepoint features of a single candle
+pub struct ChangepointFeatures {
+    pub price_changepoint: i8,
+    pub vol_changepoint: i8,
+    pub minutes_since_price_cp: i32,
+    pub minutes_since_vol_cp: i32,
+}
+
+/// Online changepoint detector over price (log returns) and volatility (absolute log returns).
+///
+/// Each series is monitored by a two-sided CUSUM on values standardized against an EWMA
+/// baseline. A flag of 1/-1 marks an upward/downward shift of the mean; after a detection
+/// the CUSUM sums are reset so the next shift is measured from the new regime.
+#[derive(Serialize, Deserialize)]
+pub struct ChangepointDetector {
+    price: Cusum,
+    volatility: Cusum,
+    prev_close: Option<f64>,
+    last_price_cp: Option<i64>,
+    last_vol_cp: Option<i64>,
+}
+
+impl Default for ChangepointDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangepointDetector {
+    pub fn new() -> Self {
+        Self {
+            price: Cusum::new(),
+            volatility: Cusum::new(),
+            prev_close: None,
+            last_price_cp: None,
+            last_vol_cp: None,
+        }
+    }
+
+    /// Add the next candle and return its changepoint features
+    pub fn update(&mut self, time: i64, close: f64) -> ChangepointFeatures {
+        let mut price_changepoint = 0;
+        let mut vol_changepoint = 0;
+
+        if let Some(prev) = self.prev_close.filter(|prev| *prev > 0.0 && close > 0.0) {
+            let ret = (close / prev).ln();
+            price_changepoint = self.price.update(ret);
+            vol_changepoint = self.volatility.update(ret.abs());
+        }
+        self.prev_close = Some(close);
+
+        if price_changepoint != 0 {
+            self.last_price_cp = Some(time);
+        }
+        if vol_changepoint != 0 {
+            self.last_vol_cp = Some(time);
+        }
+
+        ChangepointFeatures {
+            price_changepoint,
+            vol_changepoint,
+            minutes_since_price_cp: minutes_since(self.last_price_cp, time),
+            minutes_since_vol_cp: minutes_since(self.last_vol_cp, time),
+        }
+    }
+}
+
+/// Minutes elapsed since the last changepoint, -1 if none was detected yet
+fn minutes_since(last: Option<i64>, time: i64) -> i32 {
+    match last {
+        Some(last) => ((time - last) / 60) as i32,
+        None => -1,
+    }
+}
+
+/// Two-sided CUSUM over an EWMA-standardized series
+#[derive(Serialize, Deserialize)]
+struct Cusum {
+    mean: f64,
+    variance: f64,
+    count: usize,
+    pos: f64,
+    neg: f64,
+}
+
+impl Cusum {
+    fn new() -> Self {
+        Self {
+            mean: 0.0,
+            variance: 0.0,
+            count: 0,
+            pos: 0.0,
+            neg: 0.0,
+        }
+    }
+
+    /// Returns 1 on an upward shift, -1 on a downward shift, 0 otherwise
+    fn update(&mut self, value: f64) -> i8 {
+        self.count += 1;
+        if self.count == 1 {
+            self.mean = value;
+            return 0;
+        }
+
+        let stddev = self.variance.sqrt();
+        let mut signal = 0;
+
+        if self.count > WARMUP && stddev > 0.0 {
+            let z = (value - self.mean) / stddev;
+            self.pos = (self.pos + z - CUSUM_K).max(0.0);
+            self.neg = (self.neg - z - CUSUM_K).max(0.0);
+
+            if self.pos > CUSUM_H {
+                signal = 1;
+            } else if self.neg > CUSUM_H {
+                signal = -1;
+            }
+
+            if signal != 0 {
+                self.pos = 0.0;
+                self.neg = 0.0;
+            }
+        }
+
+        // Update the EWMA baseline after scoring so the shift itself is measured against the old regime
+        let diff = value - self.mean;
+        self.mean += BASELINE_ALPHA * diff;
+        self.variance = (1.0 - BASELINE_ALPHA) * (self.variance + BASELINE_ALPHA * diff * diff);
+
+        signal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changepoint_on_a_jump_after_calm_returns() {
+        let mut detector = ChangepointDetector::new();
+        for i in 0..60 {
+            let features = detector.update(i * 60, if i % 2 == 0 { 100.0 } else { 100.1 });
+            assert_eq!((features.price_changepoint, features.vol_changepoint), (0, 0));
+            assert_eq!((features.minutes_since_price_cp, features.minutes_since_vol_cp), (-1, -1));
+        }
+
+        // A 1% jump shifts both the return and its magnitude
+        let features = detector.update(60 * 60, 101.0);
+        assert_eq!((features.price_changepoint, features.vol_changepoint), (1, 1));
+        assert_eq!((features.minutes_since_price_cp, features.minutes_since_vol_cp), (0, 0));
+
+        for (minutes, close) in [(1, 100.9), (2, 101.0), (3, 100.9)] {
+            let features = detector.update((60 + minutes) * 60, close);
+            assert_eq!((features.price_changepoint, features.vol_changepoint), (0, 0));
+            assert_eq!(
+                (features.minutes_since_price_cp, features.minutes_since_vol_cp),
+                (minutes as i32, minutes as i32)
+            );
+        }
+    }
+}
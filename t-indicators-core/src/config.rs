@@ -0,0 +1,71 @@
+// File: t-indicators-core/src/config.rs
+use serde::{Deserialize, Serialize};
+
+/// Triple-barrier labeling (columns tb_label/tb_hit_time)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TripleBarrierConfig {
+    pub enabled: bool,
+    pub profit_take_atr: f64, // Верхний барьер: close + k × ATR
+    pub stop_loss_atr: f64,   // Нижний барьер: close - k × ATR
+    pub max_horizon: usize,   // Вертикальный (временной) барьер, свечи
+    pub atr_period: usize,    // Период ATR, свечи
+}
+
+impl Default for TripleBarrierConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profit_take_atr: 2.0,
+            stop_loss_atr: 2.0,
+            max_horizon: 60,
+            atr_period: 14,
+        }
+    }
+}
+
+/// Parameters of the EWMA-based adaptive zone/anomaly/cross flags
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AdaptiveThresholdsConfig {
+    pub alpha: f64,      // Коэффициент сглаживания EWMA
+    pub rsi_k: f64,      // Ширина зоны RSI в σ от среднего
+    pub volume_k: f64,   // Порог аномалии log-объёма в σ
+    pub ma_cross_k: f64, // Полоса гистерезиса пересечения MA в σ разницы MA
+}
+
+impl Default for AdaptiveThresholdsConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            rsi_k: 1.5,
+            volume_k: 2.0,
+            ma_cross_k: 0.5,
+        }
+    }
+}
+
+/// Rolling Hurst exponent of log returns
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HurstConfig {
+    pub window: usize, // Окно, свечи
+    pub method: HurstMethod,
+}
+
+impl Default for HurstConfig {
+    fn default() -> Self {
+        Self {
+            window: 128,
+            method: HurstMethod::Rs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HurstMethod {
+    #[default]
+    Rs,  // Rescaled range (R/S)
+    Dfa, // Detrended fluctuation analysis
+}
@@ -0,0 +1,127 @@
+// File: t-indicators-core/src/features.rs
+use crate::candle::Candle;
+use crate::rolling::distance_pct;
+use std::collections::VecDeque;
+
+/// Gap between consecutive candles: open vs previous close in %, and the number of
+/// missing 1-minute candles in between (0 for adjacent minutes, large over nights/weekends)
+pub fn calculate_gap(prev: &Candle, candle: &Candle) -> (f64, i32) {
+    let gap_pct = distance_pct(candle.open_price, prev.close_price);
+    let missing = ((candle.time - prev.time) / 60 - 1).clamp(0, i32::MAX as i64) as i32;
+    (gap_pct, missing)
+}
+
+/// Candle shape: high-low range in % of the open, and body/upper wick/lower wick as % of
+/// that range (they sum to 100 unless the range is zero)
+pub fn calculate_candle_shape(candle: &Candle) -> [f64; 4] {
+    let range = candle.high_price - candle.low_price;
+    let range_pct = if candle.open_price != 0.0 { range / candle.open_price * 100.0 } else { 0.0 };
+    if range <= 0.0 {
+        return [range_pct, 0.0, 0.0, 0.0];
+    }
+
+    let body_top = candle.open_price.max(candle.close_price);
+    let body_bottom = candle.open_price.min(candle.close_price);
+    [
+        range_pct,
+        (body_top - body_bottom) / range * 100.0,
+        (candle.high_price - body_top) / range * 100.0,
+        (body_bottom - candle.low_price) / range * 100.0,
+    ]
+}
+
+/// Calculate RSI (Relative Strength Index)
+pub fn calculate_rsi(gains: &VecDeque<f64>, losses: &VecDeque<f64>) -> f64 {
+    if gains.len() < 14 || losses.len() < 14 {
+        return 50.0; // Return neutral value if insufficient data
+    }
+
+    let avg_gain: f64 = gains.iter().sum::<f64>() / 14.0;
+    let avg_loss: f64 = losses.iter().sum::<f64>() / 14.0;
+
+    if avg_loss == 0.0 {
+        return 100.0;
+    }
+
+    let rs = avg_gain / avg_loss;
+    100.0 - (100.0 / (1.0 + rs))
+}
+
+/// Calculate rate of change (%) and momentum (absolute change) of the last price vs N candles ago
+pub fn calculate_roc(prices: &VecDeque<f64>, lags: &[usize]) -> (Vec<f64>, Vec<f64>) {
+    let mut roc = Vec::with_capacity(lags.len());
+    let mut momentum = Vec::with_capacity(lags.len());
+
+    let Some(&current) = prices.back() else {
+        return (vec![0.0; lags.len()], vec![0.0; lags.len()]);
+    };
+
+    for &lag in lags {
+        if lag == 0 || prices.len() <= lag {
+            roc.push(0.0);
+            momentum.push(0.0);
+            continue;
+        }
+
+        let past = prices[prices.len() - 1 - lag];
+        roc.push(if past == 0.0 { 0.0 } else { (current / past - 1.0) * 100.0 });
+        momentum.push(current - past);
+    }
+
+    (roc, momentum)
+}
+
+/// Backward log return ln(close / close `lag` candles ago), 0 if the window is too short
+pub fn calculate_log_return(prices: &VecDeque<f64>, lag: usize) -> f64 {
+    if prices.len() <= lag {
+        return 0.0;
+    }
+
+    let current = prices[prices.len() - 1];
+    let past = prices[prices.len() - 1 - lag];
+    if current <= 0.0 || past <= 0.0 {
+        return 0.0;
+    }
+
+    (current / past).ln()
+}
+
+/// Determine moving average crossing
+pub fn determine_ma_cross(
+    prev_ma_fast: f64,
+    prev_ma_slow: f64,
+    curr_ma_fast: f64,
+    curr_ma_slow: f64,
+) -> i8 {
+    // Crossing from below (golden cross)
+    if prev_ma_fast <= prev_ma_slow && curr_ma_fast > curr_ma_slow {
+        return 1;
+    }
+
+    // Crossing from above (death cross)
+    if prev_ma_fast >= prev_ma_slow && curr_ma_fast < curr_ma_slow {
+        return -1;
+    }
+
+    // No crossing
+    0
+}
+
+/// Calculate future price change and determine signal against a ±`threshold_pct` band
+pub fn calculate_future_price_change(current_price: f64, future_price: f64, threshold_pct: f64) -> (f64, i8) {
+    if current_price == 0.0 {
+        return (0.0, 0);
+    }
+
+    let price_change = ((future_price / current_price) - 1.0) * 100.0;
+
+    let signal = if price_change > threshold_pct {
+        1 // Rise above threshold
+    } else if price_change < -threshold_pct {
+        -1 // Fall below threshold
+    } else {
+        0 // Sideways
+    };
+
+    (price_change, signal)
+}
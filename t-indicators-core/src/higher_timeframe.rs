@@ -0,0 +1,141 @@
+// File: t-indicators-core/src/higher_timeframe.rs
+use super::rolling::{distance_pct, Ema, RollingSum};
+use crate::candle::Candle;
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_HOUR: i64 = 3600;
+/// EMA period of hourly closes
+pub const HOURLY_EMA_PERIOD: usize = 20;
+/// RSI period of hourly closes
+pub const HOURLY_RSI_PERIOD: usize = 14;
+/// Hourly closes loaded before a batch to warm up the EMA and RSI
+pub const HOURLY_SEED_BARS: usize = HOURLY_EMA_PERIOD * 3;
+
+/// Hourly trend features valid for a 1-minute candle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HourlyContext {
+    /// Change of the hourly EMA over the last completed hour, %
+    pub ema_slope: f64,
+    /// RSI of the hourly closes, 50 until the window fills
+    pub rsi: f64,
+}
+
+impl Default for HourlyContext {
+    fn default() -> Self {
+        Self { ema_slope: 0.0, rsi: 50.0 }
+    }
+}
+
+/// Aggregates hourly closes while streaming 1-minute candles and exposes the trend of the
+/// hours completed before the current one, so a row never sees its own unfinished hour
+#[derive(Serialize, Deserialize)]
+pub struct HourlyTrend {
+    current_hour: Option<i64>,
+    current_close: Option<f64>,
+    prev_close: Option<f64>,
+    ema: Ema,
+    gains: RollingSum,
+    losses: RollingSum,
+    context: HourlyContext,
+}
+
+impl HourlyTrend {
+    /// `seed_closes` are closes of the complete hours before the stream, oldest first
+    pub fn new(seed_closes: &[f64]) -> Self {
+        let mut trend = Self {
+            current_hour: None,
+            current_close: None,
+            prev_close: None,
+            ema: Ema::new(HOURLY_EMA_PERIOD),
+            gains: RollingSum::new(HOURLY_RSI_PERIOD),
+            losses: RollingSum::new(HOURLY_RSI_PERIOD),
+            context: HourlyContext::default(),
+        };
+        for &close in seed_closes {
+            trend.close_hour(close);
+        }
+        trend
+    }
+
+    fn close_hour(&mut self, close: f64) {
+        let prev_ema = self.ema.value();
+        let ema = self.ema.update(close);
+
+        let (gain, loss) = match self.prev_close.replace(close) {
+            Some(prev) => ((close - prev).max(0.0), (prev - close).max(0.0)),
+            None => {
+                self.context.ema_slope = 0.0;
+                return;
+            }
+        };
+        let gains = self.gains.update(gain);
+        let losses = self.losses.update(loss);
+
+        self.context = HourlyContext {
+            ema_slope: prev_ema.map_or(0.0, |prev| distance_pct(ema, prev)),
+            rsi: match (self.gains.is_full(), losses > 0.0) {
+                (false, _) => 50.0,
+                (true, false) => 100.0,
+                (true, true) => 100.0 - 100.0 / (1.0 + gains / losses),
+            },
+        };
+    }
+
+    /// Add the next candle and return the context of the completed hours before it
+    pub fn update(&mut self, candle: &Candle) -> HourlyContext {
+        let hour = candle.time.div_euclid(SECONDS_PER_HOUR);
+
+        if self.current_hour != Some(hour) {
+            if let Some(close) = self.current_close.take() {
+                self.close_hour(close);
+            }
+            self.current_hour = Some(hour);
+        }
+        self.current_close = Some(candle.close_price);
+
+        self.context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time: i64, close_price: f64) -> Candle {
+        Candle {
+            instrument_uid: "uid".to_string(),
+            time,
+            open_price: close_price,
+            high_price: close_price,
+            low_price: close_price,
+            close_price,
+            volume: 1,
+        }
+    }
+
+    #[test]
+    fn test_hourly_trend() {
+        // Rising hourly closes: seeded RSI is saturated and the EMA rises
+        let seed: Vec<f64> = (0..30).map(|i| 100.0 + i as f64).collect();
+        let mut trend = HourlyTrend::new(&seed);
+
+        let first = trend.update(&candle(0, 50.0));
+        assert_eq!(first.rsi, 100.0);
+        assert!(first.ema_slope > 0.0);
+
+        // Minutes of the same hour keep the context of the completed hours
+        assert_eq!(trend.update(&candle(1800, 40.0)), first);
+
+        // The next hour sees the drop to 40 that closed the previous one
+        let next = trend.update(&candle(3600, 45.0));
+        assert!(next.rsi < 100.0);
+        assert!(next.ema_slope < 0.0);
+    }
+
+    #[test]
+    fn test_unseeded_context() {
+        let mut trend = HourlyTrend::new(&[]);
+        assert_eq!(trend.update(&candle(0, 10.0)), HourlyContext::default());
+        assert_eq!(trend.update(&candle(3600, 11.0)), HourlyContext::default());
+    }
+}
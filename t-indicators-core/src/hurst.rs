@@ -0,0 +1,201 @@
+// File: t-indicators-core/src/hurst.rs
+use crate::config::HurstMethod;
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Smallest sub-series length used by the estimators
+const MIN_SCALE: usize = 8;
+
+/// Hurst exponent of an increment series (e.g. log returns): ~0.5 for a random walk, above
+/// for trending (persistent) and below for mean-reverting series. `None` if the series is
+/// too short for two scales or flat.
+pub fn hurst_exponent(increments: &[f64], method: HurstMethod) -> Option<f64> {
+    match method {
+        HurstMethod::Rs => rescaled_range(increments),
+        HurstMethod::Dfa => detrended_fluctuation(increments),
+    }
+}
+
+/// Classic R/S analysis: slope of log(mean R/S) over log(scale) for scales 8, 16, 32, ...
+pub fn rescaled_range(increments: &[f64]) -> Option<f64> {
+    let points: Vec<(f64, f64)> = scales(increments.len())
+        .filter_map(|scale| {
+            let ratios: Vec<f64> = increments
+                .chunks_exact(scale)
+                .filter_map(|chunk| {
+                    let mean = chunk.iter().sum::<f64>() / scale as f64;
+                    let (mut cumulative, mut max, mut min, mut sum_sq) = (0.0, 0.0_f64, 0.0_f64, 0.0);
+                    for value in chunk {
+                        cumulative += value - mean;
+                        max = max.max(cumulative);
+                        min = min.min(cumulative);
+                        sum_sq += (value - mean).powi(2);
+                    }
+                    let stddev = (sum_sq / scale as f64).sqrt();
+                    (stddev > 0.0).then(|| (max - min) / stddev)
+                })
+                .collect();
+            let mean_ratio = mean(&ratios)?;
+            (mean_ratio > 0.0).then(|| ((scale as f64).ln(), mean_ratio.ln()))
+        })
+        .collect();
+
+    slope(&points)
+}
+
+/// Detrended fluctuation analysis (DFA-1): slope of log F(scale) over log(scale), where F is
+/// the RMS residual of the integrated series around per-window linear fits
+pub fn detrended_fluctuation(increments: &[f64]) -> Option<f64> {
+    let mean_increment = mean(increments)?;
+    let profile: Vec<f64> = increments
+        .iter()
+        .scan(0.0, |sum, value| {
+            *sum += value - mean_increment;
+            Some(*sum)
+        })
+        .collect();
+
+    let points: Vec<(f64, f64)> = scales(profile.len())
+        .filter_map(|scale| {
+            let squares: Vec<f64> = profile
+                .chunks_exact(scale)
+                .map(|chunk| {
+                    let line: Vec<(f64, f64)> =
+                        chunk.iter().enumerate().map(|(i, &y)| (i as f64, y)).collect();
+                    let (a, b) = linear_fit(&line).unwrap_or((0.0, 0.0));
+                    line.iter().map(|&(x, y)| (y - a - b * x).powi(2)).sum::<f64>() / scale as f64
+                })
+                .collect();
+            let fluctuation = mean(&squares)?.sqrt();
+            (fluctuation > 0.0).then(|| ((scale as f64).ln(), fluctuation.ln()))
+        })
+        .collect();
+
+    slope(&points)
+}
+
+/// Powers of two from `MIN_SCALE` up to `len / 2` (at least two sub-series per scale), plus
+/// `len` itself when it is a power of two
+fn scales(len: usize) -> impl Iterator<Item = usize> {
+    std::iter::successors(Some(MIN_SCALE), |scale| Some(scale * 2))
+        .take_while(move |&scale| scale <= len / 2 || scale == len)
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Least-squares (intercept, slope)
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if variance <= 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+    Some((mean_y - slope * mean_x, slope))
+}
+
+fn slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    linear_fit(points).map(|(_, slope)| slope)
+}
+
+/// Hurst exponent of the log returns of the last `window` closes, 0 until the window fills
+#[derive(Serialize, Deserialize)]
+pub struct RollingHurst {
+    method: HurstMethod,
+    window: usize,
+    prev_close: Option<f64>,
+    returns: VecDeque<f64>,
+}
+
+impl RollingHurst {
+    pub fn new(window: usize, method: HurstMethod) -> Self {
+        Self {
+            method,
+            window,
+            prev_close: None,
+            returns: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> f64 {
+        let Some(prev_close) = self.prev_close.replace(close) else {
+            return 0.0;
+        };
+        if prev_close <= 0.0 || close <= 0.0 {
+            return 0.0;
+        }
+
+        if self.returns.len() == self.window {
+            self.returns.pop_front();
+        }
+        self.returns.push_back((close / prev_close).ln());
+        if self.returns.len() < self.window {
+            return 0.0;
+        }
+
+        hurst_exponent(self.returns.make_contiguous(), self.method).unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic uniform noise in [-0.5, 0.5) (64-bit LCG)
+    fn white_noise(len: usize) -> Vec<f64> {
+        let mut state: u64 = 42;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_white_noise_is_a_random_walk() {
+        let noise = white_noise(4096);
+        let rs = rescaled_range(&noise).unwrap();
+        let dfa = detrended_fluctuation(&noise).unwrap();
+        // R/S is biased upwards on short scales
+        assert!((0.45..0.65).contains(&rs), "R/S {}", rs);
+        assert!((0.4..0.6).contains(&dfa), "DFA {}", dfa);
+    }
+
+    #[test]
+    fn test_persistent_and_anti_persistent_series() {
+        // Increments that are themselves a random walk are strongly persistent
+        let trending: Vec<f64> = white_noise(4096)
+            .iter()
+            .scan(0.0, |sum, value| {
+                *sum += value;
+                Some(*sum)
+            })
+            .collect();
+        assert!(detrended_fluctuation(&trending).unwrap() > 1.2);
+        assert!(rescaled_range(&trending).unwrap() > 0.85);
+
+        // Sign-alternating increments revert immediately
+        let alternating: Vec<f64> = white_noise(4096)
+            .iter()
+            .enumerate()
+            .map(|(i, v)| if i % 2 == 0 { 1.0 + v.abs() } else { -1.0 - v.abs() })
+            .collect();
+        assert!(detrended_fluctuation(&alternating).unwrap() < 0.2);
+        assert!(rescaled_range(&alternating).unwrap() < 0.3);
+    }
+
+    #[test]
+    fn test_short_or_flat_series() {
+        assert_eq!(rescaled_range(&[0.1; 12]), None);
+        assert_eq!(detrended_fluctuation(&white_noise(10)), None);
+    }
+}
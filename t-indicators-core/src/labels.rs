@@ -0,0 +1,120 @@
+// File: t-indicators-core/src/labels.rs
+use super::rolling::true_range;
+use crate::candle::Candle;
+use crate::config::TripleBarrierConfig;
+use serde::Serialize;
+
+/// Triple-barrier label of one candle (López de Prado): which of the profit-take, stop-loss
+/// or time barrier the price touches first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BarrierLabel {
+    pub label: i8,     // 1 - profit-take, -1 - stop-loss, 0 - time barrier or unknown yet
+    pub hit_time: i64, // time of the touching candle, 0 while the outcome is unknown
+}
+
+/// Triple-barrier labeler with barriers scaled by the ATR at the entry candle
+pub struct TripleBarrier<'a> {
+    config: &'a TripleBarrierConfig,
+    atr: Vec<f64>,
+}
+
+impl<'a> TripleBarrier<'a> {
+    /// Precomputes the ATR of every candle of the series
+    pub fn new(config: &'a TripleBarrierConfig, candles: &[Candle]) -> Self {
+        Self {
+            config,
+            atr: atr_series(candles, config.atr_period),
+        }
+    }
+
+    /// Label of candle `i`, scanning at most `max_horizon` following candles.
+    /// If the series ends before any barrier is touched, the outcome is unknown (0, 0).
+    pub fn label(&self, candles: &[Candle], i: usize) -> BarrierLabel {
+        let entry = candles[i].close_price;
+        let atr = self.atr.get(i).copied().unwrap_or(0.0);
+        if atr <= 0.0 || self.config.max_horizon == 0 {
+            return BarrierLabel::default();
+        }
+
+        let upper = entry + self.config.profit_take_atr * atr;
+        let lower = entry - self.config.stop_loss_atr * atr;
+
+        for j in i + 1..=i + self.config.max_horizon {
+            let Some(candle) = candles.get(j) else {
+                return BarrierLabel::default();
+            };
+
+            // Both barriers inside one candle: intrabar order is unknown, assume the stop came first
+            if candle.low_price <= lower {
+                return BarrierLabel { label: -1, hit_time: candle.time };
+            }
+            if candle.high_price >= upper {
+                return BarrierLabel { label: 1, hit_time: candle.time };
+            }
+        }
+
+        BarrierLabel {
+            label: 0,
+            hit_time: candles[i + self.config.max_horizon].time,
+        }
+    }
+}
+
+/// Average true range with Wilder smoothing; a plain mean of the available ranges until
+/// `period` candles are seen
+pub fn atr_series(candles: &[Candle], period: usize) -> Vec<f64> {
+    let period = period.max(1);
+    let mut result = Vec::with_capacity(candles.len());
+    let mut atr = 0.0;
+
+    for (i, candle) in candles.iter().enumerate() {
+        let prev_close = i.checked_sub(1).map(|prev| candles[prev].close_price);
+        let true_range = true_range(candle.high_price, candle.low_price, prev_close);
+
+        let n = (i + 1).min(period) as f64;
+        atr += (true_range - atr) / n;
+        result.push(atr);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time: i64, high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            instrument_uid: "test".to_string(),
+            time,
+            open_price: close,
+            high_price: high,
+            low_price: low,
+            close_price: close,
+            volume: 1,
+        }
+    }
+
+    #[test]
+    fn test_triple_barrier_first_touch() {
+        let config = TripleBarrierConfig {
+            enabled: true,
+            profit_take_atr: 1.0,
+            stop_loss_atr: 1.0,
+            max_horizon: 3,
+            atr_period: 1,
+        };
+        // ATR(1) of the entry candle is its range: 2.0, so barriers are 98 and 102
+        let candles = vec![
+            candle(0, 101.0, 99.0, 100.0),
+            candle(60, 101.0, 99.0, 100.0),
+            candle(120, 102.5, 100.0, 102.0),
+            candle(180, 100.0, 97.0, 98.0),
+        ];
+
+        let tb = TripleBarrier::new(&config, &candles);
+        assert_eq!(tb.label(&candles, 0), BarrierLabel { label: 1, hit_time: 120 });
+        // Not enough future candles and no touch yet: unknown
+        assert_eq!(tb.label(&candles, 3), BarrierLabel::default());
+    }
+}
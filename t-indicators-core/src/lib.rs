@@ -0,0 +1,25 @@
+// File: t-indicators-core/src/lib.rs
+//! Indicator math of the t-indicators pipeline: rolling windows, stateful trackers, per-candle
+//! features and labeling. It has no storage, HTTP or runtime dependencies, so backtests and
+//! research tools get the same numbers as the stored indicator rows.
+
+pub mod adaptive;
+pub mod candle;
+pub mod changepoint;
+pub mod config;
+pub mod features;
+pub mod higher_timeframe;
+pub mod hurst;
+pub mod labels;
+pub mod oscillators;
+pub mod pivots;
+pub mod plugin;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quality;
+pub mod regime;
+pub mod rolling;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use candle::Candle;
@@ -0,0 +1,616 @@
+// File: t-indicators-core/src/oscillators.rs
+use crate::candle::Candle;
+use crate::rolling::{distance_pct, true_range, Ema, RollingStats, RollingSum, Wma};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Stochastic RSI: position of RSI within its recent range, with smoothed %K and %D lines
+#[derive(Serialize, Deserialize)]
+pub struct StochRsi {
+    rsi_values: VecDeque<f64>,
+    period: usize,
+    k_values: VecDeque<f64>,
+    k_period: usize,
+    d_values: VecDeque<f64>,
+    d_period: usize,
+}
+
+impl StochRsi {
+    pub fn new(period: usize, k_period: usize, d_period: usize) -> Self {
+        Self {
+            rsi_values: VecDeque::with_capacity(period),
+            period,
+            k_values: VecDeque::with_capacity(k_period),
+            k_period,
+            d_values: VecDeque::with_capacity(d_period),
+            d_period,
+        }
+    }
+
+    /// Add the next RSI value and return (StochRSI, %K, %D) on a 0-100 scale
+    pub fn update(&mut self, rsi: f64) -> (f64, f64, f64) {
+        self.rsi_values.push_back(rsi);
+        if self.rsi_values.len() > self.period {
+            self.rsi_values.pop_front();
+        }
+
+        let min = self.rsi_values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self.rsi_values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let stoch = if max > min {
+            (rsi - min) / (max - min) * 100.0
+        } else {
+            50.0 // Flat RSI range carries no information
+        };
+
+        self.k_values.push_back(stoch);
+        if self.k_values.len() > self.k_period {
+            self.k_values.pop_front();
+        }
+        let k = self.k_values.iter().sum::<f64>() / self.k_values.len() as f64;
+
+        self.d_values.push_back(k);
+        if self.d_values.len() > self.d_period {
+            self.d_values.pop_front();
+        }
+        let d = self.d_values.iter().sum::<f64>() / self.d_values.len() as f64;
+
+        (stoch, k, d)
+    }
+}
+
+/// TRIX: rate of change (%) of a triple-smoothed EMA, with an EMA signal line
+#[derive(Serialize, Deserialize)]
+pub struct Trix {
+    ema1: Ema,
+    ema2: Ema,
+    ema3: Ema,
+    signal: Ema,
+    prev_diff: Option<f64>, // previous TRIX - signal
+}
+
+impl Trix {
+    pub fn new(period: usize, signal_period: usize) -> Self {
+        Self {
+            ema1: Ema::new(period),
+            ema2: Ema::new(period),
+            ema3: Ema::new(period),
+            signal: Ema::new(signal_period),
+            prev_diff: None,
+        }
+    }
+
+    /// Add the next close and return (TRIX, signal, cross flag)
+    pub fn update(&mut self, close: f64) -> (f64, f64, i8) {
+        let prev_triple = self.ema3.value();
+        let triple = self.ema3.update(self.ema2.update(self.ema1.update(close)));
+
+        let Some(prev_triple) = prev_triple.filter(|prev| *prev != 0.0) else {
+            return (0.0, 0.0, 0);
+        };
+
+        let trix = (triple / prev_triple - 1.0) * 100.0;
+        let signal = self.signal.update(trix);
+        let diff = trix - signal;
+
+        let cross = match self.prev_diff {
+            Some(prev) if prev <= 0.0 && diff > 0.0 => 1,
+            Some(prev) if prev >= 0.0 && diff < 0.0 => -1,
+            _ => 0,
+        };
+        self.prev_diff = Some(diff);
+
+        (trix, signal, cross)
+    }
+}
+
+/// Ultimate Oscillator: weighted average of buying pressure over 7/14/28 candles
+#[derive(Serialize, Deserialize)]
+pub struct UltimateOscillator {
+    prev_close: Option<f64>,
+    bp: [RollingSum; 3],
+    tr: [RollingSum; 3],
+}
+
+impl Default for UltimateOscillator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UltimateOscillator {
+    const PERIODS: [usize; 3] = [7, 14, 28];
+    const WEIGHTS: [f64; 3] = [4.0, 2.0, 1.0];
+
+    pub fn new() -> Self {
+        Self {
+            prev_close: None,
+            bp: Self::PERIODS.map(RollingSum::new),
+            tr: Self::PERIODS.map(RollingSum::new),
+        }
+    }
+
+    /// Add the next candle and return the oscillator value (0-100, 50 until warmed up)
+    pub fn update(&mut self, candle: &Candle) -> f64 {
+        let Some(prev_close) = self.prev_close.replace(candle.close_price) else {
+            return 50.0;
+        };
+
+        let buying_pressure = candle.close_price - candle.low_price.min(prev_close);
+        let true_range = true_range(candle.high_price, candle.low_price, Some(prev_close));
+
+        let mut weighted = 0.0;
+        let mut defined = true;
+        for i in 0..3 {
+            let bp_sum = self.bp[i].update(buying_pressure);
+            let tr_sum = self.tr[i].update(true_range);
+            if !self.tr[i].is_full() || tr_sum <= 0.0 {
+                defined = false;
+                continue;
+            }
+            weighted += Self::WEIGHTS[i] * bp_sum / tr_sum;
+        }
+
+        if !defined {
+            return 50.0;
+        }
+
+        100.0 * weighted / Self::WEIGHTS.iter().sum::<f64>()
+    }
+}
+
+/// Vortex indicator: upward and downward movement over the true range of the window
+#[derive(Serialize, Deserialize)]
+pub struct Vortex {
+    prev: Option<(f64, f64, f64)>, // (high, low, close) of the previous candle
+    plus: RollingSum,
+    minus: RollingSum,
+    tr: RollingSum,
+    prev_diff: Option<f64>, // previous VI+ - VI-
+}
+
+impl Vortex {
+    pub fn new(period: usize) -> Self {
+        Self {
+            prev: None,
+            plus: RollingSum::new(period),
+            minus: RollingSum::new(period),
+            tr: RollingSum::new(period),
+            prev_diff: None,
+        }
+    }
+
+    /// Add the next candle and return (VI+, VI-, cross flag), zeros until the window fills
+    pub fn update(&mut self, candle: &Candle) -> (f64, f64, i8) {
+        let current = (candle.high_price, candle.low_price, candle.close_price);
+        let Some((prev_high, prev_low, prev_close)) = self.prev.replace(current) else {
+            return (0.0, 0.0, 0);
+        };
+
+        let plus = self.plus.update((candle.high_price - prev_low).abs());
+        let minus = self.minus.update((candle.low_price - prev_high).abs());
+        let tr = self.tr.update(true_range(candle.high_price, candle.low_price, Some(prev_close)));
+        if !self.tr.is_full() || tr <= 0.0 {
+            return (0.0, 0.0, 0);
+        }
+
+        let (vi_plus, vi_minus) = (plus / tr, minus / tr);
+        let diff = vi_plus - vi_minus;
+        let cross = match self.prev_diff {
+            Some(prev) if prev <= 0.0 && diff > 0.0 => 1,
+            Some(prev) if prev >= 0.0 && diff < 0.0 => -1,
+            _ => 0,
+        };
+        self.prev_diff = Some(diff);
+
+        (vi_plus, vi_minus, cross)
+    }
+}
+
+/// Rolling standard deviation of 1-minute log returns over 30/60/240 candles
+#[derive(Serialize, Deserialize)]
+pub struct RealizedVolatility {
+    prev_close: Option<f64>,
+    returns: [RollingStats; 3],
+}
+
+impl Default for RealizedVolatility {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealizedVolatility {
+    const WINDOWS: [usize; 3] = [30, 60, 240];
+
+    pub fn new() -> Self {
+        Self {
+            prev_close: None,
+            returns: Self::WINDOWS.map(RollingStats::new),
+        }
+    }
+
+    /// Add the next close and return the volatility for each window
+    pub fn update(&mut self, close: f64) -> [f64; 3] {
+        let prev_close = self.prev_close.replace(close);
+        // Non-positive prices have no log return, skip them rather than poison the window
+        if let Some(prev_close) = prev_close.filter(|prev| *prev > 0.0 && close > 0.0) {
+            let log_return = (close / prev_close).ln();
+            for stats in &mut self.returns {
+                stats.add(log_return);
+            }
+        }
+
+        [
+            self.returns[0].stddev(),
+            self.returns[1].stddev(),
+            self.returns[2].stddev(),
+        ]
+    }
+}
+
+/// Lag-1 autocorrelation of log returns and normalized Shannon entropy of their signs
+/// (up/down/flat) over a rolling window
+#[derive(Serialize, Deserialize)]
+pub struct ReturnStructure {
+    window: usize,
+    prev_close: Option<f64>,
+    returns: VecDeque<f64>,
+}
+
+impl ReturnStructure {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            prev_close: None,
+            returns: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Add the next close and return (autocorrelation, entropy 0-1), zeros until the window fills
+    pub fn update(&mut self, close: f64) -> (f64, f64) {
+        let prev_close = self.prev_close.replace(close);
+        if let Some(prev_close) = prev_close.filter(|prev| *prev > 0.0 && close > 0.0) {
+            if self.returns.len() == self.window {
+                self.returns.pop_front();
+            }
+            self.returns.push_back((close / prev_close).ln());
+        }
+        if self.returns.len() < self.window {
+            return (0.0, 0.0);
+        }
+
+        let n = self.returns.len() as f64;
+        let mean = self.returns.iter().sum::<f64>() / n;
+        let variance: f64 = self.returns.iter().map(|r| (r - mean).powi(2)).sum();
+        let covariance: f64 = self
+            .returns
+            .iter()
+            .zip(self.returns.iter().skip(1))
+            .map(|(prev, next)| (prev - mean) * (next - mean))
+            .sum();
+        let autocorrelation = if variance > 0.0 { covariance / variance } else { 0.0 };
+
+        let mut counts = [0usize; 3];
+        for r in &self.returns {
+            counts[if *r > 0.0 { 0 } else if *r < 0.0 { 1 } else { 2 }] += 1;
+        }
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / n;
+                -p * p.log2()
+            })
+            .sum();
+
+        (autocorrelation, entropy / 3f64.log2())
+    }
+}
+
+/// Volume-weighted moving average of closes, the plain close while the window has no volume
+#[derive(Serialize, Deserialize)]
+pub struct Vwma {
+    price_volume: RollingSum,
+    volume: RollingSum,
+}
+
+impl Vwma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            price_volume: RollingSum::new(period),
+            volume: RollingSum::new(period),
+        }
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> f64 {
+        let volume = candle.volume as f64;
+        let price_volume = self.price_volume.update(candle.close_price * volume);
+        let volume = self.volume.update(volume);
+
+        if volume > 0.0 { price_volume / volume } else { candle.close_price }
+    }
+}
+
+/// Hull moving average: WMA(2 * WMA(n/2) - WMA(n), sqrt(n)), which lags less than an SMA
+#[derive(Serialize, Deserialize)]
+pub struct HullMovingAverage {
+    wma_half: Wma,
+    wma_full: Wma,
+    wma_sqrt: Wma,
+    prev_hma: Option<f64>,
+}
+
+impl HullMovingAverage {
+    pub fn new(period: usize) -> Self {
+        Self {
+            wma_half: Wma::new(period / 2),
+            wma_full: Wma::new(period),
+            wma_sqrt: Wma::new((period as f64).sqrt().round() as usize),
+            prev_hma: None,
+        }
+    }
+
+    /// Add the next close and return (WMA(n), HMA(n), HMA slope in % vs the previous candle)
+    pub fn update(&mut self, close: f64) -> (f64, f64, f64) {
+        let half = self.wma_half.update(close);
+        let full = self.wma_full.update(close);
+        let hma = self.wma_sqrt.update(2.0 * half - full);
+
+        let slope = match self.prev_hma.replace(hma) {
+            Some(prev) => distance_pct(hma, prev),
+            None => 0.0,
+        };
+
+        (full, hma, slope)
+    }
+}
+
+/// Recursive Heikin-Ashi candle transformation
+#[derive(Serialize, Deserialize)]
+pub struct HeikinAshi {
+    prev: Option<(f64, f64)>, // previous (ha_open, ha_close)
+}
+
+impl Default for HeikinAshi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeikinAshi {
+    pub fn new() -> Self {
+        Self { prev: None }
+    }
+
+    /// Transform the next candle, returning (open, high, low, close)
+    pub fn update(&mut self, candle: &Candle) -> (f64, f64, f64, f64) {
+        let ha_close =
+            (candle.open_price + candle.high_price + candle.low_price + candle.close_price) / 4.0;
+        let ha_open = match self.prev {
+            Some((prev_open, prev_close)) => (prev_open + prev_close) / 2.0,
+            None => (candle.open_price + candle.close_price) / 2.0,
+        };
+        let ha_high = candle.high_price.max(ha_open).max(ha_close);
+        let ha_low = candle.low_price.min(ha_open).min(ha_close);
+
+        self.prev = Some((ha_open, ha_close));
+
+        (ha_open, ha_high, ha_low, ha_close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    fn candle(high: f64, low: f64, close: f64) -> Candle {
+        Candle {
+            instrument_uid: String::new(),
+            time: 0,
+            open_price: close,
+            high_price: high,
+            low_price: low,
+            close_price: close,
+            volume: 0,
+        }
+    }
+
+    fn candles() -> Vec<Candle> {
+        (0..50)
+            .map(|i| {
+                let close = 100.0 + (i as f64 * 0.7).sin() * 3.0;
+                candle(close + 0.5 + (i % 3) as f64 * 0.2, close - 0.4 - (i % 4) as f64 * 0.1, close)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stoch_rsi_known_values() {
+        let mut stoch_rsi = StochRsi::new(3, 2, 2);
+        // Flat range at the start, then the position of RSI in the last 3 values
+        assert_eq!(stoch_rsi.update(10.0), (50.0, 50.0, 50.0));
+        assert_eq!(stoch_rsi.update(20.0), (100.0, 75.0, 62.5));
+        assert_eq!(stoch_rsi.update(15.0), (50.0, 75.0, 75.0));
+        assert_eq!(stoch_rsi.update(30.0), (100.0, 75.0, 75.0));
+        assert_eq!(stoch_rsi.update(15.0), (0.0, 50.0, 62.5));
+    }
+
+    #[test]
+    fn test_stoch_rsi_state_roundtrip_is_exact() {
+        let values = [48.2, 51.7, 55.1, 49.9, 47.3, 53.8, 58.4, 52.6, 50.1, 56.9];
+        let mut stoch_rsi = StochRsi::new(4, 3, 3);
+        for &value in &values[..5] {
+            stoch_rsi.update(value);
+        }
+
+        let mut restored: StochRsi = serde_json::from_str(&serde_json::to_string(&stoch_rsi).unwrap()).unwrap();
+        for &value in &values[5..] {
+            let (expected, actual) = (stoch_rsi.update(value), restored.update(value));
+            assert_eq!(
+                (actual.0.to_bits(), actual.1.to_bits(), actual.2.to_bits()),
+                (expected.0.to_bits(), expected.1.to_bits(), expected.2.to_bits())
+            );
+        }
+    }
+
+    #[test]
+    fn test_trix_known_values() {
+        let mut trix = Trix::new(3, 3);
+        assert_eq!(trix.update(100.0), (0.0, 0.0, 0));
+
+        // Triple EMA 100 -> 101: 1%, the signal starts at it
+        let (value, signal, cross) = trix.update(108.0);
+        assert_close(value, 1.0);
+        assert_close(signal, 1.0);
+        assert_eq!(cross, 0);
+
+        // Triple EMA 101 -> 101.5, TRIX falls below the signal
+        let (value, signal, cross) = trix.update(100.0);
+        assert_close(value, 0.4950495049504955);
+        assert_close(signal, 0.7475247524752482);
+        assert_eq!(cross, -1);
+
+        // Triple EMA 101.5 -> 104, back above
+        let (value, signal, cross) = trix.update(120.0);
+        assert_close(value, 2.4630541871921263);
+        assert_close(signal, 1.6052894698336873);
+        assert_eq!(cross, 1);
+    }
+
+    #[test]
+    fn test_trix_state_roundtrip_is_exact() {
+        let closes = [101.3, 99.7, 100.1, 102.9, 98.4, 100.0, 103.3, 97.1, 99.8, 101.6];
+        let mut trix = Trix::new(3, 2);
+        for &close in &closes[..5] {
+            trix.update(close);
+        }
+
+        let mut restored: Trix = serde_json::from_str(&serde_json::to_string(&trix).unwrap()).unwrap();
+        for &close in &closes[5..] {
+            let (expected, actual) = (trix.update(close), restored.update(close));
+            assert_eq!(
+                (actual.0.to_bits(), actual.1.to_bits(), actual.2),
+                (expected.0.to_bits(), expected.1.to_bits(), expected.2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ultimate_oscillator_known_values() {
+        let mut uo = UltimateOscillator::new();
+        assert_eq!(uo.update(&candle(101.0, 99.0, 100.0)), 50.0);
+
+        // Buying pressure 1 of a true range 2, then 2 of 2 while the price climbs
+        for _ in 0..14 {
+            assert_eq!(uo.update(&candle(101.0, 99.0, 100.0)), 50.0);
+        }
+        let mut price = 100.0;
+        for _ in 0..13 {
+            assert_eq!(uo.update(&candle(price + 2.0, price, price + 2.0)), 50.0);
+            price += 2.0;
+        }
+
+        // The 28-candle window fills: 7 and 14 hold only the climb, 28 holds 14 + 14 * 2 of 56
+        assert_close(uo.update(&candle(price + 2.0, price, price + 2.0)), 100.0 * (4.0 + 2.0 + 0.75) / 7.0);
+        price += 2.0;
+        assert_close(uo.update(&candle(price + 2.0, price, price + 2.0)), 100.0 * (6.0 + 43.0 / 56.0) / 7.0);
+    }
+
+    #[test]
+    fn test_ultimate_oscillator_state_roundtrip_is_exact() {
+        let candles = candles();
+        let mut uo = UltimateOscillator::new();
+        for candle in &candles[..35] {
+            uo.update(candle);
+        }
+
+        let mut restored: UltimateOscillator = serde_json::from_str(&serde_json::to_string(&uo).unwrap()).unwrap();
+        for candle in &candles[35..] {
+            assert_eq!(restored.update(candle).to_bits(), uo.update(candle).to_bits());
+        }
+    }
+
+    #[test]
+    fn test_vortex_known_values() {
+        let mut vortex = Vortex::new(3);
+        assert_eq!(vortex.update(&candle(10.0, 8.0, 9.0)), (0.0, 0.0, 0));
+        assert_eq!(vortex.update(&candle(12.0, 9.0, 11.0)), (0.0, 0.0, 0));
+        assert_eq!(vortex.update(&candle(13.0, 10.0, 12.0)), (0.0, 0.0, 0));
+
+        let expected = [
+            (candle(12.0, 9.0, 10.0), (10.0 / 9.0, 7.0 / 9.0, 0)),
+            (candle(10.0, 7.0, 8.0), (7.0 / 9.0, 11.0 / 9.0, -1)),
+            (candle(14.0, 8.0, 13.0), (10.0 / 12.0, 11.0 / 12.0, 0)),
+            (candle(16.0, 12.0, 15.0), (16.0 / 13.0, 9.0 / 13.0, 1)),
+        ];
+        for (candle, (vi_plus, vi_minus, cross)) in &expected {
+            let actual = vortex.update(candle);
+            assert_close(actual.0, *vi_plus);
+            assert_close(actual.1, *vi_minus);
+            assert_eq!(actual.2, *cross);
+        }
+    }
+
+    #[test]
+    fn test_vortex_state_roundtrip_is_exact() {
+        let candles = candles();
+        let mut vortex = Vortex::new(14);
+        for candle in &candles[..35] {
+            vortex.update(candle);
+        }
+
+        let mut restored: Vortex = serde_json::from_str(&serde_json::to_string(&vortex).unwrap()).unwrap();
+        for candle in &candles[35..] {
+            let (expected, actual) = (vortex.update(candle), restored.update(candle));
+            assert_eq!(
+                (actual.0.to_bits(), actual.1.to_bits(), actual.2),
+                (expected.0.to_bits(), expected.1.to_bits(), expected.2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hull_moving_average_known_values() {
+        let mut hma = HullMovingAverage::new(4);
+        assert_eq!(hma.update(1.0), (1.0, 1.0, 0.0));
+
+        // WMA(2) of 2 * WMA(2) - WMA(4), each over the closes available while warming up
+        let (full, value, slope) = hma.update(2.0);
+        assert_close(full, 5.0 / 3.0);
+        assert_close(value, 13.0 / 9.0);
+        assert_close(slope, 400.0 / 9.0);
+        assert_close(hma.update(3.0).1, 23.0 / 9.0);
+        assert_close(hma.update(4.0).1, 35.0 / 9.0);
+
+        // On a line the warmed up HMA follows the close, while WMA(4) lags by one
+        let (full, value, _) = hma.update(5.0);
+        assert_close(full, 4.0);
+        assert_close(value, 5.0);
+        let (full, value, slope) = hma.update(6.0);
+        assert_close(full, 5.0);
+        assert_close(value, 6.0);
+        assert_close(slope, 20.0);
+    }
+
+    #[test]
+    fn test_hull_moving_average_state_roundtrip_is_exact() {
+        let closes = [101.3, 99.7, 100.1, 102.9, 98.4, 100.0, 103.3, 97.1, 99.8, 101.6, 100.4, 98.9];
+        let mut hma = HullMovingAverage::new(9);
+        for &close in &closes[..7] {
+            hma.update(close);
+        }
+
+        let mut restored: HullMovingAverage = serde_json::from_str(&serde_json::to_string(&hma).unwrap()).unwrap();
+        for &close in &closes[7..] {
+            let (expected, actual) = (hma.update(close), restored.update(close));
+            assert_eq!(
+                (actual.0.to_bits(), actual.1.to_bits(), actual.2.to_bits()),
+                (expected.0.to_bits(), expected.1.to_bits(), expected.2.to_bits())
+            );
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// File: t-indicators-core/src/pivots.rs
+use crate::candle::Candle;
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// High/low/close of a trading day (UTC calendar day, which covers the whole MOEX session)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DailyOhlc {
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Classic floor-trader pivot levels
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Pivots {
+    pub p: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+}
+
+impl Pivots {
+    pub fn from_day(day: &DailyOhlc) -> Self {
+        let p = (day.high + day.low + day.close) / 3.0;
+        let range = day.high - day.low;
+
+        Self {
+            p,
+            r1: 2.0 * p - day.low,
+            r2: p + range,
+            r3: day.high + 2.0 * (p - day.low),
+            s1: 2.0 * p - day.high,
+            s2: p - range,
+            s3: day.low - 2.0 * (day.high - p),
+        }
+    }
+}
+
+/// Tracks the running daily OHLC while streaming 1-minute candles and exposes
+/// the pivots of the previous completed day
+#[derive(Serialize, Deserialize)]
+pub struct PivotTracker {
+    current_day: Option<i64>,
+    current: Option<DailyOhlc>,
+    pivots: Pivots,
+}
+
+impl PivotTracker {
+    /// `previous_day` seeds pivots for the first day of the stream
+    pub fn new(previous_day: Option<DailyOhlc>) -> Self {
+        Self {
+            current_day: None,
+            current: None,
+            pivots: previous_day.as_ref().map(Pivots::from_day).unwrap_or_default(),
+        }
+    }
+
+    /// Add the next candle and return the pivots valid for it
+    pub fn update(&mut self, candle: &Candle) -> Pivots {
+        let day = candle.time.div_euclid(SECONDS_PER_DAY);
+
+        if self.current_day != Some(day) {
+            // The day just finished becomes the pivot source
+            if let Some(finished) = self.current.take() {
+                self.pivots = Pivots::from_day(&finished);
+            }
+            self.current_day = Some(day);
+        }
+
+        self.current = Some(match self.current {
+            Some(ohlc) => DailyOhlc {
+                high: ohlc.high.max(candle.high_price),
+                low: ohlc.low.min(candle.low_price),
+                close: candle.close_price,
+            },
+            None => DailyOhlc {
+                high: candle.high_price,
+                low: candle.low_price,
+                close: candle.close_price,
+            },
+        });
+
+        self.pivots
+    }
+}
+
+/// OHLC of the last UTC day in `candles` before the day of `time`
+pub fn last_day_before(candles: &[Candle], time: i64) -> Option<DailyOhlc> {
+    let day = time.div_euclid(SECONDS_PER_DAY);
+    let end = candles.partition_point(|candle| candle.time.div_euclid(SECONDS_PER_DAY) < day);
+    let last_day = candles[..end].last()?.time.div_euclid(SECONDS_PER_DAY);
+    let start = candles[..end].partition_point(|candle| candle.time.div_euclid(SECONDS_PER_DAY) < last_day);
+
+    let day_candles = &candles[start..end];
+    Some(DailyOhlc {
+        high: day_candles.iter().map(|candle| candle.high_price).fold(f64::MIN, f64::max),
+        low: day_candles.iter().map(|candle| candle.low_price).fold(f64::MAX, f64::min),
+        close: day_candles.last()?.close_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time: i64, high_price: f64, low_price: f64, close_price: f64) -> Candle {
+        Candle {
+            instrument_uid: "uid".to_string(),
+            time,
+            open_price: close_price,
+            high_price,
+            low_price,
+            close_price,
+            volume: 1,
+        }
+    }
+
+    #[test]
+    fn test_last_day_before() {
+        let friday = 4 * SECONDS_PER_DAY;
+        let monday = 7 * SECONDS_PER_DAY;
+        let candles = vec![
+            candle(friday - 60, 9.0, 8.0, 8.5),
+            candle(friday, 12.0, 10.0, 11.0),
+            candle(friday + 60, 13.0, 9.0, 10.0),
+            candle(monday, 20.0, 18.0, 19.0),
+        ];
+
+        let day = last_day_before(&candles, monday + 600).unwrap();
+        assert_eq!((day.high, day.low, day.close), (13.0, 9.0, 10.0));
+        assert!(last_day_before(&candles, friday - 60).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+// File: t-indicators-core/src/plugin.rs
+//! Stable C ABI of custom indicator plugins. A plugin is a shared library (`cdylib`) placed in
+//! `indicators_updater.plugins_dir` and listed in `indicators_updater.plugins`; the updater
+//! loads it at startup and stores one value per candle in the `plugin_values` column, in
+//! config order.
+//!
+//! A library exports two symbols:
+//! - `t_indicators_plugin_abi_version() -> u32`, returning [`PLUGIN_ABI_VERSION`];
+//! - `t_indicators_plugin_compute(candles, len, params, params_len, out) -> i32`, see [`ComputeFn`].
+//!
+//! A Rust plugin depends on this crate and exports a safe function with [`export_plugin!`]:
+//!
+//! ```ignore
+//! use t_indicators_core::plugin::PluginCandle;
+//!
+//! /// Close relative to the highest high of the last `params[0]` candles
+//! fn close_to_high(candles: &[PluginCandle], params: &[f64], out: &mut [f64]) {
+//!     let period = params.first().copied().unwrap_or(20.0) as usize;
+//!     for (i, candle) in candles.iter().enumerate() {
+//!         let window = &candles[(i + 1).saturating_sub(period)..=i];
+//!         let high = window.iter().map(|c| c.high).fold(f64::MIN, f64::max);
+//!         out[i] = candle.close / high;
+//!     }
+//! }
+//!
+//! t_indicators_core::export_plugin!(close_to_high);
+//! ```
+//!
+//! Plugins are stateless: every call gets the batch with the historical window of the
+//! updater in front, oldest candle first, and fills a value for each of them.
+use crate::candle::Candle;
+
+/// Version of the ABI below; a library returning another one is not loaded
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Null-terminated names of the exported symbols
+pub const ABI_VERSION_SYMBOL: &[u8] = b"t_indicators_plugin_abi_version\0";
+pub const COMPUTE_SYMBOL: &[u8] = b"t_indicators_plugin_compute\0";
+
+/// Candle passed to plugins, `time` in unix seconds
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PluginCandle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+impl From<&Candle> for PluginCandle {
+    fn from(candle: &Candle) -> Self {
+        Self {
+            time: candle.time,
+            open: candle.open_price,
+            high: candle.high_price,
+            low: candle.low_price,
+            close: candle.close_price,
+            volume: candle.volume,
+        }
+    }
+}
+
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+
+/// Fills `out[..len]` with a value per candle of `candles[..len]`; `params[..params_len]` come
+/// from the plugin's config entry. Returns 0 on success; the values of a failed call are dropped.
+/// NaN marks a candle without a value (stored as 0, like the built-in columns).
+pub type ComputeFn = unsafe extern "C" fn(
+    candles: *const PluginCandle,
+    len: usize,
+    params: *const f64,
+    params_len: usize,
+    out: *mut f64,
+) -> i32;
+
+/// Exports `$compute: fn(&[PluginCandle], &[f64], &mut [f64])` with the plugin ABI
+#[macro_export]
+macro_rules! export_plugin {
+    ($compute:path) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn t_indicators_plugin_abi_version() -> u32 {
+            $crate::plugin::PLUGIN_ABI_VERSION
+        }
+
+        /// # Safety
+        /// Pointers must be valid for their lengths, as the updater guarantees
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn t_indicators_plugin_compute(
+            candles: *const $crate::plugin::PluginCandle,
+            len: usize,
+            params: *const f64,
+            params_len: usize,
+            out: *mut f64,
+        ) -> i32 {
+            unsafe { $crate::plugin::call_compute($compute, candles, len, params, params_len, out) }
+        }
+    };
+}
+
+/// Body of the exported compute function: builds the slices and keeps a panic from unwinding
+/// into the updater
+///
+/// # Safety
+/// Pointers must be valid for their lengths; null is accepted for a zero length
+#[doc(hidden)]
+pub unsafe fn call_compute(
+    compute: fn(&[PluginCandle], &[f64], &mut [f64]),
+    candles: *const PluginCandle,
+    len: usize,
+    params: *const f64,
+    params_len: usize,
+    out: *mut f64,
+) -> i32 {
+    if len == 0 {
+        return 0;
+    }
+    if candles.is_null() || out.is_null() || (params.is_null() && params_len > 0) {
+        return 1;
+    }
+
+    let candles = unsafe { std::slice::from_raw_parts(candles, len) };
+    let params = match params_len {
+        0 => &[][..],
+        _ => unsafe { std::slice::from_raw_parts(params, params_len) },
+    };
+    let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| compute(candles, params, out))) {
+        Ok(()) => 0,
+        Err(_) => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaled_close(candles: &[PluginCandle], params: &[f64], out: &mut [f64]) {
+        if params.is_empty() {
+            panic!("scale is required");
+        }
+        for (value, candle) in out.iter_mut().zip(candles) {
+            *value = candle.close * params[0];
+        }
+    }
+
+    crate::export_plugin!(scaled_close);
+
+    #[test]
+    fn test_exported_compute() {
+        let candles: Vec<PluginCandle> = [10.0, 11.0]
+            .iter()
+            .map(|&close| PluginCandle { time: 0, open: close, high: close, low: close, close, volume: 1 })
+            .collect();
+        let params = [2.0];
+        let mut out = vec![f64::NAN; candles.len()];
+
+        assert_eq!(t_indicators_plugin_abi_version(), PLUGIN_ABI_VERSION);
+        let status = unsafe {
+            t_indicators_plugin_compute(candles.as_ptr(), candles.len(), params.as_ptr(), 1, out.as_mut_ptr())
+        };
+        assert_eq!((status, out), (0, vec![20.0, 22.0]));
+
+        // A panic is reported, not unwound across the ABI
+        let mut out = vec![0.0; candles.len()];
+        let status = unsafe {
+            t_indicators_plugin_compute(candles.as_ptr(), candles.len(), std::ptr::null(), 0, out.as_mut_ptr())
+        };
+        assert_eq!(status, 2);
+    }
+}
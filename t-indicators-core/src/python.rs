@@ -0,0 +1,128 @@
+// File: t-indicators-core/src/python.rs
+//! Python bindings of the indicator core, so notebooks compute features with the production
+//! code instead of pandas reimplementations. Built as a wheel with `maturin build` in
+//! `t-indicators-core` (see pyproject.toml).
+//!
+//! Series are returned per input candle. Values before the warm-up completes differ from the
+//! stored rows, which are calculated with a historical window in front. Omitted parameters
+//! take the defaults of `indicators_updater`.
+use crate::candle::Candle;
+use crate::config::{HurstConfig, HurstMethod, TripleBarrierConfig};
+use crate::features::{calculate_log_return, calculate_roc};
+use crate::hurst::RollingHurst;
+use crate::labels::{atr_series, TripleBarrier};
+use crate::oscillators::HullMovingAverage;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::VecDeque;
+
+/// Hull moving average of closes (`hma` column)
+#[pyfunction]
+fn hma(closes: Vec<f64>, period: usize) -> Vec<f64> {
+    let mut hull = HullMovingAverage::new(period);
+    closes.iter().map(|&close| hull.update(close).1).collect()
+}
+
+/// Rate of change (%) against the close `lag` candles earlier (`roc` column)
+#[pyfunction]
+fn roc(closes: Vec<f64>, lag: usize) -> Vec<f64> {
+    rolling_window(&closes, lag + 1, |window| calculate_roc(window, &[lag]).0[0])
+}
+
+/// Log return against the close `lag` candles earlier (`log_return_*` columns)
+#[pyfunction]
+fn log_return(closes: Vec<f64>, lag: usize) -> Vec<f64> {
+    rolling_window(&closes, lag + 1, |window| calculate_log_return(window, lag))
+}
+
+/// Rolling Hurst exponent of log returns (`hurst` column); `method` is "rs" or "dfa"
+#[pyfunction]
+#[pyo3(signature = (closes, window=None, method=None))]
+fn hurst(closes: Vec<f64>, window: Option<usize>, method: Option<&str>) -> PyResult<Vec<f64>> {
+    let defaults = HurstConfig::default();
+    let method = match method {
+        None => defaults.method,
+        Some("rs") => HurstMethod::Rs,
+        Some("dfa") => HurstMethod::Dfa,
+        Some(other) => return Err(PyValueError::new_err(format!("unknown Hurst method {}", other))),
+    };
+    let mut hurst = RollingHurst::new(window.unwrap_or(defaults.window), method);
+    Ok(closes.iter().map(|&close| hurst.update(close)).collect())
+}
+
+/// Average true range with Wilder smoothing
+#[pyfunction]
+fn atr(high: Vec<f64>, low: Vec<f64>, close: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    let candles = candles(vec![0; close.len()], high, low, close)?;
+    Ok(atr_series(&candles, period))
+}
+
+/// Triple-barrier labels: (`tb_label`, `tb_hit_time`) per candle, `time` in unix seconds
+#[pyfunction]
+#[pyo3(signature = (time, high, low, close, profit_take_atr=None, stop_loss_atr=None, max_horizon=None, atr_period=None))]
+#[allow(clippy::too_many_arguments)]
+fn triple_barrier(
+    time: Vec<i64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    profit_take_atr: Option<f64>,
+    stop_loss_atr: Option<f64>,
+    max_horizon: Option<usize>,
+    atr_period: Option<usize>,
+) -> PyResult<(Vec<i8>, Vec<i64>)> {
+    let defaults = TripleBarrierConfig::default();
+    let config = TripleBarrierConfig {
+        enabled: true,
+        profit_take_atr: profit_take_atr.unwrap_or(defaults.profit_take_atr),
+        stop_loss_atr: stop_loss_atr.unwrap_or(defaults.stop_loss_atr),
+        max_horizon: max_horizon.unwrap_or(defaults.max_horizon),
+        atr_period: atr_period.unwrap_or(defaults.atr_period),
+    };
+    let candles = candles(time, high, low, close)?;
+    let barrier = TripleBarrier::new(&config, &candles);
+    Ok((0..candles.len()).map(|i| barrier.label(&candles, i)).map(|label| (label.label, label.hit_time)).unzip())
+}
+
+/// Applies `f` to the trailing window of `size` closes at every candle
+fn rolling_window(closes: &[f64], size: usize, f: impl Fn(&VecDeque<f64>) -> f64) -> Vec<f64> {
+    let mut window = VecDeque::with_capacity(size);
+    closes
+        .iter()
+        .map(|&close| {
+            window.push_back(close);
+            if window.len() > size {
+                window.pop_front();
+            }
+            f(&window)
+        })
+        .collect()
+}
+
+fn candles(time: Vec<i64>, high: Vec<f64>, low: Vec<f64>, close: Vec<f64>) -> PyResult<Vec<Candle>> {
+    if [high.len(), low.len(), close.len()].iter().any(|&len| len != time.len()) {
+        return Err(PyValueError::new_err("price arrays must have the same length"));
+    }
+    Ok((0..time.len())
+        .map(|i| Candle {
+            instrument_uid: String::new(),
+            time: time[i],
+            open_price: close[i],
+            high_price: high[i],
+            low_price: low[i],
+            close_price: close[i],
+            volume: 0,
+        })
+        .collect())
+}
+
+#[pymodule]
+fn t_indicators_core(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(hma, module)?)?;
+    module.add_function(wrap_pyfunction!(roc, module)?)?;
+    module.add_function(wrap_pyfunction!(log_return, module)?)?;
+    module.add_function(wrap_pyfunction!(hurst, module)?)?;
+    module.add_function(wrap_pyfunction!(atr, module)?)?;
+    module.add_function(wrap_pyfunction!(triple_barrier, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,222 @@
+// File: t-indicators-core/src/quality.rs
+use crate::candle::Candle;
+use std::collections::{HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+
+/// Penalty applied to rows whose candle had duplicates removed
+const DUPLICATE_PENALTY: f64 = 0.9;
+
+/// Orders candles by time and removes those with a repeated time (keeping the copy received
+/// last), returns the times that had duplicates
+pub fn dedup_candles(candles: &mut Vec<Candle>) -> HashSet<i64> {
+    // Stable, so copies of one time stay in the order received
+    candles.sort_by_key(|candle| candle.time);
+    let mut duplicates = HashSet::new();
+    let mut deduped: Vec<Candle> = Vec::with_capacity(candles.len());
+
+    for candle in candles.drain(..) {
+        match deduped.last_mut() {
+            Some(last) if last.time == candle.time => {
+                duplicates.insert(candle.time);
+                *last = candle;
+            }
+            _ => deduped.push(candle),
+        }
+    }
+
+    *candles = deduped;
+    duplicates
+}
+
+/// A candle is stale when nothing traded and the close repeats the previous one
+pub fn is_stale_price(prev_close: Option<f64>, candle: &Candle) -> bool {
+    candle.volume == 0 && prev_close == Some(candle.close_price)
+}
+
+/// Counts consecutive stale candles (typical for illiquid names)
+#[derive(Serialize, Deserialize)]
+pub struct StalePriceTracker {
+    prev_close: Option<f64>,
+    run: i32,
+}
+
+impl Default for StalePriceTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StalePriceTracker {
+    pub fn new() -> Self {
+        Self {
+            prev_close: None,
+            run: 0,
+        }
+    }
+
+    /// Add the next candle and return (stale flag, length of the current stale run)
+    pub fn update(&mut self, candle: &Candle) -> (i8, i32) {
+        let is_stale = is_stale_price(self.prev_close, candle);
+        self.prev_close = Some(candle.close_price);
+
+        if is_stale {
+            self.run += 1;
+            (1, self.run)
+        } else {
+            self.run = 0;
+            (0, 0)
+        }
+    }
+}
+
+/// Rolling data-quality score in [0, 1] summarizing the inputs behind each row:
+/// warm-up completeness, missing minutes in the window, stale prices and removed duplicates
+#[derive(Serialize, Deserialize)]
+pub struct QualityTracker {
+    window: VecDeque<(i64, bool)>, // (time, is_stale)
+    window_size: usize,
+    stale_count: usize,
+    prev_close: Option<f64>,
+}
+
+impl QualityTracker {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            stale_count: 0,
+            prev_close: None,
+        }
+    }
+
+    /// Add the next candle and return the quality score of its row
+    pub fn update(&mut self, candle: &Candle, duplicated: bool) -> f64 {
+        let is_stale = is_stale_price(self.prev_close, candle);
+        self.prev_close = Some(candle.close_price);
+
+        self.window.push_back((candle.time, is_stale));
+        if is_stale {
+            self.stale_count += 1;
+        }
+        if self.window.len() > self.window_size && matches!(self.window.pop_front(), Some((_, true))) {
+            self.stale_count -= 1;
+        }
+
+        let count = self.window.len() as f64;
+        let warmup = count / self.window_size as f64;
+
+        let continuity = match (self.window.front(), self.window.back()) {
+            (Some((first, _)), Some((last, _))) => {
+                let expected = ((last - first) / 60 + 1) as f64;
+                (count / expected).min(1.0)
+            }
+            _ => 0.0,
+        };
+
+        let freshness = 1.0 - self.stale_count as f64 / count;
+        let duplicates = if duplicated { DUPLICATE_PENALTY } else { 1.0 };
+
+        warmup * continuity * freshness * duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(minute: i64, close_price: f64, volume: i64) -> Candle {
+        Candle {
+            instrument_uid: "uid".to_string(),
+            time: minute * 60,
+            open_price: close_price,
+            high_price: close_price,
+            low_price: close_price,
+            close_price,
+            volume,
+        }
+    }
+
+    fn times(candles: &[Candle]) -> Vec<i64> {
+        candles.iter().map(|candle| candle.time / 60).collect()
+    }
+
+    #[test]
+    fn test_dedup_candles_keeps_last_copy() {
+        let mut candles = vec![candle(0, 1.0, 10), candle(1, 2.0, 10), candle(1, 3.0, 20), candle(2, 4.0, 10)];
+        let duplicates = dedup_candles(&mut candles);
+
+        assert_eq!(times(&candles), vec![0, 1, 2]);
+        assert_eq!((candles[1].close_price, candles[1].volume), (3.0, 20));
+        assert_eq!(duplicates, HashSet::from([60]));
+
+        // Nothing left to remove
+        assert!(dedup_candles(&mut candles).is_empty());
+        assert_eq!(times(&candles), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_dedup_candles_out_of_order() {
+        let mut candles = vec![candle(2, 4.0, 10), candle(0, 1.0, 10), candle(1, 2.0, 10), candle(0, 5.0, 10)];
+        let duplicates = dedup_candles(&mut candles);
+
+        assert_eq!(times(&candles), vec![0, 1, 2]);
+        assert_eq!(candles[0].close_price, 5.0);
+        assert_eq!(duplicates, HashSet::from([0]));
+    }
+
+    #[test]
+    fn test_quality_tracker_score() {
+        let mut tracker = QualityTracker::new(4);
+
+        // Warm-up: a quarter of the window per candle
+        assert_eq!(tracker.update(&candle(0, 1.0, 10), false), 0.25);
+        assert_eq!(tracker.update(&candle(1, 2.0, 10), false), 0.5);
+        assert_eq!(tracker.update(&candle(2, 3.0, 10), false), 0.75);
+        assert_eq!(tracker.update(&candle(3, 4.0, 10), false), 1.0);
+
+        // Removed duplicates
+        assert_eq!(tracker.update(&candle(4, 5.0, 10), true), DUPLICATE_PENALTY);
+
+        // Minute 5 is missing: 4 candles over 5 minutes
+        assert!((tracker.update(&candle(6, 6.0, 10), false) - 0.8).abs() < 1e-12);
+
+        // A stale price on top of the gap
+        assert!((tracker.update(&candle(7, 6.0, 0), false) - 0.8 * 0.75).abs() < 1e-12);
+
+        // The gap and the stale candle leave the window
+        for minute in 8..12 {
+            tracker.update(&candle(minute, minute as f64, 10), false);
+        }
+        assert_eq!(tracker.update(&candle(12, 12.0, 10), false), 1.0);
+    }
+
+    #[test]
+    fn test_stale_price_tracker_counts_runs() {
+        let mut tracker = StalePriceTracker::new();
+        // Nothing to compare the first candle with
+        assert_eq!(tracker.update(&candle(0, 10.0, 0)), (0, 0));
+        assert_eq!(tracker.update(&candle(1, 10.0, 0)), (1, 1));
+        assert_eq!(tracker.update(&candle(2, 10.0, 0)), (1, 2));
+        assert_eq!(tracker.update(&candle(3, 10.0, 0)), (1, 3));
+
+        // A new price ends the run, and the next one starts from 1
+        assert_eq!(tracker.update(&candle(4, 10.5, 0)), (0, 0));
+        assert_eq!(tracker.update(&candle(5, 10.5, 0)), (1, 1));
+
+        // Volume at the same price is a trade, not a stale candle
+        assert_eq!(tracker.update(&candle(6, 10.5, 7)), (0, 0));
+        assert_eq!(tracker.update(&candle(7, 10.5, 0)), (1, 1));
+    }
+
+    #[test]
+    fn test_stale_price_tracker_state_roundtrip() {
+        let mut tracker = StalePriceTracker::new();
+        for minute in 0..3 {
+            tracker.update(&candle(minute, 10.0, 0));
+        }
+
+        // A resumed run keeps counting
+        let mut restored: StalePriceTracker = serde_json::from_str(&serde_json::to_string(&tracker).unwrap()).unwrap();
+        assert_eq!(restored.update(&candle(3, 10.0, 0)), (1, 3));
+    }
+}
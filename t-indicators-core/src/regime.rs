@@ -0,0 +1,183 @@
+// File: t-indicators-core/src/regime.rs
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Market regime codes stored in the `regime` column
+pub const REGIME_RANGE: i8 = 0;
+pub const REGIME_TREND_UP: i8 = 1;
+pub const REGIME_TREND_DOWN: i8 = -1;
+pub const REGIME_HIGH_VOL: i8 = 2;
+
+/// Number of candles used for the efficiency ratio (trend strength)
+const TREND_WINDOW: usize = 30;
+/// Short and long windows of 1-candle returns used to detect volatility bursts
+const SHORT_VOL_WINDOW: usize = 15;
+const LONG_VOL_WINDOW: usize = 60;
+
+/// Efficiency ratio above which the market is considered trending
+const TREND_THRESHOLD: f64 = 0.4;
+/// Short/long volatility ratio above which the market is considered high-volatility
+const HIGH_VOL_RATIO: f64 = 2.0;
+
+/// Rolling statistics classifier labeling each candle with a market regime.
+///
+/// High volatility takes precedence: a burst of short-term volatility relative to the
+/// longer baseline is reported as `REGIME_HIGH_VOL`. Otherwise Kaufman's efficiency ratio
+/// (net move / path length) decides between a directional trend and a range.
+#[derive(Serialize, Deserialize)]
+pub struct RegimeClassifier {
+    closes: VecDeque<f64>,
+    short_returns: ReturnWindow,
+    long_returns: ReturnWindow,
+}
+
+impl Default for RegimeClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegimeClassifier {
+    pub fn new() -> Self {
+        Self {
+            closes: VecDeque::with_capacity(TREND_WINDOW + 1),
+            short_returns: ReturnWindow::new(SHORT_VOL_WINDOW),
+            long_returns: ReturnWindow::new(LONG_VOL_WINDOW),
+        }
+    }
+
+    /// Add the next close price and return the regime of that candle
+    pub fn update(&mut self, close: f64) -> i8 {
+        let prev = self.closes.back().copied().unwrap_or(0.0);
+        if prev > 0.0 && close > 0.0 {
+            let ret = (close / prev).ln();
+            self.short_returns.add(ret);
+            self.long_returns.add(ret);
+        }
+
+        self.closes.push_back(close);
+        if self.closes.len() > TREND_WINDOW + 1 {
+            self.closes.pop_front();
+        }
+
+        self.classify()
+    }
+
+    fn classify(&self) -> i8 {
+        if self.closes.len() <= TREND_WINDOW {
+            return REGIME_RANGE;
+        }
+
+        let long_vol = self.long_returns.stddev();
+        if self.long_returns.is_full()
+            && long_vol > 0.0
+            && self.short_returns.stddev() / long_vol > HIGH_VOL_RATIO
+        {
+            return REGIME_HIGH_VOL;
+        }
+
+        let first = self.closes.front().copied().unwrap_or(0.0);
+        let last = self.closes.back().copied().unwrap_or(0.0);
+        let path: f64 = self
+            .closes
+            .iter()
+            .zip(self.closes.iter().skip(1))
+            .map(|(a, b)| (b - a).abs())
+            .sum();
+
+        if path == 0.0 {
+            return REGIME_RANGE;
+        }
+
+        let efficiency = (last - first) / path;
+        if efficiency >= TREND_THRESHOLD {
+            REGIME_TREND_UP
+        } else if efficiency <= -TREND_THRESHOLD {
+            REGIME_TREND_DOWN
+        } else {
+            REGIME_RANGE
+        }
+    }
+}
+
+/// Rolling window of returns with running sums for O(1) standard deviation
+#[derive(Serialize, Deserialize)]
+struct ReturnWindow {
+    values: VecDeque<f64>,
+    window_size: usize,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl ReturnWindow {
+    fn new(window_size: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(window_size),
+            window_size,
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        if self.values.len() > self.window_size {
+            let old = self.values.pop_front().unwrap_or(0.0);
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() >= self.window_size
+    }
+
+    fn stddev(&self) -> f64 {
+        if self.values.len() <= 1 {
+            return 0.0;
+        }
+
+        let n = self.values.len() as f64;
+        let variance = (self.sum_sq - (self.sum * self.sum) / n) / (n - 1.0);
+
+        if variance <= 0.0 {
+            return 0.0;
+        }
+
+        variance.sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(closes: impl IntoIterator<Item = f64>) -> Vec<i8> {
+        let mut classifier = RegimeClassifier::new();
+        closes.into_iter().map(|close| classifier.update(close)).collect()
+    }
+
+    #[test]
+    fn test_regime_trend_and_range() {
+        // Range until the efficiency window fills
+        let rising = classify((0..40).map(|i| 100.0 + i as f64));
+        assert!(rising[..TREND_WINDOW].iter().all(|&regime| regime == REGIME_RANGE));
+        assert!(rising[TREND_WINDOW..].iter().all(|&regime| regime == REGIME_TREND_UP));
+
+        let falling = classify((0..40).map(|i| 100.0 - i as f64));
+        assert!(falling[TREND_WINDOW..].iter().all(|&regime| regime == REGIME_TREND_DOWN));
+
+        let choppy = classify((0..40).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }));
+        assert!(choppy.iter().all(|&regime| regime == REGIME_RANGE));
+    }
+
+    #[test]
+    fn test_regime_volatility_burst() {
+        let regimes = classify((0..80).map(|_| 100.0).chain([105.0, 100.0, 105.0, 100.0]));
+        assert!(regimes[..80].iter().all(|&regime| regime == REGIME_RANGE));
+        assert_eq!(&regimes[81..], &[REGIME_HIGH_VOL; 3]);
+    }
+}
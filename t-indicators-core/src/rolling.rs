@@ -0,0 +1,416 @@
+// File: t-indicators-core/src/rolling.rs
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Rolling maximum and minimum over the last `period` values using monotonic deques,
+/// O(1) amortized per update
+#[derive(Serialize, Deserialize)]
+pub struct RollingExtrema {
+    period: usize,
+    index: usize,
+    max_deque: VecDeque<(usize, f64)>, // decreasing values
+    min_deque: VecDeque<(usize, f64)>, // increasing values
+}
+
+impl RollingExtrema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            index: 0,
+            max_deque: VecDeque::with_capacity(period),
+            min_deque: VecDeque::with_capacity(period),
+        }
+    }
+
+    /// Add the next high/low pair and return the (max high, min low) over the window
+    pub fn update(&mut self, high: f64, low: f64) -> (f64, f64) {
+        while self.max_deque.back().is_some_and(|&(_, value)| value <= high) {
+            self.max_deque.pop_back();
+        }
+        self.max_deque.push_back((self.index, high));
+
+        while self.min_deque.back().is_some_and(|&(_, value)| value >= low) {
+            self.min_deque.pop_back();
+        }
+        self.min_deque.push_back((self.index, low));
+
+        // Drop values that left the window
+        let oldest = (self.index + 1).saturating_sub(self.period);
+        while self.max_deque.front().is_some_and(|&(i, _)| i < oldest) {
+            self.max_deque.pop_front();
+        }
+        while self.min_deque.front().is_some_and(|&(i, _)| i < oldest) {
+            self.min_deque.pop_front();
+        }
+
+        self.index += 1;
+
+        (
+            self.max_deque.front().map_or(high, |&(_, value)| value),
+            self.min_deque.front().map_or(low, |&(_, value)| value),
+        )
+    }
+}
+
+/// Running sum over the last `period` values, O(1) per update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingSum {
+    values: VecDeque<f64>,
+    period: usize,
+    sum: f64,
+}
+
+impl RollingSum {
+    pub fn new(period: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(period),
+            period,
+            sum: 0.0,
+        }
+    }
+
+    /// Add the next value and return the sum over the window
+    pub fn update(&mut self, value: f64) -> f64 {
+        self.values.push_back(value);
+        self.sum += value;
+
+        if self.values.len() > self.period {
+            self.sum -= self.values.pop_front().unwrap_or(0.0);
+        }
+
+        self.sum
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.values.len() >= self.period
+    }
+}
+
+/// Rolling mean and sample standard deviation over the last `window_size` values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingStats {
+    values: VecDeque<f64>,
+    window_size: usize,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl RollingStats {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(window_size),
+            window_size,
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        // Add new value
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        // Remove old value if window size is exceeded
+        if self.values.len() > self.window_size {
+            let old_value = self.values.pop_front().unwrap_or(0.0);
+            self.sum -= old_value;
+            self.sum_sq -= old_value * old_value;
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        self.sum / self.values.len() as f64
+    }
+
+    pub fn stddev(&self) -> f64 {
+        if self.values.len() <= 1 {
+            return 0.0;
+        }
+
+        let n = self.values.len() as f64;
+        let variance = (self.sum_sq - (self.sum * self.sum) / n) / (n - 1.0);
+
+        if variance <= 0.0 {
+            return 0.0;
+        }
+
+        variance.sqrt()
+    }
+
+    /// Z-score of `value` against the window, 0 if the window is flat
+    pub fn normalize(&self, value: f64) -> f64 {
+        let mean = self.mean();
+        let stddev = self.stddev();
+
+        if stddev == 0.0 {
+            return 0.0;
+        }
+
+        (value - mean) / stddev
+    }
+}
+
+/// Simple moving average over the last `period` values from a running sum, O(1) per update.
+/// 0 until the window fills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sma {
+    sum: RollingSum,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            sum: RollingSum::new(period),
+        }
+    }
+
+    /// Add the next value and return the average over the window
+    pub fn update(&mut self, value: f64) -> f64 {
+        let sum = self.sum.update(value);
+        match self.sum.period > 0 && self.sum.is_full() {
+            true => sum / self.sum.period as f64,
+            false => 0.0,
+        }
+    }
+}
+
+/// Exponential moving average seeded with the first value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ema {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            alpha: 2.0 / (period as f64 + 1.0),
+            value: None,
+        }
+    }
+
+    /// Add the next value and return the updated average
+    pub fn update(&mut self, value: f64) -> f64 {
+        let next = match self.value {
+            Some(prev) => prev + self.alpha * (value - prev),
+            None => value,
+        };
+        self.value = Some(next);
+        next
+    }
+
+    /// Current average, `None` before the first value
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Linearly weighted moving average over the last `period` values (newest weight = period),
+/// O(1) per update. Uses the available values until the window fills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wma {
+    values: VecDeque<f64>,
+    period: usize,
+    sum: f64,
+    weighted_sum: f64,
+}
+
+impl Wma {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            values: VecDeque::with_capacity(period),
+            period,
+            sum: 0.0,
+            weighted_sum: 0.0,
+        }
+    }
+
+    /// Add the next value and return the updated average
+    pub fn update(&mut self, value: f64) -> f64 {
+        if self.values.len() == self.period {
+            // Every weight drops by one, the oldest value falls out at weight zero
+            self.weighted_sum += self.period as f64 * value - self.sum;
+            self.sum -= self.values.pop_front().unwrap_or(0.0);
+        } else {
+            self.weighted_sum += (self.values.len() + 1) as f64 * value;
+        }
+        self.values.push_back(value);
+        self.sum += value;
+
+        let n = self.values.len() as f64;
+        self.weighted_sum / (n * (n + 1.0) / 2.0)
+    }
+}
+
+/// Least-squares line through the last `period` values against their position in the window,
+/// O(1) per update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingRegression {
+    values: VecDeque<f64>,
+    period: usize,
+    sum: f64,
+    sum_sq: f64,
+    weighted_sum: f64, // sum of position * value, oldest at position 0
+}
+
+impl RollingRegression {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(2);
+        Self {
+            values: VecDeque::with_capacity(period),
+            period,
+            sum: 0.0,
+            sum_sq: 0.0,
+            weighted_sum: 0.0,
+        }
+    }
+
+    /// Add the next value and return (slope per step, R²), zeros until the window fills
+    pub fn update(&mut self, value: f64) -> (f64, f64) {
+        if self.values.len() == self.period {
+            // Every position drops by one, the oldest value falls out at position zero
+            let oldest = self.values.pop_front().unwrap_or(0.0);
+            self.sum -= oldest;
+            self.sum_sq -= oldest * oldest;
+            self.weighted_sum -= self.sum;
+        }
+        self.weighted_sum += self.values.len() as f64 * value;
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        if self.values.len() < self.period {
+            return (0.0, 0.0);
+        }
+
+        let n = self.period as f64;
+        let sum_x = n * (n - 1.0) / 2.0;
+        let sum_x_sq = (n - 1.0) * n * (2.0 * n - 1.0) / 6.0;
+        let covariance = n * self.weighted_sum - sum_x * self.sum;
+        let variance_x = n * sum_x_sq - sum_x * sum_x;
+        let variance_y = n * self.sum_sq - self.sum * self.sum;
+
+        let slope = covariance / variance_x;
+        let r_squared = if variance_y > 0.0 {
+            (covariance * covariance / (variance_x * variance_y)).min(1.0)
+        } else {
+            0.0
+        };
+
+        (slope, r_squared)
+    }
+
+    /// Mean of the values in the window
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        self.sum / self.values.len() as f64
+    }
+}
+
+/// True range of a candle: high-low extended to the previous close when there is one
+pub fn true_range(high: f64, low: f64, prev_close: Option<f64>) -> f64 {
+    match prev_close {
+        Some(prev_close) => high.max(prev_close) - low.min(prev_close),
+        None => high - low,
+    }
+}
+
+/// Percentage distance of a price from a level, 0 if the level is undefined
+pub fn distance_pct(price: f64, level: f64) -> f64 {
+    if level == 0.0 {
+        return 0.0;
+    }
+    (price / level - 1.0) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_extrema_window() {
+        let mut extrema = RollingExtrema::new(3);
+        assert_eq!(extrema.update(5.0, 4.0), (5.0, 4.0));
+        assert_eq!(extrema.update(7.0, 3.0), (7.0, 3.0));
+        assert_eq!(extrema.update(6.0, 5.0), (7.0, 3.0));
+        assert_eq!(extrema.update(2.0, 1.0), (7.0, 1.0));
+        // 7.0 and 3.0 leave the window
+        assert_eq!(extrema.update(1.0, 2.0), (6.0, 1.0));
+        assert_eq!(extrema.update(1.0, 2.0), (2.0, 1.0));
+    }
+
+    #[test]
+    fn test_wma_matches_direct_formula() {
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let mut wma = Wma::new(3);
+        for (i, &value) in values.iter().enumerate() {
+            let window = &values[(i + 1).saturating_sub(3)..=i];
+            let weights = (1..=window.len()).map(|w| w as f64);
+            let expected = window.iter().zip(weights.clone()).map(|(v, w)| v * w).sum::<f64>()
+                / weights.sum::<f64>();
+            assert!((wma.update(value) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_sma_matches_direct_formula() {
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let mut sma = Sma::new(3);
+        for (i, &value) in values.iter().enumerate() {
+            let expected = match i {
+                0 | 1 => 0.0,
+                _ => values[i - 2..=i].iter().sum::<f64>() / 3.0,
+            };
+            assert!((sma.update(value) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_rolling_regression() {
+        let mut regression = RollingRegression::new(4);
+        assert_eq!(regression.update(10.0), (0.0, 0.0));
+        regression.update(7.0);
+        regression.update(1.0);
+
+        // Exact line 1, 3, 5, 7 after the first values leave the window
+        for value in [1.0, 3.0, 5.0, 7.0] {
+            regression.update(value);
+        }
+        let (slope, r_squared) = regression.update(9.0);
+        assert!((slope - 2.0).abs() < 1e-9);
+        assert!((r_squared - 1.0).abs() < 1e-9);
+
+        // Flat window: no slope and no explained variance
+        for _ in 0..4 {
+            regression.update(5.0);
+        }
+        assert_eq!(regression.update(5.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_state_roundtrip_is_exact() {
+        let values = [101.3, 99.7, 100.1, 102.9, 98.4, 100.0, 103.3, 97.1];
+        let mut stats = RollingStats::new(5);
+        let mut wma = Wma::new(4);
+        for &value in &values[..5] {
+            stats.add(value);
+            wma.update(value);
+        }
+
+        let mut restored_stats: RollingStats = serde_json::from_str(&serde_json::to_string(&stats).unwrap()).unwrap();
+        let mut restored_wma: Wma = serde_json::from_str(&serde_json::to_string(&wma).unwrap()).unwrap();
+        for &value in &values[5..] {
+            stats.add(value);
+            restored_stats.add(value);
+            assert_eq!(restored_stats.normalize(value).to_bits(), stats.normalize(value).to_bits());
+            assert_eq!(restored_wma.update(value).to_bits(), wma.update(value).to_bits());
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// File: t-indicators-core/src/wasm.rs
+//! Browser bindings of the configurable indicators, so the dashboard can preview a parameter
+//! change on loaded candles before asking the server to recalculate. Built with
+//! `wasm-pack build t-indicators-core --target web -- --features wasm`.
+//!
+//! Series are returned per input candle. Values before the warm-up completes differ from the
+//! stored rows, which are calculated with a historical window in front.
+use crate::candle::Candle;
+use crate::config::{HurstConfig, TripleBarrierConfig};
+use crate::features::calculate_roc;
+use crate::hurst::RollingHurst;
+use crate::labels::TripleBarrier;
+use crate::oscillators::HullMovingAverage;
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+/// Hull moving average of closes (`hma` column)
+#[wasm_bindgen]
+pub fn hma(closes: &[f64], period: usize) -> Vec<f64> {
+    let mut hull = HullMovingAverage::new(period);
+    closes.iter().map(|&close| hull.update(close).1).collect()
+}
+
+/// Rate of change (%) against the close `lag` candles earlier (`roc` column)
+#[wasm_bindgen]
+pub fn roc(closes: &[f64], lag: usize) -> Vec<f64> {
+    let mut window = VecDeque::with_capacity(lag + 1);
+    closes
+        .iter()
+        .map(|&close| {
+            window.push_back(close);
+            if window.len() > lag + 1 {
+                window.pop_front();
+            }
+            calculate_roc(&window, &[lag]).0[0]
+        })
+        .collect()
+}
+
+/// Rolling Hurst exponent of log returns (`hurst` column); `config` as `indicators_updater.hurst`
+#[wasm_bindgen]
+pub fn hurst(closes: &[f64], config: JsValue) -> Result<Vec<f64>, JsError> {
+    let config: HurstConfig = serde_wasm_bindgen::from_value(config)?;
+    let mut hurst = RollingHurst::new(config.window, config.method);
+    Ok(closes.iter().map(|&close| hurst.update(close)).collect())
+}
+
+/// Triple-barrier labels (`tb_label`/`tb_hit_time` columns) as `{label, hit_time}` objects;
+/// `candles` are `{time, open_price, high_price, low_price, close_price, volume}` objects and
+/// `config` is `indicators_updater.triple_barrier`
+#[wasm_bindgen(js_name = tripleBarrier)]
+pub fn triple_barrier(candles: JsValue, config: JsValue) -> Result<JsValue, JsError> {
+    let candles: Vec<Candle> = serde_wasm_bindgen::from_value(candles)?;
+    let config: TripleBarrierConfig = serde_wasm_bindgen::from_value(config)?;
+    let barrier = TripleBarrier::new(&config, &candles);
+    let labels: Vec<_> = (0..candles.len()).map(|i| barrier.label(&candles, i)).collect();
+    Ok(serde_wasm_bindgen::to_value(&labels)?)
+}